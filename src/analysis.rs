@@ -10,6 +10,8 @@
 //   counts/amounts/quotations/dates/events/tone/GCAM) plus translation provenance, so the
 //   data can be flattened into the canonical relational schema (see docs roadmap Part C).
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 /// A named entity (person or organisation) detected in the document text.
@@ -19,13 +21,17 @@ pub struct EntityMention {
     pub surface_form: String,
     /// Entity category: PERSON | ORG | GPE | MISC ...
     pub entity_type: String,
-    /// Byte offset of the mention within `Document.text`.
+    /// Byte offset of the mention within `Document::english_text()`.
     pub char_offset: usize,
     /// Relative importance of this entity in the document (0.0..1.0).
     pub salience: f64,
     /// Canonical entity id once resolved (LEI-first); `None` until `mod_entity_resolve`
     /// resolves it. False links are worse than nulls, so this stays `None` when uncertain.
     pub entity_id: Option<String>,
+    /// Targeted tone of the text around this entity's occurrences (`mod_tone::mention_tone`,
+    /// same scale as `ToneScores::tone`); `None` if not scored.
+    #[serde(default)]
+    pub sentiment: Option<f64>,
 }
 
 /// A geographic location mention, optionally resolved to coordinates / a feature id.
@@ -78,15 +84,19 @@ pub struct Quotation {
     pub char_offset: usize,
 }
 
-/// A date referenced in the text (not the publication date).
+/// A date referenced in the text (not the publication date). For a period coarser than a day
+/// (see `temporal`) the date is the period's first day.
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct DateRef {
-    /// "year" | "month" | "day".
+    /// "day" | "week" | "month" | "quarter" | "half" | "year" | "fiscal_year".
     pub resolution: String,
     pub year: i32,
     pub month: u32,
     pub day: u32,
     pub char_offset: usize,
+    /// The text the date was read from ("2026-06-10", "last Friday", "Q3FY24").
+    #[serde(default)]
+    pub expression: String,
 }
 
 /// A coded event (CAMEO-lite for general news, or a RegEventType for regulatory docs).
@@ -143,8 +153,14 @@ pub struct DocAnalysis {
     pub gcam: Vec<GcamScore>,
     /// ISO 639 language code of the source text (e.g. "hi", "mr", "en").
     pub lang: String,
-    /// English translation of `Document.text` when the source was non-English.
+    /// English version of `Document.text` when the source was non-English, or its
+    /// Latin-script lines when it was printed in two scripts.
     pub text_en: String,
+    /// Version of each vocabulary the results were produced with, keyed by kind
+    /// (`themebook`, `tone_lexicon`, `gazetteer`, `org_gazetteer`; see `vocab`), stamped on
+    /// the rows `mod_emit_tables` writes. Provenance only: not counted by `is_empty`.
+    #[serde(default)]
+    pub vocab_versions: BTreeMap<String, String>,
 }
 
 impl DocAnalysis {
@@ -220,6 +236,7 @@ mod tests {
             char_offset: 0,
             salience: 1.0,
             entity_id: None,
+            sentiment: None,
         });
         assert!(!a.is_empty());
     }
@@ -48,6 +48,9 @@ fn main() {
         println!("Usage:");
         println!("  newslookout_app <config_file>          # run the news pipeline");
         println!("  newslookout_app batch <config_file>    # run batch data feeds (NSE/BSE/...)");
+        println!("  newslookout_app batch <config_file> --from YYYY-MM-DD --to YYYY-MM-DD [--feed <name>]");
+        println!("                                         # backfill feeds for every business day in range");
+        println!("  newslookout_app daemon <config_file>   # run retrievers and feeds on their configured schedules");
         std::process::exit(1);
     }
 
@@ -58,13 +61,20 @@ fn main() {
     println!("NewsLookout, version: {}", now);
 
     // Subcommand dispatch: `batch <config>` runs the periodic data feeds (externally
-    // scheduled); the default (no subcommand) runs the news scraping pipeline.
+    // scheduled), `daemon <config>` keeps running and schedules retrievers and feeds itself;
+    // the default (no subcommand) runs the news scraping pipeline once.
     if env::args().nth(1).as_deref() == Some("batch") {
         if env::args().len() < 3 {
-            println!("Usage: newslookout_app batch <config_file>");
+            println!("Usage: newslookout_app batch <config_file> [--from YYYY-MM-DD --to YYYY-MM-DD [--feed <name>]]");
             std::process::exit(1);
         }
         run_batch();
+    } else if env::args().nth(1).as_deref() == Some("daemon") {
+        if env::args().len() < 3 {
+            println!("Usage: newslookout_app daemon <config_file>");
+            std::process::exit(1);
+        }
+        run_daemon();
     } else {
         run_pipeline();
     }
@@ -72,8 +82,17 @@ fn main() {
 
 /// Run the batch-feed subsystem: initialise PID/logging/store, then run all enabled
 /// `batch_feed` plugins in parallel (one cron-friendly invocation). No news pipeline runs.
+/// With `--from/--to`, backfills every business day in that range instead.
 fn run_batch() {
     let config_file: String = env::args().nth(2).unwrap();
+    let extra_args: Vec<String> = env::args().skip(3).collect();
+    let backfill_range = match newslookout::feeds::parse_backfill_args(&extra_args) {
+        Ok(r) => r,
+        Err(e) => {
+            println!("Invalid batch arguments: {}", e);
+            std::process::exit(1);
+        }
+    };
     println!("Loading configuration from file: {}", config_file);
 
     let config = read_config_from_file(config_file);
@@ -87,8 +106,16 @@ fn run_batch() {
     // Ensure the metadata/market schema (incl. batch_run_log) exists before feeds run.
     newslookout::store::init_at_startup(&db_path);
 
-    let executed = newslookout::feeds::run_batch_feeds(configref.clone(), &db_path);
-    println!("Batch feeds completed: {} feed(s) executed.", executed);
+    match backfill_range {
+        Some(range) => {
+            let loaded = newslookout::feeds::run_backfill(configref.clone(), &db_path, &range);
+            println!("Backfill completed: {} date(s) loaded.", loaded);
+        }
+        None => {
+            let executed = newslookout::feeds::run_batch_feeds(configref.clone(), &db_path);
+            println!("Batch feeds completed: {} feed(s) executed.", executed);
+        }
+    }
 
     cleanup_pid_file(configref);
 }
@@ -112,18 +139,9 @@ fn run_pipeline(){
     let db_path = newslookout::cfg::get_database_filename(&configref);
     newslookout::store::init_at_startup(&db_path);
 
-    let rl_model_path = configref.get_string("rl_model_path").ok();
-    info!("Initializing content extractor (model path: {:?})...", rl_model_path);
-    let extractor_init_result = panic::catch_unwind(AssertUnwindSafe(|| {
-        newslookout::content_extraction::init_html_extractor(rl_model_path.as_deref());
-    }));
-    if let Err(e) = extractor_init_result {
-        let msg = if let Some(s) = e.downcast_ref::<&str>() { s.to_string() }
-                  else if let Some(s) = e.downcast_ref::<String>() { s.clone() }
-                  else { "unknown error".to_string() };
-        error!("Content extractor initialization failed (will use CSS fallback): {}", msg);
-        println!("WARNING: Content extractor initialization failed: {}. Using CSS fallback.", msg);
-    }
+    newslookout::utils::init_ocr_settings(&configref);
+    init_content_extractor(&configref);
+
     let all_api_mutexes: HashMap<String, Arc<Mutex<isize>>> = create_api_mutexes();
     info!("Starting the data pipeline");
 
@@ -236,14 +254,7 @@ fn run_pipeline(){
 
     info!("Loaded {} retriever and {} processing plugins to run.", retriever_plugins.len(), data_proc_plugins.len());
 
-    // Start web API status server if enabled in config
-    let status_tracker = create_status_tracker();
-    let web_api_enabled = configref.get_bool("web_api_enabled").unwrap_or(false);
-    if web_api_enabled {
-        let host = configref.get_string("web_api_host").unwrap_or_else(|_| "127.0.0.1".to_string());
-        let port = configref.get_int("web_api_port").unwrap_or(8080) as u16;
-        start_web_api(&host, port, status_tracker.clone());
-    }
+    let status_tracker = start_status_server(&configref);
 
     let docs_retrieved = pipeline::start_data_pipeline(
         retriever_plugins,
@@ -259,6 +270,63 @@ fn run_pipeline(){
 }
 
 
+/// Load the content-extraction model; on failure the CSS-selector fallback is used.
+fn init_content_extractor(configref: &Arc<Config>) {
+    let rl_model_path = configref.get_string("rl_model_path").ok();
+    info!("Initializing content extractor (model path: {:?})...", rl_model_path);
+    let extractor_init_result = panic::catch_unwind(AssertUnwindSafe(|| {
+        newslookout::content_extraction::init_html_extractor(rl_model_path.as_deref());
+    }));
+    if let Err(e) = extractor_init_result {
+        let msg = if let Some(s) = e.downcast_ref::<&str>() { s.to_string() }
+                  else if let Some(s) = e.downcast_ref::<String>() { s.clone() }
+                  else { "unknown error".to_string() };
+        error!("Content extractor initialization failed (will use CSS fallback): {}", msg);
+        println!("WARNING: Content extractor initialization failed: {}. Using CSS fallback.", msg);
+    }
+}
+
+/// Create the shared status tracker and start the web API status server if enabled in config.
+fn start_status_server(configref: &Arc<Config>) -> newslookout::web_api::SharedStatus {
+    let status_tracker = create_status_tracker();
+    let web_api_enabled = configref.get_bool("web_api_enabled").unwrap_or(false);
+    if web_api_enabled {
+        let host = configref.get_string("web_api_host").unwrap_or_else(|_| "127.0.0.1".to_string());
+        let port = configref.get_int("web_api_port").unwrap_or(8080) as u16;
+        let market_db = newslookout::cfg::get_market_data_db(configref);
+        start_web_api(&host, port, status_tracker.clone(), Some(market_db));
+    }
+    status_tracker
+}
+
+/// Run in daemon mode: initialise everything the news pipeline and the batch feeds need once,
+/// keep the web API up, and let `newslookout::scheduler` run each retriever and batch feed on
+/// its own schedule until the process is stopped.
+fn run_daemon() {
+    let config_file: String = env::args().nth(2).unwrap();
+    println!("Loading configuration from file: {}", config_file);
+
+    let config = read_config_from_file(config_file);
+    let configref = Arc::new(config);
+    println!("Initializing PID file...");
+    init_pid_file(configref.clone());
+    println!("Initializing logging...");
+    init_logging(configref.clone());
+
+    newslookout::store::init_at_startup(&newslookout::cfg::get_database_filename(&configref));
+    newslookout::store::init_at_startup(&newslookout::cfg::get_market_data_db(&configref));
+    newslookout::utils::init_ocr_settings(&configref);
+    init_content_extractor(&configref);
+
+    let status_tracker = start_status_server(&configref);
+    info!("Starting daemon mode");
+    newslookout::scheduler::run_daemon(configref.clone(), Some(status_tracker));
+
+    // run_daemon only returns when nothing is scheduled.
+    println!("Daemon stopped: no enabled retrievers or batch feeds to schedule.");
+    cleanup_pid_file(configref);
+}
+
 // document filtering
 fn run_filter(tx: Sender<Document>, rx: Receiver<Document>, _app_config: &Config, api_mutexes: &mut HashMap<String, Arc<Mutex<isize>>>){
     info!("Starting module 'filter'");
@@ -0,0 +1,291 @@
+// file: calendar.rs
+// Purpose:
+//   Exchange trading calendar (NSE/BSE). Weekends are never trading days; exchange holidays
+//   are loaded from a CSV file (`trading_holidays_file`, shipped as conf/trading_holidays.csv)
+//   and from the `trading_holidays` table in the market-data DB, which the
+//   `feed_trading_holidays` batch feed refreshes from the exchange. Feeds and any other code
+//   that needs the previous/next trading session should ask a `TradingCalendar` rather than
+//   assume every weekday is a session. A year with no holidays listed for an exchange is
+//   logged as an error when sessions are requested for it, rather than silently treating
+//   its holidays as sessions.
+//
+//   CSV format (header required, extra columns ignored):
+//     exchange,date,description
+//     NSE,2025-02-26,Mahashivratri
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use log::{error, info, warn};
+use rusqlite::Connection;
+
+/// Upper bound on how far `previous`/`next` searches walk, so a corrupt holiday list cannot
+/// loop forever.
+const MAX_SEARCH_DAYS: usize = 30;
+
+/// Trading holidays per exchange (upper-case exchange code → dates).
+#[derive(Debug, Clone, Default)]
+pub struct TradingCalendar {
+    holidays: HashMap<String, HashSet<NaiveDate>>,
+}
+
+impl TradingCalendar {
+    pub fn new() -> Self {
+        TradingCalendar::default()
+    }
+
+    pub fn add_holiday(&mut self, exchange: &str, date: NaiveDate) {
+        self.holidays.entry(exchange.trim().to_uppercase()).or_default().insert(date);
+    }
+
+    /// Number of holidays known for `exchange`.
+    pub fn holiday_count(&self, exchange: &str) -> usize {
+        self.holidays.get(&exchange.to_uppercase()).map(|h| h.len()).unwrap_or(0)
+    }
+
+    /// Years from `from` to `to` inclusive for which no holiday of `exchange` is known. Every
+    /// exchange has holidays each year, so such a year is missing from the holiday sources.
+    pub fn years_without_holidays(&self, exchange: &str, from: NaiveDate, to: NaiveDate) -> Vec<i32> {
+        let known = self.holidays.get(&exchange.to_uppercase());
+        (from.year()..=to.year())
+            .filter(|year| !known.is_some_and(|h| h.iter().any(|d| d.year() == *year)))
+            .collect()
+    }
+
+    /// Logs an error for each year of the range with no holidays listed, since its holidays
+    /// would otherwise be taken for trading sessions without any sign of it.
+    fn check_coverage(&self, exchange: &str, from: NaiveDate, to: NaiveDate) {
+        for year in self.years_without_holidays(exchange, from, to) {
+            error!("calendar: no {} holidays listed for {}; every weekday of {} is treated as a session. \
+                Add them to the trading_holidays_file or run feed_trading_holidays.", exchange, year, year);
+        }
+    }
+
+    pub fn is_holiday(&self, exchange: &str, date: NaiveDate) -> bool {
+        self.holidays.get(&exchange.to_uppercase()).is_some_and(|h| h.contains(&date))
+    }
+
+    /// True if `date` is a weekday and not a holiday on `exchange`.
+    pub fn is_trading_day(&self, exchange: &str, date: NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !self.is_holiday(exchange, date)
+    }
+
+    /// The last trading session strictly before `date`.
+    pub fn previous_trading_day(&self, exchange: &str, date: NaiveDate) -> Option<NaiveDate> {
+        let mut cursor = date;
+        for _ in 0..MAX_SEARCH_DAYS {
+            cursor = cursor.pred_opt()?;
+            if self.is_trading_day(exchange, cursor) {
+                return Some(cursor);
+            }
+        }
+        None
+    }
+
+    /// The first trading session strictly after `date`.
+    pub fn next_trading_day(&self, exchange: &str, date: NaiveDate) -> Option<NaiveDate> {
+        let mut cursor = date;
+        for _ in 0..MAX_SEARCH_DAYS {
+            cursor = cursor.succ_opt()?;
+            if self.is_trading_day(exchange, cursor) {
+                return Some(cursor);
+            }
+        }
+        None
+    }
+
+    /// `date` itself if it is a trading day, else the previous trading session.
+    pub fn latest_trading_day(&self, exchange: &str, date: NaiveDate) -> Option<NaiveDate> {
+        if self.is_trading_day(exchange, date) {
+            Some(date)
+        } else {
+            self.previous_trading_day(exchange, date)
+        }
+    }
+
+    /// Up to `count` trading sessions on or before `from`, most recent first.
+    pub fn recent_trading_days(&self, exchange: &str, from: NaiveDate, count: usize) -> Vec<NaiveDate> {
+        self.check_coverage(exchange, from, from);
+        let mut days = Vec::with_capacity(count);
+        let mut cursor = self.latest_trading_day(exchange, from);
+        while let Some(day) = cursor {
+            if days.len() >= count {
+                break;
+            }
+            days.push(day);
+            cursor = self.previous_trading_day(exchange, day);
+        }
+        days
+    }
+
+    /// Every trading session from `from` to `to` inclusive, in ascending order.
+    pub fn trading_days_between(&self, exchange: &str, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        self.check_coverage(exchange, from, to);
+        crate::utils::business_days_between(from, to)
+            .into_iter()
+            .filter(|d| !self.is_holiday(exchange, *d))
+            .collect()
+    }
+
+    /// Add holidays from CSV text (`exchange,date[,description]`). Returns the number of
+    /// rows read; rows with an unparseable date are logged and skipped.
+    pub fn load_csv(&mut self, csv_content: &str) -> usize {
+        let mut added = 0;
+        for (lineno, line) in csv_content.lines().enumerate().skip(1) {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split(',').map(|f| f.trim());
+            let (exchange, date_str) = match (fields.next(), fields.next()) {
+                (Some(e), Some(d)) if !e.is_empty() => (e, d),
+                _ => {
+                    warn!("calendar: line {} has too few columns, skipping: {}", lineno + 1, line);
+                    continue;
+                }
+            };
+            match NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
+                Ok(date) => {
+                    self.add_holiday(exchange, date);
+                    added += 1;
+                }
+                Err(e) => warn!("calendar: line {} has bad date '{}': {}", lineno + 1, date_str, e),
+            }
+        }
+        added
+    }
+
+    /// Add holidays from the `trading_holidays` table. A database without the table (not
+    /// yet migrated) contributes nothing.
+    pub fn load_db(&mut self, conn: &Connection) -> usize {
+        let mut stmt = match conn.prepare("SELECT exchange, holiday_date FROM trading_holidays") {
+            Ok(s) => s,
+            Err(e) => {
+                warn!("calendar: cannot read trading_holidays: {}", e);
+                return 0;
+            }
+        };
+        let rows = match stmt.query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?))) {
+            Ok(r) => r,
+            Err(e) => {
+                warn!("calendar: cannot query trading_holidays: {}", e);
+                return 0;
+            }
+        };
+        let mut added = 0;
+        for (exchange, date_str) in rows.flatten() {
+            if let Ok(date) = NaiveDate::parse_from_str(&date_str, "%Y-%m-%d") {
+                self.add_holiday(&exchange, date);
+                added += 1;
+            }
+        }
+        added
+    }
+}
+
+/// Build the trading calendar from the configured holiday CSV file plus the holidays stored
+/// in the market-data DB. Missing sources are logged; the calendar then falls back to
+/// treating every weekday as a session.
+pub fn load_calendar(app_config: &config::Config) -> TradingCalendar {
+    let mut calendar = TradingCalendar::new();
+
+    let csv_path = crate::cfg::get_trading_holidays_file(app_config);
+    match std::fs::read_to_string(&csv_path) {
+        Ok(content) => {
+            let n = calendar.load_csv(&content);
+            info!("calendar: loaded {} holiday(s) from {}", n, csv_path);
+        }
+        Err(e) => warn!("calendar: could not read holiday file '{}': {}", csv_path, e),
+    }
+
+    let db_path = crate::cfg::get_market_data_db(app_config);
+    if std::path::Path::new(&db_path).exists() {
+        match crate::store::open(&db_path) {
+            Ok(conn) => {
+                let n = calendar.load_db(&conn);
+                info!("calendar: loaded {} holiday(s) from {}", n, db_path);
+            }
+            Err(e) => warn!("calendar: {}", e),
+        }
+    }
+    calendar
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    fn calendar() -> TradingCalendar {
+        let mut cal = TradingCalendar::new();
+        let n = cal.load_csv("exchange,date,description\n\
+            NSE,2025-02-26,Mahashivratri\n\
+            NSE,2025-03-14,Holi\n\
+            nse,bad-date,ignored\n\
+            BSE,2025-03-14,Holi\n");
+        assert_eq!(n, 3);
+        cal
+    }
+
+    #[test]
+    fn test_holidays_and_weekends_are_not_sessions() {
+        let cal = calendar();
+        assert!(!cal.is_trading_day("NSE", d(2025, 2, 26))); // holiday (Wed)
+        assert!(!cal.is_trading_day("nse", d(2025, 3, 15))); // Saturday
+        assert!(cal.is_trading_day("NSE", d(2025, 2, 27)));
+        // Holidays are per exchange.
+        assert!(cal.is_trading_day("BSE", d(2025, 2, 26)));
+        assert_eq!(cal.holiday_count("NSE"), 2);
+    }
+
+    #[test]
+    fn test_previous_and_next_trading_day_skip_holidays() {
+        let cal = calendar();
+        // Mon 2025-03-17 → previous session is Thu 13th (Fri 14th Holi, then the weekend).
+        assert_eq!(cal.previous_trading_day("NSE", d(2025, 3, 17)), Some(d(2025, 3, 13)));
+        assert_eq!(cal.next_trading_day("NSE", d(2025, 3, 13)), Some(d(2025, 3, 17)));
+        assert_eq!(cal.latest_trading_day("NSE", d(2025, 3, 16)), Some(d(2025, 3, 13)));
+        assert_eq!(
+            cal.recent_trading_days("NSE", d(2025, 2, 27), 3),
+            vec![d(2025, 2, 27), d(2025, 2, 25), d(2025, 2, 24)]
+        );
+        assert_eq!(
+            cal.trading_days_between("NSE", d(2025, 3, 13), d(2025, 3, 17)),
+            vec![d(2025, 3, 13), d(2025, 3, 17)]
+        );
+    }
+
+    #[test]
+    fn test_years_without_holidays() {
+        let cal = calendar();
+        assert!(cal.years_without_holidays("NSE", d(2025, 1, 1), d(2025, 12, 31)).is_empty());
+        assert_eq!(cal.years_without_holidays("NSE", d(2024, 6, 1), d(2026, 1, 5)), vec![2024, 2026]);
+        assert_eq!(cal.years_without_holidays("MCX", d(2025, 3, 1), d(2025, 3, 1)), vec![2025]);
+    }
+
+    #[test]
+    fn test_shipped_holiday_file_covers_current_years() {
+        let mut cal = TradingCalendar::new();
+        cal.load_csv(include_str!("../conf/trading_holidays.csv"));
+        for exchange in ["NSE", "BSE"] {
+            assert!(cal.years_without_holidays(exchange, d(2024, 1, 1), d(2026, 12, 31)).is_empty());
+            assert!(cal.is_holiday(exchange, d(2026, 1, 26)));
+        }
+    }
+
+    #[test]
+    fn test_load_db_reads_trading_holidays_table() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::store::migrate(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO trading_holidays (exchange, holiday_date, description) VALUES ('NSE', '2025-10-21', 'Diwali')",
+            [],
+        ).unwrap();
+        let mut cal = TradingCalendar::new();
+        assert_eq!(cal.load_db(&conn), 1);
+        assert!(cal.is_holiday("NSE", d(2025, 10, 21)));
+    }
+}
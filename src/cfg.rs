@@ -156,6 +156,18 @@ pub fn get_market_data_db(config: &Config) -> String {
     }
 }
 
+/// Path of the exchange trading-holiday CSV used by `calendar::load_calendar`. Read from the
+/// `trading_holidays_file` config key; defaults to `trading_holidays.csv` in `conf_dir`.
+pub fn get_trading_holidays_file(config: &Config) -> String {
+    match config.get_string("trading_holidays_file") {
+        Ok(p) if !p.is_empty() => p,
+        _ => {
+            let conf_dir = config.get_string("conf_dir").unwrap_or_else(|_| "conf".to_string());
+            std::path::Path::new(&conf_dir).join("trading_holidays.csv").to_string_lossy().to_string()
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! get_cfg {
     ($config_key:expr, $config_obj:expr, $default_value:expr) => {
@@ -209,7 +209,7 @@ impl HtmlExtractor {
 // Stopwords used by BaselineExtractor's scoring heuristic
 // ---------------------------------------------------------------------------
 
-fn english_stopwords() -> HashSet<String> {
+pub(crate) fn english_stopwords() -> HashSet<String> {
     [
         "the", "a", "an", "and", "or", "but", "in", "on", "at", "to", "for",
         "of", "with", "by", "from", "is", "are", "was", "were", "be", "been",
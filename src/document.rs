@@ -145,6 +145,17 @@ impl Document {
             "analysis": self.analysis,
         })
     }
+
+    /// The English text of this document: the paired or split-off English version recorded by
+    /// mod_lang in `analysis.text_en` (for a bilingual English document, its Latin-script
+    /// lines), otherwise `text` itself.
+    /// The text-analysis plugins read this, so the offsets they record are into this text.
+    pub fn english_text(&self) -> &str {
+        match &self.analysis {
+            Some(a) if !a.text_en.is_empty() => a.text_en.as_str(),
+            _ => self.text.as_str(),
+        }
+    }
 }
 
 /// Flag to indicate whether sentiment classification is to be run on the contents of this document
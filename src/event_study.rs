@@ -0,0 +1,518 @@
+// file: event_study.rs
+// Purpose:
+//   Event study linking news to price moves. For every document whose organisation mentions
+//   resolve to a listed NSE equity, compute abnormal returns around the publish date with a
+//   market model fitted against an index, store them per (doc_id, instrument, window) in
+//   `event_study_results`, and aggregate them by theme and by tone bucket into
+//   `event_study_aggregates`, to show which kinds of news move prices.
+//
+//   Method (per document and instrument):
+//   - event day 0 is the first trading day on or after the publish date (IST);
+//   - daily simple returns of the adjusted close (`market_data::query::ohlcv`) and of the
+//     index close (`market_series`, e.g. NSE:INDEX:NIFTY 50 from `feed_nse_index_close`),
+//     on the dates both have;
+//   - market model r = alpha + beta * m fitted by OLS over `estimation_days` trading days
+//     ending `estimation_gap` days before the earliest event window;
+//   - abnormal return AR = r - (alpha + beta * m); CAR over each event window [a, b] in
+//     trading days relative to day 0, with t = CAR / (sigma * sqrt(b - a + 1)).
+//   A window whose last day has not traded yet is left out and filled in by a later run.
+//
+//   An organisation mention resolves to an instrument when its entity is a listed security
+//   (`isin:` entity with an NSE symbol), or when its name matches the current name alias of
+//   one in the securities master. Runs as the batch job `event_study` (type "batch_feed"),
+//   re-computing documents published in the last `lookback_days`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use chrono::{Duration, NaiveDate, Utc};
+use log::{error, info, warn};
+use rusqlite::Connection;
+
+use crate::cfg::{get_database_filename, get_market_data_db};
+use crate::feeds::FeedOutcome;
+use crate::get_plugin_cfg;
+use crate::market_data::query;
+
+pub const JOB_NAME: &str = "event_study";
+const DEFAULT_INDEX: &str = "NSE:INDEX:NIFTY 50";
+const DEFAULT_ESTIMATION_DAYS: usize = 120;
+const DEFAULT_ESTIMATION_GAP: usize = 10;
+const DEFAULT_MIN_ESTIMATION_OBS: usize = 60;
+const DEFAULT_LOOKBACK_DAYS: i64 = 30;
+const DEFAULT_WINDOWS: [(i32, i32); 3] = [(-1, 1), (0, 0), (0, 5)];
+/// Document tone (-10..+10) within ±TONE_BAND is "neutral".
+const TONE_BAND: f64 = 1.0;
+/// IST offset from UTC, used to date documents on the exchange's calendar.
+const IST_OFFSET_SECS: i64 = 19_800;
+
+/// Configuration of an event study run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventStudyParams {
+    /// `market_series` instrument used as the market return.
+    pub index_instrument: String,
+    pub estimation_days: usize,
+    /// Trading days between the end of the estimation window and the earliest event window.
+    pub estimation_gap: usize,
+    /// Fewest estimation-window returns accepted for a market-model fit.
+    pub min_estimation_obs: usize,
+    /// Event windows as (first, last) trading day relative to day 0, inclusive.
+    pub windows: Vec<(i32, i32)>,
+}
+
+impl Default for EventStudyParams {
+    fn default() -> Self {
+        EventStudyParams {
+            index_instrument: DEFAULT_INDEX.to_string(),
+            estimation_days: DEFAULT_ESTIMATION_DAYS,
+            estimation_gap: DEFAULT_ESTIMATION_GAP,
+            min_estimation_obs: DEFAULT_MIN_ESTIMATION_OBS,
+            windows: DEFAULT_WINDOWS.to_vec(),
+        }
+    }
+}
+
+/// Parse an event window such as "-1,1" or "0,5".
+fn parse_window(s: &str) -> Option<(i32, i32)> {
+    let (a, b) = s.split_once(',')?;
+    let (a, b) = (a.trim().parse::<i32>().ok()?, b.trim().parse::<i32>().ok()?);
+    if a <= b { Some((a, b)) } else { None }
+}
+
+impl EventStudyParams {
+    /// Read the job's settings from its plugin entry, with defaults for anything not set.
+    /// `windows` is a list such as ["-1,1", "0,0", "0,5"].
+    pub fn from_config(app_config: &config::Config) -> Self {
+        let mut params = EventStudyParams::default();
+        let number = |key: &str| get_plugin_cfg!(JOB_NAME, key, app_config).and_then(|v| v.parse::<usize>().ok());
+        if let Some(index) = get_plugin_cfg!(JOB_NAME, "index_instrument", app_config).filter(|v| !v.is_empty()) {
+            params.index_instrument = index;
+        }
+        params.estimation_days = number("estimation_days").unwrap_or(params.estimation_days);
+        params.estimation_gap = number("estimation_gap").unwrap_or(params.estimation_gap);
+        params.min_estimation_obs = number("min_estimation_obs").unwrap_or(params.min_estimation_obs);
+
+        let configured: Vec<(i32, i32)> = app_config
+            .get_array("plugins")
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|p| p.into_table().ok())
+            .find(|t| t.get("name").map(|n| n.to_string() == JOB_NAME).unwrap_or(false))
+            .and_then(|t| t.get("windows").cloned())
+            .and_then(|w| w.into_array().ok())
+            .map(|items| {
+                items.into_iter()
+                    .filter_map(|v| {
+                        let s = v.to_string();
+                        let w = parse_window(&s);
+                        if w.is_none() {
+                            warn!("{}: ignoring invalid event window '{}'", JOB_NAME, s);
+                        }
+                        w
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        if !configured.is_empty() {
+            params.windows = configured;
+        }
+        params
+    }
+}
+
+/// Market model fitted over the estimation window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarketModel {
+    pub alpha: f64,
+    pub beta: f64,
+    /// Standard deviation of the residuals.
+    pub sigma: f64,
+    pub obs: usize,
+}
+
+/// OLS fit of `stock = alpha + beta * market`. None with fewer than three points or no
+/// variation in the market return.
+pub fn fit_market_model(stock: &[f64], market: &[f64]) -> Option<MarketModel> {
+    let n = stock.len().min(market.len());
+    if n < 3 {
+        return None;
+    }
+    let mean = |xs: &[f64]| xs[..n].iter().sum::<f64>() / n as f64;
+    let (ms, mm) = (mean(stock), mean(market));
+    let (mut cov, mut var) = (0.0, 0.0);
+    for i in 0..n {
+        cov += (market[i] - mm) * (stock[i] - ms);
+        var += (market[i] - mm).powi(2);
+    }
+    if var <= f64::EPSILON {
+        return None;
+    }
+    let beta = cov / var;
+    let alpha = ms - beta * mm;
+    let ssr: f64 = (0..n).map(|i| (stock[i] - alpha - beta * market[i]).powi(2)).sum();
+    Some(MarketModel { alpha, beta, sigma: (ssr / (n - 2) as f64).sqrt(), obs: n })
+}
+
+/// Abnormal-return result of one event window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowResult {
+    pub window: (i32, i32),
+    /// Trading day 0.
+    pub event_date: NaiveDate,
+    pub model: MarketModel,
+    /// Cumulative abnormal return over the window, as a fraction.
+    pub car: f64,
+    pub t_stat: f64,
+}
+
+/// Daily (date, stock return, market return) on consecutive dates where both series have a
+/// close.
+fn aligned_returns(closes: &[(NaiveDate, f64)], index: &BTreeMap<NaiveDate, f64>) -> Vec<(NaiveDate, f64, f64)> {
+    let both: Vec<(NaiveDate, f64, f64)> = closes
+        .iter()
+        .filter_map(|(d, c)| index.get(d).map(|m| (*d, *c, *m)))
+        .filter(|(_, c, m)| *c > 0.0 && *m > 0.0)
+        .collect();
+    both.windows(2)
+        .map(|w| (w[1].0, w[1].1 / w[0].1 - 1.0, w[1].2 / w[0].2 - 1.0))
+        .collect()
+}
+
+/// Run the study for one event: `closes` are the instrument's adjusted closes in date order
+/// and `index` the index closes by date. Returns one result per window that can be computed.
+pub fn study_event(
+    closes: &[(NaiveDate, f64)],
+    index: &BTreeMap<NaiveDate, f64>,
+    published: NaiveDate,
+    params: &EventStudyParams,
+) -> Vec<WindowResult> {
+    let returns = aligned_returns(closes, index);
+    let day0 = match returns.iter().position(|(d, _, _)| *d >= published) {
+        Some(i) => i as i64,
+        None => return Vec::new(),
+    };
+    let first_offset = params.windows.iter().map(|(a, _)| *a).min().unwrap_or(0) as i64;
+    let est_end = day0 + first_offset - params.estimation_gap as i64;
+    let est_start = (est_end - params.estimation_days as i64).max(0);
+    if est_end <= est_start || ((est_end - est_start) as usize) < params.min_estimation_obs {
+        return Vec::new();
+    }
+    let est = &returns[est_start as usize..est_end as usize];
+    let stock: Vec<f64> = est.iter().map(|r| r.1).collect();
+    let market: Vec<f64> = est.iter().map(|r| r.2).collect();
+    let model = match fit_market_model(&stock, &market) {
+        Some(m) => m,
+        None => return Vec::new(),
+    };
+
+    params.windows.iter()
+        .filter_map(|&(a, b)| {
+            let (from, to) = (day0 + a as i64, day0 + b as i64);
+            if from < est_end || to >= returns.len() as i64 {
+                return None;
+            }
+            let car: f64 = returns[from as usize..=to as usize]
+                .iter()
+                .map(|(_, r, m)| r - (model.alpha + model.beta * m))
+                .sum();
+            let t_stat = if model.sigma > 0.0 { car / (model.sigma * ((b - a + 1) as f64).sqrt()) } else { 0.0 };
+            Some(WindowResult { window: (a, b), event_date: returns[day0 as usize].0, model, car, t_stat })
+        })
+        .collect()
+}
+
+/// A document mentioning a listed instrument.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocInstrument {
+    pub doc_id: String,
+    pub published: NaiveDate,
+    pub entity_id: String,
+    pub symbol: String,
+}
+
+/// Documents published on or after `since` whose organisation mentions resolve to listed NSE
+/// equities (see the file header), one row per (document, instrument).
+pub fn documents_with_instruments(conn: &Connection, since: NaiveDate) -> Result<Vec<DocInstrument>, String> {
+    let since_ms = (since.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp() - IST_OFFSET_SECS) * 1000;
+    let mut stmt = conn
+        .prepare(
+            "SELECT DISTINCT d.doc_id, d.pubdate_ms, e.entity_id, e.nse_symbol
+             FROM documents d
+             JOIN entity_mentions em ON em.doc_id = d.doc_id
+             JOIN entities e ON e.entity_id = em.entity_id
+             WHERE d.pubdate_ms >= ?1 AND e.nse_symbol IS NOT NULL AND e.nse_symbol <> ''
+             UNION
+             SELECT DISTINCT d.doc_id, d.pubdate_ms, e.entity_id, e.nse_symbol
+             FROM documents d
+             JOIN entity_mentions em ON em.doc_id = d.doc_id
+             JOIN entities p ON p.entity_id = em.entity_id AND p.type = 'ORG'
+             JOIN entity_aliases a ON a.alias_norm = p.name_norm AND a.alias_type = 'name' AND a.valid_to IS NULL
+             JOIN entities e ON e.entity_id = a.entity_id
+             WHERE d.pubdate_ms >= ?1 AND e.nse_symbol IS NOT NULL AND e.nse_symbol <> ''
+             ORDER BY 4, 2",
+        )
+        .map_err(|e| format!("prepare document query: {}", e))?;
+    let rows = stmt
+        .query_map([since_ms], |r| Ok((r.get::<_, String>(0)?, r.get::<_, i64>(1)?, r.get::<_, String>(2)?, r.get::<_, String>(3)?)))
+        .map_err(|e| format!("query documents: {}", e))?;
+    Ok(rows
+        .flatten()
+        .filter_map(|(doc_id, pubdate_ms, entity_id, symbol)| {
+            let published = chrono::DateTime::from_timestamp(pubdate_ms / 1000 + IST_OFFSET_SECS, 0)?.date_naive();
+            Some(DocInstrument { doc_id, published, entity_id, symbol })
+        })
+        .collect())
+}
+
+/// Replace the stored results of `doc` with `results`.
+fn save_results(conn: &Connection, doc: &DocInstrument, results: &[WindowResult], now_ts: i64) -> Result<usize, String> {
+    conn.execute(
+        "DELETE FROM event_study_results WHERE doc_id=?1 AND entity_id=?2",
+        rusqlite::params![doc.doc_id, doc.entity_id],
+    )
+    .map_err(|e| format!("clear event_study_results({}): {}", doc.doc_id, e))?;
+    for r in results {
+        conn.execute(
+            "INSERT INTO event_study_results
+                (doc_id, entity_id, symbol, event_date, window_start, window_end, alpha, beta,
+                 sigma, est_obs, car, t_stat, computed_ts)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            rusqlite::params![
+                doc.doc_id, doc.entity_id, doc.symbol, r.event_date.format("%Y-%m-%d").to_string(),
+                r.window.0, r.window.1, r.model.alpha, r.model.beta, r.model.sigma, r.model.obs as i64,
+                r.car, r.t_stat, now_ts
+            ],
+        )
+        .map_err(|e| format!("insert event_study_results({}): {}", doc.doc_id, e))?;
+    }
+    Ok(results.len())
+}
+
+/// Rebuild `event_study_aggregates` from all stored results: per event window, the count,
+/// mean CAR, mean |CAR|, share of positive CARs and mean t-statistic of each theme and of each
+/// tone bucket ("negative" / "neutral" / "positive"). Returns the number of groups written.
+pub fn refresh_aggregates(conn: &Connection, now_ts: i64) -> Result<usize, String> {
+    let tone_bucket = format!(
+        "CASE WHEN d.tone < -{band} THEN 'negative' WHEN d.tone > {band} THEN 'positive' ELSE 'neutral' END",
+        band = TONE_BAND
+    );
+    let group_sql = |group_type: &str, key: &str, join: &str| {
+        format!(
+            "INSERT INTO event_study_aggregates
+                (group_type, group_key, window_start, window_end, n, mean_car, mean_abs_car,
+                 share_positive, mean_t_stat, computed_ts)
+             SELECT '{group_type}', {key}, r.window_start, r.window_end, COUNT(*), AVG(r.car),
+                    AVG(ABS(r.car)), AVG(CASE WHEN r.car > 0 THEN 1.0 ELSE 0.0 END), AVG(r.t_stat), ?1
+             FROM event_study_results r {join}
+             GROUP BY {key}, r.window_start, r.window_end"
+        )
+    };
+    // A theme repeated within a document counts once.
+    let by_theme = group_sql(
+        "theme", "t.theme",
+        "JOIN (SELECT DISTINCT doc_id, theme FROM themes) t ON t.doc_id = r.doc_id",
+    );
+    let by_tone = group_sql("tone", &tone_bucket, "JOIN documents d ON d.doc_id = r.doc_id AND d.tone IS NOT NULL");
+
+    conn.execute("DELETE FROM event_study_aggregates", [])
+        .map_err(|e| format!("clear event_study_aggregates: {}", e))?;
+    let mut groups = 0;
+    for sql in [by_theme, by_tone] {
+        groups += conn.execute(&sql, [now_ts]).map_err(|e| format!("aggregate event study: {}", e))?;
+    }
+    Ok(groups)
+}
+
+/// Adjusted closes of `symbol` between `from` and `to`.
+fn closes(conn: &Connection, symbol: &str, from: NaiveDate, to: NaiveDate) -> Result<Vec<(NaiveDate, f64)>, String> {
+    Ok(query::ohlcv(conn, symbol, from, to, true)?.into_iter().map(|b| (b.date, b.close)).collect())
+}
+
+/// Entry point invoked by the batch-feed runner.
+pub fn run(app_config: Arc<config::Config>) -> FeedOutcome {
+    let params = EventStudyParams::from_config(&app_config);
+    let lookback_days = get_plugin_cfg!(JOB_NAME, "lookback_days", &app_config)
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_LOOKBACK_DAYS);
+    let today = Utc::now().date_naive();
+    let since = today - Duration::days(lookback_days);
+
+    let mut meta = match crate::store::open_and_migrate(&get_database_filename(&app_config)) {
+        Ok(c) => c,
+        Err(e) => return FeedOutcome::fail(e),
+    };
+    let market = match crate::store::open_and_migrate(&get_market_data_db(&app_config)) {
+        Ok(c) => c,
+        Err(e) => return FeedOutcome::fail(e),
+    };
+
+    let docs = match documents_with_instruments(&meta, since) {
+        Ok(d) => d,
+        Err(e) => return FeedOutcome::fail(e),
+    };
+    info!("{}: {} (document, instrument) pair(s) published since {}.", JOB_NAME, docs.len(), since);
+    if docs.is_empty() {
+        return FeedOutcome::ok(0, "no documents mention listed instruments");
+    }
+
+    // Enough calendar days before the earliest document for the estimation window.
+    let span_days = ((params.estimation_days + params.estimation_gap) as i64) * 2 + 30;
+    let from = docs.iter().map(|d| d.published).min().unwrap_or(since) - Duration::days(span_days);
+    let index: BTreeMap<NaiveDate, f64> = match query::series(&market, &params.index_instrument, from, today) {
+        Ok(points) => points.into_iter().map(|p| (p.date, p.value)).collect(),
+        Err(e) => return FeedOutcome::fail(e),
+    };
+    if index.is_empty() {
+        return FeedOutcome::fail(format!(
+            "no '{}' closes in market_series from {}; load them with feed_nse_index_close (backfill for history)",
+            params.index_instrument, from
+        ));
+    }
+
+    let mut by_symbol: HashMap<&str, Vec<&DocInstrument>> = HashMap::new();
+    for d in &docs {
+        by_symbol.entry(d.symbol.as_str()).or_default().push(d);
+    }
+    let now_ts = Utc::now().timestamp();
+    let tx = match meta.transaction() {
+        Ok(t) => t,
+        Err(e) => return FeedOutcome::fail(format!("begin transaction: {}", e)),
+    };
+    let (mut rows, mut studied) = (0usize, 0usize);
+    for (symbol, symbol_docs) in by_symbol {
+        let prices = match closes(&market, symbol, from, today) {
+            Ok(p) => p,
+            Err(e) => {
+                warn!("{}: prices of {}: {}", JOB_NAME, symbol, e);
+                continue;
+            }
+        };
+        for doc in symbol_docs {
+            let results = study_event(&prices, &index, doc.published, &params);
+            match save_results(&tx, doc, &results, now_ts) {
+                Ok(n) => {
+                    rows += n;
+                    studied += usize::from(n > 0);
+                }
+                Err(e) => {
+                    error!("{}: {}", JOB_NAME, e);
+                    return FeedOutcome::fail(e);
+                }
+            }
+        }
+    }
+    let groups = match refresh_aggregates(&tx, now_ts).and_then(|g| tx.commit().map(|_| g).map_err(|e| format!("commit: {}", e))) {
+        Ok(g) => g,
+        Err(e) => {
+            error!("{}: {}", JOB_NAME, e);
+            crate::metrics::record_db_error();
+            return FeedOutcome::fail(e);
+        }
+    };
+    crate::metrics::record_db_writes((rows + groups) as u64);
+    FeedOutcome::ok(
+        rows as i64,
+        format!("{} window result(s) for {} of {} document-instrument pair(s); {} aggregate group(s)", rows, studied, docs.len(), groups),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(i: i64) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 1, 1).unwrap() + Duration::days(i)
+    }
+
+    /// A market that alternates ±1% and a stock with beta 1.5 and alpha 0.1%/day, plus a 5%
+    /// jump on day 150.
+    fn synthetic(jump_day: i64) -> (Vec<(NaiveDate, f64)>, BTreeMap<NaiveDate, f64>) {
+        let (mut stock, mut market) = (100.0, 1000.0);
+        let mut closes = vec![(day(0), stock)];
+        let mut index = BTreeMap::from([(day(0), market)]);
+        for i in 1..200 {
+            let m = if i % 2 == 0 { 0.01 } else { -0.012 };
+            let jump = if i == jump_day { 0.05 } else { 0.0 };
+            market *= 1.0 + m;
+            stock *= 1.0 + 0.001 + 1.5 * m + jump;
+            closes.push((day(i), stock));
+            index.insert(day(i), market);
+        }
+        (closes, index)
+    }
+
+    #[test]
+    fn test_fit_market_model() {
+        let market = [0.01, -0.02, 0.015, 0.0, -0.005];
+        let stock: Vec<f64> = market.iter().map(|m| 0.002 + 1.2 * m).collect();
+        let model = fit_market_model(&stock, &market).unwrap();
+        assert!((model.beta - 1.2).abs() < 1e-9 && (model.alpha - 0.002).abs() < 1e-9);
+        assert!(model.sigma < 1e-9);
+        assert!(fit_market_model(&[0.01, 0.02, 0.03], &[0.0, 0.0, 0.0]).is_none());
+    }
+
+    #[test]
+    fn test_study_event_finds_abnormal_return() {
+        let (closes, index) = synthetic(150);
+        let params = EventStudyParams::default();
+        let results = study_event(&closes, &index, day(150), &params);
+        assert_eq!(results.len(), 3);
+        let day0 = results.iter().find(|r| r.window == (0, 0)).unwrap();
+        assert_eq!(day0.event_date, day(150));
+        assert!((day0.model.beta - 1.5).abs() < 1e-6);
+        assert!((day0.car - 0.05).abs() < 1e-6, "CAR {}", day0.car);
+        let around = results.iter().find(|r| r.window == (-1, 1)).unwrap();
+        assert!((around.car - 0.05).abs() < 1e-6);
+
+        // A publish date on a non-trading day maps to the next trading day.
+        let (closes_gap, index_gap): (Vec<_>, BTreeMap<_, _>) =
+            (closes.iter().filter(|(d, _)| *d != day(150)).cloned().collect(), index.clone());
+        assert_eq!(study_event(&closes_gap, &index_gap, day(150), &params)[0].event_date, day(151));
+        // Too little history for the estimation window, and windows not yet traded.
+        assert!(study_event(&closes, &index, day(30), &params).is_empty());
+        assert_eq!(study_event(&closes, &index, day(197), &params).len(), 2, "(0,5) not yet complete");
+    }
+
+    #[test]
+    fn test_documents_resolved_and_aggregated() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::store::migrate(&conn).unwrap();
+        let listing = crate::store::securities::ListedSecurity {
+            isin: "INE002A01018".to_string(),
+            name: "Reliance Industries Limited".to_string(),
+            exchange: "NSE".to_string(),
+            symbol: "RELIANCE".to_string(),
+            sector: None,
+            listing_date: None,
+            canonical_name_source: true,
+        };
+        crate::store::securities::upsert_listing(&conn, &listing, day(0)).unwrap();
+        // A provisional ORG mention whose name matches the listed company's name alias.
+        let pub_ms = day(150).and_hms_opt(4, 0, 0).unwrap().and_utc().timestamp() * 1000;
+        conn.execute_batch(&format!(
+            "INSERT INTO documents (doc_id, pubdate_ms, tone) VALUES ('d1', {pub_ms}, -3.5);
+             INSERT INTO entities (entity_id, type, canonical_name, name_norm, status)
+                 VALUES ('prov:ril', 'ORG', 'Reliance Industries Limited', '{}', 'provisional');
+             INSERT INTO entity_mentions (doc_id, entity_id, surface_form) VALUES ('d1', 'prov:ril', 'Reliance Industries Limited');
+             INSERT INTO themes (doc_id, theme) VALUES ('d1', 'ECON_EARNINGS'), ('d1', 'ECON_EARNINGS');",
+            crate::analysis::norm_name("Reliance Industries Limited")
+        )).unwrap();
+
+        let docs = documents_with_instruments(&conn, day(140)).unwrap();
+        assert_eq!(docs.len(), 1);
+        assert_eq!((docs[0].symbol.as_str(), docs[0].entity_id.as_str(), docs[0].published), ("RELIANCE", "isin:INE002A01018", day(150)));
+        assert!(documents_with_instruments(&conn, day(151)).unwrap().is_empty());
+
+        let (closes, index) = synthetic(150);
+        let results = study_event(&closes, &index, docs[0].published, &EventStudyParams::default());
+        assert_eq!(save_results(&conn, &docs[0], &results, 1).unwrap(), 3);
+        assert_eq!(save_results(&conn, &docs[0], &results, 2).unwrap(), 3, "re-running replaces");
+        assert_eq!(refresh_aggregates(&conn, 2).unwrap(), 6, "3 windows x (1 theme + 1 tone bucket)");
+        let (n, mean_car): (i64, f64) = conn.query_row(
+            "SELECT n, mean_car FROM event_study_aggregates
+             WHERE group_type='tone' AND group_key='negative' AND window_start=0 AND window_end=0",
+            [], |r| Ok((r.get(0)?, r.get(1)?))).unwrap();
+        assert_eq!(n, 1);
+        assert!((mean_car - 0.05).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,382 @@
+// file: feeds/dq.rs
+// Purpose:
+//   Data-quality validation for downloaded market-data files, run by the bhavcopy feeds
+//   before a file is loaded. The CSV loaders in `market_data` skip malformed rows and
+//   otherwise trust their input, so an HTML error page or a truncated file could be loaded
+//   as a trading day; these checks catch that first.
+//
+//   Checks (name → severity):
+//     format       not a CSV at all (HTML page, empty body)                error
+//     schema       required header columns missing                         error
+//     column_count rows whose field count differs from the header          warning; error above MAX_BAD_ROW_PCT
+//     row_count    no rows, or far fewer rows than the previous day        error
+//     duplicate    the same instrument key more than once                  warning
+//     price_range  negative price/volume                                   error
+//                  zero close, high below low                              warning
+//     day_move     close moved more than MAX_DAY_MOVE_PCT from prev close  warning
+//
+//   A report with any error fails the feed run and the file is not loaded. Findings are
+//   stored in `dq_findings` (market-data DB) and summarised in `batch_run_log.message`.
+
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use log::{error, warn};
+
+use crate::feeds::split_csv_line;
+use crate::store::dq_findings::{replace_findings, DqFindingRow};
+
+/// Close-to-close moves larger than this (in percent) are flagged.
+pub const MAX_DAY_MOVE_PCT: f64 = 20.0;
+/// A file with fewer rows than this fraction of the previous day's is rejected.
+pub const MIN_ROW_RATIO: f64 = 0.5;
+/// Malformed rows above this percentage of the file fail the run.
+pub const MAX_BAD_ROW_PCT: f64 = 1.0;
+/// Row-level findings stored per check; the rest are summarised in one extra finding.
+const MAX_FINDINGS_PER_CHECK: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl Severity {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// One data-quality finding.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub check: &'static str,
+    pub severity: Severity,
+    pub instrument: Option<String>,
+    pub message: String,
+}
+
+/// Result of validating one file.
+#[derive(Debug, Clone, Default)]
+pub struct DqReport {
+    /// Data rows in the file (excluding the header and blank lines).
+    pub rows: usize,
+    pub findings: Vec<Finding>,
+}
+
+impl DqReport {
+    fn add(&mut self, check: &'static str, severity: Severity, instrument: Option<String>, message: String) {
+        self.findings.push(Finding { check, severity, instrument, message });
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.findings.iter().filter(|f| f.severity == severity).count()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.count(Severity::Error) > 0
+    }
+
+    /// One-line summary for `batch_run_log.message`, e.g.
+    /// "DQ: 2105 rows, 0 error(s), 3 warning(s) [day_move x3]".
+    pub fn summary(&self) -> String {
+        let mut per_check: Vec<(&str, usize)> = Vec::new();
+        for f in &self.findings {
+            match per_check.iter_mut().find(|(c, _)| *c == f.check) {
+                Some((_, n)) => *n += 1,
+                None => per_check.push((f.check, 1)),
+            }
+        }
+        let mut summary = format!(
+            "DQ: {} rows, {} error(s), {} warning(s)",
+            self.rows, self.count(Severity::Error), self.count(Severity::Warning)
+        );
+        if !per_check.is_empty() {
+            let checks: Vec<String> = per_check.iter().map(|(c, n)| format!("{} x{}", c, n)).collect();
+            summary.push_str(&format!(" [{}]", checks.join(", ")));
+        }
+        if let Some(first) = self.findings.iter().find(|f| f.severity == Severity::Error) {
+            summary.push_str(&format!("; {}", first.message));
+        }
+        summary
+    }
+}
+
+/// Column layout of one bhavcopy format, by header name.
+pub struct BhavcopySpec {
+    pub required: &'static [&'static str],
+    /// Columns that together identify an instrument within one day's file.
+    pub key: &'static [&'static str],
+    /// Column used to name the instrument in findings.
+    pub label: &'static str,
+    pub open: &'static str,
+    pub high: &'static str,
+    pub low: &'static str,
+    pub close: &'static str,
+    pub prev_close: &'static str,
+    pub volume: &'static str,
+}
+
+/// First trading date both NSE and BSE published the UDiFF common bhavcopy (the SEBI-mandated
+/// switch took effect on 8 July 2024); earlier dates only have the legacy per-exchange files.
+pub const UDIFF_START_DATE: NaiveDate = NaiveDate::from_ymd_opt(2024, 7, 8).expect("valid date");
+
+/// UDiFF common bhavcopy, used by both NSE and BSE since mid-2024.
+pub const UDIFF_SPEC: BhavcopySpec = BhavcopySpec {
+    required: &["TradDt", "FinInstrmId", "TckrSymb", "OpnPric", "HghPric", "LwPric", "ClsPric", "PrvsClsgPric", "TtlTradgVol"],
+    key: &["FinInstrmId"],
+    label: "TckrSymb",
+    open: "OpnPric",
+    high: "HghPric",
+    low: "LwPric",
+    close: "ClsPric",
+    prev_close: "PrvsClsgPric",
+    volume: "TtlTradgVol",
+};
+
+/// Legacy NSE bhavcopy (before `UDIFF_START_DATE`).
+pub const NSE_LEGACY_SPEC: BhavcopySpec = BhavcopySpec {
+    required: &["SYMBOL", "SERIES", "OPEN", "HIGH", "LOW", "CLOSE", "PREVCLOSE", "TOTTRDQTY", "TIMESTAMP"],
+    key: &["SYMBOL", "SERIES"],
+    label: "SYMBOL",
+    open: "OPEN",
+    high: "HIGH",
+    low: "LOW",
+    close: "CLOSE",
+    prev_close: "PREVCLOSE",
+    volume: "TOTTRDQTY",
+};
+
+/// Legacy BSE `EQDDMMYY` bhavcopy (before July 2024).
+pub const BSE_LEGACY_SPEC: BhavcopySpec = BhavcopySpec {
+    required: &["SC_CODE", "SC_NAME", "OPEN", "HIGH", "LOW", "CLOSE", "PREVCLOSE", "NO_OF_SHRS"],
+    key: &["SC_CODE"],
+    label: "SC_NAME",
+    open: "OPEN",
+    high: "HIGH",
+    low: "LOW",
+    close: "CLOSE",
+    prev_close: "PREVCLOSE",
+    volume: "NO_OF_SHRS",
+};
+
+/// Split a CSV line, dropping the empty field left by a trailing comma (legacy NSE files end
+/// every line, header included, with one).
+fn csv_fields(line: &str) -> Vec<String> {
+    let mut fields = split_csv_line(line);
+    if fields.len() > 1 && fields.last().is_some_and(|f| f.is_empty()) {
+        fields.pop();
+    }
+    fields
+}
+
+/// Add row-level findings of one check, keeping at most `MAX_FINDINGS_PER_CHECK` of them.
+fn add_capped(report: &mut DqReport, check: &'static str, severity: Severity, items: Vec<(String, String)>) {
+    let total = items.len();
+    for (instrument, message) in items.into_iter().take(MAX_FINDINGS_PER_CHECK) {
+        report.add(check, severity, Some(instrument), message);
+    }
+    if total > MAX_FINDINGS_PER_CHECK {
+        report.add(check, severity, None, format!("{} more row(s) not listed", total - MAX_FINDINGS_PER_CHECK));
+    }
+}
+
+/// Validate one bhavcopy file against `spec`. `previous_rows` is the row count loaded for
+/// the previous trading day, if any.
+pub fn validate_bhavcopy(csv: &str, spec: &BhavcopySpec, previous_rows: Option<usize>) -> DqReport {
+    let mut report = DqReport::default();
+    let trimmed = csv.trim_start();
+    if trimmed.is_empty() || trimmed.starts_with('<') {
+        report.add("format", Severity::Error, None, "body is empty or an HTML page, not a CSV".to_string());
+        return report;
+    }
+
+    let mut lines = csv.lines().filter(|l| !l.trim().is_empty());
+    let header = csv_fields(lines.next().unwrap_or(""));
+    let missing: Vec<&str> = spec.required.iter()
+        .copied()
+        .filter(|c| !header.iter().any(|h| h == c))
+        .collect();
+    if !missing.is_empty() {
+        report.add("schema", Severity::Error, None, format!("missing column(s): {}", missing.join(", ")));
+        return report;
+    }
+    let col: HashMap<&str, usize> = header.iter().enumerate().map(|(i, h)| (h.as_str(), i)).collect();
+
+    let mut bad_rows = Vec::new();
+    let mut duplicates = Vec::new();
+    let mut range_errors = Vec::new();
+    let mut range_warnings = Vec::new();
+    let mut moves = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+
+    for line in lines {
+        report.rows += 1;
+        let fields = csv_fields(line);
+        let get = |name: &str| col.get(name).and_then(|&i| fields.get(i)).map(|s| s.as_str()).unwrap_or("");
+        let num = |name: &str| get(name).parse::<f64>().ok();
+        let label = get(spec.label).to_string();
+
+        if fields.len() != header.len() {
+            bad_rows.push((label.clone(), format!("expected {} fields, got {}", header.len(), fields.len())));
+            continue;
+        }
+
+        let key: Vec<&str> = spec.key.iter().map(|k| get(k)).collect();
+        let key = key.join("|");
+        *seen.entry(key.clone()).or_insert(0) += 1;
+        if seen[&key] == 2 {
+            duplicates.push((label.clone(), format!("key '{}' appears more than once", key)));
+        }
+
+        for name in [spec.open, spec.high, spec.low, spec.close, spec.prev_close, spec.volume] {
+            if let Some(v) = num(name) && v < 0.0 {
+                range_errors.push((label.clone(), format!("negative {} ({})", name, v)));
+            }
+        }
+        if num(spec.close) == Some(0.0) {
+            range_warnings.push((label.clone(), format!("zero {}", spec.close)));
+        }
+        if let (Some(high), Some(low)) = (num(spec.high), num(spec.low)) && high < low {
+            range_warnings.push((label.clone(), format!("{} {} below {} {}", spec.high, high, spec.low, low)));
+        }
+        if let (Some(close), Some(prev)) = (num(spec.close), num(spec.prev_close)) && close > 0.0 && prev > 0.0 {
+            let pct = (close / prev - 1.0) * 100.0;
+            if pct.abs() > MAX_DAY_MOVE_PCT {
+                moves.push((label.clone(), format!("close {} vs previous close {} ({:+.1}%)", close, prev, pct)));
+            }
+        }
+    }
+
+    if report.rows == 0 {
+        report.add("row_count", Severity::Error, None, "file has a header but no rows".to_string());
+    } else if let Some(prev) = previous_rows && (report.rows as f64) < prev as f64 * MIN_ROW_RATIO {
+        report.add("row_count", Severity::Error, None,
+            format!("only {} rows vs {} on the previous trading day", report.rows, prev));
+    }
+
+    let bad_pct = if report.rows > 0 { bad_rows.len() as f64 * 100.0 / report.rows as f64 } else { 0.0 };
+    if bad_pct > MAX_BAD_ROW_PCT {
+        report.add("column_count", Severity::Error, None,
+            format!("{} of {} rows ({:.1}%) have the wrong field count", bad_rows.len(), report.rows, bad_pct));
+    }
+    add_capped(&mut report, "column_count", Severity::Warning, bad_rows);
+    add_capped(&mut report, "duplicate", Severity::Warning, duplicates);
+    add_capped(&mut report, "price_range", Severity::Error, range_errors);
+    add_capped(&mut report, "price_range", Severity::Warning, range_warnings);
+    add_capped(&mut report, "day_move", Severity::Warning, moves);
+    report
+}
+
+/// Rows loaded into `table` for the latest trade date before `date`, or None if the table
+/// does not exist yet or holds no earlier date.
+pub fn previous_day_rows(db_path: &str, table: &str, date: NaiveDate) -> Option<usize> {
+    let conn = crate::store::open(db_path).ok()?;
+    let sql = format!(
+        "SELECT COUNT(*) FROM \"{t}\" WHERE trade_date =
+            (SELECT MAX(trade_date) FROM \"{t}\" WHERE trade_date < ?1)",
+        t = table
+    );
+    let count: i64 = conn
+        .query_row(&sql, [date.format("%Y-%m-%d").to_string()], |r| r.get(0))
+        .ok()?;
+    if count > 0 { Some(count as usize) } else { None }
+}
+
+/// Store the report's findings for `(source, date)` in `dq_findings`, replacing those of an
+/// earlier attempt. Failures are logged; they never fail the feed by themselves.
+pub fn record_report(db_path: &str, source: &str, date: NaiveDate, report: &DqReport) {
+    let rows: Vec<DqFindingRow> = report.findings.iter()
+        .map(|f| DqFindingRow {
+            source: source.to_string(),
+            trade_date: date.format("%Y-%m-%d").to_string(),
+            check_name: f.check.to_string(),
+            severity: f.severity.as_str().to_string(),
+            instrument: f.instrument.clone(),
+            message: f.message.clone(),
+        })
+        .collect();
+    let result = crate::store::open_and_migrate(db_path).and_then(|mut conn| {
+        replace_findings(&mut conn, source, &date.format("%Y-%m-%d").to_string(), &rows, chrono::Utc::now().timestamp())
+    });
+    match result {
+        Ok(_) if report.has_errors() => warn!("{} {}: {}", source, date, report.summary()),
+        Ok(_) => {}
+        Err(e) => error!("{} {}: could not record DQ findings: {}", source, date, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UDIFF_HEADER: &str = "TradDt,FinInstrmId,TckrSymb,OpnPric,HghPric,LwPric,ClsPric,PrvsClsgPric,TtlTradgVol";
+
+    fn udiff(rows: &[&str]) -> String {
+        std::iter::once(UDIFF_HEADER).chain(rows.iter().copied()).collect::<Vec<_>>().join("\n")
+    }
+
+    #[test]
+    fn test_clean_file_passes() {
+        let csv = udiff(&[
+            "2025-06-03,2885,RELIANCE,1420,1432,1415,1428.5,1419.9,5000000",
+            "2025-06-03,1594,INFY,1550,1560,1540,1555,1551,3000000",
+        ]);
+        let report = validate_bhavcopy(&csv, &UDIFF_SPEC, Some(2));
+        assert!(report.findings.is_empty(), "{:?}", report.findings);
+        assert_eq!(report.summary(), "DQ: 2 rows, 0 error(s), 0 warning(s)");
+    }
+
+    #[test]
+    fn test_html_and_schema_errors() {
+        let report = validate_bhavcopy("<!DOCTYPE html><html>Service unavailable</html>", &UDIFF_SPEC, None);
+        assert!(report.has_errors());
+        assert_eq!(report.findings[0].check, "format");
+
+        let report = validate_bhavcopy("TradDt,TckrSymb\n2025-06-03,RELIANCE", &UDIFF_SPEC, None);
+        assert_eq!(report.findings[0].check, "schema");
+        assert!(report.findings[0].message.contains("ClsPric"));
+    }
+
+    #[test]
+    fn test_row_level_checks() {
+        let csv = udiff(&[
+            "2025-06-03,2885,RELIANCE,1420,1432,1415,1428.5,1419.9,5000000",
+            "2025-06-03,2885,RELIANCE,1420,1432,1415,1428.5,1419.9,5000000",
+            "2025-06-03,1594,INFY,1550,1560,1540,0,1551,0",
+            "2025-06-03,9999,PENNY,10,13,10,13,10,100",
+            "2025-06-03,8888,BROKEN,-5,1,1,1,1,1",
+        ]);
+        let report = validate_bhavcopy(&csv, &UDIFF_SPEC, None);
+        let checks: Vec<(&str, Severity)> = report.findings.iter().map(|f| (f.check, f.severity)).collect();
+        assert!(checks.contains(&("duplicate", Severity::Warning)));
+        assert!(checks.contains(&("price_range", Severity::Warning)), "zero close");
+        assert!(checks.contains(&("day_move", Severity::Warning)), "+30% move");
+        assert!(checks.contains(&("price_range", Severity::Error)), "negative open");
+        assert!(report.has_errors());
+        assert!(report.summary().contains("negative OpnPric"));
+    }
+
+    #[test]
+    fn test_row_count_drop_and_bad_rows_fail() {
+        let csv = udiff(&["2025-06-03,2885,RELIANCE,1420,1432,1415,1428.5,1419.9,5000000"]);
+        let report = validate_bhavcopy(&csv, &UDIFF_SPEC, Some(2000));
+        assert_eq!(report.findings[0].check, "row_count");
+        assert!(report.has_errors());
+
+        let csv = udiff(&["2025-06-03,2885,RELIANCE", "2025-06-03,1594,INFY,1550,1560,1540,1555,1551,3000000"]);
+        let report = validate_bhavcopy(&csv, &UDIFF_SPEC, None);
+        assert!(report.findings.iter().any(|f| f.check == "column_count" && f.severity == Severity::Error));
+    }
+
+    #[test]
+    fn test_legacy_nse_trailing_comma_is_not_a_bad_row() {
+        let csv = "SYMBOL,SERIES,OPEN,HIGH,LOW,CLOSE,LAST,PREVCLOSE,TOTTRDQTY,TOTTRDVAL,TIMESTAMP,TOTALTRADES,ISIN,\n\
+                   RELIANCE,EQ,2800,2850,2790,2840,2841,2810,1000000,2840000000,02-JAN-2024,50000,INE002A01018,";
+        let report = validate_bhavcopy(csv, &NSE_LEGACY_SPEC, None);
+        assert!(report.findings.is_empty(), "{:?}", report.findings);
+    }
+}
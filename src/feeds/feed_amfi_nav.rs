@@ -0,0 +1,107 @@
+// file: feeds/feed_amfi_nav.rs
+// Purpose:
+//   Batch feed: download AMFI's daily NAV file for all mutual-fund schemes and load each
+//   scheme's NAV into `market_series` (instrument `AMFI:<scheme code>`, unit INR).
+//
+//   NAVAll.txt is semicolon-separated. Data rows carry six fields
+//   (code;ISIN growth;ISIN reinvestment;name;NAV;date); they are interleaved with blank lines,
+//   scheme-category headings and fund-house names, which are skipped. Suspended schemes show
+//   "N.A." instead of a NAV.
+
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+use log::{error, info, warn};
+
+use crate::cfg::get_market_data_db;
+use crate::feeds::{browser_client, FeedOutcome};
+use crate::get_plugin_cfg;
+use crate::market_data::SeriesPoint;
+use crate::network::http_get;
+
+pub const FEED_NAME: &str = "feed_amfi_nav";
+const SOURCE: &str = "AMFI";
+const DEFAULT_URL: &str = "https://www.amfiindia.com/spages/NAVAll.txt";
+const TABLE_REF: &str = "amfi_navall";
+
+/// Parse NAVAll.txt content into series points, one per scheme with a published NAV.
+fn parse_nav_file(content: &str) -> Vec<SeriesPoint> {
+    let mut points = Vec::new();
+    for line in content.lines() {
+        let fields: Vec<&str> = line.split(';').map(|f| f.trim()).collect();
+        if fields.len() < 6 {
+            continue;
+        }
+        let code = fields[0];
+        if code.is_empty() || !code.chars().all(|c| c.is_ascii_digit()) {
+            continue; // header row
+        }
+        let value = match fields[4].replace(',', "").parse::<f64>() {
+            Ok(v) => v,
+            Err(_) => continue, // "N.A." for suspended schemes
+        };
+        let date = match NaiveDate::parse_from_str(fields[5], "%d-%b-%Y") {
+            Ok(d) => d,
+            Err(e) => {
+                warn!("{}: scheme {} has bad date '{}': {}", FEED_NAME, code, fields[5], e);
+                continue;
+            }
+        };
+        points.push(SeriesPoint {
+            source: SOURCE.to_string(),
+            instrument: format!("AMFI:{}", code),
+            date,
+            value,
+            unit: "INR".to_string(),
+            table_ref: TABLE_REF.to_string(),
+        });
+    }
+    points
+}
+
+/// Entry point invoked by the batch-feed runner.
+pub fn run(app_config: Arc<config::Config>) -> FeedOutcome {
+    let db_path = get_market_data_db(&app_config);
+    let url = get_plugin_cfg!(FEED_NAME, "url", &app_config).unwrap_or_else(|| DEFAULT_URL.to_string());
+    let client = match browser_client() {
+        Ok(c) => c,
+        Err(e) => return FeedOutcome::fail(e),
+    };
+
+    let content = http_get(&url, &client, 3, 5);
+    let points = parse_nav_file(&content);
+    if points.is_empty() {
+        return FeedOutcome::fail(format!("no NAVs parsed from {}", url));
+    }
+    info!("{}: parsed {} scheme NAV(s).", FEED_NAME, points.len());
+
+    match crate::market_data::save_series_to_sqlite(&points, &db_path) {
+        Ok(rows) => {
+            crate::metrics::record_db_writes(rows as u64);
+            FeedOutcome::ok(rows as i64, format!("{} AMFI NAVs loaded", rows))
+        }
+        Err(e) => {
+            crate::metrics::record_db_error();
+            FeedOutcome::fail(format!("save AMFI NAVs: {}", e))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nav_file_sample() {
+        let points = parse_nav_file(include_str!("testdata/amfi_navall_sample.txt"));
+        // 5 scheme rows, one of which is "N.A."
+        assert_eq!(points.len(), 4);
+        let p = points.iter().find(|p| p.instrument == "AMFI:119018").unwrap();
+        assert_eq!(p.value, 1198.456);
+        assert_eq!(p.date, NaiveDate::from_ymd_opt(2025, 6, 3).unwrap());
+        assert_eq!(p.unit, "INR");
+        assert!(points.iter().all(|p| p.instrument != "AMFI:100032"));
+        let fmp = points.iter().find(|p| p.instrument == "AMFI:148921").unwrap();
+        assert_eq!(fmp.date, NaiveDate::from_ymd_opt(2025, 6, 2).unwrap());
+    }
+}
@@ -5,23 +5,32 @@
 //   `mod_in_bse` news retriever (roadmap point 2g). No Document flows from this feed.
 //
 //   BSE serves the file only after market close and not on non-trading days, so we walk back
-//   over recent business days and use the first body that looks like a real CSV.
+//   over recent trading sessions (per `crate::calendar`) and use the first body that looks
+//   like a real CSV.
+//   Before July 2024 BSE published a zipped `EQDDMMYY` file with its own column layout; the
+//   date-range backfill fetches those via `load_date` into `bse_cm_bhavcopy_legacy`.
+//   Every file is validated (`feeds::dq`) before loading; a file failing a check is rejected.
 
 use std::sync::Arc;
-use std::time::Duration;
 
 use chrono::{NaiveDate, Utc};
 use log::{info, warn};
+use reqwest::blocking::Client;
 
 use crate::cfg::get_market_data_db;
-use crate::feeds::FeedOutcome;
-use crate::network::http_get_binary;
+use crate::feeds::feed_nse_bhavcopy::csv_from_zip;
+use crate::feeds::{browser_client, downloaded_file, dq, DateOutcome, FeedOutcome};
+use crate::network::http_get_binary_response;
 
 pub const FEED_NAME: &str = "feed_bse_bhavcopy";
+/// Exchange whose trading calendar decides which dates have a bhavcopy.
+pub const EXCHANGE: &str = "BSE";
 const BASE_URL: &str = "https://www.bseindia.com/";
 const LOOKBACK_BUSINESS_DAYS: usize = 5;
 /// Destination table name for the generic CSV loader.
 const BSE_TABLE: &str = "bse_cm_bhavcopy";
+/// Destination table for the pre-UDiFF (SC_CODE, SC_NAME, ...) files.
+const BSE_LEGACY_TABLE: &str = "bse_cm_bhavcopy_legacy";
 
 /// Build the BSE UDiFF equity bhavcopy CSV URL for a given date.
 fn bhavcopy_url_for(date: NaiveDate) -> String {
@@ -32,59 +41,93 @@ fn bhavcopy_url_for(date: NaiveDate) -> String {
     )
 }
 
-/// True if the bytes look like a real bhavcopy CSV rather than an HTML error / empty body.
+/// Build the legacy (pre-UDiFF) zipped equity bhavcopy URL for a given date.
+fn legacy_bhavcopy_url_for(date: NaiveDate) -> String {
+    format!("{}download/BhavCopy/Equity/EQ{}_CSV.ZIP", BASE_URL, date.format("%d%m%y"))
+}
+
+/// True if the bytes look like a real bhavcopy CSV (after `downloaded_file` has rejected
+/// empty bodies and HTML pages).
 fn looks_like_csv(bytes: &[u8]) -> bool {
-    if bytes.is_empty() || bytes.first() == Some(&b'<') {
-        return false;
-    }
     let prefix = &bytes[..bytes.len().min(64)];
     let prefix_str = String::from_utf8_lossy(prefix);
     prefix_str.starts_with("TradDt") || prefix_str.contains(',')
 }
 
+/// Download and load the bhavcopy for one date, picking the URL and destination table for
+/// the format BSE used on that date. Returns `NoData` for non-trading / unpublished dates,
+/// and `Failed` when the download failed, was refused or is not a bhavcopy.
+pub fn load_date(client: &Client, date: NaiveDate, db_path: &str) -> DateOutcome {
+    let date_str = date.format("%Y-%m-%d").to_string();
+    let (url, table) = if date < dq::UDIFF_START_DATE {
+        (legacy_bhavcopy_url_for(date), BSE_LEGACY_TABLE)
+    } else {
+        (bhavcopy_url_for(date), BSE_TABLE)
+    };
+    info!("{}: trying bhavcopy for {}: {}", FEED_NAME, date_str, url);
+
+    let body = match downloaded_file(http_get_binary_response(&url, client), &url) {
+        Ok(bytes) => bytes,
+        Err(outcome) => {
+            warn!("{}: no bhavcopy for {}: {:?}", FEED_NAME, date_str, outcome);
+            return outcome;
+        }
+    };
+    let csv = if table == BSE_LEGACY_TABLE {
+        csv_from_zip(body.as_ref())
+    } else if looks_like_csv(body.as_ref()) {
+        Some(String::from_utf8_lossy(body.as_ref()).to_string())
+    } else {
+        None
+    };
+    let csv = match csv {
+        Some(c) => c,
+        None => return DateOutcome::Failed(format!("BSE bhavcopy for {} is not a CSV file", date_str)),
+    };
+
+    let spec = if table == BSE_LEGACY_TABLE { &dq::BSE_LEGACY_SPEC } else { &dq::UDIFF_SPEC };
+    let report = dq::validate_bhavcopy(&csv, spec, dq::previous_day_rows(db_path, table, date));
+    dq::record_report(db_path, FEED_NAME, date, &report);
+    if report.has_errors() {
+        return DateOutcome::Failed(format!("BSE bhavcopy {} rejected — {}", date_str, report.summary()));
+    }
+
+    match crate::market_data::save_csv_to_sqlite(&csv, table, &date_str, db_path) {
+        Ok(rows) => {
+            crate::metrics::record_db_writes(rows as u64);
+            DateOutcome::Loaded(rows, report.summary())
+        }
+        Err(e) => {
+            crate::metrics::record_db_error();
+            DateOutcome::Failed(format!("save BSE bhavcopy {}: {}", date_str, e))
+        }
+    }
+}
+
 /// Entry point invoked by the batch-feed runner.
 pub fn run(app_config: Arc<config::Config>) -> FeedOutcome {
     let db_path = get_market_data_db(&app_config);
     info!("{}: market-data DB = {}", FEED_NAME, db_path);
 
     // BSE requires a desktop browser User-Agent to serve the bhavcopy file.
-    let client = match reqwest::blocking::Client::builder()
-        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36")
-        .timeout(Duration::from_secs(60))
-        .gzip(true)
-        .build()
-    {
+    let client = match browser_client() {
         Ok(c) => c,
-        Err(e) => return FeedOutcome::fail(format!("could not build HTTP client: {}", e)),
+        Err(e) => return FeedOutcome::fail(e),
     };
 
-    let candidate_days = crate::utils::recent_business_days(Utc::now().date_naive(), LOOKBACK_BUSINESS_DAYS);
+    let calendar = crate::calendar::load_calendar(&app_config);
+    let candidate_days = calendar.recent_trading_days(EXCHANGE, Utc::now().date_naive(), LOOKBACK_BUSINESS_DAYS);
 
     for date in candidate_days {
-        let url = bhavcopy_url_for(date);
         let date_str = date.format("%Y-%m-%d").to_string();
-        info!("{}: trying bhavcopy for {}: {}", FEED_NAME, date_str, url);
-
-        let body = http_get_binary(&url, &client);
-        if !looks_like_csv(body.as_ref()) {
-            warn!("{}: no CSV for {} (not published / non-trading day), trying earlier.", FEED_NAME, date_str);
-            continue;
+        match load_date(&client, date, &db_path) {
+            DateOutcome::Loaded(rows, summary) => return FeedOutcome::ok(rows as i64, format!("BSE bhavcopy {} loaded; {}", date_str, summary)),
+            DateOutcome::NoData => continue,
+            DateOutcome::Failed(e) => return FeedOutcome::fail(e),
         }
-
-        let csv = String::from_utf8_lossy(body.as_ref()).to_string();
-        return match crate::market_data::save_csv_to_sqlite(&csv, BSE_TABLE, &date_str, &db_path) {
-            Ok(rows) => {
-                crate::metrics::record_db_writes(rows as u64);
-                FeedOutcome::ok(rows as i64, format!("BSE bhavcopy {} loaded", date_str))
-            }
-            Err(e) => {
-                crate::metrics::record_db_error();
-                FeedOutcome::fail(format!("save BSE bhavcopy {}: {}", date_str, e))
-            }
-        };
     }
 
-    FeedOutcome::fail(format!("no BSE bhavcopy found in last {} business days", LOOKBACK_BUSINESS_DAYS))
+    FeedOutcome::fail(format!("no BSE bhavcopy found in last {} trading days", LOOKBACK_BUSINESS_DAYS))
 }
 
 #[cfg(test)]
@@ -100,6 +143,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_legacy_bhavcopy_url_format() {
+        let date = NaiveDate::from_ymd_opt(2024, 7, 5).unwrap();
+        assert!(date < dq::UDIFF_START_DATE);
+        assert_eq!(
+            legacy_bhavcopy_url_for(date),
+            "https://www.bseindia.com/download/BhavCopy/Equity/EQ050724_CSV.ZIP"
+        );
+    }
+
     #[test]
     fn test_looks_like_csv() {
         assert!(looks_like_csv(b"TradDt,TckrSymb\n2025-06-03,RELIANCE"));
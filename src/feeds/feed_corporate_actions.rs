@@ -0,0 +1,329 @@
+// file: feeds/feed_corporate_actions.rs
+// Purpose:
+//   Batch feed: download NSE and BSE corporate actions for a window around today and store
+//   them in `corporate_actions` (market-data DB), where `market_data::adjusted_ohlcv` uses
+//   them to back-adjust bhavcopy prices. The exchanges describe each action in free text
+//   ("Bonus 1:1", "Face Value Split (Sub-Division) - From Rs 10/- Per Share To Rs 2/- Per
+//   Share", "Final Dividend - Rs 24 Per Share / Special Dividend - Rs 66 Per Share"); the
+//   share ratio or cash amount needed for adjustment is parsed from that text. A compound
+//   description ("Bonus 1:1 / Dividend Rs 5") is stored as one action per part.
+//
+//   The window is `lookback_days` before and `lookahead_days` after the run date (both
+//   configurable); announced actions are refreshed until they go ex. Set a large
+//   `lookback_days` once to load history.
+
+use std::sync::Arc;
+
+use chrono::{Duration, NaiveDate, Utc};
+use log::{error, info, warn};
+use regex::Regex;
+use serde_json::Value;
+
+use crate::cfg::get_market_data_db;
+use crate::feeds::{browser_client, FeedOutcome};
+use crate::get_plugin_cfg;
+use crate::market_data::{ActionType, CorporateAction};
+use crate::network::http_get_json_with_session;
+
+pub const FEED_NAME: &str = "feed_corporate_actions";
+const NSE_SESSION_PAGES: [&str; 2] = [
+    "https://www.nseindia.com/",
+    "https://www.nseindia.com/companies-listing/corporate-filings-actions",
+];
+const BSE_SESSION_PAGES: [&str; 1] = ["https://www.bseindia.com/corporates/corporate_act.aspx"];
+const DEFAULT_LOOKBACK_DAYS: i64 = 30;
+const DEFAULT_LOOKAHEAD_DAYS: i64 = 30;
+
+fn nse_actions_url(from: NaiveDate, to: NaiveDate) -> String {
+    format!(
+        "https://www.nseindia.com/api/corporates-corporateActions?index=equities&from_date={}&to_date={}",
+        from.format("%d-%m-%Y"), to.format("%d-%m-%Y")
+    )
+}
+
+fn bse_actions_url(from: NaiveDate, to: NaiveDate) -> String {
+    format!(
+        "https://api.bseindia.com/BseIndiaAPI/api/DefaultData/w?Fdate={}&Purposecode=&TDate={}&ddlcategorys=E&ddlindustrys=&scripcode=&segment=0&strSearch=S",
+        from.format("%Y%m%d"), to.format("%Y%m%d")
+    )
+}
+
+/// Parsers for the action descriptions, compiled once per run.
+struct SubjectParser {
+    split: Regex,
+    ratio: Regex,
+    rupees: Regex,
+}
+
+impl SubjectParser {
+    fn new() -> Self {
+        SubjectParser {
+            split: Regex::new(r"(?i)from\s+r[se]\.?\s*([0-9]+(?:\.[0-9]+)?).*?to\s+r[se]\.?\s*([0-9]+(?:\.[0-9]+)?)").unwrap(),
+            ratio: Regex::new(r"([0-9]+)\s*:\s*([0-9]+)").unwrap(),
+            rupees: Regex::new(r"(?i)r[se]\.?\s*-?\s*([0-9]+(?:\.[0-9]+)?)").unwrap(),
+        }
+    }
+
+    /// Classify a description and extract (share ratio, cash per share) where it has one.
+    /// A bonus "a:b" gives a new shares per b held, i.e. a share ratio of (a + b) / b; a split
+    /// from face value F to f gives F / f. Several dividends in one description are summed.
+    fn parse(&self, subject: &str) -> (ActionType, Option<f64>, Option<f64>) {
+        let lower = subject.to_lowercase();
+        let number = |c: &regex::Captures, i: usize| c.get(i).and_then(|m| m.as_str().parse::<f64>().ok());
+
+        if lower.contains("split") || lower.contains("sub-division") || lower.contains("sub division") {
+            let ratio = self.split.captures(subject).and_then(|c| match (number(&c, 1), number(&c, 2)) {
+                (Some(old), Some(new)) if old > 0.0 && new > 0.0 => Some(old / new),
+                _ => None,
+            });
+            return (ActionType::Split, ratio, None);
+        }
+        if lower.contains("rights") {
+            return (ActionType::Rights, None, None);
+        }
+        if lower.contains("bonus") {
+            let ratio = self.ratio.captures(subject).and_then(|c| match (number(&c, 1), number(&c, 2)) {
+                (Some(a), Some(b)) if a > 0.0 && b > 0.0 => Some((a + b) / b),
+                _ => None,
+            });
+            return (ActionType::Bonus, ratio, None);
+        }
+        if lower.contains("dividend") {
+            let amounts: Vec<f64> = subject
+                .split('/')
+                .filter(|part| part.to_lowercase().contains("dividend"))
+                .filter_map(|part| self.rupees.captures(part).and_then(|c| number(&c, 1)))
+                .collect();
+            let cash = if amounts.is_empty() { None } else { Some(amounts.iter().sum()) };
+            return (ActionType::Dividend, None, cash);
+        }
+        (ActionType::Other, None, None)
+    }
+
+    /// Split a description into the actions it announces, each with its own part of the text.
+    /// Parts are separated by ";" or "/" (but not the "/-" after an amount); dividend parts are
+    /// kept together so that they are summed, and parts naming no action are dropped. A
+    /// description announcing a single action keeps its full text.
+    fn parse_all(&self, subject: &str) -> Vec<(String, ActionType, Option<f64>, Option<f64>)> {
+        let mut parts: Vec<String> = Vec::new();
+        let mut current = String::new();
+        let mut chars = subject.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c == ';' || (c == '/' && chars.peek() != Some(&'-')) {
+                parts.push(std::mem::take(&mut current));
+            } else {
+                current.push(c);
+            }
+        }
+        parts.push(current);
+
+        let mut texts: Vec<String> = Vec::new();
+        let mut dividend_idx: Option<usize> = None;
+        for part in parts.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+            match self.parse(part).0 {
+                ActionType::Other => {}
+                ActionType::Dividend => match dividend_idx {
+                    Some(i) => texts[i] = format!("{} / {}", texts[i], part),
+                    None => {
+                        dividend_idx = Some(texts.len());
+                        texts.push(part.to_string());
+                    }
+                },
+                _ => texts.push(part.to_string()),
+            }
+        }
+        if texts.len() <= 1 {
+            let (action_type, share_ratio, cash_amount) = self.parse(subject);
+            return vec![(subject.to_string(), action_type, share_ratio, cash_amount)];
+        }
+        texts.into_iter()
+            .map(|text| {
+                let (action_type, share_ratio, cash_amount) = self.parse(&text);
+                (text, action_type, share_ratio, cash_amount)
+            })
+            .collect()
+    }
+}
+
+fn text(item: &Value, key: &str) -> String {
+    item.get(key).and_then(|v| v.as_str()).unwrap_or("").trim().to_string()
+}
+
+/// Build the actions described by one exchange row; none if the ex-date is missing or
+/// unparseable.
+fn build_actions(
+    parser: &SubjectParser,
+    exchange: &str,
+    symbol: String,
+    isin: Option<String>,
+    subject: String,
+    ex_date: &str,
+    record_date: &str,
+) -> Vec<CorporateAction> {
+    if symbol.is_empty() || subject.is_empty() {
+        return Vec::new();
+    }
+    // NSE writes dates as "28-Oct-2024", BSE as "28 Oct 2024".
+    let date_format = if exchange == "BSE" { "%d %b %Y" } else { "%d-%b-%Y" };
+    let Ok(ex_date) = NaiveDate::parse_from_str(ex_date, date_format) else {
+        return Vec::new();
+    };
+    let record_date = NaiveDate::parse_from_str(record_date, date_format).ok();
+    parser.parse_all(&subject)
+        .into_iter()
+        .map(|(subject, action_type, share_ratio, cash_amount)| CorporateAction {
+            exchange: exchange.to_string(),
+            symbol: symbol.clone(),
+            isin: isin.clone(),
+            ex_date,
+            action_type,
+            subject,
+            share_ratio,
+            cash_amount,
+            record_date,
+        })
+        .collect()
+}
+
+/// Parse the NSE corporate-actions API response (array of symbol/subject/exDate/... objects).
+fn parse_nse_actions(json: &Value, parser: &SubjectParser) -> Vec<CorporateAction> {
+    let items = match json.as_array() {
+        Some(a) => a,
+        None => return Vec::new(),
+    };
+    items.iter()
+        .flat_map(|item| {
+            let isin = text(item, "isin");
+            build_actions(
+                parser, "NSE", text(item, "symbol"),
+                if isin.is_empty() { None } else { Some(isin) },
+                text(item, "subject"), &text(item, "exDate"), &text(item, "recDate"),
+            )
+        })
+        .collect()
+}
+
+/// Parse the BSE corporate-actions API response (array of scrip_code/Purpose/Ex_date/...).
+/// BSE does not give ISINs here; actions are keyed by scrip code.
+fn parse_bse_actions(json: &Value, parser: &SubjectParser) -> Vec<CorporateAction> {
+    let items = match json.as_array().or_else(|| json.get("Table").and_then(|t| t.as_array())) {
+        Some(a) => a,
+        None => return Vec::new(),
+    };
+    items.iter()
+        .flat_map(|item| {
+            build_actions(
+                parser, "BSE", text(item, "scrip_code"), None,
+                text(item, "Purpose"), &text(item, "Ex_date"), &text(item, "RD_Date"),
+            )
+        })
+        .collect()
+}
+
+/// Entry point invoked by the batch-feed runner.
+pub fn run(app_config: Arc<config::Config>) -> FeedOutcome {
+    let db_path = get_market_data_db(&app_config);
+    let days = |key: &str, default: i64| {
+        get_plugin_cfg!(FEED_NAME, key, &app_config)
+            .and_then(|v| v.parse::<i64>().ok())
+            .unwrap_or(default)
+    };
+    let today = Utc::now().date_naive();
+    let from = today - Duration::days(days("lookback_days", DEFAULT_LOOKBACK_DAYS));
+    let to = today + Duration::days(days("lookahead_days", DEFAULT_LOOKAHEAD_DAYS));
+    let client = match browser_client() {
+        Ok(c) => c,
+        Err(e) => return FeedOutcome::fail(e),
+    };
+    let parser = SubjectParser::new();
+
+    let nse = http_get_json_with_session(&nse_actions_url(from, to), &NSE_SESSION_PAGES, &client, 3, 5)
+        .map(|j| parse_nse_actions(&j, &parser))
+        .unwrap_or_default();
+    let bse = http_get_json_with_session(&bse_actions_url(from, to), &BSE_SESSION_PAGES, &client, 3, 5)
+        .map(|j| parse_bse_actions(&j, &parser))
+        .unwrap_or_default();
+    info!("{}: {} NSE and {} BSE action(s) between {} and {}.", FEED_NAME, nse.len(), bse.len(), from, to);
+    if nse.is_empty() && bse.is_empty() {
+        warn!("{}: no corporate actions from either exchange.", FEED_NAME);
+        return FeedOutcome::fail(format!("no corporate actions retrieved for {} to {}", from, to));
+    }
+
+    let actions: Vec<CorporateAction> = nse.into_iter().chain(bse).collect();
+    match crate::market_data::save_corporate_actions(&actions, &db_path) {
+        Ok(rows) => {
+            crate::metrics::record_db_writes(rows as u64);
+            FeedOutcome::ok(rows as i64, format!("{} corporate actions loaded", rows))
+        }
+        Err(e) => {
+            error!("{}: {}", FEED_NAME, e);
+            crate::metrics::record_db_error();
+            FeedOutcome::fail(format!("save corporate actions: {}", e))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_subjects() {
+        let p = SubjectParser::new();
+        assert_eq!(p.parse("Bonus 1:1"), (ActionType::Bonus, Some(2.0), None));
+        assert_eq!(p.parse("Bonus issue 3:2"), (ActionType::Bonus, Some(2.5), None));
+        assert_eq!(
+            p.parse("Face Value Split (Sub-Division) - From Rs 10/- Per Share To Rs 2/- Per Share"),
+            (ActionType::Split, Some(5.0), None)
+        );
+        assert_eq!(p.parse("Stock  Split From Rs.10/- to Rs.2/-"), (ActionType::Split, Some(5.0), None));
+        assert_eq!(
+            p.parse("Final Dividend - Rs 24 Per Share / Special Dividend - Rs 66 Per Share"),
+            (ActionType::Dividend, None, Some(90.0))
+        );
+        assert_eq!(p.parse("Final Dividend - Rs. - 24.0000"), (ActionType::Dividend, None, Some(24.0)));
+        assert_eq!(p.parse("Rights 1:5 @ Premium Rs 90/-"), (ActionType::Rights, None, None));
+        assert_eq!(p.parse("Annual General Meeting"), (ActionType::Other, None, None));
+    }
+
+    #[test]
+    fn test_parse_compound_subjects() {
+        let p = SubjectParser::new();
+        let actions = p.parse_all("Bonus 1:1 / Dividend Rs 5");
+        assert_eq!(actions, vec![
+            ("Bonus 1:1".to_string(), ActionType::Bonus, Some(2.0), None),
+            ("Dividend Rs 5".to_string(), ActionType::Dividend, None, Some(5.0)),
+        ]);
+        let actions = p.parse_all("Interim Dividend - Rs 3 Per Share; Special Dividend - Rs 2 Per Share; Stock Split From Rs.10/- to Rs.5/-");
+        assert_eq!(actions.len(), 2);
+        assert_eq!(actions[0].1, ActionType::Dividend);
+        assert_eq!(actions[0].3, Some(5.0));
+        assert_eq!((actions[1].1, actions[1].2), (ActionType::Split, Some(2.0)));
+
+        // Single actions keep their full description, including "/-" amounts and summed dividends.
+        let subject = "Face Value Split (Sub-Division) - From Rs 10/- Per Share To Rs 2/- Per Share";
+        assert_eq!(p.parse_all(subject), vec![(subject.to_string(), ActionType::Split, Some(5.0), None)]);
+        let subject = "Final Dividend - Rs 24 Per Share / Special Dividend - Rs 66 Per Share";
+        assert_eq!(p.parse_all(subject), vec![(subject.to_string(), ActionType::Dividend, None, Some(90.0))]);
+        assert_eq!(p.parse_all("Annual General Meeting / Book Closure").len(), 1);
+    }
+
+    #[test]
+    fn test_parse_nse_and_bse_samples() {
+        let p = SubjectParser::new();
+        let nse: Value = serde_json::from_str(include_str!("testdata/nse_corporate_actions_sample.json")).unwrap();
+        let nse = parse_nse_actions(&nse, &p);
+        assert_eq!(nse.len(), 5, "row without an ex-date is dropped");
+        let hdfc = nse.iter().find(|a| a.symbol == "HDFCBANK").unwrap();
+        assert_eq!(hdfc.cash_amount, Some(5.5));
+        assert_eq!(hdfc.ex_date, NaiveDate::from_ymd_opt(2025, 7, 25).unwrap());
+        assert_eq!(hdfc.isin.as_deref(), Some("INE040A01034"));
+
+        let bse: Value = serde_json::from_str(include_str!("testdata/bse_corporate_actions_sample.json")).unwrap();
+        let bse = parse_bse_actions(&bse, &p);
+        assert_eq!(bse.len(), 3);
+        let ril = bse.iter().find(|a| a.symbol == "500325").unwrap();
+        assert_eq!((ril.action_type, ril.share_ratio), (ActionType::Bonus, Some(2.0)));
+        assert_eq!(ril.record_date, NaiveDate::from_ymd_opt(2024, 10, 28));
+    }
+}
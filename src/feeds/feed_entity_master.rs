@@ -0,0 +1,321 @@
+// file: feeds/feed_entity_master.rs
+// Purpose:
+//   Batch feed: load legal-entity reference data from local files into the entity master
+//   (`store::entity_master`) so `mod_entity_resolve` can link news mentions to LEI / CIN /
+//   ISIN-backed entities:
+//     - the GLEIF LEI golden-copy CSV (`gleif_file`), filtered to `lei_countries`
+//       (comma-separated ISO codes, default "IN"; "*" loads every country). For Indian
+//       entities GLEIF's registration-authority id is the CIN;
+//     - GLEIF's LEI–ISIN relationship CSV (`gleif_isin_file`), used to merge LEI records into
+//       the listed entities the symbol-master feed created;
+//     - the MCA company master CSV (`mca_file`).
+//   The files are large downloads refreshed by hand, so they are read from disk rather than
+//   fetched, streamed line by line, and written in chunked transactions. Like the symbol
+//   master, this writes to the main metadata DB (`completed_urls_datafile`).
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::sync::Arc;
+
+use chrono::{NaiveDate, Utc};
+use log::{error, info, warn};
+use rusqlite::Connection;
+
+use crate::cfg::get_database_filename;
+use crate::feeds::{split_csv_line, FeedOutcome};
+use crate::get_plugin_cfg;
+use crate::store::entity_master::{derive_missing_aliases, upsert_reference_entity, ReferenceEntity};
+
+pub const FEED_NAME: &str = "feed_entity_master";
+/// Records written per transaction.
+const CHUNK_SIZE: usize = 5000;
+const DEFAULT_LEI_COUNTRIES: &str = "IN";
+const GLEIF_OTHER_NAME_PREFIXES: [&str; 2] = [
+    "ENTITY.OTHERENTITYNAMES.OTHERENTITYNAME.",
+    "ENTITY.TRANSLITERATEDOTHERENTITYNAMES.TRANSLITERATEDOTHERENTITYNAME.",
+];
+
+/// True for a 21-character Indian corporate identification number, e.g. L17110MH1973PLC019786.
+fn is_cin(s: &str) -> bool {
+    let b = s.as_bytes();
+    b.len() == 21
+        && (b[0] == b'L' || b[0] == b'U')
+        && b[1..6].iter().all(u8::is_ascii_digit)
+        && b[6..8].iter().all(u8::is_ascii_uppercase)
+        && b[8..12].iter().all(u8::is_ascii_digit)
+        && b[12..15].iter().all(u8::is_ascii_uppercase)
+        && b[15..].iter().all(u8::is_ascii_digit)
+}
+
+fn non_empty(s: Option<&String>) -> Option<String> {
+    s.map(|v| v.trim()).filter(|v| !v.is_empty()).map(str::to_string)
+}
+
+/// Read a CSV stream, calling `each(header, fields)` per data row; the header is upper-cased.
+/// Returns the number of data rows read.
+fn for_each_row(reader: impl BufRead, mut each: impl FnMut(&[String], &[String]) -> Result<(), String>) -> Result<usize, String> {
+    let mut lines = reader.lines();
+    let header: Vec<String> = match lines.next() {
+        Some(line) => split_csv_line(line.map_err(|e| format!("read header: {}", e))?.trim_start_matches('\u{feff}'))
+            .into_iter()
+            .map(|c| c.to_uppercase())
+            .collect(),
+        None => return Ok(0),
+    };
+    let mut rows = 0;
+    for line in lines {
+        let line = line.map_err(|e| format!("read line {}: {}", rows + 2, e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        each(&header, &split_csv_line(&line))?;
+        rows += 1;
+    }
+    Ok(rows)
+}
+
+/// GLEIF LEI–ISIN map (LEI, ISIN columns): LEI → ISINs.
+fn read_isin_map(reader: impl BufRead) -> Result<HashMap<String, Vec<String>>, String> {
+    let mut map: HashMap<String, Vec<String>> = HashMap::new();
+    for_each_row(reader, |header, fields| {
+        let col = |name: &str| header.iter().position(|c| c == name);
+        let (Some(lei_idx), Some(isin_idx)) = (col("LEI"), col("ISIN")) else {
+            return Err(format!("unrecognised LEI-ISIN header: {:?}", header));
+        };
+        if let (Some(lei), Some(isin)) = (non_empty(fields.get(lei_idx)), non_empty(fields.get(isin_idx))) {
+            map.entry(lei).or_default().push(isin);
+        }
+        Ok(())
+    })?;
+    Ok(map)
+}
+
+/// One GLEIF golden-copy row as a reference entity, or None if it is outside `countries`
+/// (empty = all countries) or has no LEI / legal name.
+fn gleif_record(header: &[String], fields: &[String], countries: &HashSet<String>, isins: &HashMap<String, Vec<String>>) -> Option<ReferenceEntity> {
+    let get = |name: &str| header.iter().position(|c| c == name).and_then(|i| non_empty(fields.get(i)));
+    let country = get("ENTITY.LEGALADDRESS.COUNTRY").unwrap_or_default();
+    if !countries.is_empty() && !countries.contains(&country) {
+        return None;
+    }
+    let lei = get("LEI")?;
+    let name = get("ENTITY.LEGALNAME")?;
+    let other_names: Vec<String> = header
+        .iter()
+        .enumerate()
+        .filter(|(_, h)| {
+            GLEIF_OTHER_NAME_PREFIXES.iter().any(|p| {
+                h.strip_prefix(p).is_some_and(|rest| rest.chars().all(|c| c.is_ascii_digit()))
+            })
+        })
+        .filter_map(|(i, _)| non_empty(fields.get(i)))
+        .filter(|n| *n != name)
+        .collect();
+    let cin = get("ENTITY.REGISTRATIONAUTHORITY.REGISTRATIONAUTHORITYENTITYID").filter(|id| is_cin(id));
+    Some(ReferenceEntity {
+        isins: isins.get(&lei).cloned().unwrap_or_default(),
+        lei: Some(lei),
+        name,
+        other_names,
+        cin,
+        status: get("ENTITY.ENTITYSTATUS"),
+        legal_form: get("ENTITY.LEGALFORM.ENTITYLEGALFORMCODE"),
+    })
+}
+
+/// One MCA company-master row as a reference entity, or None without a valid CIN / name.
+fn mca_record(header: &[String], fields: &[String]) -> Option<ReferenceEntity> {
+    let get = |names: &[&str]| {
+        names.iter().find_map(|n| header.iter().position(|c| c == n)).and_then(|i| non_empty(fields.get(i)))
+    };
+    let cin = get(&["CORPORATE_IDENTIFICATION_NUMBER", "CIN"]).filter(|c| is_cin(c))?;
+    Some(ReferenceEntity {
+        name: get(&["COMPANY_NAME"])?,
+        cin: Some(cin),
+        status: get(&["COMPANY_STATUS"]),
+        legal_form: get(&["COMPANY_CLASS"]),
+        ..Default::default()
+    })
+}
+
+/// Upsert a chunk of records in one transaction. Returns the number written.
+fn write_chunk(conn: &mut Connection, chunk: &mut Vec<ReferenceEntity>, as_of: NaiveDate) -> Result<usize, String> {
+    let tx = conn.transaction().map_err(|e| format!("begin transaction: {}", e))?;
+    for rec in chunk.iter() {
+        upsert_reference_entity(&tx, rec, as_of)?;
+    }
+    tx.commit().map_err(|e| format!("commit: {}", e))?;
+    let n = chunk.len();
+    chunk.clear();
+    Ok(n)
+}
+
+/// Stream a reference file through `parse`, writing records in chunks. Returns the number
+/// of entities upserted.
+fn load_file(
+    conn: &mut Connection,
+    reader: impl BufRead,
+    as_of: NaiveDate,
+    mut parse: impl FnMut(&[String], &[String]) -> Option<ReferenceEntity>,
+) -> Result<usize, String> {
+    let mut chunk: Vec<ReferenceEntity> = Vec::with_capacity(CHUNK_SIZE);
+    let mut written = 0;
+    for_each_row(reader, |header, fields| {
+        if let Some(rec) = parse(header, fields) {
+            chunk.push(rec);
+            if chunk.len() >= CHUNK_SIZE {
+                written += write_chunk(conn, &mut chunk, as_of)?;
+            }
+        }
+        Ok(())
+    })?;
+    written += write_chunk(conn, &mut chunk, as_of)?;
+    Ok(written)
+}
+
+fn open_file(path: &str) -> Result<BufReader<File>, String> {
+    File::open(path).map(BufReader::new).map_err(|e| format!("open '{}': {}", path, e))
+}
+
+/// Load whichever reference files are configured, ISIN map first. Returns the number of
+/// entities upserted.
+fn load_all(
+    conn: &mut Connection,
+    gleif: Option<&str>,
+    gleif_isin: Option<&str>,
+    mca: Option<&str>,
+    countries: &HashSet<String>,
+    as_of: NaiveDate,
+) -> Result<usize, String> {
+    let isins = match gleif_isin {
+        Some(path) => read_isin_map(open_file(path)?)?,
+        None => HashMap::new(),
+    };
+    let mut loaded = 0;
+    if let Some(path) = gleif {
+        let n = load_file(conn, open_file(path)?, as_of, |h, f| gleif_record(h, f, countries, &isins))?;
+        info!("{}: {} LEI record(s) loaded from '{}'.", FEED_NAME, n, path);
+        loaded += n;
+    }
+    if let Some(path) = mca {
+        let n = load_file(conn, open_file(path)?, as_of, mca_record)?;
+        info!("{}: {} MCA compan(ies) loaded from '{}'.", FEED_NAME, n, path);
+        loaded += n;
+    }
+    // Listed companies loaded by the symbol-master feed get the same derived aliases.
+    let derived = derive_missing_aliases(conn, as_of)?;
+    info!("{}: {} derived alias(es) added to existing names.", FEED_NAME, derived);
+    Ok(loaded)
+}
+
+/// Entry point invoked by the batch-feed runner.
+pub fn run(app_config: Arc<config::Config>) -> FeedOutcome {
+    let path = |key: &str| get_plugin_cfg!(FEED_NAME, key, &app_config).filter(|p| !p.trim().is_empty());
+    let (gleif, gleif_isin, mca) = (path("gleif_file"), path("gleif_isin_file"), path("mca_file"));
+    if gleif.is_none() && mca.is_none() {
+        return FeedOutcome::fail("neither gleif_file nor mca_file is configured");
+    }
+    if gleif.is_some() && gleif_isin.is_none() {
+        warn!("{}: no gleif_isin_file; LEI records are merged with listed companies by CIN and name only.", FEED_NAME);
+    }
+    let countries: HashSet<String> = get_plugin_cfg!(FEED_NAME, "lei_countries", &app_config)
+        .unwrap_or_else(|| DEFAULT_LEI_COUNTRIES.to_string())
+        .split(',')
+        .map(|c| c.trim().to_uppercase())
+        .filter(|c| !c.is_empty() && c != "*")
+        .collect();
+
+    let mut conn = match crate::store::open_and_migrate(&get_database_filename(&app_config)) {
+        Ok(c) => c,
+        Err(e) => return FeedOutcome::fail(e),
+    };
+    match load_all(&mut conn, gleif.as_deref(), gleif_isin.as_deref(), mca.as_deref(), &countries, Utc::now().date_naive()) {
+        Ok(rows) => {
+            crate::metrics::record_db_writes(rows as u64);
+            FeedOutcome::ok(rows as i64, format!("{} reference entities loaded", rows))
+        }
+        Err(e) => {
+            error!("{}: {}", FEED_NAME, e);
+            crate::metrics::record_db_error();
+            FeedOutcome::fail(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::securities::{upsert_listing, ListedSecurity};
+
+    #[test]
+    fn test_parse_reference_files() {
+        let isins = read_isin_map(include_str!("testdata/gleif_isin_sample.csv").as_bytes()).unwrap();
+        assert_eq!(isins["5493003CWGDTZCDMI489"].len(), 2);
+
+        let india: HashSet<String> = HashSet::from(["IN".to_string()]);
+        let mut gleif = Vec::new();
+        for_each_row(include_str!("testdata/gleif_lei_sample.csv").as_bytes(), |h, f| {
+            gleif.extend(gleif_record(h, f, &india, &isins));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(gleif.len(), 3, "US entity filtered out");
+        assert_eq!(gleif[0].cin.as_deref(), Some("L17110MH1973PLC019786"));
+        assert_eq!(gleif[0].other_names, vec!["RIL"]);
+        assert_eq!(gleif[0].isins, vec!["INE002A01018", "INE002A08534"]);
+        assert_eq!(gleif[1].status.as_deref(), Some("INACTIVE"));
+
+        let mut mca = Vec::new();
+        for_each_row(include_str!("testdata/mca_company_master_sample.csv").as_bytes(), |h, f| {
+            mca.extend(mca_record(h, f));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(mca.len(), 3, "row without a CIN is dropped");
+        assert_eq!(mca[2].name, "SHARMA, GUPTA & SONS PRIVATE LIMITED");
+        assert!(is_cin("U65929KA2018PLC116815") && !is_cin("691011"));
+    }
+
+    #[test]
+    fn test_load_merges_with_listed_companies() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        crate::store::migrate(&conn).unwrap();
+        let as_of = NaiveDate::from_ymd_opt(2025, 6, 3).unwrap();
+        let listing = |isin: &str, name: &str, symbol: &str| ListedSecurity {
+            isin: isin.to_string(),
+            name: name.to_string(),
+            exchange: "NSE".to_string(),
+            symbol: symbol.to_string(),
+            sector: None,
+            listing_date: None,
+            canonical_name_source: true,
+        };
+        upsert_listing(&conn, &listing("INE002A01018", "Reliance Industries Limited", "RELIANCE"), as_of).unwrap();
+        upsert_listing(&conn, &listing("INE237A01028", "Kotak Mahindra Bank Limited", "KOTAKBANK"), as_of).unwrap();
+
+        let isins = read_isin_map(include_str!("testdata/gleif_isin_sample.csv").as_bytes()).unwrap();
+        let n = load_file(&mut conn, include_str!("testdata/gleif_lei_sample.csv").as_bytes(), as_of, |h, f| {
+            gleif_record(h, f, &HashSet::new(), &isins)
+        })
+        .unwrap();
+        assert_eq!(n, 4);
+        load_file(&mut conn, include_str!("testdata/mca_company_master_sample.csv").as_bytes(), as_of, mca_record).unwrap();
+
+        let ids: Vec<String> = conn
+            .prepare("SELECT entity_id FROM entities ORDER BY entity_id").unwrap()
+            .query_map([], |r| r.get(0)).unwrap().flatten().collect();
+        assert_eq!(ids, vec![
+            "cin:U65929KA2018PLC116815", "cin:U74999DL2015PTC282418", "isin:INE002A01018",
+            "isin:INE237A01028", "lei:335800QNDZ3C1L5OOA78", "lei:549300GKFG0RYRRQ1414",
+        ]);
+        let (lei, cin): (String, String) = conn
+            .query_row("SELECT lei, cin FROM entities WHERE entity_id='isin:INE237A01028'", [], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap();
+        assert_eq!((lei.as_str(), cin.as_str()), ("984500B2D5F6E7G8H9J0", "L65110MH1985PLC038137"));
+        let acronym: String = conn
+            .query_row("SELECT entity_id FROM entity_aliases WHERE alias_type='acronym' AND alias_norm='hdfc'", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(acronym, "lei:335800QNDZ3C1L5OOA78");
+    }
+}
@@ -0,0 +1,225 @@
+// file: feeds/feed_fbil_rates.rs
+// Purpose:
+//   Batch feed: load FBIL (Financial Benchmarks India) benchmark rates into `market_series`:
+//   FX reference rates, overnight/term MIBOR and the T-bill curve. Each dataset is a CSV
+//   export whose URL is configured on the feed entry (`fx_url`, `mibor_url`, `tbill_url`);
+//   datasets without a URL are skipped.
+//
+//   Columns are found by header name rather than position, since the exports differ in
+//   column order and labels ("Rate", "Rate (%)", "Yield (%)"). Instrument ids:
+//     FBIL:FX:USDINR      (unit INR; JPY is quoted per 100 JPY)
+//     FBIL:MIBOR:OVERNIGHT, FBIL:MIBOR:TERM_14_DAYS, ...   (unit PCT)
+//     FBIL:TBILL:91D, ...                                 (unit PCT)
+
+use std::sync::Arc;
+
+use log::{error, info, warn};
+
+use crate::cfg::get_market_data_db;
+use crate::feeds::{browser_client, split_csv_line, FeedOutcome};
+use crate::get_plugin_cfg;
+use crate::market_data::SeriesPoint;
+use crate::network::http_get;
+use crate::utils::parse_date_with_formats;
+
+pub const FEED_NAME: &str = "feed_fbil_rates";
+const SOURCE: &str = "FBIL";
+const DATE_FORMATS: [&str; 4] = ["%d-%m-%Y", "%d-%b-%Y", "%d/%m/%Y", "%Y-%m-%d"];
+
+/// The FBIL datasets this feed understands.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Dataset {
+    Fx,
+    Mibor,
+    TBill,
+}
+
+impl Dataset {
+    /// Feed config key holding the dataset's CSV URL.
+    fn url_key(&self) -> &'static str {
+        match self {
+            Dataset::Fx => "fx_url",
+            Dataset::Mibor => "mibor_url",
+            Dataset::TBill => "tbill_url",
+        }
+    }
+
+    fn table_ref(&self) -> &'static str {
+        match self {
+            Dataset::Fx => "fbil_fx_reference",
+            Dataset::Mibor => "fbil_mibor",
+            Dataset::TBill => "fbil_tbill",
+        }
+    }
+
+    /// Header words identifying the column that names the series within a row.
+    fn key_headers(&self) -> &'static [&'static str] {
+        match self {
+            Dataset::Fx => &["currency"],
+            Dataset::Mibor => &["benchmark", "tenor"],
+            Dataset::TBill => &["tenor"],
+        }
+    }
+
+    /// Build the instrument id and unit for a row's series label, or None if unrecognised.
+    fn instrument(&self, label: &str) -> Option<(String, String)> {
+        let upper = label.to_uppercase();
+        let tokens: Vec<&str> = upper
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|t| !t.is_empty())
+            .collect();
+        match self {
+            Dataset::Fx => {
+                let currencies: Vec<&str> = tokens.iter()
+                    .copied()
+                    .filter(|t| t.len() == 3 && t.chars().all(|c| c.is_ascii_alphabetic()))
+                    .collect();
+                if currencies.len() != 2 {
+                    return None;
+                }
+                let unit = if currencies[0] == "JPY" { "INR/100JPY" } else { "INR" };
+                Some((format!("FBIL:FX:{}{}", currencies[0], currencies[1]), unit.to_string()))
+            }
+            Dataset::Mibor => {
+                let name: Vec<&str> = tokens.into_iter().filter(|t| *t != "FBIL" && *t != "MIBOR").collect();
+                if name.is_empty() {
+                    return None;
+                }
+                Some((format!("FBIL:MIBOR:{}", name.join("_")), "PCT".to_string()))
+            }
+            Dataset::TBill => {
+                let days = tokens.iter().find(|t| t.chars().all(|c| c.is_ascii_digit()))?;
+                Some((format!("FBIL:TBILL:{}D", days), "PCT".to_string()))
+            }
+        }
+    }
+}
+
+/// Parse one FBIL CSV export into series points. Rows with a missing/non-numeric rate
+/// (e.g. "NA" on a non-publication day) are skipped.
+fn parse_fbil_csv(content: &str, dataset: Dataset) -> Vec<SeriesPoint> {
+    let mut lines = content.lines().filter(|l| !l.trim().is_empty());
+    let header: Vec<String> = match lines.next() {
+        Some(h) => split_csv_line(h).into_iter().map(|c| c.to_lowercase()).collect(),
+        None => return Vec::new(),
+    };
+    let date_idx = header.iter().position(|c| c.contains("date"));
+    let key_idx = header.iter().position(|c| dataset.key_headers().iter().any(|k| c.contains(k)));
+    let value_idx = header.iter()
+        .enumerate()
+        .position(|(i, c)| Some(i) != key_idx && (c.contains("rate") || c.contains("yield")));
+    let (date_idx, key_idx, value_idx) = match (date_idx, key_idx, value_idx) {
+        (Some(d), Some(k), Some(v)) => (d, k, v),
+        _ => {
+            warn!("{}: unrecognised {:?} header: {:?}", FEED_NAME, dataset, header);
+            return Vec::new();
+        }
+    };
+
+    let mut points = Vec::new();
+    for line in lines {
+        let fields = split_csv_line(line);
+        let field = |i: usize| fields.get(i).map(|s| s.as_str()).unwrap_or("");
+        let date = match parse_date_with_formats(field(date_idx), &DATE_FORMATS) {
+            Some(d) => d,
+            None => continue,
+        };
+        let value = match field(value_idx).parse::<f64>() {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        let (instrument, unit) = match dataset.instrument(field(key_idx)) {
+            Some(iu) => iu,
+            None => {
+                warn!("{}: unrecognised {:?} series '{}'", FEED_NAME, dataset, field(key_idx));
+                continue;
+            }
+        };
+        points.push(SeriesPoint {
+            source: SOURCE.to_string(),
+            instrument,
+            date,
+            value,
+            unit,
+            table_ref: dataset.table_ref().to_string(),
+        });
+    }
+    points
+}
+
+/// Entry point invoked by the batch-feed runner.
+pub fn run(app_config: Arc<config::Config>) -> FeedOutcome {
+    let db_path = get_market_data_db(&app_config);
+    let client = match browser_client() {
+        Ok(c) => c,
+        Err(e) => return FeedOutcome::fail(e),
+    };
+
+    let mut points = Vec::new();
+    let mut configured = 0;
+    for dataset in [Dataset::Fx, Dataset::Mibor, Dataset::TBill] {
+        let url = match get_plugin_cfg!(FEED_NAME, dataset.url_key(), &app_config) {
+            Some(u) if !u.is_empty() => u,
+            _ => continue,
+        };
+        configured += 1;
+        let parsed = parse_fbil_csv(&http_get(&url, &client, 3, 5), dataset);
+        info!("{}: {} point(s) from {:?} dataset.", FEED_NAME, parsed.len(), dataset);
+        points.extend(parsed);
+    }
+    if configured == 0 {
+        return FeedOutcome::fail("no FBIL dataset URLs configured (fx_url, mibor_url, tbill_url)");
+    }
+    if points.is_empty() {
+        return FeedOutcome::fail("no FBIL rates parsed");
+    }
+
+    match crate::market_data::save_series_to_sqlite(&points, &db_path) {
+        Ok(rows) => {
+            crate::metrics::record_db_writes(rows as u64);
+            FeedOutcome::ok(rows as i64, format!("{} FBIL rates loaded", rows))
+        }
+        Err(e) => {
+            crate::metrics::record_db_error();
+            FeedOutcome::fail(format!("save FBIL rates: {}", e))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn value_of(points: &[SeriesPoint], instrument: &str) -> Option<f64> {
+        points.iter().find(|p| p.instrument == instrument).map(|p| p.value)
+    }
+
+    #[test]
+    fn test_parse_fx_reference_rates() {
+        let points = parse_fbil_csv(include_str!("testdata/fbil_fx_sample.csv"), Dataset::Fx);
+        assert_eq!(points.len(), 4);
+        assert_eq!(value_of(&points, "FBIL:FX:USDINR"), Some(85.5863));
+        let jpy = points.iter().find(|p| p.instrument == "FBIL:FX:JPYINR").unwrap();
+        assert_eq!(jpy.unit, "INR/100JPY");
+        assert_eq!(jpy.date, NaiveDate::from_ymd_opt(2025, 6, 3).unwrap());
+    }
+
+    #[test]
+    fn test_parse_mibor_skips_missing_rates() {
+        let points = parse_fbil_csv(include_str!("testdata/fbil_mibor_sample.csv"), Dataset::Mibor);
+        assert_eq!(points.len(), 3);
+        assert_eq!(value_of(&points, "FBIL:MIBOR:OVERNIGHT"), Some(5.80));
+        assert_eq!(value_of(&points, "FBIL:MIBOR:TERM_14_DAYS"), Some(5.95));
+        assert_eq!(value_of(&points, "FBIL:MIBOR:TERM_1_MONTH"), Some(6.10));
+        assert!(points.iter().all(|p| p.unit == "PCT"));
+    }
+
+    #[test]
+    fn test_parse_tbill_curve() {
+        let points = parse_fbil_csv(include_str!("testdata/fbil_tbill_sample.csv"), Dataset::TBill);
+        let instruments: Vec<&str> = points.iter().map(|p| p.instrument.as_str()).collect();
+        assert_eq!(instruments, vec!["FBIL:TBILL:14D", "FBIL:TBILL:91D", "FBIL:TBILL:182D", "FBIL:TBILL:364D"]);
+        assert_eq!(value_of(&points, "FBIL:TBILL:91D"), Some(5.6134));
+    }
+}
@@ -0,0 +1,397 @@
+// file: feeds/feed_gazetteer.rs
+// Purpose:
+//   Batch feed: load the bulk gazetteer (`store::geo`) that `mod_geocode` resolves place names
+//   against, from local files:
+//     - a GeoNames dump (`geonames_file`: cities500.txt / cities15000.txt, a country file such
+//       as IN.txt, or allCountries.txt; tab-separated, no header), keeping populated places,
+//       countries and ADM1/ADM2 areas, filtered to `countries` (comma-separated ISO codes,
+//       default "*" = all) and `min_population`. ADM1/ADM2 codes are turned into names with
+//       GeoNames' admin1CodesASCII.txt / admin2Codes.txt (`geonames_admin1_file`,
+//       `geonames_admin2_file`);
+//     - the India Post PIN directory (`pin_directory_file`, the data.gov.in "All India Pincode
+//       Directory" as CSV, or as a spreadsheet read with calamine): one feature per PIN,
+//       named after its head / sub post office, with every office name of the PIN as an
+//       alias, its district as ADM2 and its state as ADM1.
+//   The dumps are large downloads refreshed by hand, so they are read from disk, streamed
+//   where the format allows, and written in chunked transactions to the main metadata DB
+//   (`completed_urls_datafile`), where `mod_geocode` reads them.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::sync::{Arc, OnceLock};
+
+use calamine::{open_workbook_auto, Reader};
+use log::{error, info, warn};
+use regex::Regex;
+use rusqlite::Connection;
+
+use crate::cfg::get_database_filename;
+use crate::feeds::{split_csv_line, FeedOutcome};
+use crate::get_plugin_cfg;
+use crate::store::geo::{upsert_feature, GeoFeature};
+
+pub const FEED_NAME: &str = "feed_gazetteer";
+/// Features written per transaction.
+const CHUNK_SIZE: usize = 5000;
+/// GeoNames area features kept besides populated places (feature class P).
+const AREA_CODES: [&str; 3] = ["PCLI", "ADM1", "ADM2"];
+/// Spreadsheet extensions read with calamine; anything else is read as CSV.
+const SPREADSHEET_EXTENSIONS: [&str; 4] = ["xlsx", "xlsm", "xls", "ods"];
+
+/// A feature with the extra names it is looked up by.
+type NamedFeature = (GeoFeature, Vec<String>);
+
+/// GeoNames admin code file (`IN.16<TAB>Maharashtra<TAB>...`): code → name.
+fn read_admin_codes(reader: impl BufRead) -> Result<HashMap<String, String>, String> {
+    let mut codes = HashMap::new();
+    for line in reader.lines() {
+        let line = line.map_err(|e| format!("read admin codes: {}", e))?;
+        let mut fields = line.split('\t');
+        if let (Some(code), Some(name)) = (fields.next(), fields.next()) {
+            codes.insert(code.to_string(), name.to_string());
+        }
+    }
+    Ok(codes)
+}
+
+/// Filters and code tables applied to GeoNames rows.
+struct GeonamesFilter {
+    countries: HashSet<String>,
+    min_population: i64,
+    admin1: HashMap<String, String>,
+    admin2: HashMap<String, String>,
+}
+
+/// One GeoNames dump row as a feature with its ASCII / Latin-script alternate names, or None
+/// if it is not a place or area kept by `filter`.
+fn geonames_record(line: &str, filter: &GeonamesFilter) -> Option<NamedFeature> {
+    let f: Vec<&str> = line.split('\t').collect();
+    if f.len() < 15 {
+        return None;
+    }
+    let (class, code, country) = (f[6], f[7], f[8]);
+    if !(class == "P" || (class == "A" && AREA_CODES.contains(&code))) {
+        return None;
+    }
+    if !filter.countries.is_empty() && !filter.countries.contains(country) {
+        return None;
+    }
+    let population = f[14].parse::<i64>().unwrap_or(0);
+    // Areas are kept whatever their population.
+    if class == "P" && population < filter.min_population {
+        return None;
+    }
+    let admin = |codes: &HashMap<String, String>, key: String| codes.get(&key).cloned().unwrap_or_default();
+    let adm1 = admin(&filter.admin1, format!("{}.{}", country, f[10]));
+    let adm2 = admin(&filter.admin2, format!("{}.{}.{}", country, f[10], f[11]));
+    let mut names: Vec<String> = vec![f[2].to_string()];
+    // Latin-script alternate names only; all-caps ones are mostly airport / postal codes.
+    names.extend(
+        f[3].split(',')
+            .filter(|n| n.is_ascii() && n.len() >= 3 && n.chars().any(|c| c.is_ascii_lowercase()))
+            .map(str::to_string),
+    );
+    let feature = GeoFeature {
+        feature_id: f[0].to_string(),
+        name: f[1].to_string(),
+        lat: f[4].parse().ok(),
+        lon: f[5].parse().ok(),
+        feature_code: code.to_string(),
+        country: country.to_string(),
+        adm1,
+        adm2,
+        population,
+        source: "geonames".to_string(),
+    };
+    Some((feature, names))
+}
+
+/// "Marol Bazar S.O" → "Marol Bazar": post-office type suffixes removed.
+fn office_place_name(office: &str) -> String {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| Regex::new(r"(?i)\s+(?:[HSB]\.?\s?O\.?|G\.?P\.?O\.?)$").expect("office suffix regex"));
+    re.replace(office.trim(), "").trim().to_string()
+}
+
+/// "ANDHRA PRADESH" → "Andhra Pradesh".
+fn title_case(s: &str) -> String {
+    s.split_whitespace()
+        .map(|w| {
+            let mut chars = w.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Group PIN directory rows (header lower-cased) into one feature per PIN. Returns the
+/// features and the number of rows skipped for a missing / malformed PIN.
+fn pin_records(header: &[String], rows: impl Iterator<Item = Vec<String>>) -> Result<(Vec<NamedFeature>, usize), String> {
+    let col = |names: &[&str]| names.iter().find_map(|n| header.iter().position(|h| h == n));
+    let (Some(office_idx), Some(pin_idx)) = (col(&["officename", "office name"]), col(&["pincode", "pin code"])) else {
+        return Err(format!("unrecognised PIN directory header: {:?}", header));
+    };
+    let (type_idx, district_idx, state_idx) = (col(&["officetype", "office type"]), col(&["district", "districtname"]), col(&["statename", "state"]));
+    let (lat_idx, lon_idx) = (col(&["latitude"]), col(&["longitude"]));
+
+    // PIN → (office rank, feature); lower rank wins the name: head, then sub, then branch office.
+    let mut by_pin: BTreeMap<String, (u8, NamedFeature)> = BTreeMap::new();
+    let mut skipped = 0;
+    for fields in rows {
+        let get = |idx: Option<usize>| idx.and_then(|i| fields.get(i)).map(|v| v.trim()).unwrap_or_default();
+        let pin = get(Some(pin_idx));
+        if pin.len() != 6 || !pin.bytes().all(|b| b.is_ascii_digit()) || pin.starts_with('0') {
+            skipped += 1;
+            continue;
+        }
+        let name = office_place_name(get(Some(office_idx)));
+        if name.is_empty() {
+            skipped += 1;
+            continue;
+        }
+        let rank = match get(type_idx).to_ascii_uppercase().as_str() {
+            "HO" => 0,
+            "SO" | "PO" => 1,
+            _ => 2,
+        };
+        let coord = |idx: Option<usize>, limit: f64| get(idx).parse::<f64>().ok().filter(|v| v.abs() <= limit && *v != 0.0);
+        let (lat, lon) = match (coord(lat_idx, 90.0), coord(lon_idx, 180.0)) {
+            (Some(lat), Some(lon)) => (Some(lat), Some(lon)),
+            _ => (None, None),
+        };
+        let Some((best_rank, (feature, names))) = by_pin.get_mut(pin) else {
+            let feature = GeoFeature {
+                feature_id: format!("IN-{}", pin),
+                name,
+                lat,
+                lon,
+                feature_code: "PO".to_string(),
+                country: "IN".to_string(),
+                adm1: title_case(get(state_idx)),
+                adm2: title_case(get(district_idx)),
+                source: "india_post".to_string(),
+                ..Default::default()
+            };
+            by_pin.insert(pin.to_string(), (rank, (feature, Vec::new())));
+            continue;
+        };
+        // The preferred office names the area (and places it, if it has coordinates); the
+        // others become aliases.
+        if rank < *best_rank {
+            names.push(std::mem::replace(&mut feature.name, name.clone()));
+            *best_rank = rank;
+            if lat.is_some() {
+                (feature.lat, feature.lon) = (lat, lon);
+            }
+        }
+        if feature.lat.is_none() {
+            (feature.lat, feature.lon) = (lat, lon);
+        }
+        if feature.name != name && !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    Ok((by_pin.into_values().map(|(_, f)| f).collect(), skipped))
+}
+
+/// Read a PIN directory file: a spreadsheet's first sheet, or CSV. Returns the lower-cased
+/// header and the data rows.
+fn read_table(path: &str) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
+    let is_sheet = std::path::Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| SPREADSHEET_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()));
+    let mut rows: Vec<Vec<String>> = if is_sheet {
+        let mut workbook = open_workbook_auto(path).map_err(|e| format!("open '{}': {}", path, e))?;
+        let range = workbook
+            .worksheet_range_at(0)
+            .ok_or_else(|| format!("'{}' has no worksheet", path))?
+            .map_err(|e| format!("read '{}': {}", path, e))?;
+        range.rows().map(|r| r.iter().map(|c| c.to_string()).collect()).collect()
+    } else {
+        let reader = open_file(path)?;
+        let mut rows = Vec::new();
+        for line in reader.lines() {
+            let line = line.map_err(|e| format!("read '{}': {}", path, e))?;
+            if !line.trim().is_empty() {
+                rows.push(split_csv_line(line.trim_start_matches('\u{feff}')));
+            }
+        }
+        rows
+    };
+    if rows.is_empty() {
+        return Err(format!("'{}' is empty", path));
+    }
+    let header = rows.remove(0).into_iter().map(|h| h.trim().to_lowercase()).collect();
+    Ok((header, rows))
+}
+
+/// Upsert a chunk of features in one transaction. Returns the number written.
+fn write_chunk(conn: &mut Connection, chunk: &mut Vec<NamedFeature>) -> Result<usize, String> {
+    let tx = conn.transaction().map_err(|e| format!("begin transaction: {}", e))?;
+    for (feature, names) in chunk.iter() {
+        upsert_feature(&tx, feature, names)?;
+    }
+    tx.commit().map_err(|e| format!("commit: {}", e))?;
+    let n = chunk.len();
+    chunk.clear();
+    Ok(n)
+}
+
+/// Stream a GeoNames dump into the gazetteer. Returns the number of features written.
+fn load_geonames(conn: &mut Connection, reader: impl BufRead, filter: &GeonamesFilter) -> Result<usize, String> {
+    let mut chunk: Vec<NamedFeature> = Vec::with_capacity(CHUNK_SIZE);
+    let mut written = 0;
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| format!("read line {}: {}", i + 1, e))?;
+        if let Some(rec) = geonames_record(&line, filter) {
+            chunk.push(rec);
+            if chunk.len() >= CHUNK_SIZE {
+                written += write_chunk(conn, &mut chunk)?;
+            }
+        }
+    }
+    written += write_chunk(conn, &mut chunk)?;
+    Ok(written)
+}
+
+/// Write PIN features in chunks. Returns the number written.
+fn load_pins(conn: &mut Connection, features: Vec<NamedFeature>) -> Result<usize, String> {
+    let mut written = 0;
+    for chunk in features.chunks(CHUNK_SIZE) {
+        written += write_chunk(conn, &mut chunk.to_vec())?;
+    }
+    Ok(written)
+}
+
+fn open_file(path: &str) -> Result<BufReader<File>, String> {
+    File::open(path).map(BufReader::new).map_err(|e| format!("open '{}': {}", path, e))
+}
+
+/// Entry point invoked by the batch-feed runner.
+pub fn run(app_config: Arc<config::Config>) -> FeedOutcome {
+    let path = |key: &str| get_plugin_cfg!(FEED_NAME, key, &app_config).filter(|p| !p.trim().is_empty());
+    let (geonames, pins) = (path("geonames_file"), path("pin_directory_file"));
+    if geonames.is_none() && pins.is_none() {
+        return FeedOutcome::fail("neither geonames_file nor pin_directory_file is configured");
+    }
+    let admin_codes = |key: &str| match path(key) {
+        Some(p) => open_file(&p).and_then(read_admin_codes).unwrap_or_else(|e| {
+            warn!("{}: {}; {} names left empty.", FEED_NAME, e, key);
+            HashMap::new()
+        }),
+        None => HashMap::new(),
+    };
+    let filter = GeonamesFilter {
+        countries: get_plugin_cfg!(FEED_NAME, "countries", &app_config)
+            .unwrap_or_default()
+            .split(',')
+            .map(|c| c.trim().to_uppercase())
+            .filter(|c| !c.is_empty() && c != "*")
+            .collect(),
+        min_population: get_plugin_cfg!(FEED_NAME, "min_population", &app_config).and_then(|v| v.parse().ok()).unwrap_or(0),
+        admin1: admin_codes("geonames_admin1_file"),
+        admin2: admin_codes("geonames_admin2_file"),
+    };
+
+    let mut conn = match crate::store::open_and_migrate(&get_database_filename(&app_config)) {
+        Ok(c) => c,
+        Err(e) => return FeedOutcome::fail(e),
+    };
+    let mut load = || -> Result<usize, String> {
+        let mut loaded = 0;
+        if let Some(p) = &geonames {
+            let n = load_geonames(&mut conn, open_file(p)?, &filter)?;
+            info!("{}: {} GeoNames feature(s) loaded from '{}'.", FEED_NAME, n, p);
+            loaded += n;
+        }
+        if let Some(p) = &pins {
+            let (header, rows) = read_table(p)?;
+            let (features, skipped) = pin_records(&header, rows.into_iter())?;
+            let n = load_pins(&mut conn, features)?;
+            info!("{}: {} PIN area(s) loaded from '{}' ({} row(s) skipped).", FEED_NAME, n, p, skipped);
+            loaded += n;
+        }
+        Ok(loaded)
+    };
+    match load() {
+        Ok(rows) => {
+            crate::metrics::record_db_writes(rows as u64);
+            FeedOutcome::ok(rows as i64, format!("{} gazetteer features loaded", rows))
+        }
+        Err(e) => {
+            error!("{}: {}", FEED_NAME, e);
+            crate::metrics::record_db_error();
+            FeedOutcome::fail(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::geo::lookup;
+
+    fn filter(countries: &[&str], min_population: i64) -> GeonamesFilter {
+        GeonamesFilter {
+            countries: countries.iter().map(|c| c.to_string()).collect(),
+            min_population,
+            admin1: read_admin_codes(include_str!("testdata/geonames_admin1_sample.txt").as_bytes()).unwrap(),
+            admin2: read_admin_codes(include_str!("testdata/geonames_admin2_sample.txt").as_bytes()).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_geonames_records_filtered_with_admin_names() {
+        let rows: Vec<NamedFeature> = include_str!("testdata/geonames_sample.txt")
+            .lines()
+            .filter_map(|l| geonames_record(l, &filter(&["IN"], 100_000)))
+            .collect();
+        let ids: Vec<&str> = rows.iter().map(|(f, _)| f.feature_id.as_str()).collect();
+        assert_eq!(ids, vec!["1275339", "1278149", "1264418", "1275715"], "small town, river and other countries dropped");
+        let (mumbai, names) = &rows[0];
+        assert_eq!((mumbai.adm1.as_str(), mumbai.population), ("Maharashtra", 12_691_836));
+        assert!(names.contains(&"Bombay".to_string()) && !names.contains(&"BOM".to_string()));
+        assert_eq!(rows[1].0.adm2, "Aurangabad");
+
+        let all = include_str!("testdata/geonames_sample.txt").lines().filter_map(|l| geonames_record(l, &filter(&[], 0))).count();
+        assert_eq!(all, 8);
+    }
+
+    #[test]
+    fn test_pin_directory_grouped_per_pin() {
+        let mut lines = include_str!("testdata/india_post_pin_sample.csv").lines();
+        let header: Vec<String> = split_csv_line(lines.next().unwrap()).into_iter().map(|h| h.to_lowercase()).collect();
+        let (features, skipped) = pin_records(&header, lines.map(split_csv_line)).unwrap();
+        assert_eq!(skipped, 1, "malformed PIN");
+        let ids: Vec<&str> = features.iter().map(|(f, _)| f.feature_id.as_str()).collect();
+        assert_eq!(ids, vec!["IN-400059", "IN-400069", "IN-824101"]);
+        let (marol, names) = &features[0];
+        assert_eq!((marol.name.as_str(), marol.lat), ("Marol Bazar", Some(19.1143)));
+        assert_eq!(names, &vec!["Kondivita".to_string()]);
+        assert_eq!((marol.adm1.as_str(), marol.adm2.as_str()), ("Maharashtra", "Mumbai"));
+        assert_eq!(features[2].0.name, "Aurangabad (Bihar)");
+        assert_eq!(office_place_name("Delhi G.P.O."), "Delhi");
+    }
+
+    #[test]
+    fn test_load_into_gazetteer() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        crate::store::migrate(&conn).unwrap();
+        let n = load_geonames(&mut conn, include_str!("testdata/geonames_sample.txt").as_bytes(), &filter(&[], 0)).unwrap();
+        assert_eq!(n, 8);
+        let mut lines = include_str!("testdata/india_post_pin_sample.csv").lines();
+        let header: Vec<String> = split_csv_line(lines.next().unwrap()).into_iter().map(|h| h.to_lowercase()).collect();
+        let (features, _) = pin_records(&header, lines.map(split_csv_line)).unwrap();
+        assert_eq!(load_pins(&mut conn, features).unwrap(), 3);
+
+        let aurangabad = lookup(&conn, "Aurangabad").unwrap();
+        assert_eq!(aurangabad.iter().map(|f| f.adm1.as_str()).collect::<Vec<_>>(), vec!["Maharashtra", "Bihar"]);
+        assert_eq!(lookup(&conn, "Chhatrapati Sambhajinagar").unwrap()[0].feature_id, "1278149");
+        assert_eq!(lookup(&conn, "Kondivita").unwrap()[0].feature_id, "IN-400059");
+    }
+}
@@ -0,0 +1,174 @@
+// file: feeds/feed_index_constituents.rs
+// Purpose:
+//   Batch feed: download the constituent lists of the main NSE indices and record index
+//   membership in the securities master (`store::securities`). Each constituent is upserted
+//   as a listed NSE security (with its NSE industry as sector), then the index's open
+//   memberships are replaced, so additions and removals are kept with `valid_from/valid_to`.
+//
+//   Like the symbol-master feed, this writes to the main metadata DB.
+
+use std::sync::Arc;
+
+use chrono::{NaiveDate, Utc};
+use log::{error, info, warn};
+
+use crate::cfg::get_database_filename;
+use crate::feeds::{browser_client, split_csv_line, FeedOutcome};
+use crate::network::http_get;
+use crate::store::securities::{set_index_constituents, upsert_listing, ListedSecurity};
+
+pub const FEED_NAME: &str = "feed_index_constituents";
+
+/// Index name → file stem of its constituent CSV on the NSE archives.
+const INDICES: [(&str, &str); 3] = [
+    ("NIFTY 50", "ind_nifty50list"),
+    ("NIFTY NEXT 50", "ind_niftynext50list"),
+    ("NIFTY 500", "ind_nifty500list"),
+];
+
+fn constituents_url(stem: &str) -> String {
+    format!("https://nsearchives.nseindia.com/content/indices/{}.csv", stem)
+}
+
+/// Parse an NSE index constituent file (Company Name, Industry, Symbol, Series, ISIN Code).
+fn parse_constituents(csv: &str) -> Vec<ListedSecurity> {
+    let mut lines = csv.lines().filter(|l| !l.trim().is_empty());
+    let header: Vec<String> = match lines.next() {
+        Some(h) => split_csv_line(h).into_iter().map(|c| c.to_uppercase()).collect(),
+        None => return Vec::new(),
+    };
+    let col = |name: &str| header.iter().position(|c| c == name);
+    let (name_idx, symbol_idx, isin_idx) = match (col("COMPANY NAME"), col("SYMBOL"), col("ISIN CODE")) {
+        (Some(n), Some(s), Some(i)) => (n, s, i),
+        _ => {
+            warn!("{}: unrecognised constituent file header: {:?}", FEED_NAME, header);
+            return Vec::new();
+        }
+    };
+    let industry_idx = col("INDUSTRY");
+
+    lines
+        .filter_map(|line| {
+            let fields = split_csv_line(line);
+            let get = |i: usize| fields.get(i).map(|s| s.as_str()).unwrap_or("");
+            if get(isin_idx).is_empty() || get(symbol_idx).is_empty() {
+                return None;
+            }
+            let industry = industry_idx.map(get).unwrap_or("");
+            Some(ListedSecurity {
+                isin: get(isin_idx).to_string(),
+                name: get(name_idx).to_string(),
+                exchange: "NSE".to_string(),
+                symbol: get(symbol_idx).to_string(),
+                sector: if industry.is_empty() { None } else { Some(industry.to_string()) },
+                listing_date: None,
+                canonical_name_source: false,
+            })
+        })
+        .collect()
+}
+
+/// Upsert one index's constituents and replace its open memberships. Returns
+/// (constituents, added, removed).
+fn load_index(
+    conn: &rusqlite::Connection,
+    index_name: &str,
+    constituents: &[ListedSecurity],
+    as_of: NaiveDate,
+) -> Result<(usize, usize, usize), String> {
+    let mut entity_ids = Vec::with_capacity(constituents.len());
+    for sec in constituents {
+        entity_ids.push(upsert_listing(conn, sec, as_of)?);
+    }
+    let (added, removed) = set_index_constituents(conn, index_name, &entity_ids, as_of)?;
+    Ok((entity_ids.len(), added, removed))
+}
+
+/// Entry point invoked by the batch-feed runner.
+pub fn run(app_config: Arc<config::Config>) -> FeedOutcome {
+    let db_path = get_database_filename(&app_config);
+    let client = match browser_client() {
+        Ok(c) => c,
+        Err(e) => return FeedOutcome::fail(e),
+    };
+    let as_of = Utc::now().date_naive();
+
+    // Download everything first so the DB transaction is not held across network calls.
+    let mut lists = Vec::new();
+    for (index_name, stem) in INDICES {
+        let constituents = parse_constituents(&http_get(&constituents_url(stem), &client, 3, 5));
+        if constituents.is_empty() {
+            // An empty list would remove every member; keep the previous membership instead.
+            warn!("{}: no constituents parsed for {}; skipped.", FEED_NAME, index_name);
+            continue;
+        }
+        lists.push((index_name, constituents));
+    }
+    if lists.is_empty() {
+        return FeedOutcome::fail("no index constituent file could be loaded");
+    }
+
+    let mut conn = match crate::store::open_and_migrate(&db_path) {
+        Ok(c) => c,
+        Err(e) => return FeedOutcome::fail(e),
+    };
+    let result = conn
+        .transaction()
+        .map_err(|e| format!("begin transaction: {}", e))
+        .and_then(|tx| {
+            let mut rows = 0;
+            for (index_name, constituents) in &lists {
+                let (count, added, removed) = load_index(&tx, index_name, constituents, as_of)?;
+                info!("{}: {} has {} constituent(s), {} added, {} removed.", FEED_NAME, index_name, count, added, removed);
+                rows += count;
+            }
+            tx.commit().map_err(|e| format!("commit: {}", e))?;
+            Ok(rows)
+        });
+
+    match result {
+        Ok(rows) => {
+            crate::metrics::record_db_writes(rows as u64);
+            FeedOutcome::ok(rows as i64, format!("{} index constituent(s) across {} indices", rows, lists.len()))
+        }
+        Err(e) => {
+            error!("{}: {}", FEED_NAME, e);
+            crate::metrics::record_db_error();
+            FeedOutcome::fail(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_constituents_sample() {
+        let list = parse_constituents(include_str!("testdata/nse_index_constituents_sample.csv"));
+        assert_eq!(list.len(), 3);
+        let ril = list.iter().find(|s| s.symbol == "RELIANCE").unwrap();
+        assert_eq!(ril.isin, "INE002A01018");
+        assert_eq!(ril.sector.as_deref(), Some("Oil, Gas & Consumable Fuels"), "quoted comma kept");
+        assert!(parse_constituents("Access Denied").is_empty());
+    }
+
+    #[test]
+    fn test_load_index_tracks_membership_changes() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        crate::store::migrate(&conn).unwrap();
+        let list = parse_constituents(include_str!("testdata/nse_index_constituents_sample.csv"));
+        let d1 = NaiveDate::from_ymd_opt(2025, 6, 3).unwrap();
+        assert_eq!(load_index(&conn, "NIFTY 50", &list, d1).unwrap(), (3, 3, 0));
+
+        // HDFCBANK drops out at the next rebalance.
+        let d2 = NaiveDate::from_ymd_opt(2025, 9, 30).unwrap();
+        let without: Vec<ListedSecurity> = list.into_iter().filter(|s| s.symbol != "HDFCBANK").collect();
+        assert_eq!(load_index(&conn, "NIFTY 50", &without, d2).unwrap(), (2, 0, 1));
+
+        let valid_to: Option<String> = conn.query_row(
+            "SELECT valid_to FROM index_constituents WHERE index_name='NIFTY 50' AND entity_id='isin:INE040A01034'",
+            [], |r| r.get(0)).unwrap();
+        assert_eq!(valid_to.as_deref(), Some("2025-09-30"));
+    }
+}
@@ -1,30 +1,44 @@
 // file: feeds/feed_nse_bhavcopy.rs
 // Purpose:
-//   Batch feed: download the NSE Capital Market (equity) UDiFF bhavcopy and load it directly
+//   Batch feed: download the NSE Capital Market (equity) bhavcopy and load it directly
 //   into the market-data SQLite DB (`market_series`/`nse_cm_bhavcopy`). This replaces the
 //   bhavcopy-download path that previously lived in the `mod_in_nse` news retriever (roadmap
 //   point 2g) — here there is no Document; the feed writes its own data.
 //
 //   NSE publishes the day's file only after market close, and not at all on
-//   weekends/holidays, so we walk back over recent business days and use the first valid zip.
+//   weekends/holidays, so we walk back over recent trading sessions (per `crate::calendar`)
+//   and use the first valid zip.
+//   For history, `load_date` is driven by the date-range backfill (`batch --from/--to`):
+//   files before the UDiFF switch (8 July 2024) use the legacy archive URL and column layout, which are loaded
+//   into `nse_cm_bhavcopy_legacy`.
+//   Every file is validated (`feeds::dq`) before loading; a file failing a check is rejected.
 
 use std::io::Read;
 use std::sync::Arc;
-use std::time::Duration;
 
 use chrono::{NaiveDate, Utc};
 use log::{info, warn};
+use reqwest::blocking::Client;
 use zip::ZipArchive;
 
 use crate::cfg::get_market_data_db;
-use crate::feeds::FeedOutcome;
-use crate::network::http_get_binary;
+use crate::feeds::{browser_client, downloaded_file, dq, DateOutcome, FeedOutcome};
+use crate::network::http_get_binary_response;
 
 pub const FEED_NAME: &str = "feed_nse_bhavcopy";
+/// Exchange whose trading calendar decides which dates have a bhavcopy.
+pub const EXCHANGE: &str = "NSE";
 const NSE_LOOKBACK_BUSINESS_DAYS: usize = 5;
 
-/// NSE equity bhavcopy URL (UDiFF zip) for a given date.
+/// NSE equity bhavcopy URL (zip) for a given date, in the format in force on that date.
 fn nse_bhavcopy_url_for(date: NaiveDate) -> String {
+    if date < dq::UDIFF_START_DATE {
+        let month = date.format("%b").to_string().to_uppercase();
+        return format!(
+            "https://nsearchives.nseindia.com/content/historical/EQUITIES/{}/{}/cm{}{}{}bhav.csv.zip",
+            date.format("%Y"), month, date.format("%d"), month, date.format("%Y")
+        );
+    }
     let date_compact = date.format("%Y%m%d").to_string();
     format!(
         "https://nsearchives.nseindia.com/content/cm/BhavCopy_NSE_CM_0_0_0_{}_F_0000.csv.zip",
@@ -33,7 +47,7 @@ fn nse_bhavcopy_url_for(date: NaiveDate) -> String {
 }
 
 /// Extract the first `.csv` member from a bhavcopy zip's bytes.
-fn csv_from_zip(zip_bytes: &[u8]) -> Option<String> {
+pub(crate) fn csv_from_zip(zip_bytes: &[u8]) -> Option<String> {
     let cursor = std::io::Cursor::new(zip_bytes);
     let mut archive = ZipArchive::new(cursor).ok()?;
     for i in 0..archive.len() {
@@ -48,54 +62,74 @@ fn csv_from_zip(zip_bytes: &[u8]) -> Option<String> {
     None
 }
 
+/// Download and load the bhavcopy for one date. Returns `NoData` when NSE has no file for
+/// the date (non-trading day, or not yet published), and `Failed` when the download failed
+/// or was refused.
+pub fn load_date(client: &Client, date: NaiveDate, db_path: &str) -> DateOutcome {
+    let url = nse_bhavcopy_url_for(date);
+    let date_str = date.format("%Y-%m-%d").to_string();
+    info!("{}: trying bhavcopy for {}: {}", FEED_NAME, date_str, url);
+
+    let zip_bytes = match downloaded_file(http_get_binary_response(&url, client), &url) {
+        Ok(bytes) => bytes,
+        Err(outcome) => {
+            warn!("{}: no bhavcopy for {}: {:?}", FEED_NAME, date_str, outcome);
+            return outcome;
+        }
+    };
+
+    let csv = match csv_from_zip(zip_bytes.as_ref()) {
+        Some(c) => c,
+        None => return DateOutcome::Failed(format!("no CSV inside NSE bhavcopy zip for {}", date_str)),
+    };
+
+    let (spec, table) = if crate::market_data::is_nse_legacy_header(csv.lines().next().unwrap_or("")) {
+        (&dq::NSE_LEGACY_SPEC, "nse_cm_bhavcopy_legacy")
+    } else {
+        (&dq::UDIFF_SPEC, "nse_cm_bhavcopy")
+    };
+    let report = dq::validate_bhavcopy(&csv, spec, dq::previous_day_rows(db_path, table, date));
+    dq::record_report(db_path, FEED_NAME, date, &report);
+    if report.has_errors() {
+        return DateOutcome::Failed(format!("NSE bhavcopy {} rejected — {}", date_str, report.summary()));
+    }
+
+    match crate::market_data::save_nse_bhavcopy_csv(&csv, db_path) {
+        Ok(rows) => {
+            crate::metrics::record_db_writes(rows as u64);
+            DateOutcome::Loaded(rows, report.summary())
+        }
+        Err(e) => {
+            crate::metrics::record_db_error();
+            DateOutcome::Failed(format!("save NSE bhavcopy {}: {}", date_str, e))
+        }
+    }
+}
+
 /// Entry point invoked by the batch-feed runner.
 pub fn run(app_config: Arc<config::Config>) -> FeedOutcome {
     let db_path = get_market_data_db(&app_config);
     info!("{}: market-data DB = {}", FEED_NAME, db_path);
 
     // NSE requires a desktop Chrome User-Agent for bhavcopy downloads.
-    let client = match reqwest::blocking::Client::builder()
-        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36")
-        .timeout(Duration::from_secs(60))
-        .gzip(true)
-        .build()
-    {
+    let client = match browser_client() {
         Ok(c) => c,
-        Err(e) => return FeedOutcome::fail(format!("could not build HTTP client: {}", e)),
+        Err(e) => return FeedOutcome::fail(e),
     };
 
-    let candidate_days = crate::utils::recent_business_days(Utc::now().date_naive(), NSE_LOOKBACK_BUSINESS_DAYS);
+    let calendar = crate::calendar::load_calendar(&app_config);
+    let candidate_days = calendar.recent_trading_days(EXCHANGE, Utc::now().date_naive(), NSE_LOOKBACK_BUSINESS_DAYS);
 
     for date in candidate_days {
-        let url = nse_bhavcopy_url_for(date);
         let date_str = date.format("%Y-%m-%d").to_string();
-        info!("{}: trying bhavcopy for {}: {}", FEED_NAME, date_str, url);
-
-        let zip_bytes = http_get_binary(&url, &client);
-        // Empty or HTML (starts with '<') means the file is not published for this date.
-        if zip_bytes.is_empty() || zip_bytes.first() == Some(&b'<') {
-            warn!("{}: no bhavcopy for {} (not published / non-trading day), trying earlier.", FEED_NAME, date_str);
-            continue;
+        match load_date(&client, date, &db_path) {
+            DateOutcome::Loaded(rows, summary) => return FeedOutcome::ok(rows as i64, format!("NSE bhavcopy {} loaded; {}", date_str, summary)),
+            DateOutcome::NoData => continue,
+            DateOutcome::Failed(e) => return FeedOutcome::fail(e),
         }
-
-        let csv = match csv_from_zip(zip_bytes.as_ref()) {
-            Some(c) => c,
-            None => { warn!("{}: no CSV inside zip for {}, trying earlier.", FEED_NAME, date_str); continue; }
-        };
-
-        return match crate::market_data::save_nse_csv_to_sqlite(&csv, &db_path) {
-            Ok(rows) => {
-                crate::metrics::record_db_writes(rows as u64);
-                FeedOutcome::ok(rows as i64, format!("NSE bhavcopy {} loaded", date_str))
-            }
-            Err(e) => {
-                crate::metrics::record_db_error();
-                FeedOutcome::fail(format!("save NSE bhavcopy {}: {}", date_str, e))
-            }
-        };
     }
 
-    FeedOutcome::fail(format!("no NSE bhavcopy found in last {} business days", NSE_LOOKBACK_BUSINESS_DAYS))
+    FeedOutcome::fail(format!("no NSE bhavcopy found in last {} trading days", NSE_LOOKBACK_BUSINESS_DAYS))
 }
 
 #[cfg(test)]
@@ -111,6 +145,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_nse_legacy_bhavcopy_url_format() {
+        let date = NaiveDate::from_ymd_opt(2024, 7, 5).unwrap();
+        assert_eq!(
+            nse_bhavcopy_url_for(date),
+            "https://nsearchives.nseindia.com/content/historical/EQUITIES/2024/JUL/cm05JUL2024bhav.csv.zip"
+        );
+        let first_udiff = NaiveDate::from_ymd_opt(2024, 7, 8).unwrap();
+        assert!(nse_bhavcopy_url_for(first_udiff).ends_with("BhavCopy_NSE_CM_0_0_0_20240708_F_0000.csv.zip"));
+    }
+
     #[test]
     fn test_csv_from_zip_roundtrip() {
         // Build a tiny in-memory zip containing a .csv and a non-csv entry.
@@ -0,0 +1,127 @@
+// file: feeds/feed_nse_index_close.rs
+// Purpose:
+//   Batch feed: download NSE's daily closing values of all its indices (ind_close_all file)
+//   and load each index's close into `market_series` (instrument `NSE:INDEX:<INDEX NAME>`,
+//   e.g. `NSE:INDEX:NIFTY 50`, unit INDEX). The event study (`crate::event_study`) uses these
+//   as the market return; `load_date` lets the date-range backfill load the history its
+//   estimation window needs.
+
+use std::sync::Arc;
+
+use chrono::{NaiveDate, Utc};
+use log::{info, warn};
+use reqwest::blocking::Client;
+
+use crate::cfg::get_market_data_db;
+use crate::feeds::{browser_client, downloaded_file, split_csv_line, DateOutcome, FeedOutcome};
+use crate::market_data::SeriesPoint;
+use crate::network::http_get_binary_response;
+
+pub const FEED_NAME: &str = "feed_nse_index_close";
+/// Exchange whose trading calendar decides which dates have a file.
+pub const EXCHANGE: &str = "NSE";
+const SOURCE: &str = "NSE";
+const TABLE_REF: &str = "nse_ind_close_all";
+const LOOKBACK_TRADING_DAYS: usize = 5;
+
+/// `market_series` instrument id of an NSE index.
+pub fn index_instrument(index_name: &str) -> String {
+    format!("NSE:INDEX:{}", index_name.trim().to_uppercase())
+}
+
+fn index_close_url(date: NaiveDate) -> String {
+    format!("https://nsearchives.nseindia.com/content/indices/ind_close_all_{}.csv", date.format("%d%m%Y"))
+}
+
+/// Parse an ind_close_all file (Index Name, Index Date, ..., Closing Index Value, ...).
+/// Returns None if the header is not recognised.
+fn parse_index_close(csv: &str) -> Option<Vec<SeriesPoint>> {
+    let mut lines = csv.lines().filter(|l| !l.trim().is_empty());
+    let header: Vec<String> = split_csv_line(lines.next()?).into_iter().map(|c| c.to_uppercase()).collect();
+    let col = |name: &str| header.iter().position(|c| c == name);
+    let (name_idx, date_idx, close_idx) = (col("INDEX NAME")?, col("INDEX DATE")?, col("CLOSING INDEX VALUE")?);
+
+    Some(
+        lines
+            .filter_map(|line| {
+                let fields = split_csv_line(line);
+                let name = fields.get(name_idx)?;
+                let date = NaiveDate::parse_from_str(fields.get(date_idx)?, "%d-%m-%Y").ok()?;
+                let value = fields.get(close_idx)?.replace(',', "").parse::<f64>().ok()?;
+                Some(SeriesPoint {
+                    source: SOURCE.to_string(),
+                    instrument: index_instrument(name),
+                    date,
+                    value,
+                    unit: "INDEX".to_string(),
+                    table_ref: TABLE_REF.to_string(),
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Download and load the index closes for one date. Returns `NoData` when NSE has no file
+/// for the date (non-trading day, or not yet published).
+pub fn load_date(client: &Client, date: NaiveDate, db_path: &str) -> DateOutcome {
+    let url = index_close_url(date);
+    let csv = match downloaded_file(http_get_binary_response(&url, client), &url) {
+        Ok(body) => String::from_utf8_lossy(body.as_ref()).to_string(),
+        Err(outcome) => {
+            warn!("{}: no index close file for {}: {:?}", FEED_NAME, date, outcome);
+            return outcome;
+        }
+    };
+    let points = match parse_index_close(&csv) {
+        Some(p) if !p.is_empty() => p,
+        Some(_) => return DateOutcome::NoData,
+        None => return DateOutcome::Failed(format!("unrecognised index close file header at {}", url)),
+    };
+    match crate::market_data::save_series_to_sqlite(&points, db_path) {
+        Ok(rows) => {
+            crate::metrics::record_db_writes(rows as u64);
+            DateOutcome::Loaded(rows, format!("{} index closes", rows))
+        }
+        Err(e) => {
+            crate::metrics::record_db_error();
+            DateOutcome::Failed(format!("save NSE index closes {}: {}", date, e))
+        }
+    }
+}
+
+/// Entry point invoked by the batch-feed runner.
+pub fn run(app_config: Arc<config::Config>) -> FeedOutcome {
+    let db_path = get_market_data_db(&app_config);
+    let client = match browser_client() {
+        Ok(c) => c,
+        Err(e) => return FeedOutcome::fail(e),
+    };
+    let calendar = crate::calendar::load_calendar(&app_config);
+    for date in calendar.recent_trading_days(EXCHANGE, Utc::now().date_naive(), LOOKBACK_TRADING_DAYS) {
+        match load_date(&client, date, &db_path) {
+            DateOutcome::Loaded(rows, _) => {
+                info!("{}: loaded {} index closes for {}.", FEED_NAME, rows, date);
+                return FeedOutcome::ok(rows as i64, format!("NSE index closes {} loaded", date));
+            }
+            DateOutcome::NoData => continue,
+            DateOutcome::Failed(e) => return FeedOutcome::fail(e),
+        }
+    }
+    FeedOutcome::fail(format!("no NSE index close file found in last {} trading days", LOOKBACK_TRADING_DAYS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_index_close_sample() {
+        let points = parse_index_close(include_str!("testdata/nse_index_close_sample.csv")).unwrap();
+        assert_eq!(points.len(), 5);
+        let nifty = points.iter().find(|p| p.instrument == "NSE:INDEX:NIFTY 50").unwrap();
+        assert_eq!(nifty.value, 24542.50);
+        assert_eq!(nifty.date, NaiveDate::from_ymd_opt(2025, 6, 3).unwrap());
+        assert!(parse_index_close("Access Denied").is_none());
+        assert_eq!(index_close_url(nifty.date), "https://nsearchives.nseindia.com/content/indices/ind_close_all_03062025.csv");
+    }
+}
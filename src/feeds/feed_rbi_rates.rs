@@ -0,0 +1,119 @@
+// file: feeds/feed_rbi_rates.rs
+// Purpose:
+//   Batch feed: read the RBI's current policy rates and reserve ratios from the "Current
+//   Rates" panel of the RBI home page and record them in `market_series` as of the run date
+//   (instrument `RBI:<RATE>`, unit PCT). The panel only shows today's values, so running the
+//   feed daily builds up the history; unchanged rates simply repeat.
+
+use std::sync::Arc;
+
+use chrono::{NaiveDate, Utc};
+use log::{error, info, warn};
+use regex::Regex;
+use scraper::Html;
+
+use crate::cfg::get_market_data_db;
+use crate::feeds::{browser_client, FeedOutcome};
+use crate::get_plugin_cfg;
+use crate::market_data::SeriesPoint;
+use crate::network::http_get;
+
+pub const FEED_NAME: &str = "feed_rbi_rates";
+const SOURCE: &str = "RBI";
+const DEFAULT_URL: &str = "https://www.rbi.org.in/home.aspx";
+const TABLE_REF: &str = "rbi_current_rates";
+
+/// Panel label → instrument id suffix.
+const RATE_LABELS: [(&str, &str); 7] = [
+    ("Policy Repo Rate", "POLICY_REPO"),
+    ("Standing Deposit Facility Rate", "SDF"),
+    ("Marginal Standing Facility Rate", "MSF"),
+    ("Bank Rate", "BANK_RATE"),
+    ("Fixed Reverse Repo Rate", "FIXED_REVERSE_REPO"),
+    ("CRR", "CRR"),
+    ("SLR", "SLR"),
+];
+
+/// Extract the single-valued rates shown in the panel. Ranges (e.g. base rate, MCLR) are
+/// not in `RATE_LABELS` and are ignored.
+fn parse_current_rates(html: &str, as_of: NaiveDate) -> Vec<SeriesPoint> {
+    let document = Html::parse_document(html);
+    let text = document.root_element().text().collect::<Vec<_>>().join(" ");
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    let mut points = Vec::new();
+    for (label, id) in RATE_LABELS {
+        let pattern = format!(r"\b{}\s*:?\s*([0-9]+(?:\.[0-9]+)?)\s*%", regex::escape(label));
+        let re = match Regex::new(&pattern) {
+            Ok(r) => r,
+            Err(e) => { warn!("{}: bad pattern for '{}': {}", FEED_NAME, label, e); continue; }
+        };
+        let value = re.captures(&text)
+            .and_then(|c| c.get(1))
+            .and_then(|m| m.as_str().parse::<f64>().ok());
+        match value {
+            Some(value) => points.push(SeriesPoint {
+                source: SOURCE.to_string(),
+                instrument: format!("RBI:{}", id),
+                date: as_of,
+                value,
+                unit: "PCT".to_string(),
+                table_ref: TABLE_REF.to_string(),
+            }),
+            None => warn!("{}: '{}' not found on the rates panel.", FEED_NAME, label),
+        }
+    }
+    points
+}
+
+/// Entry point invoked by the batch-feed runner.
+pub fn run(app_config: Arc<config::Config>) -> FeedOutcome {
+    let db_path = get_market_data_db(&app_config);
+    let url = get_plugin_cfg!(FEED_NAME, "url", &app_config).unwrap_or_else(|| DEFAULT_URL.to_string());
+    let client = match browser_client() {
+        Ok(c) => c,
+        Err(e) => return FeedOutcome::fail(e),
+    };
+
+    let points = parse_current_rates(&http_get(&url, &client, 3, 5), Utc::now().date_naive());
+    if points.is_empty() {
+        return FeedOutcome::fail(format!("no rates found on {}", url));
+    }
+    info!("{}: {} rate(s) read.", FEED_NAME, points.len());
+
+    match crate::market_data::save_series_to_sqlite(&points, &db_path) {
+        Ok(rows) => {
+            crate::metrics::record_db_writes(rows as u64);
+            FeedOutcome::ok(rows as i64, format!("{} RBI rates loaded", rows))
+        }
+        Err(e) => {
+            crate::metrics::record_db_error();
+            FeedOutcome::fail(format!("save RBI rates: {}", e))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_current_rates_sample() {
+        let as_of = NaiveDate::from_ymd_opt(2025, 6, 10).unwrap();
+        let points = parse_current_rates(include_str!("testdata/rbi_current_rates_sample.html"), as_of);
+        assert_eq!(points.len(), RATE_LABELS.len());
+        let value = |id: &str| points.iter().find(|p| p.instrument == id).map(|p| p.value);
+        assert_eq!(value("RBI:POLICY_REPO"), Some(5.50));
+        assert_eq!(value("RBI:SDF"), Some(5.25));
+        // "Fixed Reverse Repo Rate" must not be read as the policy repo rate, nor vice versa.
+        assert_eq!(value("RBI:FIXED_REVERSE_REPO"), Some(3.35));
+        assert_eq!(value("RBI:SLR"), Some(18.00));
+        assert!(points.iter().all(|p| p.date == as_of && p.unit == "PCT"));
+    }
+
+    #[test]
+    fn test_parse_current_rates_empty_on_block_page() {
+        let as_of = NaiveDate::from_ymd_opt(2025, 6, 10).unwrap();
+        assert!(parse_current_rates("<html><body>Access Denied</body></html>", as_of).is_empty());
+    }
+}
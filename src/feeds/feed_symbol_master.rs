@@ -0,0 +1,272 @@
+// file: feeds/feed_symbol_master.rs
+// Purpose:
+//   Batch feed: load the NSE and BSE equity lists into the securities master
+//   (`store::securities`) so news can be linked to listed companies by name, ISIN, NSE symbol
+//   or BSE code. NSE symbol changes are applied with their effective dates, and securities
+//   missing from a complete list have their listing closed (delisted), keeping the history
+//   in `entity_aliases.valid_from/valid_to`.
+//
+//   Unlike the market-data feeds, this writes to the main metadata DB
+//   (`completed_urls_datafile`), where the news pipeline's `entities` live.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use chrono::{NaiveDate, Utc};
+use log::{error, info, warn};
+use serde_json::Value;
+
+use crate::cfg::get_database_filename;
+use crate::feeds::{browser_client, split_csv_line, FeedOutcome};
+use crate::get_plugin_cfg;
+use crate::network::{http_get, http_get_json_with_session};
+use crate::store::securities::{apply_symbol_change, close_missing_listings, upsert_listing, ListedSecurity};
+
+pub const FEED_NAME: &str = "feed_symbol_master";
+const NSE_EQUITY_LIST_URL: &str = "https://nsearchives.nseindia.com/content/equities/EQUITY_L.csv";
+const NSE_SYMBOL_CHANGE_URL: &str = "https://nsearchives.nseindia.com/content/equities/symbolchange.csv";
+const BSE_SCRIP_LIST_URL: &str = "https://api.bseindia.com/BseIndiaAPI/api/ListofScripData/w?Group=&Scripcode=&industry=&segment=Equity&status=Active";
+const BSE_SESSION_PAGES: [&str; 1] = ["https://www.bseindia.com/corporates/List_Scrips.html"];
+/// A list shorter than this is treated as a truncated download: nothing is delisted from it.
+const DEFAULT_MIN_COMPLETE_LIST: usize = 1000;
+
+/// Parse NSE's EQUITY_L.csv (SYMBOL, NAME OF COMPANY, SERIES, DATE OF LISTING, ..., ISIN NUMBER).
+fn parse_nse_equity_list(csv: &str) -> Vec<ListedSecurity> {
+    let mut lines = csv.lines().filter(|l| !l.trim().is_empty());
+    let header: Vec<String> = match lines.next() {
+        Some(h) => split_csv_line(h).into_iter().map(|c| c.to_uppercase()).collect(),
+        None => return Vec::new(),
+    };
+    let col = |name: &str| header.iter().position(|c| c == name);
+    let (symbol_idx, name_idx, isin_idx) = match (col("SYMBOL"), col("NAME OF COMPANY"), col("ISIN NUMBER")) {
+        (Some(s), Some(n), Some(i)) => (s, n, i),
+        _ => {
+            warn!("{}: unrecognised NSE equity list header: {:?}", FEED_NAME, header);
+            return Vec::new();
+        }
+    };
+    let listing_idx = col("DATE OF LISTING");
+
+    lines
+        .filter_map(|line| {
+            let fields = split_csv_line(line);
+            let get = |i: usize| fields.get(i).map(|s| s.as_str()).unwrap_or("");
+            if get(isin_idx).is_empty() || get(symbol_idx).is_empty() {
+                return None;
+            }
+            Some(ListedSecurity {
+                isin: get(isin_idx).to_string(),
+                name: get(name_idx).to_string(),
+                exchange: "NSE".to_string(),
+                symbol: get(symbol_idx).to_string(),
+                sector: None,
+                listing_date: listing_idx.and_then(|i| NaiveDate::parse_from_str(get(i), "%d-%b-%Y").ok()),
+                canonical_name_source: true,
+            })
+        })
+        .collect()
+}
+
+/// Parse NSE's symbolchange.csv (company, old symbol, new symbol, effective date) in
+/// effective-date order.
+fn parse_symbol_changes(csv: &str) -> Vec<(String, String, NaiveDate)> {
+    let mut changes: Vec<(String, String, NaiveDate)> = csv
+        .lines()
+        .filter_map(|line| {
+            let fields = split_csv_line(line);
+            if fields.len() < 4 {
+                return None;
+            }
+            // The header (if any) fails the date parse and is dropped here.
+            let date = NaiveDate::parse_from_str(&fields[3], "%d-%b-%Y").ok()?;
+            if fields[1].is_empty() || fields[2].is_empty() {
+                return None;
+            }
+            Some((fields[1].clone(), fields[2].clone(), date))
+        })
+        .collect();
+    changes.sort_by_key(|(_, _, date)| *date);
+    changes
+}
+
+/// Parse BSE's list-of-scrips JSON (array of objects with SCRIP_CD, ISIN_NUMBER, ...).
+fn parse_bse_scrip_list(json: &Value) -> Vec<ListedSecurity> {
+    let items = match json.as_array() {
+        Some(a) => a,
+        None => return Vec::new(),
+    };
+    let text = |item: &Value, key: &str| -> String {
+        item.get(key).and_then(|v| v.as_str()).unwrap_or("").trim().to_string()
+    };
+    items.iter()
+        .filter_map(|item| {
+            let isin = text(item, "ISIN_NUMBER");
+            let code = text(item, "SCRIP_CD");
+            if isin.is_empty() || code.is_empty() {
+                return None;
+            }
+            let issuer = text(item, "Issuer_Name");
+            let name = if issuer.is_empty() { text(item, "Scrip_Name") } else { issuer };
+            let sector = text(item, "INDUSTRY");
+            Some(ListedSecurity {
+                isin,
+                name,
+                exchange: "BSE".to_string(),
+                symbol: code,
+                sector: if sector.is_empty() { None } else { Some(sector) },
+                listing_date: None,
+                canonical_name_source: false,
+            })
+        })
+        .collect()
+}
+
+/// Upsert one exchange's list and, if it looks complete, close listings missing from it.
+/// Returns the number of securities upserted.
+fn load_exchange_list(
+    conn: &rusqlite::Connection,
+    exchange: &str,
+    securities: &[ListedSecurity],
+    min_complete: usize,
+    as_of: NaiveDate,
+) -> Result<usize, String> {
+    let mut seen = HashSet::new();
+    for sec in securities {
+        upsert_listing(conn, sec, as_of)?;
+        seen.insert(sec.isin.trim().to_uppercase());
+    }
+    if securities.len() >= min_complete {
+        let closed = close_missing_listings(conn, exchange, &seen, as_of)?;
+        info!("{}: {} {} listing(s) closed (no longer listed).", FEED_NAME, closed, exchange);
+    } else {
+        warn!("{}: {} list has only {} row(s); not closing missing listings.", FEED_NAME, exchange, securities.len());
+    }
+    Ok(securities.len())
+}
+
+/// Apply the symbol changes and both exchange lists in one transaction. Returns the number
+/// of securities upserted.
+fn load_all(
+    conn: &mut rusqlite::Connection,
+    symbol_changes: &[(String, String, NaiveDate)],
+    nse_list: &[ListedSecurity],
+    bse_list: &[ListedSecurity],
+    min_complete: usize,
+    as_of: NaiveDate,
+) -> Result<usize, String> {
+    let tx = conn.transaction().map_err(|e| format!("begin transaction: {}", e))?;
+    // Symbol changes first, so a renamed symbol's alias closes on its effective date rather
+    // than on today's date when the new symbol is first seen in the list.
+    let mut renamed = 0;
+    for (old, new, date) in symbol_changes {
+        if apply_symbol_change(&tx, old, new, *date)? {
+            renamed += 1;
+        }
+    }
+    info!("{}: applied {} symbol change(s).", FEED_NAME, renamed);
+    let mut upserted = 0;
+    if !nse_list.is_empty() {
+        upserted += load_exchange_list(&tx, "NSE", nse_list, min_complete, as_of)?;
+    }
+    if !bse_list.is_empty() {
+        upserted += load_exchange_list(&tx, "BSE", bse_list, min_complete, as_of)?;
+    }
+    tx.commit().map_err(|e| format!("commit: {}", e))?;
+    Ok(upserted)
+}
+
+/// Entry point invoked by the batch-feed runner.
+pub fn run(app_config: Arc<config::Config>) -> FeedOutcome {
+    let db_path = get_database_filename(&app_config);
+    let min_complete = get_plugin_cfg!(FEED_NAME, "min_complete_list", &app_config)
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_MIN_COMPLETE_LIST);
+    let client = match browser_client() {
+        Ok(c) => c,
+        Err(e) => return FeedOutcome::fail(e),
+    };
+    let as_of = Utc::now().date_naive();
+
+    let nse_list = parse_nse_equity_list(&http_get(&NSE_EQUITY_LIST_URL.to_string(), &client, 3, 5));
+    let symbol_changes = parse_symbol_changes(&http_get(&NSE_SYMBOL_CHANGE_URL.to_string(), &client, 3, 5));
+    let bse_list = http_get_json_with_session(BSE_SCRIP_LIST_URL, &BSE_SESSION_PAGES, &client, 3, 5)
+        .map(|j| parse_bse_scrip_list(&j))
+        .unwrap_or_default();
+    info!("{}: NSE {} securities, {} symbol changes; BSE {} securities.",
+        FEED_NAME, nse_list.len(), symbol_changes.len(), bse_list.len());
+    if nse_list.is_empty() && bse_list.is_empty() {
+        return FeedOutcome::fail("neither the NSE nor the BSE equity list could be loaded");
+    }
+
+    let mut conn = match crate::store::open_and_migrate(&db_path) {
+        Ok(c) => c,
+        Err(e) => return FeedOutcome::fail(e),
+    };
+    let result = load_all(&mut conn, &symbol_changes, &nse_list, &bse_list, min_complete, as_of);
+
+    match result {
+        Ok(rows) => {
+            crate::metrics::record_db_writes(rows as u64);
+            FeedOutcome::ok(rows as i64, format!("{} listed securities refreshed", rows))
+        }
+        Err(e) => {
+            error!("{}: {}", FEED_NAME, e);
+            crate::metrics::record_db_error();
+            FeedOutcome::fail(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nse_equity_list() {
+        let list = parse_nse_equity_list(include_str!("testdata/nse_equity_list_sample.csv"));
+        assert_eq!(list.len(), 3, "row without ISIN is dropped");
+        let ril = list.iter().find(|s| s.symbol == "RELIANCE").unwrap();
+        assert_eq!(ril.isin, "INE002A01018");
+        assert_eq!(ril.name, "Reliance Industries Limited");
+        assert_eq!(ril.listing_date, NaiveDate::from_ymd_opt(1995, 11, 29));
+    }
+
+    #[test]
+    fn test_parse_symbol_changes_and_bse_list() {
+        let changes = parse_symbol_changes(include_str!("testdata/nse_symbol_change_sample.csv"));
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[1], ("ZOMATO".to_string(), "ETERNAL".to_string(), NaiveDate::from_ymd_opt(2025, 3, 24).unwrap()));
+
+        let json: Value = serde_json::from_str(include_str!("testdata/bse_scrip_list_sample.json")).unwrap();
+        let bse = parse_bse_scrip_list(&json);
+        assert_eq!(bse.len(), 2);
+        assert_eq!(bse[0].name, "Reliance Industries Ltd");
+        assert_eq!(bse[1].name, "VASCON ENGINEERS LTD.", "falls back to Scrip_Name");
+        assert_eq!(bse[1].sector.as_deref(), Some("Civil Construction"));
+    }
+
+    #[test]
+    fn test_load_links_nse_and_bse_listings_by_isin() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        crate::store::migrate(&conn).unwrap();
+        let as_of = NaiveDate::from_ymd_opt(2025, 6, 3).unwrap();
+        let nse = parse_nse_equity_list(include_str!("testdata/nse_equity_list_sample.csv"));
+        let json: Value = serde_json::from_str(include_str!("testdata/bse_scrip_list_sample.json")).unwrap();
+        let bse = parse_bse_scrip_list(&json);
+        load_exchange_list(&conn, "NSE", &nse, 1, as_of).unwrap();
+        load_exchange_list(&conn, "BSE", &bse, 1, as_of).unwrap();
+
+        let (symbol, code, sector): (String, String, String) = conn.query_row(
+            "SELECT nse_symbol, bse_code, sector FROM entities WHERE entity_id='isin:INE002A01018'",
+            [], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?))).unwrap();
+        assert_eq!((symbol.as_str(), code.as_str(), sector.as_str()), ("RELIANCE", "500325", "Refineries & Marketing"));
+
+        // A later, complete NSE list without TATAMOTORS delists it (it has no BSE listing here).
+        let later = NaiveDate::from_ymd_opt(2025, 7, 1).unwrap();
+        let without: Vec<ListedSecurity> = nse.into_iter().filter(|s| s.symbol != "TATAMOTORS").collect();
+        load_exchange_list(&conn, "NSE", &without, 1, later).unwrap();
+        let status: String = conn.query_row(
+            "SELECT status FROM entities WHERE entity_id='isin:INE155A01022'", [], |r| r.get(0)).unwrap();
+        assert_eq!(status, "delisted");
+    }
+}
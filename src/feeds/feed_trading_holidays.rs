@@ -0,0 +1,124 @@
+// file: feeds/feed_trading_holidays.rs
+// Purpose:
+//   Batch feed: refresh the exchange trading-holiday list from NSE's holiday master and store
+//   it in the `trading_holidays` table of the market-data DB, where `calendar::load_calendar`
+//   picks it up alongside the shipped conf/trading_holidays.csv. NSE and BSE observe the same
+//   equity-segment holidays, so the capital-market list is recorded for both exchanges.
+
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+use log::{info, warn};
+use rusqlite::Connection;
+use serde_json::Value;
+
+use crate::cfg::get_market_data_db;
+use crate::feeds::{browser_client, FeedOutcome};
+use crate::network::http_get_json_with_session;
+
+pub const FEED_NAME: &str = "feed_trading_holidays";
+const HOLIDAY_API_URL: &str = "https://www.nseindia.com/api/holiday-master?type=trading";
+/// Pages that set the cookies the NSE API insists on.
+const SESSION_PAGES: [&str; 2] = [
+    "https://www.nseindia.com/",
+    "https://www.nseindia.com/resources/exchange-communication-holidays",
+];
+const EXCHANGES: [&str; 2] = ["NSE", "BSE"];
+
+/// Extract (date, description) pairs for the capital-market segment ("CM") from the
+/// holiday-master response.
+fn holidays_from_json(json: &Value) -> Vec<(NaiveDate, String)> {
+    let items = match json.get("CM").and_then(|v| v.as_array()) {
+        Some(a) => a,
+        None => return Vec::new(),
+    };
+    items.iter()
+        .filter_map(|item| {
+            let date_str = item.get("tradingDate")?.as_str()?;
+            let date = NaiveDate::parse_from_str(date_str.trim(), "%d-%b-%Y").ok()?;
+            let description = item.get("description").and_then(|v| v.as_str()).unwrap_or("").trim().to_string();
+            Some((date, description))
+        })
+        .collect()
+}
+
+/// Upsert the holidays for every exchange in `exchanges`; returns the number of rows written.
+fn save_holidays(conn: &Connection, exchanges: &[&str], holidays: &[(NaiveDate, String)]) -> Result<usize, String> {
+    let mut stmt = conn
+        .prepare("INSERT OR REPLACE INTO trading_holidays (exchange, holiday_date, description) VALUES (?1, ?2, ?3)")
+        .map_err(|e| format!("prepare insert: {}", e))?;
+    let mut written = 0;
+    for exchange in exchanges {
+        for (date, description) in holidays {
+            written += stmt
+                .execute(rusqlite::params![exchange, date.format("%Y-%m-%d").to_string(), description])
+                .map_err(|e| format!("insert holiday {}: {}", date, e))?;
+        }
+    }
+    Ok(written)
+}
+
+/// Entry point invoked by the batch-feed runner.
+pub fn run(app_config: Arc<config::Config>) -> FeedOutcome {
+    let db_path = get_market_data_db(&app_config);
+    let client = match browser_client() {
+        Ok(c) => c,
+        Err(e) => return FeedOutcome::fail(e),
+    };
+
+    let json = match http_get_json_with_session(HOLIDAY_API_URL, &SESSION_PAGES, &client, 3, 2) {
+        Some(j) => j,
+        None => return FeedOutcome::fail(format!("no JSON from {}", HOLIDAY_API_URL)),
+    };
+    let holidays = holidays_from_json(&json);
+    if holidays.is_empty() {
+        warn!("{}: holiday master had no capital-market holidays.", FEED_NAME);
+        return FeedOutcome::fail("no holidays in NSE holiday master response");
+    }
+    info!("{}: {} holiday(s) in NSE holiday master.", FEED_NAME, holidays.len());
+
+    let conn = match crate::store::open(&db_path) {
+        Ok(c) => c,
+        Err(e) => return FeedOutcome::fail(e),
+    };
+    match save_holidays(&conn, &EXCHANGES, &holidays) {
+        Ok(rows) => {
+            crate::metrics::record_db_writes(rows as u64);
+            FeedOutcome::ok(rows as i64, format!("{} trading holidays refreshed", holidays.len()))
+        }
+        Err(e) => {
+            crate::metrics::record_db_error();
+            FeedOutcome::fail(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_holidays_saved_for_both_exchanges() {
+        let json: Value = serde_json::from_str(r#"{
+            "CM": [
+                {"Sr_no": 1, "tradingDate": "26-Feb-2025", "weekDay": "Wednesday", "description": "Mahashivratri"},
+                {"Sr_no": 2, "tradingDate": "14-Mar-2025", "weekDay": "Friday", "description": "Holi"},
+                {"Sr_no": 3, "tradingDate": "", "description": "malformed"}
+            ],
+            "FO": [{"tradingDate": "01-Jan-2025", "description": "not equity"}]
+        }"#).unwrap();
+        let holidays = holidays_from_json(&json);
+        assert_eq!(holidays.len(), 2);
+        assert_eq!(holidays[1], (NaiveDate::from_ymd_opt(2025, 3, 14).unwrap(), "Holi".to_string()));
+
+        let conn = Connection::open_in_memory().unwrap();
+        crate::store::migrate(&conn).unwrap();
+        assert_eq!(save_holidays(&conn, &EXCHANGES, &holidays), Ok(4));
+        // Re-running replaces rather than duplicates.
+        assert_eq!(save_holidays(&conn, &EXCHANGES, &holidays), Ok(4));
+
+        let mut cal = crate::calendar::TradingCalendar::new();
+        assert_eq!(cal.load_db(&conn), 4);
+        assert!(!cal.is_trading_day("BSE", NaiveDate::from_ymd_opt(2025, 2, 26).unwrap()));
+    }
+}
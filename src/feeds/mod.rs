@@ -1,32 +1,63 @@
 // file: feeds/mod.rs
 // Purpose:
 //   The batch-feed subsystem (roadmap Stage 2 / point 2). Distinct from the news pipeline in
-//   `plugins/`: batch feeds fetch *structured datasets* (NSE/BSE bhavcopy, and later RBI/CCIL
-//   series, reference-data dumps) on a periodic, externally-scheduled cadence. Unlike news
-//   retrievers, **no shared `Document` flows** out of a feed — each feed is independent and
-//   writes its own data directly to the relevant store (e.g. the market-data SQLite DB).
+//   `plugins/`: batch feeds fetch *structured datasets* (NSE/BSE bhavcopy, FBIL/RBI reference
+//   rates, AMFI NAVs, and later reference-data dumps) on a periodic, externally-scheduled
+//   cadence. Unlike news retrievers, **no shared `Document` flows** out of a feed — each feed
+//   is independent and writes its own data directly to the relevant store (e.g. the
+//   market-data SQLite DB or its `market_series` table).
 //
 //   Design highlights:
 //   - Configured in the same TOML as news plugins, with `type = "batch_feed"` and a
 //     per-feed `frequency_days`. One CLI entry (`newslookout_app batch <config>`) runs them.
-//   - Feeds run in PARALLEL (one thread each), since they are independent.
+//   - Feeds run in PARALLEL (one thread each), except where a feed declares
+//     `depends_on = ["feed_x", ...]`: feeds then run in waves, each wave holding the feeds
+//     whose prerequisites have all finished. A feed whose prerequisite failed (or was itself
+//     skipped) is not run and is recorded in `batch_run_log` with status 'skipped'.
 //   - A feed that pulls many files for the SAME source (e.g. NSE multiple zips) keeps all of
 //     that logic inside ONE feed function and fetches serially, to stay polite to the server.
 //   - `batch_run_log` (store layer) records the last successful run per (source, dataset) so
 //     re-running within `frequency_days` is skipped — safe for cron re-entry.
 //   - All feeds may write to the same metadata DB; SQLite WAL + busy_timeout (store::open)
 //     coordinates concurrent writers.
+//   - History is filled with `batch <config> --from YYYY-MM-DD --to YYYY-MM-DD [--feed name]`:
+//     each date-capable feed walks every trading day in the range, recording one
+//     `batch_run_log` row per date (dataset `date:YYYY-MM-DD`), so already-loaded dates are
+//     skipped and an interrupted backfill resumes where it stopped. Exchange holidays (see
+//     `crate::calendar`) are not requested at all. Only a 404 marks a past date as having no
+//     file; network errors, error pages and blocked (HTML) responses fail the date so it is
+//     retried.
+//   - Downloaded bhavcopy files are validated before loading (`dq`): a file failing a
+//     data-quality check fails the run and is not loaded; findings go to `dq_findings`.
+//   - Analysis jobs that derive tables from loaded data (e.g. `crate::event_study`) register
+//     here too, so they get the same scheduling, `depends_on` ordering and run log.
 
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::thread;
+use std::time::Duration;
 
+use chrono::NaiveDate;
 use log::{error, info, warn};
 
+use crate::calendar::TradingCalendar;
+use crate::network::BinaryResponse;
 use crate::pipeline::{extract_plugin_params, PluginType};
 use crate::store::batch_log::{self, RunStatus};
 
+pub mod dq;
 pub mod feed_nse_bhavcopy;
 pub mod feed_bse_bhavcopy;
+pub mod feed_trading_holidays;
+pub mod feed_fbil_rates;
+pub mod feed_rbi_rates;
+pub mod feed_amfi_nav;
+pub mod feed_symbol_master;
+pub mod feed_index_constituents;
+pub mod feed_corporate_actions;
+pub mod feed_nse_index_close;
+pub mod feed_entity_master;
+pub mod feed_gazetteer;
 
 /// Outcome of a single feed run, used to populate `batch_run_log`.
 pub struct FeedOutcome {
@@ -44,14 +75,53 @@ impl FeedOutcome {
     }
 }
 
+/// Outcome of loading a single date during a backfill.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DateOutcome {
+    /// The date's file passed validation and was loaded: new rows, and the data-quality
+    /// summary (`dq::DqReport::summary`) for the run log.
+    Loaded(usize, String),
+    /// The source has no file for this date (holiday, or not yet published).
+    NoData,
+    Failed(String),
+}
+
+/// The body of a per-date download, or the outcome to record when there is none. Only a 404
+/// means the source has no file for the date; a transport error, any other error status, an
+/// empty body or an HTML page (the exchanges' access-denied and session pages) fails the
+/// date, so that a later run retries it.
+pub(crate) fn downloaded_file(response: Result<BinaryResponse, String>, url: &str) -> Result<bytes::Bytes, DateOutcome> {
+    let response = response.map_err(|e| DateOutcome::Failed(format!("download failed: {}", e)))?;
+    if response.status == 404 {
+        return Err(DateOutcome::NoData);
+    }
+    if !(200..300).contains(&response.status) {
+        return Err(DateOutcome::Failed(format!("HTTP {} for {}", response.status, url)));
+    }
+    let is_html = response.content_type.to_lowercase().contains("html")
+        || response.body.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'<');
+    if is_html {
+        return Err(DateOutcome::Failed(format!("HTML page instead of a file at {} (access denied or session expired?)", url)));
+    }
+    if response.body.is_empty() {
+        return Err(DateOutcome::Failed(format!("empty response from {}", url)));
+    }
+    Ok(response.body)
+}
+
 /// A feed's worker entry point: independent, returns its outcome (no Document channel).
 pub type FeedFn = fn(Arc<config::Config>) -> FeedOutcome;
 
+/// A feed's per-date loader, used by the date-range backfill.
+pub type FeedDateFn = fn(&reqwest::blocking::Client, NaiveDate, &str) -> DateOutcome;
+
 /// A configured batch feed ready to run.
 pub struct BatchFeed {
     pub name: String,
     pub enabled: bool,
     pub frequency_days: u32,
+    /// Names of feeds that must complete successfully in the same run before this one.
+    pub depends_on: Vec<String>,
     pub run: FeedFn,
 }
 
@@ -60,9 +130,68 @@ fn registry() -> &'static [(&'static str, FeedFn)] {
     &[
         (feed_nse_bhavcopy::FEED_NAME, feed_nse_bhavcopy::run),
         (feed_bse_bhavcopy::FEED_NAME, feed_bse_bhavcopy::run),
+        (feed_trading_holidays::FEED_NAME, feed_trading_holidays::run),
+        (feed_fbil_rates::FEED_NAME, feed_fbil_rates::run),
+        (feed_rbi_rates::FEED_NAME, feed_rbi_rates::run),
+        (feed_amfi_nav::FEED_NAME, feed_amfi_nav::run),
+        (feed_symbol_master::FEED_NAME, feed_symbol_master::run),
+        (feed_index_constituents::FEED_NAME, feed_index_constituents::run),
+        (feed_corporate_actions::FEED_NAME, feed_corporate_actions::run),
+        (feed_nse_index_close::FEED_NAME, feed_nse_index_close::run),
+        (feed_entity_master::FEED_NAME, feed_entity_master::run),
+        (feed_gazetteer::FEED_NAME, feed_gazetteer::run),
+        // Analysis job over loaded prices and news, scheduled like a feed.
+        (crate::event_study::JOB_NAME, crate::event_study::run),
     ]
 }
 
+/// Feeds that can load an arbitrary past date: feed config name → (exchange whose trading
+/// calendar applies, per-date loader).
+fn backfill_registry() -> &'static [(&'static str, &'static str, FeedDateFn)] {
+    &[
+        (feed_nse_bhavcopy::FEED_NAME, feed_nse_bhavcopy::EXCHANGE, feed_nse_bhavcopy::load_date),
+        (feed_bse_bhavcopy::FEED_NAME, feed_bse_bhavcopy::EXCHANGE, feed_bse_bhavcopy::load_date),
+        (feed_nse_index_close::FEED_NAME, feed_nse_index_close::EXCHANGE, feed_nse_index_close::load_date),
+    ]
+}
+
+/// Pause between consecutive dates of one feed during a backfill, to stay polite.
+const BACKFILL_DELAY_MS: u64 = 1000;
+
+/// HTTP client with a desktop browser User-Agent; the exchanges refuse bhavcopy downloads
+/// from anything else.
+pub(crate) fn browser_client() -> Result<reqwest::blocking::Client, String> {
+    reqwest::blocking::Client::builder()
+        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36")
+        .timeout(Duration::from_secs(60))
+        .gzip(true)
+        .cookie_store(true)
+        .build()
+        .map_err(|e| format!("could not build HTTP client: {}", e))
+}
+
+/// Split one CSV line into trimmed fields. Double-quoted fields may contain commas, and a
+/// doubled quote inside them stands for a literal quote.
+pub(crate) fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field).trim().to_string()),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
 /// Read `frequency_days` from a plugin's config map (default 1 = daily).
 fn read_frequency_days(plugin_map: &config::Map<String, config::Value>) -> u32 {
     plugin_map
@@ -72,6 +201,18 @@ fn read_frequency_days(plugin_map: &config::Map<String, config::Value>) -> u32 {
         .unwrap_or(1)
 }
 
+/// Read `depends_on` from a plugin's config map: a list of feed names, or a single name.
+fn read_depends_on(plugin_map: &config::Map<String, config::Value>) -> Vec<String> {
+    let value = match plugin_map.get("depends_on") {
+        Some(v) => v.clone(),
+        None => return Vec::new(),
+    };
+    match value.clone().into_array() {
+        Ok(items) => items.into_iter().filter_map(|v| v.into_string().ok()).collect(),
+        Err(_) => value.into_string().ok().filter(|s| !s.is_empty()).into_iter().collect(),
+    }
+}
+
 /// Load enabled batch-feed definitions from the application config (entries with
 /// `type = "batch_feed"`), resolving each name against the dispatch table.
 pub fn load_batch_feeds(app_config: &config::Config) -> Vec<BatchFeed> {
@@ -101,6 +242,7 @@ pub fn load_batch_feeds(app_config: &config::Config) -> Vec<BatchFeed> {
                     name,
                     enabled,
                     frequency_days: read_frequency_days(&plugin_map),
+                    depends_on: read_depends_on(&plugin_map),
                     run: *run,
                 });
             }
@@ -110,68 +252,338 @@ pub fn load_batch_feeds(app_config: &config::Config) -> Vec<BatchFeed> {
     feeds
 }
 
-/// Run all enabled batch feeds in parallel, honouring per-feed `frequency_days` via
-/// `batch_run_log`, and recording each outcome. `db_path` is the metadata DB holding
-/// `batch_run_log` (already migrated). Returns the number of feeds actually executed.
+/// Order feeds into waves by their `depends_on` lists: every feed in a wave depends only on
+/// feeds in earlier waves, so the feeds of one wave can run in parallel. Prerequisites that
+/// are not among `feeds` (unknown or disabled) are ignored with a warning. Returns the waves
+/// (as indices into `feeds`) and the feeds that can never run because of a dependency cycle.
+fn plan_waves(feeds: &[BatchFeed]) -> (Vec<Vec<usize>>, Vec<usize>) {
+    let names: HashSet<&str> = feeds.iter().map(|f| f.name.as_str()).collect();
+    for feed in feeds {
+        for dep in feed.depends_on.iter().filter(|d| !names.contains(d.as_str())) {
+            warn!("feeds: '{}' depends on '{}', which is not enabled; ignoring it.", feed.name, dep);
+        }
+    }
+
+    let mut placed: HashSet<&str> = HashSet::new();
+    let mut remaining: Vec<usize> = (0..feeds.len()).collect();
+    let mut waves = Vec::new();
+    while !remaining.is_empty() {
+        let (ready, waiting): (Vec<usize>, Vec<usize>) = remaining.iter().partition(|&&i| {
+            feeds[i].depends_on.iter()
+                .all(|d| placed.contains(d.as_str()) || !names.contains(d.as_str()))
+        });
+        if ready.is_empty() {
+            return (waves, waiting);
+        }
+        placed.extend(ready.iter().map(|&i| feeds[i].name.as_str()));
+        waves.push(ready);
+        remaining = waiting;
+    }
+    (waves, Vec::new())
+}
+
+/// Record a feed that was not run because of its dependencies.
+fn record_skip(db_path: &str, name: &str, message: &str) {
+    warn!("feeds: '{}' SKIPPED — {}", name, message);
+    if let Ok(conn) = crate::store::open(db_path) {
+        batch_log::record_result(&conn, name, name, RunStatus::Skipped, 0, message, chrono::Utc::now().timestamp());
+    }
+}
+
+/// Run all enabled batch feeds, honouring per-feed `frequency_days` via `batch_run_log` and
+/// `depends_on` ordering, and recording each outcome. Independent feeds run in parallel.
+/// `db_path` is the DB holding `batch_run_log` (already migrated). Returns the number of
+/// feeds actually executed.
 pub fn run_batch_feeds(app_config: Arc<config::Config>, db_path: &str) -> usize {
     let feeds = load_batch_feeds(&app_config);
-    info!("feeds: {} batch feed(s) configured.", feeds.len());
+    run_feeds(app_config, feeds, db_path)
+}
+
+/// Run the given feeds as described for `run_batch_feeds`.
+fn run_feeds(app_config: Arc<config::Config>, feeds: Vec<BatchFeed>, db_path: &str) -> usize {
+    let feeds: Vec<BatchFeed> = feeds
+        .into_iter()
+        .filter(|f| {
+            if !f.enabled {
+                info!("feeds: '{}' disabled, skipping.", f.name);
+            }
+            f.enabled
+        })
+        .collect();
+    info!("feeds: {} batch feed(s) enabled.", feeds.len());
 
     let now_ts = chrono::Utc::now().timestamp();
-    let mut handles = Vec::new();
+    let (waves, cyclic) = plan_waves(&feeds);
+    // Feeds that failed or were skipped in this run; their dependents are skipped.
+    let mut blocked: HashSet<String> = HashSet::new();
+    if !cyclic.is_empty() {
+        let names: Vec<&str> = cyclic.iter().map(|&i| feeds[i].name.as_str()).collect();
+        error!("feeds: dependency cycle among {:?}; these feeds will not run.", names);
+        for name in &names {
+            record_skip(db_path, name, &format!("dependency cycle among {}", names.join(", ")));
+            blocked.insert(name.to_string());
+        }
+    }
 
-    for feed in feeds {
-        if !feed.enabled {
-            info!("feeds: '{}' disabled, skipping.", feed.name);
-            continue;
+    let mut executed = 0;
+    for (wave_no, wave) in waves.iter().enumerate() {
+        let mut handles = Vec::new();
+        for &i in wave {
+            let feed = &feeds[i];
+            if let Some(dep) = feed.depends_on.iter().find(|d| blocked.contains(*d)) {
+                record_skip(db_path, &feed.name, &format!("prerequisite '{}' did not complete", dep));
+                blocked.insert(feed.name.clone());
+                continue;
+            }
+
+            // Frequency check uses (source=name, dataset=name) at this level; a feed that manages
+            // multiple datasets records finer-grained dataset rows itself. A feed skipped here
+            // ran recently, so its dependents may still run.
+            let skip = match crate::store::open(db_path) {
+                Ok(conn) => batch_log::should_skip(&conn, &feed.name, &feed.name, feed.frequency_days, now_ts),
+                Err(e) => { error!("feeds: cannot open '{}' for run-log check: {}", db_path, e); false }
+            };
+            if skip {
+                info!("feeds: '{}' ran within {} day(s); skipping (use frequency_days=0 to force).",
+                    feed.name, feed.frequency_days);
+                continue;
+            }
+
+            let cfg = app_config.clone();
+            let dbp = db_path.to_string();
+            let name = feed.name.clone();
+            let run = feed.run;
+            let handle = thread::Builder::new()
+                .name(feed.name.clone())
+                .spawn(move || execute_feed(cfg, &dbp, &name, run).status);
+            match handle {
+                Ok(h) => handles.push((feed.name.clone(), h)),
+                Err(e) => {
+                    error!("feeds: could not spawn thread for feed: {}", e);
+                    blocked.insert(feed.name.clone());
+                }
+            }
         }
 
-        // Frequency check uses (source=name, dataset=name) at this level; a feed that manages
-        // multiple datasets records finer-grained dataset rows itself.
-        let skip = match crate::store::open(db_path) {
-            Ok(conn) => batch_log::should_skip(&conn, &feed.name, &feed.name, feed.frequency_days, now_ts),
-            Err(e) => { error!("feeds: cannot open '{}' for run-log check: {}", db_path, e); false }
-        };
-        if skip {
-            info!("feeds: '{}' ran within {} day(s); skipping (use frequency_days=0 to force).",
-                feed.name, feed.frequency_days);
-            continue;
+        if !handles.is_empty() {
+            info!("feeds: wave {} running {} feed(s).", wave_no + 1, handles.len());
+        }
+        executed += handles.len();
+        for (name, h) in handles {
+            match h.join() {
+                Ok(RunStatus::Success) => {}
+                Ok(_) => { blocked.insert(name); }
+                Err(e) => {
+                    error!("feeds: a feed thread panicked: {:?}", e);
+                    blocked.insert(name);
+                }
+            }
+        }
+    }
+    info!("feeds: completed {} feed run(s).", executed);
+    executed
+}
+
+/// Run one feed in the current thread, recording the attempt and its outcome in
+/// `batch_run_log`.
+fn execute_feed(app_config: Arc<config::Config>, db_path: &str, name: &str, run: FeedFn) -> FeedOutcome {
+    info!("feeds: starting '{}'", name);
+    if let Ok(conn) = crate::store::open(db_path) {
+        batch_log::record_attempt(&conn, name, name, chrono::Utc::now().timestamp());
+    }
+    let outcome = run(app_config);
+    let done_ts = chrono::Utc::now().timestamp();
+    if let Ok(conn) = crate::store::open(db_path) {
+        batch_log::record_result(&conn, name, name, outcome.status, outcome.rows, &outcome.message, done_ts);
+    }
+    match outcome.status {
+        RunStatus::Success => info!("feeds: '{}' OK — {} ({} rows)", name, outcome.message, outcome.rows),
+        _ => error!("feeds: '{}' FAILED — {}", name, outcome.message),
+    }
+    outcome
+}
+
+/// Run the feed `name` once, as scheduled by daemon mode (`crate::scheduler`). The schedule
+/// replaces the `frequency_days` check; `depends_on` is honoured by requiring each
+/// prerequisite's latest run in `batch_run_log` to have succeeded, so a dependent feed should
+/// be scheduled after its prerequisites.
+pub fn run_scheduled_feed(app_config: Arc<config::Config>, db_path: &str, name: &str) -> FeedOutcome {
+    let feed = match load_batch_feeds(&app_config).into_iter().find(|f| f.name == name) {
+        Some(f) => f,
+        None => return FeedOutcome::fail(format!("'{}' is not a registered batch feed", name)),
+    };
+    let conn = match crate::store::open(db_path) {
+        Ok(c) => c,
+        Err(e) => return FeedOutcome::fail(e),
+    };
+    for dep in &feed.depends_on {
+        let status = batch_log::last_status(&conn, dep, dep);
+        if status.as_deref() != Some("success") {
+            let message = format!("prerequisite '{}' has not completed (last status: {})",
+                dep, status.as_deref().unwrap_or("never run"));
+            record_skip(db_path, name, &message);
+            return FeedOutcome { status: RunStatus::Skipped, rows: 0, message };
+        }
+    }
+    drop(conn);
+    execute_feed(app_config, db_path, name, feed.run)
+}
+
+/// A date range (inclusive) to backfill, optionally restricted to one feed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackfillRange {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub feed: Option<String>,
+}
+
+/// Parse `--from YYYY-MM-DD --to YYYY-MM-DD [--feed name]` from the arguments following the
+/// config file. Returns Ok(None) when no range is given (a normal batch run).
+pub fn parse_backfill_args(args: &[String]) -> Result<Option<BackfillRange>, String> {
+    let mut from = None;
+    let mut to = None;
+    let mut feed = None;
+    let mut iter = args.iter();
+    while let Some(flag) = iter.next() {
+        let value = iter.next().ok_or_else(|| format!("missing value for '{}'", flag))?;
+        match flag.as_str() {
+            "--from" | "--to" => {
+                let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                    .map_err(|e| format!("invalid date '{}' for {}: {}", value, flag, e))?;
+                if flag == "--from" { from = Some(date) } else { to = Some(date) }
+            }
+            "--feed" => feed = Some(value.clone()),
+            other => return Err(format!("unknown argument '{}'", other)),
         }
+    }
+    match (from, to) {
+        (None, None) if feed.is_none() => Ok(None),
+        (Some(from), Some(to)) if from <= to => Ok(Some(BackfillRange { from, to, feed })),
+        (Some(from), Some(to)) => Err(format!("--from {} is after --to {}", from, to)),
+        _ => Err("both --from and --to are required for a backfill".to_string()),
+    }
+}
 
-        let cfg = app_config.clone();
+/// `batch_run_log` dataset key for one backfilled date.
+fn date_dataset(date: NaiveDate) -> String {
+    format!("date:{}", date.format("%Y-%m-%d"))
+}
+
+/// Trading days of `exchange` in `range` not yet successfully loaded by `feed_name`.
+fn pending_dates(
+    conn: &rusqlite::Connection,
+    feed_name: &str,
+    exchange: &str,
+    calendar: &TradingCalendar,
+    range: &BackfillRange,
+) -> Vec<NaiveDate> {
+    calendar.trading_days_between(exchange, range.from, range.to)
+        .into_iter()
+        .filter(|d| !batch_log::has_succeeded(conn, feed_name, &date_dataset(*d)))
+        .collect()
+}
+
+/// Map a date's outcome to a run-log status. A missing file (404) for a past date is final (a
+/// holiday absent from the calendar), so it counts as done; for today it may simply not be
+/// published yet. Failed downloads are failures, so the date is retried.
+fn date_run_result(outcome: &DateOutcome, date: NaiveDate, today: NaiveDate) -> (RunStatus, i64, String) {
+    match outcome {
+        DateOutcome::Loaded(rows, summary) => (RunStatus::Success, *rows as i64, format!("loaded; {}", summary)),
+        DateOutcome::NoData if date < today => (RunStatus::Success, 0, "no file (non-trading day)".to_string()),
+        DateOutcome::NoData => (RunStatus::Failure, 0, "no file yet".to_string()),
+        DateOutcome::Failed(e) => (RunStatus::Failure, 0, e.clone()),
+    }
+}
+
+/// Backfill every trading day in `range` for each enabled, date-capable feed (or only the
+/// feed named in the range). Feeds run in parallel; dates within a feed are fetched serially.
+/// Returns the number of dates loaded across all feeds.
+pub fn run_backfill(app_config: Arc<config::Config>, db_path: &str, range: &BackfillRange) -> usize {
+    let feeds: Vec<(String, &'static str, FeedDateFn)> = load_batch_feeds(&app_config)
+        .into_iter()
+        .filter(|f| match &range.feed {
+            Some(name) => &f.name == name,
+            None => f.enabled,
+        })
+        .filter_map(|f| {
+            let entry = backfill_registry().iter().find(|(n, _, _)| *n == f.name.as_str());
+            if entry.is_none() {
+                warn!("feeds: '{}' does not support date-range backfill, skipping.", f.name);
+            }
+            entry.map(|(_, exchange, loader)| (f.name, *exchange, *loader))
+        })
+        .collect();
+    if feeds.is_empty() {
+        warn!("feeds: no backfill-capable feed matched {:?}.", range.feed);
+        return 0;
+    }
+    info!("feeds: backfilling {} to {} for {} feed(s).", range.from, range.to, feeds.len());
+    let calendar = Arc::new(crate::calendar::load_calendar(&app_config));
+
+    let mut handles = Vec::new();
+    for (name, exchange, loader) in feeds {
         let dbp = db_path.to_string();
+        let range = range.clone();
+        let cal = calendar.clone();
         let handle = thread::Builder::new()
-            .name(feed.name.clone())
-            .spawn(move || {
-                let name = feed.name.clone();
-                info!("feeds: starting '{}'", name);
-                if let Ok(conn) = crate::store::open(&dbp) {
-                    batch_log::record_attempt(&conn, &name, &name, now_ts);
-                }
-                let outcome = (feed.run)(cfg);
-                let done_ts = chrono::Utc::now().timestamp();
-                if let Ok(conn) = crate::store::open(&dbp) {
-                    batch_log::record_result(&conn, &name, &name, outcome.status, outcome.rows, &outcome.message, done_ts);
-                }
-                match outcome.status {
-                    RunStatus::Success => info!("feeds: '{}' OK — {} ({} rows)", name, outcome.message, outcome.rows),
-                    RunStatus::Failure => error!("feeds: '{}' FAILED — {}", name, outcome.message),
-                }
-            });
+            .name(name.clone())
+            .spawn(move || backfill_feed(&name, exchange, loader, &cal, &dbp, &range));
         match handle {
             Ok(h) => handles.push(h),
-            Err(e) => error!("feeds: could not spawn thread for feed: {}", e),
+            Err(e) => error!("feeds: could not spawn backfill thread: {}", e),
         }
     }
 
-    let executed = handles.len();
+    let mut loaded = 0;
     for h in handles {
-        if let Err(e) = h.join() {
-            error!("feeds: a feed thread panicked: {:?}", e);
+        match h.join() {
+            Ok(n) => loaded += n,
+            Err(e) => error!("feeds: a backfill thread panicked: {:?}", e),
         }
     }
-    info!("feeds: completed {} feed run(s).", executed);
-    executed
+    info!("feeds: backfill completed, {} date(s) loaded.", loaded);
+    loaded
+}
+
+/// Walk the pending dates of one feed, recording each date's outcome as it goes.
+fn backfill_feed(
+    name: &str,
+    exchange: &str,
+    loader: FeedDateFn,
+    calendar: &TradingCalendar,
+    db_path: &str,
+    range: &BackfillRange,
+) -> usize {
+    let conn = match crate::store::open(db_path) {
+        Ok(c) => c,
+        Err(e) => { error!("feeds: cannot open '{}' for backfill of '{}': {}", db_path, name, e); return 0; }
+    };
+    let client = match browser_client() {
+        Ok(c) => c,
+        Err(e) => { error!("feeds: '{}': {}", name, e); return 0; }
+    };
+
+    let dates = pending_dates(&conn, name, exchange, calendar, range);
+    info!("feeds: '{}' has {} date(s) to load.", name, dates.len());
+    let today = chrono::Utc::now().date_naive();
+    let mut loaded = 0;
+
+    for (i, date) in dates.iter().enumerate() {
+        if i > 0 {
+            thread::sleep(Duration::from_millis(BACKFILL_DELAY_MS));
+        }
+        let dataset = date_dataset(*date);
+        batch_log::record_attempt(&conn, name, &dataset, chrono::Utc::now().timestamp());
+        let outcome = loader(&client, *date, db_path);
+        let (status, rows, message) = date_run_result(&outcome, *date, today);
+        batch_log::record_result(&conn, name, &dataset, status, rows, &message, chrono::Utc::now().timestamp());
+        match outcome {
+            DateOutcome::Loaded(n, summary) => { loaded += 1; info!("feeds: '{}' {} loaded ({} rows) — {}", name, date, n, summary); }
+            DateOutcome::NoData => info!("feeds: '{}' {} has no file", name, date),
+            DateOutcome::Failed(e) => error!("feeds: '{}' {} FAILED — {}", name, date, e),
+        }
+    }
+    loaded
 }
 
 #[cfg(test)]
@@ -220,4 +632,164 @@ mod tests {
         assert_eq!(nse.frequency_days, 7);
         assert_eq!(bse.frequency_days, 1, "default frequency should be 1 day");
     }
+
+    fn feed(name: &str, depends_on: &[&str]) -> BatchFeed {
+        fn noop(_: Arc<config::Config>) -> FeedOutcome { FeedOutcome::ok(0, "") }
+        BatchFeed {
+            name: name.to_string(),
+            enabled: true,
+            frequency_days: 1,
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+            run: noop,
+        }
+    }
+
+    #[test]
+    fn test_depends_on_parsed_as_list_or_single_name() {
+        let toml = r#"
+            plugins = [
+              { enabled = true, name = "feed_index_constituents", type = "batch_feed", depends_on = ["feed_symbol_master"] },
+              { enabled = true, name = "feed_nse_bhavcopy", type = "batch_feed", depends_on = "feed_trading_holidays" },
+              { enabled = true, name = "feed_bse_bhavcopy", type = "batch_feed" },
+            ]
+        "#;
+        let feeds = load_batch_feeds(&cfg_with(toml));
+        let deps = |n: &str| feeds.iter().find(|f| f.name == n).unwrap().depends_on.clone();
+        assert_eq!(deps("feed_index_constituents"), vec!["feed_symbol_master"]);
+        assert_eq!(deps("feed_nse_bhavcopy"), vec!["feed_trading_holidays"]);
+        assert!(deps("feed_bse_bhavcopy").is_empty());
+    }
+
+    #[test]
+    fn test_plan_waves_orders_dependencies() {
+        let feeds = vec![
+            feed("feed_nse_bhavcopy", &["feed_trading_holidays"]),
+            feed("feed_index_constituents", &["feed_symbol_master"]),
+            feed("feed_trading_holidays", &[]),
+            feed("feed_symbol_master", &[]),
+            feed("feed_amfi_nav", &["feed_not_enabled"]),
+        ];
+        let (waves, cyclic) = plan_waves(&feeds);
+        assert_eq!(waves, vec![vec![2, 3, 4], vec![0, 1]]);
+        assert!(cyclic.is_empty());
+
+        let feeds = vec![feed("a", &["b"]), feed("b", &["a"]), feed("c", &[]), feed("d", &["a"])];
+        let (waves, cyclic) = plan_waves(&feeds);
+        assert_eq!(waves, vec![vec![2]]);
+        assert_eq!(cyclic, vec![0, 1, 3], "a cycle blocks its dependents too");
+    }
+
+    #[test]
+    fn test_split_csv_line_handles_quotes() {
+        assert_eq!(split_csv_line(" a , b ,"), vec!["a", "b", ""]);
+        assert_eq!(
+            split_csv_line(r#"Reliance,"Oil, Gas & Fuels","say ""hi""",RELIANCE"#),
+            vec!["Reliance", "Oil, Gas & Fuels", r#"say "hi""#, "RELIANCE"]
+        );
+    }
+
+    #[test]
+    fn test_run_feeds_skips_dependents_of_failed_feed() {
+        fn failing(_: Arc<config::Config>) -> FeedOutcome { FeedOutcome::fail("http 503") }
+        let tmp = std::env::temp_dir().join("feeds_depends_on_test.db");
+        let db_path = tmp.to_str().unwrap();
+        let _ = std::fs::remove_file(db_path);
+        crate::store::open_and_migrate(db_path).unwrap();
+
+        let mut master = feed("feed_symbol_master", &[]);
+        master.run = failing;
+        let feeds = vec![
+            master,
+            feed("feed_index_constituents", &["feed_symbol_master"]),
+            feed("feed_corporate_actions", &["feed_index_constituents"]),
+            feed("feed_amfi_nav", &[]),
+        ];
+        let cfg = Arc::new(cfg_with("plugins = []"));
+        assert_eq!(run_feeds(cfg, feeds, db_path), 2, "only the independent feeds run");
+
+        let conn = crate::store::open(db_path).unwrap();
+        let status = |name: &str| -> String {
+            conn.query_row("SELECT status FROM batch_run_log WHERE source=?1", [name], |r| r.get(0)).unwrap()
+        };
+        assert_eq!(status("feed_symbol_master"), "failure");
+        assert_eq!(status("feed_index_constituents"), "skipped");
+        assert_eq!(status("feed_corporate_actions"), "skipped", "skips propagate down the chain");
+        assert_eq!(status("feed_amfi_nav"), "success");
+        drop(conn);
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_backfill_args() {
+        assert_eq!(parse_backfill_args(&[]), Ok(None));
+        let range = parse_backfill_args(&args(&["--from", "2024-07-29", "--to", "2024-08-02", "--feed", "feed_nse_bhavcopy"]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(range.from, NaiveDate::from_ymd_opt(2024, 7, 29).unwrap());
+        assert_eq!(range.to, NaiveDate::from_ymd_opt(2024, 8, 2).unwrap());
+        assert_eq!(range.feed.as_deref(), Some("feed_nse_bhavcopy"));
+
+        assert!(parse_backfill_args(&args(&["--from", "2024-07-29"])).is_err());
+        assert!(parse_backfill_args(&args(&["--from", "2024-08-02", "--to", "2024-07-29"])).is_err());
+        assert!(parse_backfill_args(&args(&["--from", "29/07/2024", "--to", "2024-08-02"])).is_err());
+        assert!(parse_backfill_args(&args(&["--since", "2024-07-29"])).is_err());
+    }
+
+    #[test]
+    fn test_pending_dates_skip_loaded_dates_and_holidays_but_retry_failures() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        crate::store::migrate(&conn).unwrap();
+        let d = |day| NaiveDate::from_ymd_opt(2024, 7, day).unwrap();
+        let range = BackfillRange { from: d(29), to: NaiveDate::from_ymd_opt(2024, 8, 2).unwrap(), feed: None };
+        let today = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+
+        // Mon loaded, Tue had no file, Wed failed mid-way; Thu 1 Aug is a calendar holiday.
+        for (date, outcome) in [
+            (d(29), DateOutcome::Loaded(2000, "DQ: 2000 rows, 0 error(s), 0 warning(s)".to_string())),
+            (d(30), DateOutcome::NoData),
+            (d(31), DateOutcome::Failed("timeout".to_string())),
+        ] {
+            let (status, rows, msg) = date_run_result(&outcome, date, today);
+            batch_log::record_result(&conn, "feed_nse_bhavcopy", &date_dataset(date), status, rows, &msg, 1);
+        }
+
+        let mut calendar = TradingCalendar::new();
+        calendar.add_holiday("NSE", NaiveDate::from_ymd_opt(2024, 8, 1).unwrap());
+
+        let pending = pending_dates(&conn, "feed_nse_bhavcopy", "NSE", &calendar, &range);
+        assert_eq!(pending, vec![d(31), NaiveDate::from_ymd_opt(2024, 8, 2).unwrap()]);
+        // Another feed's progress is tracked independently, against its own exchange calendar.
+        assert_eq!(pending_dates(&conn, "feed_bse_bhavcopy", "BSE", &calendar, &range).len(), 5);
+        // A missing file for today is retried on the next run.
+        assert_eq!(date_run_result(&DateOutcome::NoData, today, today).0, RunStatus::Failure);
+    }
+
+    #[test]
+    fn test_failed_downloads_are_not_recorded_as_success() {
+        let response = |status: u16, content_type: &str, body: &'static [u8]| {
+            Ok(BinaryResponse { status, content_type: content_type.to_string(), body: bytes::Bytes::from_static(body) })
+        };
+        let url = "https://nsearchives.nseindia.com/content/cm/x.csv.zip";
+        let date = NaiveDate::from_ymd_opt(2024, 7, 30).unwrap();
+        let today = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+
+        assert_eq!(downloaded_file(response(404, "text/html", b"<html>Not Found</html>"), url), Err(DateOutcome::NoData));
+        assert_eq!(downloaded_file(response(200, "application/zip", b"PK\x03\x04"), url).unwrap().len(), 4);
+        for failed in [
+            Err("connection reset".to_string()),
+            response(200, "application/zip", b""),
+            response(200, "text/html; charset=utf-8", b"Access Denied"),
+            response(200, "application/octet-stream", b"  <html><body>Access Denied</body></html>"),
+            response(403, "text/html", b"<html>Forbidden</html>"),
+            response(503, "", b""),
+        ] {
+            let outcome = downloaded_file(failed, url).unwrap_err();
+            assert!(matches!(outcome, DateOutcome::Failed(_)), "{:?}", outcome);
+            assert_eq!(date_run_result(&outcome, date, today).0, RunStatus::Failure);
+        }
+    }
 }
@@ -234,6 +234,7 @@ use crate::pipeline::{load_dataproc_plugins, load_retriever_plugins, RetrieverPl
 
 pub mod plugins {
     pub mod html_news;
+    pub(crate) mod regulator_listing;
     pub mod mod_en_in_indiankanoon;
     pub(crate) mod mod_en_in_rbi;
     pub(crate) mod mod_en_in_business_standard;
@@ -241,11 +242,16 @@ pub mod plugins {
     pub mod split_text;
     pub mod mod_dedupe;
     pub mod mod_mentions;
+    pub mod mod_lang;
     pub mod mod_extract_quant;
+    pub mod mod_classify;
     pub mod mod_themes;
     pub mod mod_tone;
     pub mod mod_geocode;
     pub mod mod_ner;
+    pub mod mod_entity_resolve;
+    pub mod mod_quotes;
+    pub mod mod_events;
     pub mod mod_entity_graph;
     pub mod mod_emit_graph;
     pub mod mod_emit_tables;
@@ -295,6 +301,10 @@ pub mod plugins {
     pub mod mod_en_au_abc;
     pub mod mod_en_in_irdai;
     pub mod mod_en_in_sebi;
+    pub(crate) mod mod_en_in_mca;
+    pub(crate) mod mod_en_in_pfrda;
+    pub(crate) mod mod_en_in_ifsca;
+    pub(crate) mod mod_en_in_fiu;
     pub mod mod_in_nse;
     pub(crate) mod mod_in_bse;
     pub mod mod_doc_type;
@@ -314,8 +324,14 @@ pub mod feeds;
 pub mod pipeline;
 pub mod cfg;
 pub mod content_extraction;
+pub mod pdf_tables;
 pub mod web_api;
 pub mod market_data;
+pub mod calendar;
+pub mod scheduler;
+pub mod event_study;
+pub mod vocab;
+pub mod temporal;
 
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 const CARGO_PKG_NAME: &str = env!("CARGO_PKG_NAME");
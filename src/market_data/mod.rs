@@ -0,0 +1,981 @@
+// file: market_data/mod.rs
+// Purpose: Save CSV tabular market data (NSE/BSE bhavcopy) to a SQLite database, and read
+//          back corporate-action-adjusted price series. Read-side queries (latest close,
+//          OHLCV ranges, returns, top movers, market_series) live in `query`.
+
+use log::{error, info, warn};
+use rusqlite::Connection;
+
+use crate::feeds::split_csv_line;
+
+pub mod query;
+
+/// Sanitize a table name by replacing disallowed characters with underscores.
+fn sanitize_table_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c == '-' || c == ' ' || c == '.' { '_' } else { c })
+        .collect()
+}
+
+/// Parse the CSV header line into a vector of trimmed column names.
+fn parse_header(header_line: &str) -> Vec<String> {
+    split_csv_line(header_line)
+}
+
+/// NSE Capital Market bhavcopy schema:
+///   nse_cm_bhavcopy(trade_date, biz_date, segment, src, fin_instrm_tp, fin_instrm_id,
+///                   isin, ticker_symbol, security_series, expiry_date, actual_expiry_date,
+///                   strike_price, option_type, fin_instrm_name, open_price, high_price,
+///                   low_price, close_price, last_price, prev_close, underlying_price,
+///                   settlement_price, open_interest, chg_open_interest, total_volume,
+///                   total_value, total_trades, session_id, lot_size)
+/// Unique key: (trade_date, fin_instrm_id)
+///
+/// Column mapping from CSV headers:
+///   TradDt->trade_date, BizDt->biz_date, Sgmt->segment, Src->src,
+///   FinInstrmTp->fin_instrm_tp, FinInstrmId->fin_instrm_id, ISIN->isin,
+///   TckrSymb->ticker_symbol, SctySrs->security_series, XpryDt->expiry_date,
+///   FininstrmActlXpryDt->actual_expiry_date, StrkPric->strike_price, OptnTp->option_type,
+///   FinInstrmNm->fin_instrm_name, OpnPric->open_price, HghPric->high_price,
+///   LwPric->low_price, ClsPric->close_price, LastPric->last_price,
+///   PrvsClsgPric->prev_close, UndrlygPric->underlying_price, SttlmPric->settlement_price,
+///   OpnIntrst->open_interest, ChngInOpnIntrst->chg_open_interest, TtlTradgVol->total_volume,
+///   TtlTrfVal->total_value, TtlNbOfTxsExctd->total_trades, SsnId->session_id,
+///   NewBrdLotQty->lot_size
+const NSE_CREATE_TABLE: &str = "
+CREATE TABLE IF NOT EXISTS nse_cm_bhavcopy (
+    id                  INTEGER PRIMARY KEY AUTOINCREMENT,
+    trade_date          TEXT NOT NULL,
+    biz_date            TEXT,
+    segment             TEXT,
+    src                 TEXT,
+    fin_instrm_tp       TEXT,
+    fin_instrm_id       INTEGER,
+    isin                TEXT,
+    ticker_symbol       TEXT,
+    security_series     TEXT,
+    expiry_date         TEXT,
+    actual_expiry_date  TEXT,
+    strike_price        REAL,
+    option_type         TEXT,
+    fin_instrm_name     TEXT,
+    open_price          REAL,
+    high_price          REAL,
+    low_price           REAL,
+    close_price         REAL,
+    last_price          REAL,
+    prev_close          REAL,
+    underlying_price    REAL,
+    settlement_price    REAL,
+    open_interest       REAL,
+    chg_open_interest   REAL,
+    total_volume        REAL,
+    total_value         REAL,
+    total_trades        INTEGER,
+    session_id          TEXT,
+    lot_size            INTEGER
+)";
+
+const NSE_CREATE_INDEX_DEDUP: &str =
+    "CREATE UNIQUE INDEX IF NOT EXISTS idx_nse_cm_dedup ON nse_cm_bhavcopy (trade_date, fin_instrm_id)";
+const NSE_CREATE_INDEX_DATE: &str =
+    "CREATE INDEX IF NOT EXISTS idx_nse_cm_date ON nse_cm_bhavcopy (trade_date)";
+const NSE_CREATE_INDEX_TICKER: &str =
+    "CREATE INDEX IF NOT EXISTS idx_nse_cm_ticker ON nse_cm_bhavcopy (ticker_symbol, trade_date)";
+const NSE_CREATE_INDEX_ISIN: &str =
+    "CREATE INDEX IF NOT EXISTS idx_nse_cm_isin ON nse_cm_bhavcopy (isin, trade_date)";
+
+const NSE_INSERT: &str = "
+INSERT OR IGNORE INTO nse_cm_bhavcopy
+    (trade_date, biz_date, segment, src, fin_instrm_tp, fin_instrm_id, isin,
+     ticker_symbol, security_series, expiry_date, actual_expiry_date, strike_price,
+     option_type, fin_instrm_name, open_price, high_price, low_price, close_price,
+     last_price, prev_close, underlying_price, settlement_price, open_interest,
+     chg_open_interest, total_volume, total_value, total_trades, session_id, lot_size)
+VALUES
+    (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16,
+     ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29)";
+
+fn parse_opt_real(s: &str) -> Option<f64> {
+    let s = s.trim();
+    if s.is_empty() { None } else { s.parse::<f64>().ok() }
+}
+
+fn parse_opt_int(s: &str) -> Option<i64> {
+    let s = s.trim();
+    if s.is_empty() { None } else { s.parse::<f64>().ok().map(|f| f as i64) }
+}
+
+/// Save NSE Capital Market bhavcopy CSV content to the `nse_cm_bhavcopy` table.
+///
+/// The first line of `csv_content` must be the NSE CM header:
+///   TradDt,BizDt,Sgmt,Src,FinInstrmTp,FinInstrmId,ISIN,TckrSymb,...
+///
+/// Returns Ok(rows_inserted) or Err(message).
+pub fn save_nse_csv_to_sqlite(csv_content: &str, db_path: &str) -> Result<usize, String> {
+    info!("save_nse_csv_to_sqlite: db='{}'", db_path);
+
+    let conn = Connection::open(db_path).map_err(|e| format!("open db '{}': {}", db_path, e))?;
+    conn.execute_batch("PRAGMA journal_mode=WAL; PRAGMA synchronous=NORMAL;")
+        .map_err(|e| format!("pragma: {}", e))?;
+
+    conn.execute(NSE_CREATE_TABLE, [])
+        .map_err(|e| format!("create table: {}", e))?;
+    for idx_sql in [NSE_CREATE_INDEX_DEDUP, NSE_CREATE_INDEX_DATE, NSE_CREATE_INDEX_TICKER, NSE_CREATE_INDEX_ISIN] {
+        conn.execute(idx_sql, []).map_err(|e| format!("create index: {}", e))?;
+    }
+
+    let mut lines = csv_content.lines();
+    let header = match lines.next() {
+        Some(h) if !h.trim().is_empty() => h,
+        _ => return Err("NSE CSV has no header".to_string()),
+    };
+
+    // Build a column-name to index map so we're robust to column reordering
+    let col_names = parse_header(header);
+    let col_idx: std::collections::HashMap<&str, usize> = col_names.iter()
+        .enumerate()
+        .map(|(i, name)| (name.as_str(), i))
+        .collect();
+
+    macro_rules! get_col {
+        ($name:expr, $fields:expr) => {
+            col_idx.get($name).and_then(|&i| $fields.get(i)).map(|s| s.trim()).unwrap_or("")
+        };
+    }
+
+    let mut stmt = conn.prepare(NSE_INSERT).map_err(|e| format!("prepare insert: {}", e))?;
+    let mut inserted: usize = 0;
+    let expected = col_names.len();
+
+    for (lineno, line) in lines.enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() { continue; }
+
+        let fields = split_csv_line(trimmed);
+        if fields.len() != expected {
+            warn!("NSE CSV line {}: expected {} cols, got {} — skipping", lineno + 2, expected, fields.len());
+            continue;
+        }
+
+        let trade_date = get_col!("TradDt", fields);
+        let biz_date   = get_col!("BizDt", fields);
+        let segment    = get_col!("Sgmt", fields);
+        let src        = get_col!("Src", fields);
+        let tp         = get_col!("FinInstrmTp", fields);
+        let instrm_id  = parse_opt_int(get_col!("FinInstrmId", fields));
+        let isin       = get_col!("ISIN", fields);
+        let ticker     = get_col!("TckrSymb", fields);
+        let series     = get_col!("SctySrs", fields);
+        let expiry     = get_col!("XpryDt", fields);
+        let act_expiry = get_col!("FininstrmActlXpryDt", fields);
+        let strike     = parse_opt_real(get_col!("StrkPric", fields));
+        let opt_type   = get_col!("OptnTp", fields);
+        let name       = get_col!("FinInstrmNm", fields);
+        let open       = parse_opt_real(get_col!("OpnPric", fields));
+        let high       = parse_opt_real(get_col!("HghPric", fields));
+        let low        = parse_opt_real(get_col!("LwPric", fields));
+        let close      = parse_opt_real(get_col!("ClsPric", fields));
+        let last       = parse_opt_real(get_col!("LastPric", fields));
+        let prev_close = parse_opt_real(get_col!("PrvsClsgPric", fields));
+        let underlying = parse_opt_real(get_col!("UndrlygPric", fields));
+        let settlement = parse_opt_real(get_col!("SttlmPric", fields));
+        let open_int   = parse_opt_real(get_col!("OpnIntrst", fields));
+        let chg_oi     = parse_opt_real(get_col!("ChngInOpnIntrst", fields));
+        let volume     = parse_opt_real(get_col!("TtlTradgVol", fields));
+        let value      = parse_opt_real(get_col!("TtlTrfVal", fields));
+        let trades     = parse_opt_int(get_col!("TtlNbOfTxsExctd", fields));
+        let session    = get_col!("SsnId", fields);
+        let lot_size   = parse_opt_int(get_col!("NewBrdLotQty", fields));
+
+        if trade_date.is_empty() { continue; }
+
+        match stmt.execute(rusqlite::params![
+            trade_date, biz_date, segment, src, tp, instrm_id, isin,
+            ticker, series, expiry, act_expiry, strike, opt_type, name,
+            open, high, low, close, last, prev_close, underlying, settlement,
+            open_int, chg_oi, volume, value, trades, session, lot_size
+        ]) {
+            Ok(rows) => inserted += rows,
+            Err(e) => warn!("NSE insert line {}: {}", lineno + 2, e),
+        }
+    }
+
+    info!("save_nse_csv_to_sqlite: inserted {} rows.", inserted);
+    Ok(inserted)
+}
+
+/// Legacy NSE Capital Market bhavcopy schema (files up to July 2024, before the UDiFF format):
+///   nse_cm_bhavcopy_legacy(trade_date, ticker_symbol, series, open_price, high_price,
+///                          low_price, close_price, last_price, prev_close, total_volume,
+///                          total_value, total_trades, isin)
+/// Unique key: (trade_date, ticker_symbol, series)
+///
+/// Column mapping from CSV headers:
+///   TIMESTAMP->trade_date (e.g. "02-JAN-2006" → "2006-01-02"), SYMBOL->ticker_symbol,
+///   SERIES->series, OPEN, HIGH, LOW, CLOSE, LAST, PREVCLOSE->prev_close,
+///   TOTTRDQTY->total_volume, TOTTRDVAL->total_value, TOTALTRADES->total_trades, ISIN->isin
+const NSE_LEGACY_CREATE_TABLE: &str = "
+CREATE TABLE IF NOT EXISTS nse_cm_bhavcopy_legacy (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    trade_date      TEXT NOT NULL,
+    ticker_symbol   TEXT NOT NULL,
+    series          TEXT,
+    open_price      REAL,
+    high_price      REAL,
+    low_price       REAL,
+    close_price     REAL,
+    last_price      REAL,
+    prev_close      REAL,
+    total_volume    REAL,
+    total_value     REAL,
+    total_trades    INTEGER,
+    isin            TEXT
+)";
+
+const NSE_LEGACY_CREATE_INDEX_DEDUP: &str =
+    "CREATE UNIQUE INDEX IF NOT EXISTS idx_nse_leg_dedup ON nse_cm_bhavcopy_legacy (trade_date, ticker_symbol, series)";
+const NSE_LEGACY_CREATE_INDEX_DATE: &str =
+    "CREATE INDEX IF NOT EXISTS idx_nse_leg_date ON nse_cm_bhavcopy_legacy (trade_date)";
+const NSE_LEGACY_CREATE_INDEX_TICKER: &str =
+    "CREATE INDEX IF NOT EXISTS idx_nse_leg_ticker ON nse_cm_bhavcopy_legacy (ticker_symbol, trade_date)";
+
+const NSE_LEGACY_INSERT: &str = "
+INSERT OR IGNORE INTO nse_cm_bhavcopy_legacy
+    (trade_date, ticker_symbol, series, open_price, high_price, low_price, close_price,
+     last_price, prev_close, total_volume, total_value, total_trades, isin)
+VALUES
+    (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)";
+
+/// True if the header line is that of the legacy (pre-UDiFF) NSE bhavcopy.
+pub fn is_nse_legacy_header(header_line: &str) -> bool {
+    let cols = parse_header(header_line);
+    cols.iter().any(|c| c == "SYMBOL") && cols.iter().any(|c| c == "TIMESTAMP")
+}
+
+/// Save NSE bhavcopy CSV content of either format: UDiFF files go to `nse_cm_bhavcopy` and
+/// legacy files to `nse_cm_bhavcopy_legacy`. The format is detected from the header line.
+pub fn save_nse_bhavcopy_csv(csv_content: &str, db_path: &str) -> Result<usize, String> {
+    match csv_content.lines().next() {
+        Some(header) if is_nse_legacy_header(header) => save_nse_legacy_csv_to_sqlite(csv_content, db_path),
+        _ => save_nse_csv_to_sqlite(csv_content, db_path),
+    }
+}
+
+/// Save a legacy NSE Capital Market bhavcopy CSV (SYMBOL,SERIES,OPEN,...,TIMESTAMP,...) to the
+/// `nse_cm_bhavcopy_legacy` table. Legacy files end every line with a trailing comma, so rows
+/// are matched to columns by header position rather than by an exact column count.
+///
+/// Returns Ok(rows_inserted) or Err(message).
+pub fn save_nse_legacy_csv_to_sqlite(csv_content: &str, db_path: &str) -> Result<usize, String> {
+    info!("save_nse_legacy_csv_to_sqlite: db='{}'", db_path);
+
+    let conn = Connection::open(db_path).map_err(|e| format!("open db '{}': {}", db_path, e))?;
+    conn.execute_batch("PRAGMA journal_mode=WAL; PRAGMA synchronous=NORMAL;")
+        .map_err(|e| format!("pragma: {}", e))?;
+    conn.execute(NSE_LEGACY_CREATE_TABLE, [])
+        .map_err(|e| format!("create table: {}", e))?;
+    for idx_sql in [NSE_LEGACY_CREATE_INDEX_DEDUP, NSE_LEGACY_CREATE_INDEX_DATE, NSE_LEGACY_CREATE_INDEX_TICKER] {
+        conn.execute(idx_sql, []).map_err(|e| format!("create index: {}", e))?;
+    }
+
+    let mut lines = csv_content.lines();
+    let header = match lines.next() {
+        Some(h) if is_nse_legacy_header(h) => h,
+        _ => return Err("CSV is not a legacy NSE bhavcopy (no SYMBOL/TIMESTAMP header)".to_string()),
+    };
+    let col_names = parse_header(header);
+    let col_idx: std::collections::HashMap<&str, usize> = col_names.iter()
+        .enumerate()
+        .map(|(i, name)| (name.as_str(), i))
+        .collect();
+
+    macro_rules! get_col {
+        ($name:expr, $fields:expr) => {
+            col_idx.get($name).and_then(|&i| $fields.get(i)).map(|s| s.trim()).unwrap_or("")
+        };
+    }
+
+    let mut stmt = conn.prepare(NSE_LEGACY_INSERT).map_err(|e| format!("prepare insert: {}", e))?;
+    let mut inserted: usize = 0;
+
+    for (lineno, line) in lines.enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() { continue; }
+        let fields = split_csv_line(trimmed);
+
+        let trade_date = match chrono::NaiveDate::parse_from_str(get_col!("TIMESTAMP", fields), "%d-%b-%Y") {
+            Ok(d) => d.format("%Y-%m-%d").to_string(),
+            Err(e) => {
+                warn!("NSE legacy CSV line {}: bad TIMESTAMP '{}': {} — skipping", lineno + 2, get_col!("TIMESTAMP", fields), e);
+                continue;
+            }
+        };
+        let symbol = get_col!("SYMBOL", fields);
+        if symbol.is_empty() { continue; }
+
+        match stmt.execute(rusqlite::params![
+            trade_date,
+            symbol,
+            get_col!("SERIES", fields),
+            parse_opt_real(get_col!("OPEN", fields)),
+            parse_opt_real(get_col!("HIGH", fields)),
+            parse_opt_real(get_col!("LOW", fields)),
+            parse_opt_real(get_col!("CLOSE", fields)),
+            parse_opt_real(get_col!("LAST", fields)),
+            parse_opt_real(get_col!("PREVCLOSE", fields)),
+            parse_opt_real(get_col!("TOTTRDQTY", fields)),
+            parse_opt_real(get_col!("TOTTRDVAL", fields)),
+            parse_opt_int(get_col!("TOTALTRADES", fields)),
+            get_col!("ISIN", fields),
+        ]) {
+            Ok(rows) => inserted += rows,
+            Err(e) => warn!("NSE legacy insert line {}: {}", lineno + 2, e),
+        }
+    }
+
+    info!("save_nse_legacy_csv_to_sqlite: inserted {} rows.", inserted);
+    Ok(inserted)
+}
+
+/// One observation of a time series (reference rate, NAV, ...) destined for `market_series`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeriesPoint {
+    /// Publisher, e.g. "FBIL", "RBI", "AMFI".
+    pub source: String,
+    /// Stable instrument id, e.g. "FBIL:MIBOR:OVERNIGHT" or "AMFI:119551".
+    pub instrument: String,
+    pub date: chrono::NaiveDate,
+    pub value: f64,
+    /// Unit of `value`: "PCT" for rates in percent, a currency code for prices.
+    pub unit: String,
+    /// The dataset the point came from, for provenance.
+    pub table_ref: String,
+}
+
+/// Upsert series points into `market_series` (created by the store migrations) in one
+/// transaction. A later publication for the same (source, instrument, date) replaces the
+/// earlier value, so revised rates overwrite provisional ones.
+///
+/// Returns Ok(rows_written) or Err(message).
+pub fn save_series_to_sqlite(points: &[SeriesPoint], db_path: &str) -> Result<usize, String> {
+    info!("save_series_to_sqlite: {} point(s), db='{}'", points.len(), db_path);
+    let mut conn = crate::store::open_and_migrate(db_path)?;
+    let tx = conn.transaction().map_err(|e| format!("begin transaction: {}", e))?;
+    let mut written = 0;
+    {
+        let mut stmt = tx
+            .prepare(
+                "INSERT OR REPLACE INTO market_series (source, instrument, date, value, unit, table_ref)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )
+            .map_err(|e| format!("prepare insert: {}", e))?;
+        for p in points {
+            match stmt.execute(rusqlite::params![
+                p.source,
+                p.instrument,
+                p.date.format("%Y-%m-%d").to_string(),
+                p.value,
+                p.unit,
+                p.table_ref,
+            ]) {
+                Ok(rows) => written += rows,
+                Err(e) => warn!("market_series insert {} {}: {}", p.instrument, p.date, e),
+            }
+        }
+    }
+    tx.commit().map_err(|e| format!("commit: {}", e))?;
+    Ok(written)
+}
+
+/// Kind of corporate action, as far as price adjustment is concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionType {
+    Split,
+    Bonus,
+    Dividend,
+    Rights,
+    Other,
+}
+
+impl ActionType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActionType::Split => "SPLIT",
+            ActionType::Bonus => "BONUS",
+            ActionType::Dividend => "DIVIDEND",
+            ActionType::Rights => "RIGHTS",
+            ActionType::Other => "OTHER",
+        }
+    }
+
+    pub fn parse(s: &str) -> ActionType {
+        match s {
+            "SPLIT" => ActionType::Split,
+            "BONUS" => ActionType::Bonus,
+            "DIVIDEND" => ActionType::Dividend,
+            "RIGHTS" => ActionType::Rights,
+            _ => ActionType::Other,
+        }
+    }
+}
+
+/// One corporate action, as stored in `corporate_actions`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorporateAction {
+    /// "NSE" or "BSE".
+    pub exchange: String,
+    /// NSE trading symbol or BSE scrip code.
+    pub symbol: String,
+    pub isin: Option<String>,
+    pub ex_date: chrono::NaiveDate,
+    pub action_type: ActionType,
+    /// The exchange's description, e.g. "Bonus 1:1" or "Dividend - Rs 8 Per Share".
+    pub subject: String,
+    /// Shares held after the action per share held before (splits and bonuses).
+    pub share_ratio: Option<f64>,
+    /// Cash paid per share (dividends).
+    pub cash_amount: Option<f64>,
+    pub record_date: Option<chrono::NaiveDate>,
+}
+
+/// Upsert corporate actions into `corporate_actions` in one transaction.
+///
+/// Returns Ok(rows_written) or Err(message).
+pub fn save_corporate_actions(actions: &[CorporateAction], db_path: &str) -> Result<usize, String> {
+    info!("save_corporate_actions: {} action(s), db='{}'", actions.len(), db_path);
+    let mut conn = crate::store::open_and_migrate(db_path)?;
+    let tx = conn.transaction().map_err(|e| format!("begin transaction: {}", e))?;
+    let mut written = 0;
+    {
+        let mut stmt = tx
+            .prepare(
+                "INSERT OR REPLACE INTO corporate_actions
+                    (exchange, symbol, isin, ex_date, action_type, subject, share_ratio, cash_amount, record_date)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            )
+            .map_err(|e| format!("prepare insert: {}", e))?;
+        for a in actions {
+            match stmt.execute(rusqlite::params![
+                a.exchange,
+                a.symbol,
+                a.isin,
+                a.ex_date.format("%Y-%m-%d").to_string(),
+                a.action_type.as_str(),
+                a.subject,
+                a.share_ratio,
+                a.cash_amount,
+                a.record_date.map(|d| d.format("%Y-%m-%d").to_string()),
+            ]) {
+                Ok(rows) => written += rows,
+                Err(e) => warn!("corporate_actions insert {} {}: {}", a.symbol, a.ex_date, e),
+            }
+        }
+    }
+    tx.commit().map_err(|e| format!("commit: {}", e))?;
+    Ok(written)
+}
+
+/// Corporate actions of `symbol` on `exchange` going ex after `from` and up to `to`, in
+/// ex-date order.
+pub fn load_corporate_actions(
+    conn: &Connection,
+    exchange: &str,
+    symbol: &str,
+    from: chrono::NaiveDate,
+    to: chrono::NaiveDate,
+) -> Result<Vec<CorporateAction>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT exchange, symbol, isin, ex_date, action_type, subject, share_ratio, cash_amount, record_date
+             FROM corporate_actions
+             WHERE exchange=?1 AND symbol=?2 AND ex_date > ?3 AND ex_date <= ?4
+             ORDER BY ex_date",
+        )
+        .map_err(|e| format!("prepare corporate_actions query: {}", e))?;
+    let parse_date = |s: Option<String>| s.and_then(|d| chrono::NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok());
+    let rows = stmt
+        .query_map(
+            rusqlite::params![exchange, symbol, from.format("%Y-%m-%d").to_string(), to.format("%Y-%m-%d").to_string()],
+            |r| {
+                Ok((
+                    r.get::<_, String>(0)?, r.get::<_, String>(1)?, r.get::<_, Option<String>>(2)?,
+                    r.get::<_, String>(3)?, r.get::<_, String>(4)?, r.get::<_, String>(5)?,
+                    r.get::<_, Option<f64>>(6)?, r.get::<_, Option<f64>>(7)?, r.get::<_, Option<String>>(8)?,
+                ))
+            },
+        )
+        .map_err(|e| format!("query corporate_actions: {}", e))?;
+    Ok(rows
+        .flatten()
+        .filter_map(|(exchange, symbol, isin, ex_date, action_type, subject, share_ratio, cash_amount, record_date)| {
+            Some(CorporateAction {
+                exchange,
+                symbol,
+                isin,
+                ex_date: parse_date(Some(ex_date))?,
+                action_type: ActionType::parse(&action_type),
+                subject,
+                share_ratio,
+                cash_amount,
+                record_date: parse_date(record_date),
+            })
+        })
+        .collect())
+}
+
+/// One daily price bar.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OhlcvBar {
+    pub date: chrono::NaiveDate,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+}
+
+/// Back-adjust `bars` (in date order) for `actions`, so that prices before each ex-date are
+/// comparable with prices after it:
+///   - split/bonus with share ratio r: earlier prices × 1/r, earlier volumes × r;
+///   - dividend d: earlier prices × (1 − d / close of the last bar before the ex-date).
+///
+/// Actions without a ratio or amount (rights, others) do not adjust anything, nor does a
+/// dividend with no earlier bar to price it against.
+pub fn adjust_ohlcv(bars: &[OhlcvBar], actions: &[CorporateAction]) -> Vec<OhlcvBar> {
+    // (ex_date, price factor, volume factor) for every action that adjusts prices.
+    let factors: Vec<(chrono::NaiveDate, f64, f64)> = actions.iter()
+        .filter_map(|a| match (a.action_type, a.share_ratio, a.cash_amount) {
+            (ActionType::Split | ActionType::Bonus, Some(r), _) if r > 0.0 => Some((a.ex_date, 1.0 / r, r)),
+            (ActionType::Dividend, _, Some(d)) if d > 0.0 => {
+                let prev_close = bars.iter().rev().find(|b| b.date < a.ex_date).map(|b| b.close)?;
+                if prev_close > d { Some((a.ex_date, 1.0 - d / prev_close, 1.0)) } else { None }
+            }
+            _ => None,
+        })
+        .collect();
+
+    bars.iter()
+        .map(|b| {
+            let (price, volume) = factors.iter()
+                .filter(|(ex_date, _, _)| b.date < *ex_date)
+                .fold((1.0, 1.0), |(p, v), (_, pf, vf)| (p * pf, v * vf));
+            OhlcvBar {
+                date: b.date,
+                open: b.open * price,
+                high: b.high * price,
+                low: b.low * price,
+                close: b.close * price,
+                volume: b.volume * volume,
+            }
+        })
+        .collect()
+}
+
+/// True if `table` exists in the database.
+fn table_exists(conn: &Connection, table: &str) -> bool {
+    conn.query_row("SELECT 1 FROM sqlite_master WHERE type='table' AND name=?1", [table], |_| Ok(()))
+        .is_ok()
+}
+
+/// Raw daily bars of an NSE symbol (EQ series) from both bhavcopy tables, in date order.
+/// Where both formats hold a date, the UDiFF row wins.
+fn load_nse_bars(
+    conn: &Connection,
+    symbol: &str,
+    from: chrono::NaiveDate,
+    to: chrono::NaiveDate,
+) -> Result<Vec<OhlcvBar>, String> {
+    let mut bars = std::collections::BTreeMap::new();
+    for (table, series_col) in [("nse_cm_bhavcopy_legacy", "series"), ("nse_cm_bhavcopy", "security_series")] {
+        if !table_exists(conn, table) {
+            continue;
+        }
+        let sql = format!(
+            "SELECT trade_date, open_price, high_price, low_price, close_price, total_volume FROM {}
+             WHERE ticker_symbol=?1 AND {}='EQ' AND trade_date BETWEEN ?2 AND ?3 AND close_price IS NOT NULL",
+            table, series_col
+        );
+        let mut stmt = conn.prepare(&sql).map_err(|e| format!("prepare {} query: {}", table, e))?;
+        let rows = stmt
+            .query_map(
+                rusqlite::params![symbol, from.format("%Y-%m-%d").to_string(), to.format("%Y-%m-%d").to_string()],
+                |r| {
+                    Ok((
+                        r.get::<_, String>(0)?, r.get::<_, Option<f64>>(1)?, r.get::<_, Option<f64>>(2)?,
+                        r.get::<_, Option<f64>>(3)?, r.get::<_, f64>(4)?, r.get::<_, Option<f64>>(5)?,
+                    ))
+                },
+            )
+            .map_err(|e| format!("query {}: {}", table, e))?;
+        for (date, open, high, low, close, volume) in rows.flatten() {
+            if let Ok(date) = chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+                bars.insert(date, OhlcvBar {
+                    date,
+                    open: open.unwrap_or(close),
+                    high: high.unwrap_or(close),
+                    low: low.unwrap_or(close),
+                    close,
+                    volume: volume.unwrap_or(0.0),
+                });
+            }
+        }
+    }
+    Ok(bars.into_values().collect())
+}
+
+/// Daily OHLCV of an NSE equity (EQ series) between `from` and `to` inclusive, back-adjusted
+/// for the splits, bonuses and dividends in `corporate_actions` that go ex within the range.
+/// Prices are therefore expressed in terms of the last date's share.
+///
+/// Returns Ok(bars in date order) or Err(message).
+pub fn adjusted_ohlcv(
+    db_path: &str,
+    symbol: &str,
+    from: chrono::NaiveDate,
+    to: chrono::NaiveDate,
+) -> Result<Vec<OhlcvBar>, String> {
+    let conn = crate::store::open_and_migrate(db_path)?;
+    query::ohlcv(&conn, symbol, from, to, true)
+}
+
+/// Save CSV market data (generic BSE bhavcopy or other) to a SQLite database table.
+///
+/// # Arguments
+/// * `csv_content` - Full CSV text; first line is the header, remaining lines are data rows.
+/// * `table_name`  - Destination table name (will be sanitized).
+/// * `date`        - Trade date string stored in the `trade_date` column (e.g. `"2024-01-15"`).
+/// * `db_path`     - Filesystem path of the SQLite database file (created if absent).
+///
+/// # Returns
+/// `Ok(n)` where `n` is the number of rows inserted, or `Err(msg)` on fatal errors.
+pub fn save_csv_to_sqlite(
+    csv_content: &str,
+    table_name: &str,
+    date: &str,
+    db_path: &str,
+) -> Result<usize, String> {
+    let safe_table = sanitize_table_name(table_name);
+    info!(
+        "save_csv_to_sqlite: table='{}', date='{}', db='{}'",
+        safe_table, date, db_path
+    );
+
+    let conn = Connection::open(db_path).map_err(|e| {
+        error!("Failed to open database '{}': {}", db_path, e);
+        format!("Failed to open database '{}': {}", db_path, e)
+    })?;
+
+    let mut lines = csv_content.lines();
+
+    let header_line = match lines.next() {
+        Some(line) if !line.trim().is_empty() => line,
+        _ => {
+            error!("CSV content has no header line.");
+            return Err("CSV content has no header line.".to_string());
+        }
+    };
+
+    let columns = parse_header(header_line);
+    if columns.is_empty() {
+        error!("CSV header is empty.");
+        return Err("CSV header is empty.".to_string());
+    }
+
+    // For generic CSVs, the dedup key is (trade_date, first_col, second_col) to avoid
+    // the case where first_col is the same for all rows (e.g. date columns).
+    let dedup_cols = if columns.len() >= 2 {
+        format!("trade_date, \"{}\", \"{}\"", columns[0], columns[1])
+    } else {
+        format!("trade_date, \"{}\"", columns[0])
+    };
+
+    let col_defs: String = columns
+        .iter()
+        .map(|c| format!("\"{}\" TEXT", c))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let create_sql = format!(
+        "CREATE TABLE IF NOT EXISTS \"{safe_table}\" \
+         (trade_date TEXT NOT NULL, {col_defs})"
+    );
+    conn.execute(&create_sql, []).map_err(|e| {
+        error!("Failed to create table '{}': {}", safe_table, e);
+        format!("Failed to create table '{}': {}", safe_table, e)
+    })?;
+
+    let index_sql = format!(
+        "CREATE UNIQUE INDEX IF NOT EXISTS \"idx_{safe_table}_dedup\" \
+         ON \"{safe_table}\" ({dedup_cols})"
+    );
+    conn.execute(&index_sql, []).map_err(|e| {
+        error!("Failed to create unique index on '{}': {}", safe_table, e);
+        format!("Failed to create unique index on '{}': {}", safe_table, e)
+    })?;
+
+    let placeholders: String = std::iter::repeat_n("?", columns.len() + 1)
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let col_names: String = columns
+        .iter()
+        .map(|c| format!("\"{}\"", c))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let insert_sql = format!(
+        "INSERT OR IGNORE INTO \"{safe_table}\" (trade_date, {col_names}) VALUES ({placeholders})"
+    );
+
+    let mut stmt = conn.prepare(&insert_sql).map_err(|e| {
+        error!("Failed to prepare INSERT statement: {}", e);
+        format!("Failed to prepare INSERT statement: {}", e)
+    })?;
+
+    let expected_col_count = columns.len();
+    let mut inserted: usize = 0;
+
+    for (line_num, line) in lines.enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() { continue; }
+
+        let fields = split_csv_line(trimmed);
+        if fields.len() != expected_col_count {
+            warn!(
+                "Line {}: expected {} columns, got {} — skipping.",
+                line_num + 2, expected_col_count, fields.len()
+            );
+            continue;
+        }
+
+        let params: Vec<&str> = std::iter::once(date)
+            .chain(fields.iter().map(String::as_str))
+            .collect();
+
+        match stmt.execute(rusqlite::params_from_iter(params.iter())) {
+            Ok(rows_changed) => inserted += rows_changed,
+            Err(e) => warn!("Failed to insert line {}: {} — skipping.", line_num + 2, e),
+        }
+    }
+
+    info!("save_csv_to_sqlite: inserted {} rows into '{}'.", inserted, safe_table);
+    Ok(inserted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_BSE_CSV: &str = "\
+SYMBOL,SERIES,OPEN,HIGH,LOW,CLOSE,TOTTRDQTY
+RELIANCE,EQ,2800.00,2850.00,2790.00,2840.00,1000000
+INFY,EQ,1500.00,1520.00,1490.00,1510.00,500000
+TCS,EQ,3500.00,3550.00,3480.00,3530.00,300000";
+
+    const SAMPLE_NSE_CSV: &str = "\
+TradDt,BizDt,Sgmt,Src,FinInstrmTp,FinInstrmId,ISIN,TckrSymb,SctySrs,XpryDt,FininstrmActlXpryDt,StrkPric,OptnTp,FinInstrmNm,OpnPric,HghPric,LwPric,ClsPric,LastPric,PrvsClsgPric,UndrlygPric,SttlmPric,OpnIntrst,ChngInOpnIntrst,TtlTradgVol,TtlTrfVal,TtlNbOfTxsExctd,SsnId,NewBrdLotQty,Rmks,Rsvd1,Rsvd2,Rsvd3,Rsvd4
+2026-05-29,2026-05-29,CM,NSE,STK,2885,INE002A01018,RELIANCE,EQ,,,,,RELIANCE INDUSTRIES LTD,1360.10,1368.50,1352.40,1356.30,1357.00,1367.00,,1356.30,,,13769747,18716137070.80,148444,F1,1,,,,,
+2026-05-29,2026-05-29,CM,NSE,STK,4963,INE009A01021,INFY,EQ,,,,,INFOSYS LTD,1450.00,1465.00,1445.00,1460.00,1460.00,1455.00,,1460.00,,,5000000,7300000000.00,95000,F1,1,,,,,";
+
+    #[test]
+    fn test_sanitize_table_name() {
+        assert_eq!(sanitize_table_name("NSE-bhavcopy"), "NSE_bhavcopy");
+        assert_eq!(sanitize_table_name("BSE bhavcopy"), "BSE_bhavcopy");
+        assert_eq!(sanitize_table_name("eq.bhavcopy"), "eq_bhavcopy");
+        assert_eq!(sanitize_table_name("clean_name"), "clean_name");
+    }
+
+    #[test]
+    fn test_parse_header() {
+        let cols = parse_header("SYMBOL, SERIES, OPEN, HIGH");
+        assert_eq!(cols, vec!["SYMBOL", "SERIES", "OPEN", "HIGH"]);
+    }
+
+    #[test]
+    fn test_save_bse_csv_to_sqlite() {
+        let tmp = std::env::temp_dir().join("bse_market_data_test.db");
+        let db_path = tmp.to_str().unwrap();
+        let _ = std::fs::remove_file(db_path);
+
+        let result = save_csv_to_sqlite(SAMPLE_BSE_CSV, "BSE-bhavcopy", "2024-01-15", db_path);
+        assert!(result.is_ok(), "Expected Ok, got: {:?}", result);
+        assert_eq!(result.unwrap(), 3);
+
+        // Duplicate import must not insert duplicates.
+        let result2 = save_csv_to_sqlite(SAMPLE_BSE_CSV, "BSE-bhavcopy", "2024-01-15", db_path);
+        assert!(result2.is_ok());
+        assert_eq!(result2.unwrap(), 0, "Duplicate rows should be ignored");
+
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn test_save_nse_csv_to_sqlite() {
+        let tmp = std::env::temp_dir().join("nse_market_data_test.db");
+        let db_path = tmp.to_str().unwrap();
+        let _ = std::fs::remove_file(db_path);
+
+        let result = save_nse_csv_to_sqlite(SAMPLE_NSE_CSV, db_path);
+        assert!(result.is_ok(), "Expected Ok, got: {:?}", result);
+        assert_eq!(result.unwrap(), 2, "Should insert 2 rows");
+
+        // Duplicate import must be idempotent.
+        let result2 = save_nse_csv_to_sqlite(SAMPLE_NSE_CSV, db_path);
+        assert!(result2.is_ok());
+        assert_eq!(result2.unwrap(), 0, "Duplicate rows should be ignored");
+
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    const SAMPLE_NSE_LEGACY_CSV: &str = "\
+SYMBOL,SERIES,OPEN,HIGH,LOW,CLOSE,LAST,PREVCLOSE,TOTTRDQTY,TOTTRDVAL,TIMESTAMP,TOTALTRADES,ISIN,
+RELIANCE,EQ,2800,2850,2790,2840,2841,2795,1000000,2840000000,02-JAN-2024,120000,INE002A01018,
+INFY,EQ,1500,1520,1490,1510,1511,1495,500000,755000000,02-JAN-2024,80000,INE009A01021,";
+
+    #[test]
+    fn test_save_nse_bhavcopy_csv_detects_legacy_format() {
+        assert!(is_nse_legacy_header(SAMPLE_NSE_LEGACY_CSV.lines().next().unwrap()));
+        assert!(!is_nse_legacy_header(SAMPLE_NSE_CSV.lines().next().unwrap()));
+
+        let tmp = std::env::temp_dir().join("nse_legacy_market_data_test.db");
+        let db_path = tmp.to_str().unwrap();
+        let _ = std::fs::remove_file(db_path);
+
+        assert_eq!(save_nse_bhavcopy_csv(SAMPLE_NSE_LEGACY_CSV, db_path), Ok(2));
+        assert_eq!(save_nse_bhavcopy_csv(SAMPLE_NSE_LEGACY_CSV, db_path), Ok(0), "Duplicate rows should be ignored");
+        let conn = Connection::open(db_path).unwrap();
+        let (date, close): (String, f64) = conn.query_row(
+            "SELECT trade_date, close_price FROM nse_cm_bhavcopy_legacy WHERE ticker_symbol='INFY'",
+            [], |r| Ok((r.get(0)?, r.get(1)?))).unwrap();
+        assert_eq!(date, "2024-01-02");
+        assert_eq!(close, 1510.0);
+
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn test_save_series_replaces_revised_values() {
+        let tmp = std::env::temp_dir().join("market_series_test.db");
+        let db_path = tmp.to_str().unwrap();
+        let _ = std::fs::remove_file(db_path);
+
+        let point = |value: f64| SeriesPoint {
+            source: "FBIL".to_string(),
+            instrument: "FBIL:MIBOR:OVERNIGHT".to_string(),
+            date: chrono::NaiveDate::from_ymd_opt(2025, 6, 3).unwrap(),
+            value,
+            unit: "PCT".to_string(),
+            table_ref: "fbil_mibor".to_string(),
+        };
+        assert_eq!(save_series_to_sqlite(&[point(5.80)], db_path), Ok(1));
+        assert_eq!(save_series_to_sqlite(&[point(5.82)], db_path), Ok(1));
+
+        let conn = Connection::open(db_path).unwrap();
+        let (n, value): (i64, f64) = conn.query_row(
+            "SELECT COUNT(*), MAX(value) FROM market_series WHERE instrument='FBIL:MIBOR:OVERNIGHT'",
+            [], |r| Ok((r.get(0)?, r.get(1)?))).unwrap();
+        assert_eq!((n, value), (1, 5.82));
+
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn test_bad_column_count_rows_skipped() {
+        let csv = "SYMBOL,SERIES,CLOSE\nRELIANCE,EQ\nINFY,EQ,1510.00";
+        let tmp = std::env::temp_dir().join("market_data_badrow_test.db");
+        let db_path = tmp.to_str().unwrap();
+        let _ = std::fs::remove_file(db_path);
+
+        let result = save_csv_to_sqlite(csv, "test_table", "2024-01-16", db_path);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), 1);
+
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn test_quoted_commas_keep_columns_aligned() {
+        let csv = "SC_CODE,SC_NAME,CLOSE\n500001,\"ABC Industries, Ltd.\",101.50\n500002,XYZ Ltd,55.00";
+        let tmp = std::env::temp_dir().join("market_data_quoted_test.db");
+        let db_path = tmp.to_str().unwrap();
+        let _ = std::fs::remove_file(db_path);
+
+        assert_eq!(save_csv_to_sqlite(csv, "quoted_table", "2024-01-16", db_path), Ok(2));
+        let conn = Connection::open(db_path).unwrap();
+        let (name, close): (String, String) = conn.query_row(
+            "SELECT SC_NAME, CLOSE FROM quoted_table WHERE SC_CODE='500001'",
+            [], |r| Ok((r.get(0)?, r.get(1)?))).unwrap();
+        assert_eq!((name.as_str(), close.as_str()), ("ABC Industries, Ltd.", "101.50"));
+
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    fn bar(day: u32, close: f64, volume: f64) -> OhlcvBar {
+        OhlcvBar {
+            date: chrono::NaiveDate::from_ymd_opt(2025, 6, day).unwrap(),
+            open: close, high: close, low: close, close, volume,
+        }
+    }
+
+    fn action(day: u32, action_type: ActionType, share_ratio: Option<f64>, cash_amount: Option<f64>) -> CorporateAction {
+        CorporateAction {
+            exchange: "NSE".to_string(),
+            symbol: "XYZ".to_string(),
+            isin: None,
+            ex_date: chrono::NaiveDate::from_ymd_opt(2025, 6, day).unwrap(),
+            action_type,
+            subject: String::new(),
+            share_ratio,
+            cash_amount,
+            record_date: None,
+        }
+    }
+
+    #[test]
+    fn test_adjust_ohlcv_for_split_and_dividend() {
+        let bars = vec![bar(2, 1000.0, 10.0), bar(3, 1010.0, 10.0), bar(4, 202.0, 50.0), bar(5, 200.0, 50.0)];
+        // 1:5 split ex on the 4th, then a Rs 2 dividend ex on the 5th.
+        let actions = vec![
+            action(4, ActionType::Split, Some(5.0), None),
+            action(5, ActionType::Dividend, None, Some(2.02)),
+        ];
+        let adjusted = adjust_ohlcv(&bars, &actions);
+        let dividend_factor = 1.0 - 2.02 / 202.0;
+        assert!((adjusted[0].close - 200.0 * dividend_factor).abs() < 1e-9);
+        assert!((adjusted[1].close - 202.0 * dividend_factor).abs() < 1e-9);
+        assert_eq!(adjusted[0].volume, 50.0);
+        assert!((adjusted[2].close - 202.0 * dividend_factor).abs() < 1e-9);
+        assert_eq!(adjusted[3], bars[3], "bars on or after the last ex-date are unchanged");
+        // Rights issues carry no ratio here and change nothing.
+        assert_eq!(adjust_ohlcv(&bars, &[action(4, ActionType::Rights, None, None)]), bars);
+    }
+
+    #[test]
+    fn test_adjusted_ohlcv_reads_bhavcopy_and_actions() {
+        let tmp = std::env::temp_dir().join("market_data_adjusted_test.db");
+        let db_path = tmp.to_str().unwrap();
+        let _ = std::fs::remove_file(db_path);
+
+        assert_eq!(save_nse_csv_to_sqlite(SAMPLE_NSE_CSV, db_path), Ok(2));
+        let bonus = CorporateAction {
+            symbol: "RELIANCE".to_string(),
+            ex_date: chrono::NaiveDate::from_ymd_opt(2026, 6, 1).unwrap(),
+            subject: "Bonus 1:1".to_string(),
+            ..action(1, ActionType::Bonus, Some(2.0), None)
+        };
+        assert_eq!(save_corporate_actions(&[bonus], db_path), Ok(1));
+
+        let from = chrono::NaiveDate::from_ymd_opt(2026, 5, 1).unwrap();
+        let to = chrono::NaiveDate::from_ymd_opt(2026, 6, 30).unwrap();
+        let bars = adjusted_ohlcv(db_path, "RELIANCE", from, to).unwrap();
+        assert_eq!(bars.len(), 1);
+        assert!((bars[0].close - 1356.30 / 2.0).abs() < 1e-9);
+        assert_eq!(bars[0].volume, 13769747.0 * 2.0);
+        // An action going ex after the range end does not adjust it.
+        let before_bonus = chrono::NaiveDate::from_ymd_opt(2026, 5, 31).unwrap();
+        assert_eq!(adjusted_ohlcv(db_path, "RELIANCE", from, before_bonus).unwrap()[0].close, 1356.30);
+
+        let _ = std::fs::remove_file(db_path);
+    }
+}
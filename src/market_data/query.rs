@@ -0,0 +1,304 @@
+// file: market_data/query.rs
+// Purpose:
+//   Read-side queries over the market-data DB, so dashboards and notebooks need not write
+//   SQL against the raw bhavcopy tables: latest close, OHLCV over a date range, return over
+//   a window of trading days, top movers for a date, and `market_series` observations for an
+//   instrument. Also renders results as CSV; the web API serves them under `/market/...`.
+//
+//   Equity prices come from the NSE bhavcopy tables (EQ series; both the UDiFF and legacy
+//   formats). BSE bhavcopies are kept as loaded in generic text tables and are not queried
+//   here. Reference rates, NAVs and other series come from `market_series`.
+
+use chrono::{Duration, NaiveDate};
+use rusqlite::Connection;
+
+use super::{adjust_ohlcv, load_corporate_actions, load_nse_bars, table_exists, OhlcvBar, SeriesPoint};
+
+/// (table, series column) of the NSE bhavcopy tables, UDiFF first: where both hold a date,
+/// the UDiFF rows are used.
+const NSE_TABLES: [(&str, &str); 2] = [("nse_cm_bhavcopy", "security_series"), ("nse_cm_bhavcopy_legacy", "series")];
+
+/// One equity's move on a trading day, relative to the previous close.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mover {
+    pub symbol: String,
+    pub isin: Option<String>,
+    pub close: f64,
+    pub prev_close: f64,
+    pub change_pct: f64,
+    pub volume: f64,
+}
+
+/// Largest gainers and losers of a trading day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopMovers {
+    pub date: NaiveDate,
+    /// Highest change first.
+    pub gainers: Vec<Mover>,
+    /// Lowest change first.
+    pub losers: Vec<Mover>,
+}
+
+/// Close-to-close return of an equity over a window of trading days.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowReturn {
+    pub symbol: String,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub start_close: f64,
+    pub end_close: f64,
+    pub return_pct: f64,
+}
+
+fn ymd(d: NaiveDate) -> String {
+    d.format("%Y-%m-%d").to_string()
+}
+
+/// The most recent bar of an NSE equity (unadjusted), or None if it has no prices.
+pub fn latest_close(conn: &Connection, symbol: &str) -> Result<Option<OhlcvBar>, String> {
+    let mut latest: Option<String> = None;
+    for (table, series_col) in NSE_TABLES {
+        if !table_exists(conn, table) {
+            continue;
+        }
+        let sql = format!(
+            "SELECT MAX(trade_date) FROM {} WHERE ticker_symbol=?1 AND {}='EQ' AND close_price IS NOT NULL",
+            table, series_col
+        );
+        let date: Option<String> = conn
+            .query_row(&sql, [symbol], |r| r.get(0))
+            .map_err(|e| format!("query latest date in {}: {}", table, e))?;
+        if date > latest {
+            latest = date;
+        }
+    }
+    let date = match latest.and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok()) {
+        Some(d) => d,
+        None => return Ok(None),
+    };
+    Ok(load_nse_bars(conn, symbol, date, date)?.pop())
+}
+
+/// Daily bars of an NSE equity between `from` and `to` inclusive, in date order; back-adjusted
+/// for corporate actions going ex within the range when `adjusted` is set (see
+/// `super::adjust_ohlcv`).
+pub fn ohlcv(conn: &Connection, symbol: &str, from: NaiveDate, to: NaiveDate, adjusted: bool) -> Result<Vec<OhlcvBar>, String> {
+    let bars = load_nse_bars(conn, symbol, from, to)?;
+    if !adjusted {
+        return Ok(bars);
+    }
+    let actions = load_corporate_actions(conn, "NSE", symbol, from, to)?;
+    Ok(adjust_ohlcv(&bars, &actions))
+}
+
+/// Adjusted close-to-close return of an NSE equity over the last `window` trading days
+/// ending on or before `end`. None if it has fewer than `window + 1` bars in that span.
+pub fn window_return(conn: &Connection, symbol: &str, end: NaiveDate, window: usize) -> Result<Option<WindowReturn>, String> {
+    if window == 0 {
+        return Err("window must be at least one trading day".to_string());
+    }
+    // Enough calendar days to cover `window` trading days through weekends and holidays.
+    let from = end - Duration::days(window as i64 * 2 + 14);
+    let bars = ohlcv(conn, symbol, from, end, true)?;
+    if bars.len() <= window {
+        return Ok(None);
+    }
+    let (start, last) = (&bars[bars.len() - 1 - window], &bars[bars.len() - 1]);
+    if start.close <= 0.0 {
+        return Ok(None);
+    }
+    Ok(Some(WindowReturn {
+        symbol: symbol.to_string(),
+        from: start.date,
+        to: last.date,
+        start_close: start.close,
+        end_close: last.close,
+        return_pct: (last.close / start.close - 1.0) * 100.0,
+    }))
+}
+
+/// The `limit` largest gainers and losers among NSE equities on `date`, by change from the
+/// previous close. Empty lists if no bhavcopy is loaded for the date.
+pub fn top_movers(conn: &Connection, date: NaiveDate, limit: usize) -> Result<TopMovers, String> {
+    let mut movers: Vec<Mover> = Vec::new();
+    for (table, series_col) in NSE_TABLES {
+        if !table_exists(conn, table) {
+            continue;
+        }
+        let sql = format!(
+            "SELECT ticker_symbol, isin, close_price, prev_close, total_volume FROM {}
+             WHERE trade_date=?1 AND {}='EQ' AND close_price IS NOT NULL AND prev_close > 0",
+            table, series_col
+        );
+        let mut stmt = conn.prepare(&sql).map_err(|e| format!("prepare {} query: {}", table, e))?;
+        let rows = stmt
+            .query_map([ymd(date)], |r| {
+                let close: f64 = r.get(2)?;
+                let prev_close: f64 = r.get(3)?;
+                Ok(Mover {
+                    symbol: r.get(0)?,
+                    isin: r.get::<_, Option<String>>(1)?.filter(|s| !s.is_empty()),
+                    close,
+                    prev_close,
+                    change_pct: (close / prev_close - 1.0) * 100.0,
+                    volume: r.get::<_, Option<f64>>(4)?.unwrap_or(0.0),
+                })
+            })
+            .map_err(|e| format!("query {}: {}", table, e))?;
+        movers = rows.flatten().collect();
+        if !movers.is_empty() {
+            break;
+        }
+    }
+
+    movers.sort_by(|a, b| b.change_pct.total_cmp(&a.change_pct).then_with(|| a.symbol.cmp(&b.symbol)));
+    let gainers: Vec<Mover> = movers.iter().filter(|m| m.change_pct > 0.0).take(limit).cloned().collect();
+    let losers: Vec<Mover> = movers.iter().rev().filter(|m| m.change_pct < 0.0).take(limit).cloned().collect();
+    Ok(TopMovers { date, gainers, losers })
+}
+
+/// Observations of `instrument` in `market_series` between `from` and `to` inclusive, in date
+/// order (an instrument published by several sources has one row per source and date).
+pub fn series(conn: &Connection, instrument: &str, from: NaiveDate, to: NaiveDate) -> Result<Vec<SeriesPoint>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT source, instrument, date, value, unit, table_ref FROM market_series
+             WHERE instrument=?1 AND date BETWEEN ?2 AND ?3 AND value IS NOT NULL
+             ORDER BY date, source",
+        )
+        .map_err(|e| format!("prepare market_series query: {}", e))?;
+    let rows = stmt
+        .query_map(rusqlite::params![instrument, ymd(from), ymd(to)], |r| {
+            Ok((
+                r.get::<_, String>(0)?, r.get::<_, String>(1)?, r.get::<_, String>(2)?,
+                r.get::<_, f64>(3)?, r.get::<_, Option<String>>(4)?, r.get::<_, Option<String>>(5)?,
+            ))
+        })
+        .map_err(|e| format!("query market_series: {}", e))?;
+    Ok(rows
+        .flatten()
+        .filter_map(|(source, instrument, date, value, unit, table_ref)| {
+            Some(SeriesPoint {
+                source,
+                instrument,
+                date: NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok()?,
+                value,
+                unit: unit.unwrap_or_default(),
+                table_ref: table_ref.unwrap_or_default(),
+            })
+        })
+        .collect())
+}
+
+/// Render bars as CSV with a `date,open,high,low,close,volume` header.
+pub fn bars_to_csv(bars: &[OhlcvBar]) -> String {
+    let mut csv = String::from("date,open,high,low,close,volume\n");
+    for b in bars {
+        csv.push_str(&format!("{},{},{},{},{},{}\n", b.date, b.open, b.high, b.low, b.close, b.volume));
+    }
+    csv
+}
+
+/// Render top movers as CSV, gainers then losers, with a `side` column.
+pub fn movers_to_csv(movers: &TopMovers) -> String {
+    let mut csv = String::from("date,side,symbol,isin,close,prev_close,change_pct,volume\n");
+    for (side, list) in [("gainer", &movers.gainers), ("loser", &movers.losers)] {
+        for m in list {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{:.4},{}\n",
+                movers.date, side, m.symbol, m.isin.as_deref().unwrap_or(""), m.close, m.prev_close, m.change_pct, m.volume
+            ));
+        }
+    }
+    csv
+}
+
+/// Render series points as CSV with a `date,source,instrument,value,unit` header.
+pub fn series_to_csv(points: &[SeriesPoint]) -> String {
+    let mut csv = String::from("date,source,instrument,value,unit\n");
+    for p in points {
+        csv.push_str(&format!("{},{},{},{},{}\n", p.date, p.source, p.instrument, p.value, p.unit));
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market_data::{save_nse_legacy_csv_to_sqlite, save_series_to_sqlite};
+
+    const HEADER: &str = "SYMBOL,SERIES,OPEN,HIGH,LOW,CLOSE,LAST,PREVCLOSE,TOTTRDQTY,TOTTRDVAL,TIMESTAMP,TOTALTRADES,ISIN,";
+
+    /// Three trading days of RELIANCE, INFY and TCS, plus a non-EQ row that must be ignored.
+    fn sample_db(name: &str) -> (String, Connection) {
+        let tmp = std::env::temp_dir().join(name);
+        let db_path = tmp.to_str().unwrap().to_string();
+        let _ = std::fs::remove_file(&db_path);
+        let csv = format!("{}\n{}", HEADER, [
+            "RELIANCE,EQ,2800,2850,2790,2840,2841,2795,1000000,1,02-JAN-2024,1,INE002A01018,",
+            "INFY,EQ,1500,1520,1490,1510,1511,1495,500000,1,02-JAN-2024,1,INE009A01021,",
+            "RELIANCE,EQ,2840,2900,2830,2900,2900,2840,900000,1,03-JAN-2024,1,INE002A01018,",
+            "INFY,EQ,1510,1515,1450,1460,1460,1510,700000,1,03-JAN-2024,1,INE009A01021,",
+            "TCS,EQ,3700,3720,3690,3710,3710,3700,200000,1,03-JAN-2024,1,INE467B01029,",
+            "RELIANCE,BE,10,10,10,10,10,1,1,1,03-JAN-2024,1,INE002A01018,",
+            "RELIANCE,EQ,2900,2960,2880,2958,2958,2900,800000,1,04-JAN-2024,1,INE002A01018,",
+        ].join("\n"));
+        assert_eq!(save_nse_legacy_csv_to_sqlite(&csv, &db_path), Ok(7));
+        let conn = crate::store::open_and_migrate(&db_path).unwrap();
+        (db_path, conn)
+    }
+
+    fn d(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, day).unwrap()
+    }
+
+    #[test]
+    fn test_latest_close_ohlcv_and_returns() {
+        let (db_path, conn) = sample_db("market_query_prices_test.db");
+
+        let latest = latest_close(&conn, "RELIANCE").unwrap().unwrap();
+        assert_eq!((latest.date, latest.close), (d(4), 2958.0));
+        assert!(latest_close(&conn, "UNKNOWN").unwrap().is_none());
+
+        let bars = ohlcv(&conn, "RELIANCE", d(1), d(3), false).unwrap();
+        assert_eq!(bars.iter().map(|b| b.close).collect::<Vec<_>>(), vec![2840.0, 2900.0], "BE series excluded");
+        assert!(bars_to_csv(&bars).starts_with("date,open,high,low,close,volume\n2024-01-02,2800,2850,2790,2840,1000000\n"));
+
+        let r = window_return(&conn, "RELIANCE", d(5), 2).unwrap().unwrap();
+        assert_eq!((r.from, r.to), (d(2), d(4)));
+        assert!((r.return_pct - (2958.0 / 2840.0 - 1.0) * 100.0).abs() < 1e-9);
+        assert!(window_return(&conn, "RELIANCE", d(5), 3).unwrap().is_none(), "not enough history");
+
+        let _ = std::fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_top_movers_and_series() {
+        let (db_path, conn) = sample_db("market_query_movers_test.db");
+
+        let movers = top_movers(&conn, d(3), 5).unwrap();
+        let gainers: Vec<&str> = movers.gainers.iter().map(|m| m.symbol.as_str()).collect();
+        assert_eq!(gainers, vec!["RELIANCE", "TCS"]);
+        assert_eq!(movers.losers.len(), 1);
+        assert_eq!(movers.losers[0].symbol, "INFY");
+        assert!((movers.losers[0].change_pct - (1460.0 / 1510.0 - 1.0) * 100.0).abs() < 1e-9);
+        assert_eq!(top_movers(&conn, d(3), 1).unwrap().gainers.len(), 1);
+        assert!(top_movers(&conn, d(9), 5).unwrap().gainers.is_empty());
+        assert_eq!(movers_to_csv(&movers).lines().count(), 4);
+
+        let point = |day: u32, value: f64| SeriesPoint {
+            source: "FBIL".to_string(),
+            instrument: "FBIL:MIBOR:OVERNIGHT".to_string(),
+            date: d(day),
+            value,
+            unit: "PCT".to_string(),
+            table_ref: "fbil_mibor".to_string(),
+        };
+        save_series_to_sqlite(&[point(3, 6.71), point(2, 6.75), point(8, 6.70)], &db_path).unwrap();
+        let points = series(&conn, "FBIL:MIBOR:OVERNIGHT", d(1), d(5)).unwrap();
+        assert_eq!(points, vec![point(2, 6.75), point(3, 6.71)]);
+        assert_eq!(series_to_csv(&points).lines().nth(1), Some("2024-01-02,FBIL,FBIL:MIBOR:OVERNIGHT,6.75,PCT"));
+
+        let _ = std::fs::remove_file(&db_path);
+    }
+}
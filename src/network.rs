@@ -2,7 +2,7 @@ extern crate reqwest;
 
 use std::thread;
 use std::time::Duration;
-use log::{error, warn, info, debug};
+use log::{error, warn, info};
 use std::array;
 use std::error::Error;
 use std::io::Bytes;
@@ -203,8 +203,26 @@ pub fn build_llm_api_client(connect_timeout: u64, fetch_timeout: u64, proxy_url:
 
 
 pub fn http_get_binary(website_url: &String, client: &reqwest::blocking::Client) -> bytes::Bytes {
+    http_get_binary_response(website_url, client)
+        .map(|response| response.body)
+        .unwrap_or_default()
+}
+
+/// A downloaded file with the HTTP status and content type it was served with, so callers
+/// can tell a missing file (404) from an error page or a blocked request.
+pub struct BinaryResponse {
+    pub status: u16,
+    pub content_type: String,
+    pub body: bytes::Bytes,
+}
+
+/// GET a binary resource, retrying transport errors and retryable statuses (5xx, 408, 429).
+/// Returns the last response received, whatever its status, or Err if no response was
+/// received on any attempt.
+pub fn http_get_binary_response(website_url: &String, client: &reqwest::blocking::Client) -> Result<BinaryResponse, String> {
     let retry_times = 3;
     let wait_time = 2;
+    let mut last_result = Err(format!("no response from {}", website_url));
 
     for attempt_no in 0..retry_times {
         log::info!("HTTP GET waiting for {} sec", wait_time);
@@ -213,26 +231,38 @@ pub fn http_get_binary(website_url: &String, client: &reqwest::blocking::Client)
         let req_builder = client.get(website_url);
         match req_builder.send() {
             Ok(resp) => {
+                let status = resp.status();
+                crate::metrics::record_http_status(status.as_u16());
+                let content_type = resp.headers()
+                    .get(CONTENT_TYPE)
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or("")
+                    .to_string();
                 match resp.bytes() {
                     Ok(binary_data) => {
-                        log::debug!("HTTP GET retrieved bytes array of length: {}", binary_data.len());
-                        return binary_data;
+                        log::debug!("HTTP GET retrieved bytes array of length: {} (status {})", binary_data.len(), status);
+                        last_result = Ok(BinaryResponse { status: status.as_u16(), content_type, body: binary_data });
+                        if !is_retryable_status(status) {
+                            return last_result;
+                        }
+                        log::warn!("Failed attempt #{}, retryable status {} for {}", attempt_no, status, website_url);
                     },
                     Err(ex) => {
                         log::error!("Failed attempt #{}, When retrieving binary data from HTTP GET: {:?}", attempt_no, ex.to_string());
-                        log::info!("HTTP GET waiting for an additional {} sec", wait_time);
-                        thread::sleep(Duration::from_secs(wait_time));
+                        last_result = Err(format!("reading body of {}: {}", website_url, ex));
                     }
                 }
             }
             Err(e) => {
+                crate::metrics::record_http_transport_error();
                 log::error!("Failed attempt #{}, When executing binary HTTP GET on url {}, error: {:?}", attempt_no, website_url, e.to_string());
-                log::info!("HTTP GET waiting for an additional {} sec", wait_time);
-                thread::sleep(Duration::from_secs(wait_time));
+                last_result = Err(format!("GET {}: {}", website_url, e));
             }
         }
+        log::info!("HTTP GET waiting for an additional {} sec", wait_time);
+        thread::sleep(Duration::from_secs(wait_time));
     }
-    return bytes::Bytes::new();
+    last_result
 }
 
 /// Returns true for HTTP status codes that are worth retrying.
@@ -314,6 +344,47 @@ pub fn http_get(website_url: &String, client: &reqwest::blocking::Client, retry_
     String::from("")
 }
 
+/// Load the landing pages of a site so that the client's cookie store receives the session
+/// cookies its API requires (NSE, BSE). Returns true if at least one page loaded.
+pub fn establish_session(warmup_urls: &[&str], client: &reqwest::blocking::Client, wait_time: usize) -> bool {
+    let mut loaded = false;
+    for warmup_url in warmup_urls {
+        let content = http_get(&warmup_url.to_string(), client, 1, wait_time);
+        if content.is_empty() {
+            warn!("Could not load {} to establish session cookies.", warmup_url);
+        } else {
+            loaded = true;
+        }
+    }
+    loaded
+}
+
+/// Parse an API response body as JSON. Returns None for an empty body and for the HTML page
+/// that NSE/BSE serve in place of data when the session cookies are missing or have expired.
+pub fn parse_json_body(body: &str) -> Option<serde_json::Value> {
+    let body = body.trim_start_matches('\u{feff}').trim();
+    if body.is_empty() || body.starts_with('<') {
+        return None;
+    }
+    serde_json::from_str::<serde_json::Value>(body).ok()
+}
+
+/// GET a JSON API endpoint of a site that needs a cookie handshake. If the response is not
+/// JSON the session is re-established via `warmup_urls` and the request is tried once more.
+pub fn http_get_json_with_session(
+    api_url: &str,
+    warmup_urls: &[&str],
+    client: &reqwest::blocking::Client,
+    retry_times: usize,
+    wait_time: usize) -> Option<serde_json::Value>
+{
+    if let Some(json_val) = parse_json_body(&http_get(&api_url.to_string(), client, retry_times, wait_time)) {
+        return Some(json_val);
+    }
+    info!("No JSON from {}, re-establishing the session and trying again.", api_url);
+    establish_session(warmup_urls, client, wait_time);
+    parse_json_body(&http_get(&api_url.to_string(), client, retry_times, wait_time))
+}
 
 #[cfg(test)]
 mod tests {
@@ -355,4 +426,12 @@ mod tests {
             assert!(backoff_jitter_ms() < 1000);
         }
     }
+
+    #[test]
+    fn test_parse_json_body_rejects_html_block_page() {
+        assert!(parse_json_body("<!DOCTYPE html><html><body>Access Denied</body></html>").is_none());
+        assert!(parse_json_body("").is_none());
+        let json_val = parse_json_body("\u{feff} {\"data\": [1, 2]}").expect("json body");
+        assert_eq!(json_val["data"][1], 2);
+    }
 }
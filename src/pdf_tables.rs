@@ -0,0 +1,571 @@
+// file: pdf_tables.rs
+// Purpose:
+//   Table extraction from PDF text layers. Many SEBI/RBI circulars carry their substance in
+//   tables (fee schedules, limits, margin rates) which `pdf-extract` flattens into unreadable
+//   runs of numbers. This module walks each page's content stream with `lopdf`, tracking the
+//   text and graphics matrices to recover the position of every text fragment, then rebuilds
+//   lines (by baseline), cells (by horizontal gaps) and columns (by overlapping x-extents).
+//   Runs of consecutive multi-cell lines that share a column layout are emitted as tables.
+//
+//   Tables are attached to the document as JSON under `generated_content["tables"]`, each
+//   also written as a CSV side file next to the PDF, and persisted to the `pdf_tables` store
+//   table by `mod_emit_tables`. Glyph widths are estimated rather than read from font
+//   metrics, so this is a layout heuristic and not an exact reconstruction.
+
+use std::fs;
+use std::path::Path;
+
+use log::{debug, error, info};
+use lopdf::content::Content;
+use lopdf::Object;
+use serde::{Deserialize, Serialize};
+
+use crate::document::Document;
+
+/// Key under which the tables are stored in `Document::generated_content`.
+pub const TABLES_KEY: &str = "tables";
+
+/// A table needs at least this many rows (including the header row).
+const MIN_TABLE_ROWS: usize = 3;
+/// Approximate glyph advance as a fraction of the font size, used to estimate fragment ends.
+const AVG_GLYPH_WIDTH_EM: f32 = 0.5;
+/// Horizontal gap (in ems) above which two fragments on a line belong to different cells.
+const CELL_GAP_EM: f32 = 1.0;
+/// Vertical gap (in ems) between lines above which a table run is broken.
+const MAX_ROW_GAP_EM: f32 = 3.0;
+
+/// A table reconstructed from one page of a PDF.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PdfTable {
+    /// 1-based page number on which the table appears.
+    pub page: u32,
+    pub n_rows: usize,
+    pub n_cols: usize,
+    /// Cell text, row-major; the first row is usually the header.
+    pub rows: Vec<Vec<String>>,
+    /// Path of the CSV side file, empty if it was not written.
+    #[serde(default)]
+    pub csv_file: String,
+}
+
+impl PdfTable {
+    /// Render the table as CSV text (RFC 4180 quoting).
+    pub fn to_csv(&self) -> String {
+        let mut out = String::new();
+        for row in &self.rows {
+            let line: Vec<String> = row.iter().map(|cell| csv_escape(cell)).collect();
+            out.push_str(&line.join(","));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+fn csv_escape(cell: &str) -> String {
+    if cell.contains(',') || cell.contains('"') || cell.contains('\n') {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+/// A run of text placed at a known position on a page (PDF user space, y grows upwards).
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextFragment {
+    pub page: u32,
+    pub x: f32,
+    pub y: f32,
+    /// Estimated right edge of the fragment.
+    pub x_end: f32,
+    /// Effective font size in user space.
+    pub size: f32,
+    pub text: String,
+}
+
+/// A cell within a reconstructed line.
+#[derive(Debug, Clone, PartialEq)]
+struct Cell {
+    x: f32,
+    x_end: f32,
+    text: String,
+}
+
+/// A line of text with its baseline, split into cells.
+#[derive(Debug, Clone, PartialEq)]
+struct Line {
+    y: f32,
+    size: f32,
+    cells: Vec<Cell>,
+}
+
+type Matrix = [f32; 6];
+
+const IDENTITY: Matrix = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+
+/// Matrix product `m1 × m2` in PDF's row-vector convention.
+fn multiply(m1: &Matrix, m2: &Matrix) -> Matrix {
+    [
+        m1[0] * m2[0] + m1[1] * m2[2],
+        m1[0] * m2[1] + m1[1] * m2[3],
+        m1[2] * m2[0] + m1[3] * m2[2],
+        m1[2] * m2[1] + m1[3] * m2[3],
+        m1[4] * m2[0] + m1[5] * m2[2] + m2[4],
+        m1[4] * m2[1] + m1[5] * m2[3] + m2[5],
+    ]
+}
+
+fn translate(tx: f32, ty: f32) -> Matrix {
+    [1.0, 0.0, 0.0, 1.0, tx, ty]
+}
+
+fn operand_floats(operands: &[Object]) -> Vec<f32> {
+    operands.iter().filter_map(|o| o.as_float().ok()).collect()
+}
+
+/// Extract positioned text fragments from every page of a PDF.
+pub fn extract_fragments(pdf_data: &[u8]) -> Result<Vec<TextFragment>, String> {
+    let pdf_doc = lopdf::Document::load_mem(pdf_data)
+        .map_err(|e| format!("Could not parse PDF: {}", e))?;
+    let mut fragments = Vec::new();
+    for (page_no, page_id) in pdf_doc.get_pages() {
+        match page_fragments(&pdf_doc, page_no, page_id) {
+            Ok(mut page_frags) => fragments.append(&mut page_frags),
+            Err(e) => debug!("Skipping page {} for table extraction: {}", page_no, e),
+        }
+    }
+    Ok(fragments)
+}
+
+fn page_fragments(pdf_doc: &lopdf::Document, page_no: u32, page_id: lopdf::ObjectId) -> Result<Vec<TextFragment>, String> {
+    let fonts = pdf_doc.get_page_fonts(page_id).map_err(|e| e.to_string())?;
+    let encodings: Vec<(Vec<u8>, lopdf::Encoding)> = fonts
+        .into_iter()
+        .filter_map(|(name, font)| font.get_font_encoding(pdf_doc).ok().map(|enc| (name, enc)))
+        .collect();
+    let content_data = pdf_doc.get_page_content(page_id).map_err(|e| e.to_string())?;
+    let content = Content::decode(&content_data).map_err(|e| e.to_string())?;
+
+    let mut fragments = Vec::new();
+    let mut ctm = IDENTITY;
+    let mut ctm_stack: Vec<Matrix> = Vec::new();
+    let mut tm = IDENTITY;
+    let mut tlm = IDENTITY;
+    let mut leading = 0.0f32;
+    let mut font_size = 0.0f32;
+    let mut encoding: Option<&lopdf::Encoding> = None;
+
+    for op in &content.operations {
+        let nums = operand_floats(&op.operands);
+        match op.operator.as_str() {
+            "q" => ctm_stack.push(ctm),
+            "Q" => ctm = ctm_stack.pop().unwrap_or(IDENTITY),
+            "cm" if nums.len() == 6 => {
+                ctm = multiply(&[nums[0], nums[1], nums[2], nums[3], nums[4], nums[5]], &ctm);
+            }
+            "BT" => {
+                tm = IDENTITY;
+                tlm = IDENTITY;
+            }
+            "Tf" => {
+                encoding = op.operands.first()
+                    .and_then(|o| o.as_name().ok())
+                    .and_then(|name| encodings.iter().find(|(n, _)| n.as_slice() == name))
+                    .map(|(_, enc)| enc);
+                font_size = nums.first().copied().unwrap_or(font_size);
+            }
+            "TL" if !nums.is_empty() => leading = nums[0],
+            "Td" | "TD" if nums.len() == 2 => {
+                if op.operator == "TD" {
+                    leading = -nums[1];
+                }
+                tlm = multiply(&translate(nums[0], nums[1]), &tlm);
+                tm = tlm;
+            }
+            "Tm" if nums.len() == 6 => {
+                tlm = [nums[0], nums[1], nums[2], nums[3], nums[4], nums[5]];
+                tm = tlm;
+            }
+            "T*" => {
+                tlm = multiply(&translate(0.0, -leading), &tlm);
+                tm = tlm;
+            }
+            "Tj" | "TJ" | "'" | "\"" => {
+                if op.operator == "'" || op.operator == "\"" {
+                    tlm = multiply(&translate(0.0, -leading), &tlm);
+                    tm = tlm;
+                }
+                let Some(enc) = encoding else { continue };
+                // `"` carries word and character spacing ahead of the string.
+                let skip = if op.operator == "\"" { 2 } else { 0 };
+                let (text, advance_em) = decode_show_operands(enc, op.operands.get(skip..).unwrap_or(&[]));
+                let trm = multiply(&tm, &ctm);
+                let scale = (trm[2] * trm[2] + trm[3] * trm[3]).sqrt();
+                let size = font_size * scale;
+                let advance = advance_em * font_size;
+                tm = multiply(&translate(advance, 0.0), &tm);
+                let text = text.trim().to_string();
+                if !text.is_empty() && size > 0.0 {
+                    let end = multiply(&tm, &ctm);
+                    fragments.push(TextFragment {
+                        page: page_no,
+                        x: trm[4],
+                        y: trm[5],
+                        x_end: end[4].max(trm[4]),
+                        size,
+                        text,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(fragments)
+}
+
+/// Decode the string operands of a text-showing operator. Returns the text and its
+/// estimated horizontal advance in ems (text space units / font size). Large negative
+/// kerning adjustments in `TJ` arrays are rendered as spaces.
+fn decode_show_operands(enc: &lopdf::Encoding, operands: &[Object]) -> (String, f32) {
+    let mut text = String::new();
+    let mut advance = 0.0f32;
+    for operand in operands {
+        match operand {
+            Object::String(bytes, _) => {
+                let decoded = lopdf::Document::decode_text(enc, bytes).unwrap_or_default();
+                advance += decoded.chars().count() as f32 * AVG_GLYPH_WIDTH_EM;
+                text.push_str(&decoded);
+            }
+            Object::Array(items) => {
+                let (t, a) = decode_show_operands(enc, items);
+                text.push_str(&t);
+                advance += a;
+            }
+            Object::Integer(_) | Object::Real(_) => {
+                let adjust = operand.as_float().unwrap_or(0.0) / 1000.0;
+                advance -= adjust;
+                if adjust < -0.2 && !text.ends_with(' ') {
+                    text.push(' ');
+                }
+            }
+            _ => {}
+        }
+    }
+    (text, advance)
+}
+
+/// Group fragments of one page into lines (top to bottom), each split into cells.
+fn build_lines(mut fragments: Vec<TextFragment>) -> Vec<Line> {
+    fragments.sort_by(|a, b| b.y.total_cmp(&a.y).then(a.x.total_cmp(&b.x)));
+    let mut lines: Vec<(f32, f32, Vec<TextFragment>)> = Vec::new();
+    for frag in fragments {
+        match lines.last_mut() {
+            Some((y, size, frags)) if (*y - frag.y).abs() <= size.max(frag.size) * 0.5 => {
+                *size = size.max(frag.size);
+                frags.push(frag);
+            }
+            _ => lines.push((frag.y, frag.size, vec![frag])),
+        }
+    }
+    lines.into_iter().map(|(y, size, mut frags)| {
+        frags.sort_by(|a, b| a.x.total_cmp(&b.x));
+        let mut cells: Vec<Cell> = Vec::new();
+        for frag in frags {
+            match cells.last_mut() {
+                Some(cell) if frag.x - cell.x_end < size * CELL_GAP_EM => {
+                    cell.text.push(' ');
+                    cell.text.push_str(&frag.text);
+                    cell.x_end = cell.x_end.max(frag.x_end);
+                }
+                _ => cells.push(Cell { x: frag.x, x_end: frag.x_end, text: frag.text }),
+            }
+        }
+        Line { y, size, cells }
+    }).collect()
+}
+
+/// Derive column extents from the fullest rows of a candidate table by merging
+/// overlapping cell extents, so both left- and right-aligned columns are handled.
+fn column_extents(rows: &[Line]) -> Vec<(f32, f32)> {
+    let widest = rows.iter().map(|l| l.cells.len()).max().unwrap_or(0);
+    let mut spans: Vec<(f32, f32)> = rows.iter()
+        .filter(|l| l.cells.len() == widest)
+        .flat_map(|l| l.cells.iter().map(|c| (c.x, c.x_end)))
+        .collect();
+    spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut columns: Vec<(f32, f32)> = Vec::new();
+    for (start, end) in spans {
+        match columns.last_mut() {
+            Some(col) if start <= col.1 => col.1 = col.1.max(end),
+            _ => columns.push((start, end)),
+        }
+    }
+    columns
+}
+
+/// Index of the column whose extent best matches the cell: greatest overlap, else nearest.
+fn column_for(cell: &Cell, columns: &[(f32, f32)]) -> usize {
+    let mut best = 0usize;
+    let mut best_score = f32::MIN;
+    for (i, (start, end)) in columns.iter().enumerate() {
+        let overlap = cell.x_end.min(*end) - cell.x.max(*start);
+        let score = if overlap > 0.0 { overlap } else { overlap.min(0.0) - 1e-3 };
+        if score > best_score {
+            best_score = score;
+            best = i;
+        }
+    }
+    best
+}
+
+fn lines_to_table(page: u32, run: &[Line]) -> Option<PdfTable> {
+    let columns = column_extents(run);
+    if columns.len() < 2 {
+        return None;
+    }
+    let rows: Vec<Vec<String>> = run.iter().map(|line| {
+        let mut row = vec![String::new(); columns.len()];
+        for cell in &line.cells {
+            let slot = &mut row[column_for(cell, &columns)];
+            if !slot.is_empty() {
+                slot.push(' ');
+            }
+            slot.push_str(&cell.text);
+        }
+        row
+    }).collect();
+    // At least half of the rows must fill every column, otherwise this is likely ragged
+    // body text rather than a table.
+    let full_rows = rows.iter().filter(|r| r.iter().all(|c| !c.is_empty())).count();
+    if full_rows * 2 < rows.len() {
+        return None;
+    }
+    Some(PdfTable { page, n_rows: rows.len(), n_cols: columns.len(), rows, csv_file: String::new() })
+}
+
+/// Reconstruct tables from positioned fragments, in page order and top to bottom.
+pub fn tables_from_fragments(fragments: Vec<TextFragment>) -> Vec<PdfTable> {
+    let mut pages: Vec<u32> = fragments.iter().map(|f| f.page).collect();
+    pages.sort_unstable();
+    pages.dedup();
+    let mut tables = Vec::new();
+    for page in pages {
+        let page_frags: Vec<TextFragment> = fragments.iter().filter(|f| f.page == page).cloned().collect();
+        let lines = build_lines(page_frags);
+        let mut run: Vec<Line> = Vec::new();
+        for line in lines {
+            let contiguous = run.last()
+                .map(|prev| prev.y - line.y <= prev.size.max(line.size) * MAX_ROW_GAP_EM)
+                .unwrap_or(true);
+            if line.cells.len() >= 2 && contiguous {
+                run.push(line);
+                continue;
+            }
+            if run.len() >= MIN_TABLE_ROWS {
+                tables.extend(lines_to_table(page, &run));
+            }
+            run.clear();
+            if line.cells.len() >= 2 {
+                run.push(line);
+            }
+        }
+        if run.len() >= MIN_TABLE_ROWS {
+            tables.extend(lines_to_table(page, &run));
+        }
+    }
+    tables
+}
+
+/// Extract all tables from a PDF held in memory.
+pub fn extract_tables(pdf_data: &[u8]) -> Result<Vec<PdfTable>, String> {
+    Ok(tables_from_fragments(extract_fragments(pdf_data)?))
+}
+
+/// Extract tables from a saved PDF, write each as `<pdf stem>_table<N>.csv` beside it and
+/// store them all as JSON in `generated_content["tables"]`.
+///
+/// returns: the number of tables found
+pub fn attach_tables(input_doc: &mut Document, pdf_file_path: &Path) -> usize {
+    let pdf_data = match fs::read(pdf_file_path) {
+        Ok(data) => data,
+        Err(e) => {
+            error!("[{}] Could not read PDF {:?} for table extraction: {}", input_doc.module, pdf_file_path, e);
+            return 0;
+        }
+    };
+    let mut tables = match extract_tables(&pdf_data) {
+        Ok(tables) => tables,
+        Err(e) => {
+            error!("[{}] Table extraction failed for {:?}: {}", input_doc.module, pdf_file_path, e);
+            return 0;
+        }
+    };
+    if tables.is_empty() {
+        return 0;
+    }
+    let stem = pdf_file_path.file_stem().and_then(|s| s.to_str()).unwrap_or("document").to_string();
+    for (idx, table) in tables.iter_mut().enumerate() {
+        let csv_path = pdf_file_path.with_file_name(format!("{}_table{}.csv", stem, idx + 1));
+        match fs::write(&csv_path, table.to_csv()) {
+            Ok(_) => table.csv_file = csv_path.to_string_lossy().to_string(),
+            Err(e) => error!("When writing table CSV file {:?}: {}", csv_path, e),
+        }
+    }
+    info!("[{}] Extracted {} table(s) from PDF {:?}", input_doc.module, tables.len(), pdf_file_path);
+    match serde_json::to_string(&tables) {
+        Ok(json) => { input_doc.generated_content.insert(TABLES_KEY.to_string(), json); },
+        Err(e) => error!("When serialising tables to JSON: {}", e),
+    }
+    tables.len()
+}
+
+/// Read back the tables stored on a document by `attach_tables`.
+pub fn tables_from_document(doc: &Document) -> Vec<PdfTable> {
+    doc.generated_content.get(TABLES_KEY)
+        .and_then(|json| serde_json::from_str(json).ok())
+        .unwrap_or_default()
+}
+
+/// A page of a PDF built by `make_test_pdf`.
+#[cfg(test)]
+pub(crate) enum TestPdfPage<'a> {
+    /// Courier text runs, each an (x, y, font size, text) with its own Td positioning.
+    Text(Vec<(i64, i64, i64, &'a str)>),
+    /// A scanned-style page holding only the image, stretched over the whole page.
+    Image,
+}
+
+/// Builds an A4 PDF with one page per entry, shared by the PDF tests in this module and
+/// `utils`. Image pages draw `image` (width, height, 8-bit gray pixels), or a 2x2 checker.
+#[cfg(test)]
+pub(crate) fn make_test_pdf(pages: &[TestPdfPage], image: Option<(u32, u32, Vec<u8>)>) -> Vec<u8> {
+    use lopdf::content::Operation;
+    use lopdf::{dictionary, Stream};
+
+    let (img_w, img_h, img_pixels) = image.unwrap_or((2, 2, vec![0, 255, 255, 0]));
+    let mut pdf = lopdf::Document::with_version("1.5");
+    let pages_id = pdf.new_object_id();
+    let font_id = pdf.add_object(dictionary! {
+        "Type" => "Font", "Subtype" => "Type1", "BaseFont" => "Courier",
+    });
+    let image_id = pdf.add_object(Stream::new(dictionary! {
+        "Type" => "XObject", "Subtype" => "Image",
+        "Width" => img_w as i64, "Height" => img_h as i64,
+        "ColorSpace" => "DeviceGray", "BitsPerComponent" => 8,
+    }, img_pixels));
+    let resources_id = pdf.add_object(dictionary! {
+        "Font" => dictionary! { "F1" => font_id },
+        "XObject" => dictionary! { "Im1" => image_id },
+    });
+    let mut kids: Vec<Object> = Vec::new();
+    for page in pages {
+        let operations = match page {
+            TestPdfPage::Text(runs) => runs.iter().flat_map(|&(x, y, size, text)| [
+                Operation::new("BT", vec![]),
+                Operation::new("Tf", vec!["F1".into(), size.into()]),
+                Operation::new("Td", vec![x.into(), y.into()]),
+                Operation::new("Tj", vec![Object::string_literal(text)]),
+                Operation::new("ET", vec![]),
+            ]).collect(),
+            TestPdfPage::Image => vec![
+                Operation::new("q", vec![]),
+                Operation::new("cm", vec![595.into(), 0.into(), 0.into(), 842.into(), 0.into(), 0.into()]),
+                Operation::new("Do", vec!["Im1".into()]),
+                Operation::new("Q", vec![]),
+            ],
+        };
+        let content_id = pdf.add_object(Stream::new(
+            dictionary! {}, Content { operations }.encode().unwrap()
+        ));
+        let page_id = pdf.add_object(dictionary! {
+            "Type" => "Page", "Parent" => pages_id, "Contents" => content_id,
+        });
+        kids.push(page_id.into());
+    }
+    let count = kids.len() as i64;
+    pdf.objects.insert(pages_id, Object::Dictionary(dictionary! {
+        "Type" => "Pages", "Kids" => kids, "Count" => count,
+        "Resources" => resources_id,
+        "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+    }));
+    let catalog_id = pdf.add_object(dictionary! { "Type" => "Catalog", "Pages" => pages_id });
+    pdf.trailer.set("Root", catalog_id);
+    let mut buffer = Vec::new();
+    pdf.save_to(&mut buffer).unwrap();
+    buffer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frag(page: u32, x: f32, y: f32, text: &str) -> TextFragment {
+        let size = 10.0;
+        TextFragment {
+            page, x, y,
+            x_end: x + text.chars().count() as f32 * size * AVG_GLYPH_WIDTH_EM,
+            size,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_extract_tables_from_positioned_pdf() {
+        let items = [
+            (60, 780, "Schedule of fees payable by intermediaries"),
+            (60, 740, "Category"), (260, 740, "Fee (Rs)"), (400, 740, "Due"),
+            (60, 725, "Stock broker"), (260, 725, "50,000"), (400, 725, "Annual"),
+            (60, 710, "Depository participant"), (260, 710, "25,000"), (400, 710, "Annual"),
+            (60, 695, "Investment adviser"), (260, 695, "10,000"), (400, 695, "Once"),
+            (60, 640, "These fees shall come into force with immediate effect."),
+        ];
+        let runs = items.iter().map(|&(x, y, text)| (x, y, 10, text)).collect();
+        let pdf = make_test_pdf(&[TestPdfPage::Text(runs)], None);
+        let tables = extract_tables(&pdf).unwrap();
+        assert_eq!(tables.len(), 1, "got {:?}", tables);
+        let t = &tables[0];
+        assert_eq!((t.page, t.n_rows, t.n_cols), (1, 4, 3));
+        assert_eq!(t.rows[0], vec!["Category", "Fee (Rs)", "Due"]);
+        assert_eq!(t.rows[2], vec!["Depository participant", "25,000", "Annual"]);
+    }
+
+    #[test]
+    fn test_right_aligned_numbers_share_a_column() {
+        let tables = tables_from_fragments(vec![
+            frag(1, 50.0, 500.0, "Limit"), frag(1, 200.0, 500.0, "Amount"),
+            frag(1, 50.0, 485.0, "Retail"), frag(1, 215.0, 485.0, "2,00,000"),
+            frag(1, 50.0, 470.0, "HNI"), frag(1, 225.0, 470.0, "500000"),
+        ]);
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].n_cols, 2);
+        assert_eq!(tables[0].rows[1], vec!["Retail", "2,00,000"]);
+        assert_eq!(tables[0].rows[2], vec!["HNI", "500000"]);
+    }
+
+    #[test]
+    fn test_paragraph_text_is_not_a_table() {
+        let tables = tables_from_fragments(vec![
+            frag(1, 50.0, 500.0, "All regulated entities are advised to comply."),
+            frag(1, 50.0, 485.0, "The circular is issued under section 35A."),
+            frag(1, 50.0, 470.0, "It comes into force with immediate effect."),
+        ]);
+        assert!(tables.is_empty());
+    }
+
+    #[test]
+    fn test_csv_quoting_and_document_round_trip() {
+        let table = PdfTable {
+            page: 2, n_rows: 2, n_cols: 2,
+            rows: vec![
+                vec!["Item".into(), "Fee".into()],
+                vec!["Broker, \"new\"".into(), "1,000".into()],
+            ],
+            csv_file: String::new(),
+        };
+        assert_eq!(table.to_csv(), "Item,Fee\n\"Broker, \"\"new\"\"\",\"1,000\"\n");
+
+        let mut doc = Document::default();
+        doc.generated_content.insert(TABLES_KEY.to_string(), serde_json::to_string(&vec![table.clone()]).unwrap());
+        assert_eq!(tables_from_document(&doc), vec![table]);
+        assert!(tables_from_document(&Document::default()).is_empty());
+    }
+}
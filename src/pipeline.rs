@@ -24,8 +24,8 @@ use crate::utils;
 use crate::plugins::{
     mod_en_in_business_standard, mod_en_in_rbi, mod_offline_docs, split_text,
     mod_dedupe, mod_solrsubmit, mod_summarize, mod_persist_data, mod_vectorstore, mod_cmdline,
-    mod_mentions, mod_extract_quant, mod_themes, mod_tone, mod_geocode, mod_ner,
-    mod_entity_graph, mod_emit_graph, mod_emit_tables,
+    mod_mentions, mod_lang, mod_extract_quant, mod_classify, mod_themes, mod_tone, mod_geocode, mod_ner,
+    mod_entity_resolve, mod_quotes, mod_events, mod_entity_graph, mod_emit_graph, mod_emit_tables,
     mod_en_in_thehindu, mod_en_in_livemint, mod_en_in_moneycontrol,
     mod_en_in_timesofindia, mod_en_in_forbes, mod_en_bbc, mod_en_guardian,
     mod_en_ap_news, mod_en_in_indianexpress,
@@ -40,6 +40,8 @@ use crate::plugins::{
     mod_en_sg_straitstimes, mod_en_sg_cna, mod_en_th_bangkokpost,
     mod_en_ca_cbc, mod_en_ca_globeandmail, mod_en_au_smh, mod_en_au_abc,
     mod_en_in_irdai, mod_en_in_sebi,
+    mod_en_in_mca, mod_en_in_pfrda, mod_en_in_ifsca, mod_en_in_fiu,
+    mod_in_nse, mod_in_bse,
     mod_doc_type, mod_filter, mod_metadata,
 };
 use crate::document::{Document};
@@ -236,9 +238,15 @@ pub fn load_retriever_plugins(app_config: Arc<config::Config>) -> Vec<RetrieverP
         (mod_en_au_abc::PLUGIN_NAME, mod_en_au_abc::run_worker_thread),
         (mod_en_in_irdai::PLUGIN_NAME, mod_en_in_irdai::run_worker_thread),
         (mod_en_in_sebi::PLUGIN_NAME, mod_en_in_sebi::run_worker_thread),
-        // NOTE: mod_in_nse / mod_in_bse are intentionally NOT registered as news retrievers.
-        // Their market-data (bhavcopy) download moved to the batch-feed subsystem
+        (mod_en_in_mca::PLUGIN_NAME, mod_en_in_mca::run_worker_thread),
+        (mod_en_in_pfrda::PLUGIN_NAME, mod_en_in_pfrda::run_worker_thread),
+        (mod_en_in_ifsca::PLUGIN_NAME, mod_en_in_ifsca::run_worker_thread),
+        (mod_en_in_fiu::PLUGIN_NAME, mod_en_in_fiu::run_worker_thread),
+        // NOTE: mod_in_nse / mod_in_bse retrieve exchange circulars and corporate announcements
+        // only. Their market-data (bhavcopy) download moved to the batch-feed subsystem
         // (src/feeds/feed_nse_bhavcopy.rs, feed_bse_bhavcopy.rs) per roadmap point 2g.
+        (mod_in_nse::PLUGIN_NAME, mod_in_nse::run_worker_thread),
+        (mod_in_bse::PLUGIN_NAME, mod_in_bse::run_worker_thread),
     ];
 
     for plugin in plugins_configured {
@@ -305,11 +313,16 @@ pub fn load_dataproc_plugins(app_config: Arc<config::Config>, all_api_mutexes: H
     let registry: &[(&str, ProcFn)] = &[
         ("mod_dedupe", mod_dedupe::process_data),
         (mod_mentions::PLUGIN_NAME, mod_mentions::process_data),
+        (mod_lang::PLUGIN_NAME, mod_lang::process_data),
         (mod_extract_quant::PLUGIN_NAME, mod_extract_quant::process_data),
+        (mod_classify::PLUGIN_NAME, mod_classify::process_data),
         (mod_themes::PLUGIN_NAME, mod_themes::process_data),
         (mod_tone::PLUGIN_NAME, mod_tone::process_data),
         (mod_geocode::PLUGIN_NAME, mod_geocode::process_data),
         (mod_ner::PLUGIN_NAME, mod_ner::process_data),
+        (mod_entity_resolve::PLUGIN_NAME, mod_entity_resolve::process_data),
+        (mod_quotes::PLUGIN_NAME, mod_quotes::process_data),
+        (mod_events::PLUGIN_NAME, mod_events::process_data),
         (mod_entity_graph::PLUGIN_NAME, mod_entity_graph::process_data),
         (mod_emit_graph::PLUGIN_NAME, mod_emit_graph::process_data),
         (mod_emit_tables::PLUGIN_NAME, mod_emit_tables::process_data),
@@ -455,6 +468,23 @@ pub fn start_data_pipeline(
     app_config: Arc<config::Config>,
     status_tracker: Option<SharedStatus>,
 ) -> Vec<Document> {
+    run_data_pipeline(retriever_plugins, data_proc_plugins, app_config, status_tracker).docs
+}
+
+/// Outcome of one pipeline run, for callers that record whether it succeeded.
+pub struct PipelineRun {
+    pub docs: Vec<Document>,
+    /// Retrievers whose thread could not be started or panicked, with the reason.
+    pub failed_retrievers: Vec<(String, String)>,
+}
+
+/// As `start_data_pipeline`, but also reports the retrievers that failed.
+pub fn run_data_pipeline(
+    retriever_plugins: Vec<RetrieverPlugin>,
+    data_proc_plugins: BinaryHeap<DataProcPlugin>,
+    app_config: Arc<config::Config>,
+    status_tracker: Option<SharedStatus>,
+) -> PipelineRun {
 
     // record counts in shared status before kicking off threads
     if let Some(ref st) = status_tracker {
@@ -496,7 +526,7 @@ pub fn start_data_pipeline(
     }
 
     // start the retriever plugin threads: they all send via transmit
-    let retriever_thread_handles = start_retrieval_pipeline(
+    let (retriever_thread_handles, mut failed_retrievers) = start_retrieval_pipeline(
         retriever_plugins,
         retrieve_thread_tx,
         app_config.clone()
@@ -536,9 +566,13 @@ pub fn start_data_pipeline(
     }
     // Join retriever threads for a clean shutdown. By the time the collector loop above has
     // ended, every retriever has dropped its sender, so these joins return promptly.
-    for handle in retriever_thread_handles {
+    for (name, handle) in retriever_thread_handles {
         if let Err(e) = handle.join() {
             error!("start_data_pipeline: a retriever thread panicked: {:?}", e);
+            let reason = e.downcast_ref::<&str>().map(|m| m.to_string())
+                .or_else(|| e.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            failed_retrievers.push((name, format!("panicked: {}", reason)));
         }
     }
 
@@ -549,12 +583,16 @@ pub fn start_data_pipeline(
             s.docs_processed = all_docs_processed.len();
         }
     }
-    return all_docs_processed;
+    return PipelineRun { docs: all_docs_processed, failed_retrievers };
 }
 
-fn start_retrieval_pipeline(plugins: Vec<RetrieverPlugin>, tx: Sender<document::Document>, config: Arc<config::Config>) -> Vec<JoinHandle<()>>{
+/// Start each enabled retriever in its own thread; returns the named thread handles and the
+/// retrievers whose thread could not be spawned.
+fn start_retrieval_pipeline(plugins: Vec<RetrieverPlugin>, tx: Sender<document::Document>, config: Arc<config::Config>)
+    -> (Vec<(String, JoinHandle<()>)>, Vec<(String, String)>) {
 
-    let mut task_run_handles: Vec<JoinHandle<()>> = Vec::new();
+    let mut task_run_handles: Vec<(String, JoinHandle<()>)> = Vec::new();
+    let mut spawn_failures: Vec<(String, String)> = Vec::new();
 
     for plugin in plugins {
 
@@ -569,15 +607,18 @@ fn start_retrieval_pipeline(plugins: Vec<RetrieverPlugin>, tx: Sender<document::
                 move ||  plugin_retrieve_function(msg_tx, config_clone)
             ) {
                 Result::Ok(handle) => {
-                    task_run_handles.push(handle);
                     info!("Started thread for plugin: {}", plugin.name);
+                    task_run_handles.push((plugin.name, handle));
                 },
-                Err(e) => error!("Could not spawn thread for plugin {}, error: {}", plugin.name, e)
+                Err(e) => {
+                    error!("Could not spawn thread for plugin {}, error: {}", plugin.name, e);
+                    spawn_failures.push((plugin.name, format!("could not start: {}", e)));
+                }
             }
         }
     }
 
-    return task_run_handles;
+    return (task_run_handles, spawn_failures);
 }
 
 
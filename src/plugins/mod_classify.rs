@@ -0,0 +1,397 @@
+// file: mod_classify.rs
+// Purpose:
+//   Fills the `keywords` and `industries` output fields of every document:
+//     * keywords   — RAKE keyphrases: the text is cut into candidate phrases at punctuation,
+//                    numbers and stopwords (the content extractor's list plus a few
+//                    news-reporting words); each word scores degree / frequency over the
+//                    candidates, a phrase the sum of its words, and the best-scoring phrases
+//                    (lowercased, first occurrence order on ties) are kept. Offline and
+//                    deterministic.
+//     * industries — an ONNX sequence-classification model (BERT-style, e.g. a FinBERT fine-tune,
+//                    labels from config.json `id2label`, loaded like `mod_ner`'s) when
+//                    configured; otherwise, or when the model is unsure, a rule-based classifier
+//                    against an embedded NIC/GICS-style taxonomy of industry codes and trigger
+//                    phrases. Title hits count double; an industry needs MIN_RULE_SCORE hits.
+//   Keywords and industries supplied by the source are kept; extracted ones are appended
+//   after them without duplicates. The chosen method is recorded in
+//   `doc.classification["industry_method"]` ("model" | "rules").
+//
+// Config keys:
+//   classify_model_dir  - Dir with model.onnx, vocab.txt and config.json; unset: rules only.
+//   classify_min_score  - Label probability below which a model label is dropped (default 0.5).
+//                         Multi-label models (`problem_type` "multi_label_classification")
+//                         use sigmoid scores, others softmax.
+//   taxonomy_file       - Industry taxonomy in the themebook format (see `vocab`), replacing
+//                         the embedded one; reloaded when it changes.
+//   max_keywords        - Keyphrases added per document (default 10).
+//   max_industries      - Industries added per document (default 3).
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use config::Config;
+use log::{error, info, warn};
+use ort::session::Session;
+use ort::value::Tensor;
+use regex::Regex;
+use serde_json::Value;
+
+use crate::content_extraction::english_stopwords;
+use crate::document::Document;
+use crate::get_plugin_cfg;
+use crate::plugins::mod_ner::labels_from_config;
+use crate::plugins::mod_vectorstore::{load_session, BertTokenizer};
+use crate::vocab::{Themebook, VocabSource};
+
+pub const PLUGIN_NAME: &str = "mod_classify";
+pub const TAXONOMY_VERSION: &str = "2026.07.1";
+
+const MIN_TEXT_LEN: usize = 40;
+/// Word pieces fed to the model (title and the start of the text).
+const MAX_SEQ_LEN: usize = 512;
+const DEFAULT_MIN_SCORE: f32 = 0.5;
+const DEFAULT_MAX_KEYWORDS: usize = 10;
+const DEFAULT_MAX_INDUSTRIES: usize = 3;
+/// Longest candidate keyphrase, in words; longer stopword-free runs are dropped as noise.
+const MAX_PHRASE_WORDS: usize = 3;
+/// Weighted trigger-phrase hits an industry needs under the rule classifier.
+const MIN_RULE_SCORE: usize = 2;
+
+/// Words that split keyphrases besides the English stopwords: reporting verbs, units and
+/// other words common to every news story.
+const NEWS_STOPWORDS: &[&str] = &[
+    "according", "added", "announced", "reported", "says", "told", "per", "cent", "percent", "crore", "lakh",
+    "million", "billion", "rs", "inr", "usd", "year", "years", "month", "months", "week", "day", "days", "today",
+    "yesterday", "mr", "ms", "however", "including", "around", "nearly", "least",
+];
+
+/// Industry taxonomy: (industry code, trigger phrases). Codes follow GICS industry groups,
+/// split where Indian news and NIC 2008 divisions distinguish them (NBFCs, fintech/payments,
+/// FMCG); the NIC division is noted for each.
+const TAXONOMY: &[(&str, &[&str])] = &[
+    // NIC 64
+    ("BANKS", &["bank", "banks", "banking", "lender", "lenders", "deposits", "npa", "non-performing assets", "net interest margin", "casa"]),
+    ("NBFC", &["nbfc", "nbfcs", "non-banking financial", "housing finance", "microfinance", "gold loan", "gold loans", "vehicle finance"]),
+    // NIC 65
+    ("INSURANCE", &["insurance", "insurer", "insurers", "reinsurance", "policyholders", "premium income", "irdai"]),
+    // NIC 66
+    ("CAPITAL_MARKETS", &["stock exchange", "brokerage", "stockbroker", "mutual fund", "mutual funds", "asset management", "amc", "ipo", "depository", "portfolio management", "sebi"]),
+    ("FINTECH_PAYMENTS", &["fintech", "upi", "digital payments", "payment aggregator", "payment gateway", "payments bank", "wallet", "npci"]),
+    // NIC 62-63
+    ("IT_SERVICES", &["software", "it services", "information technology", "outsourcing", "cloud", "saas", "digital transformation", "data centre", "data center"]),
+    // NIC 61
+    ("TELECOM", &["telecom", "telecommunications", "spectrum", "5g", "mobile subscribers", "arpu", "broadband", "trai"]),
+    // NIC 21, 86
+    ("PHARMA_HEALTHCARE", &["pharma", "pharmaceutical", "pharmaceuticals", "drug", "drugs", "usfda", "hospital", "hospitals", "healthcare", "vaccine", "diagnostics"]),
+    // NIC 29-30
+    ("AUTOMOBILES", &["automobile", "automaker", "carmaker", "passenger vehicle", "passenger vehicles", "two-wheeler", "two-wheelers", "electric vehicle", "electric vehicles", "tractor", "tractors"]),
+    // NIC 06, 19
+    ("OIL_GAS", &["crude", "crude oil", "oil and gas", "natural gas", "refinery", "refining", "petrol", "diesel", "lng", "upstream"]),
+    // NIC 35
+    ("POWER_UTILITIES", &["electricity", "power generation", "power sector", "power plant", "discom", "discoms", "renewable energy", "solar power", "wind energy", "thermal power", "power tariff"]),
+    // NIC 07-08, 24
+    ("METALS_MINING", &["steel", "aluminium", "copper", "zinc", "iron ore", "mining", "coal", "metals"]),
+    // NIC 23
+    ("CEMENT", &["cement", "clinker"]),
+    // NIC 68
+    ("REAL_ESTATE", &["real estate", "realty", "residential", "commercial property", "rera", "developer", "developers", "housing sales", "home sales"]),
+    // NIC 42
+    ("INFRASTRUCTURE", &["infrastructure", "highway", "highways", "road project", "ports", "airport", "airports", "metro rail", "epc", "construction"]),
+    // NIC 10-11, 20
+    ("FMCG", &["fmcg", "consumer goods", "packaged food", "personal care", "fast-moving", "rural demand", "staples"]),
+    // NIC 47
+    ("RETAIL_ECOMMERCE", &["retail", "retailer", "retailers", "e-commerce", "ecommerce", "online marketplace", "quick commerce"]),
+    // NIC 01
+    ("AGRICULTURE", &["agriculture", "farm", "farmers", "kharif", "rabi", "crop", "crops", "fertiliser", "fertilizer", "msp"]),
+    // NIC 51
+    ("AVIATION", &["airline", "airlines", "aviation", "dgca", "aircraft", "air traffic"]),
+    // NIC 13-14
+    ("TEXTILES", &["textile", "textiles", "garment", "garments", "apparel", "cotton", "yarn"]),
+    // NIC 20
+    ("CHEMICALS", &["chemical", "chemicals", "specialty chemicals", "agrochemical", "agrochemicals"]),
+    // NIC 59-60
+    ("MEDIA_ENTERTAINMENT", &["media", "broadcaster", "television", "ott", "streaming", "box office", "entertainment"]),
+];
+
+pub fn process_data(
+    tx: Sender<Document>,
+    rx: Receiver<Document>,
+    app_config: &Config,
+    _api_mutexes: &mut HashMap<String, Arc<Mutex<isize>>>,
+) {
+    let mut model = get_plugin_cfg!(PLUGIN_NAME, "classify_model_dir", app_config)
+        .filter(|dir| !dir.trim().is_empty())
+        .and_then(|dir| IndustryModel::load(&dir));
+    let min_score = get_plugin_cfg!(PLUGIN_NAME, "classify_min_score", app_config)
+        .and_then(|v| v.parse::<f32>().ok())
+        .unwrap_or(DEFAULT_MIN_SCORE);
+    let count = |key: &str, default: usize| get_plugin_cfg!(PLUGIN_NAME, key, app_config).and_then(|v| v.parse().ok()).unwrap_or(default);
+    let (max_keywords, max_industries) = (count("max_keywords", DEFAULT_MAX_KEYWORDS), count("max_industries", DEFAULT_MAX_INDUSTRIES));
+    let mut taxonomy = VocabSource::new(PLUGIN_NAME, get_plugin_cfg!(PLUGIN_NAME, "taxonomy_file", app_config), default_taxonomy().clone());
+    match &model {
+        Some(m) => info!("{}: Starting keyphrase extraction and model-backed industry classification ({} labels).", PLUGIN_NAME, m.labels.len()),
+        None => info!("{}: Starting keyphrase extraction and rule-based industry classification (taxonomy v{}).", PLUGIN_NAME, taxonomy.get().version),
+    }
+    let mut docs = 0usize;
+    for mut doc in rx {
+        taxonomy.refresh();
+        let text = doc.english_text();
+        if text.len() >= MIN_TEXT_LEN {
+            let keyphrases = extract_keyphrases(text, max_keywords);
+            let predicted = match model.as_mut() {
+                Some(m) => m.predict(&format!("{}. {}", doc.title, text), min_score).unwrap_or_else(|e| {
+                    warn!("{}: {}; using rules for this document.", PLUGIN_NAME, e);
+                    Vec::new()
+                }),
+                None => Vec::new(),
+            };
+            let (industries, method) = if predicted.is_empty() {
+                (classify_industries_with(taxonomy.get(), &doc.title, text), "rules")
+            } else {
+                (predicted, "model")
+            };
+            append_distinct(&mut doc.keywords, keyphrases);
+            if !industries.is_empty() {
+                doc.classification.insert("industry_method".to_string(), method.to_string());
+                append_distinct(&mut doc.industries, industries.into_iter().take(max_industries).collect());
+            }
+            docs += 1;
+        }
+        if let Err(e) = tx.send(doc) {
+            error!("{}: when forwarding doc: {}", PLUGIN_NAME, e);
+        }
+    }
+    info!("{}: Completed. Classified {} document(s).", PLUGIN_NAME, docs);
+}
+
+/// Append the `extra` values not already in `values` (compared case-insensitively).
+fn append_distinct(values: &mut Vec<String>, extra: Vec<String>) {
+    for v in extra {
+        if !values.iter().any(|x| x.eq_ignore_ascii_case(&v)) {
+            values.push(v);
+        }
+    }
+}
+
+/// The embedded industry taxonomy.
+pub fn default_taxonomy() -> &'static Themebook {
+    static BOOK: OnceLock<Themebook> = OnceLock::new();
+    BOOK.get_or_init(|| Themebook::from_static(TAXONOMY_VERSION, TAXONOMY))
+}
+
+// ---------------------------------------------------------------------------
+// Keyphrases
+// ---------------------------------------------------------------------------
+
+fn word_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"[\p{L}\p{N}][\p{L}\p{N}'’&-]*").expect("word regex"))
+}
+
+fn stopwords() -> &'static HashSet<String> {
+    static WORDS: OnceLock<HashSet<String>> = OnceLock::new();
+    WORDS.get_or_init(|| {
+        let mut words = english_stopwords();
+        words.extend(NEWS_STOPWORDS.iter().map(|w| w.to_string()));
+        words
+    })
+}
+
+/// Up to `max` RAKE keyphrases of `text`, best first.
+pub fn extract_keyphrases(text: &str, max: usize) -> Vec<String> {
+    let stop = stopwords();
+    // Candidate phrases: runs of content words not separated by punctuation.
+    let mut phrases: Vec<Vec<String>> = Vec::new();
+    let mut current: Vec<String> = Vec::new();
+    let mut last_end = 0;
+    let mut flush = |current: &mut Vec<String>| {
+        if !current.is_empty() && current.len() <= MAX_PHRASE_WORDS {
+            phrases.push(current.clone());
+        }
+        current.clear();
+    };
+    for m in word_re().find_iter(text) {
+        let word = m.as_str().to_lowercase();
+        let word = word.trim_end_matches(['\'', '’', '-']).trim_end_matches("'s").trim_end_matches("’s");
+        if !text[last_end..m.start()].trim().is_empty() {
+            flush(&mut current);
+        }
+        last_end = m.end();
+        let content = word.chars().count() > 1 && word.chars().any(char::is_alphabetic) && !stop.contains(word);
+        if content {
+            current.push(word.to_string());
+        } else {
+            flush(&mut current);
+        }
+    }
+    flush(&mut current);
+
+    let mut freq: HashMap<&str, f64> = HashMap::new();
+    let mut degree: HashMap<&str, f64> = HashMap::new();
+    for p in &phrases {
+        for w in p {
+            *freq.entry(w).or_default() += 1.0;
+            *degree.entry(w).or_default() += p.len() as f64;
+        }
+    }
+    let mut scored: Vec<(String, f64)> = Vec::new();
+    for p in &phrases {
+        let phrase = p.join(" ");
+        if scored.iter().any(|(s, _)| *s == phrase) {
+            continue;
+        }
+        let score = p.iter().map(|w| degree[w.as_str()] / freq[w.as_str()]).sum();
+        scored.push((phrase, score));
+    }
+    // Stable sort: equal scores keep first-occurrence order.
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored.into_iter().take(max).map(|(p, _)| p).collect()
+}
+
+// ---------------------------------------------------------------------------
+// Industries
+// ---------------------------------------------------------------------------
+
+/// Industry codes of `taxonomy` whose trigger phrases occur at least MIN_RULE_SCORE times
+/// (title occurrences counting double), highest score first.
+pub fn classify_industries_with(taxonomy: &Themebook, title: &str, text: &str) -> Vec<String> {
+    let (title, text) = (title.to_lowercase(), text.to_lowercase());
+    let mut scored: Vec<(&str, usize)> = taxonomy
+        .themes
+        .iter()
+        .map(|(code, phrases)| {
+            let hits: usize = phrases.iter().map(|p| 2 * count_whole_word(&title, p) + count_whole_word(&text, p)).sum();
+            (code.as_str(), hits)
+        })
+        .filter(|(_, hits)| *hits >= MIN_RULE_SCORE)
+        .collect();
+    scored.sort_by_key(|s| std::cmp::Reverse(s.1));
+    scored.into_iter().map(|(code, _)| code.to_string()).collect()
+}
+
+/// Classify with the embedded taxonomy (see `classify_industries_with`).
+pub fn classify_industries(title: &str, text: &str) -> Vec<String> {
+    classify_industries_with(default_taxonomy(), title, text)
+}
+
+/// Whole-word occurrences of `needle` in `haystack` (both lowercased).
+fn count_whole_word(haystack: &str, needle: &str) -> usize {
+    let bytes = haystack.as_bytes();
+    haystack
+        .match_indices(needle)
+        .filter(|(idx, _)| {
+            let after = idx + needle.len();
+            (*idx == 0 || !bytes[idx - 1].is_ascii_alphanumeric()) && (after >= bytes.len() || !bytes[after].is_ascii_alphanumeric())
+        })
+        .count()
+}
+
+/// ONNX sequence-classification model with its tokenizer and label set.
+pub struct IndustryModel {
+    session: Session,
+    tokenizer: BertTokenizer,
+    labels: Vec<String>,
+    /// Independent sigmoid scores per label rather than a softmax over them.
+    multi_label: bool,
+    /// Whether the exported graph takes a third `token_type_ids` input.
+    token_types: bool,
+}
+
+impl IndustryModel {
+    /// Load model.onnx, vocab.txt and the label map from `model_dir`. None (with a warning)
+    /// if any of them is missing, in which case the rules classify.
+    pub fn load(model_dir: &str) -> Option<Self> {
+        let config_path = format!("{}/config.json", model_dir);
+        let config = fs::read_to_string(&config_path).ok().and_then(|t| serde_json::from_str::<Value>(&t).ok());
+        let Some(labels) = config.as_ref().and_then(labels_from_config) else {
+            warn!("{}: no id2label map in '{}'; using rules only.", PLUGIN_NAME, config_path);
+            return None;
+        };
+        let multi_label = config
+            .as_ref()
+            .and_then(|c| c.get("problem_type").and_then(Value::as_str))
+            .is_some_and(|p| p == "multi_label_classification");
+        let tokenizer = BertTokenizer::from_dir(model_dir)?;
+        let session = load_session(model_dir, PLUGIN_NAME)?;
+        let token_types = session.inputs().len() > 2;
+        Some(IndustryModel { session, tokenizer, labels, multi_label, token_types })
+    }
+
+    /// Labels scoring at least `min_score` for `text`, highest first.
+    pub fn predict(&mut self, text: &str, min_score: f32) -> Result<Vec<String>, String> {
+        let (ids, mask) = self.tokenizer.tokenize(text, MAX_SEQ_LEN);
+        let len = ids.len();
+        let tensor = |values: Vec<i64>| Tensor::<i64>::from_array(([1, len], values)).map_err(|e| format!("tensor build failed: {}", e));
+        let (ids_tensor, mask_tensor) = (tensor(ids)?, tensor(mask)?);
+        let outputs = if self.token_types {
+            let types_tensor = tensor(vec![0; len])?;
+            self.session.run(ort::inputs![ids_tensor, mask_tensor, types_tensor])
+        } else {
+            self.session.run(ort::inputs![ids_tensor, mask_tensor])
+        }
+        .map_err(|e| format!("ONNX inference failed: {}", e))?;
+
+        // Output 0: logits [1, num_labels]
+        let (shape, logits) = outputs[0].try_extract_tensor::<f32>().map_err(|e| format!("tensor extract failed: {}", e))?;
+        if shape.len() != 2 || shape[1] as usize != self.labels.len() {
+            return Err(format!("unexpected output shape {:?}", shape));
+        }
+        Ok(select_labels(&label_scores(logits, self.multi_label), &self.labels, min_score))
+    }
+}
+
+/// Sigmoid (multi-label) or softmax (single-label) scores of one sequence's logits.
+fn label_scores(logits: &[f32], multi_label: bool) -> Vec<f32> {
+    if multi_label {
+        return logits.iter().map(|l| 1.0 / (1.0 + (-l).exp())).collect();
+    }
+    let max = logits.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+    let sum: f32 = logits.iter().map(|l| (l - max).exp()).sum();
+    logits.iter().map(|l| (l - max).exp() / sum).collect()
+}
+
+/// Labels scoring at least `min_score`, highest first.
+fn select_labels(scores: &[f32], labels: &[String], min_score: f32) -> Vec<String> {
+    let mut picked: Vec<(f32, &String)> = scores.iter().cloned().zip(labels).filter(|(s, _)| *s >= min_score).collect();
+    picked.sort_by(|a, b| b.0.total_cmp(&a.0));
+    picked.into_iter().map(|(_, l)| l.clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keyphrases_rake() {
+        let text = "The Reserve Bank of India kept the repo rate unchanged. Economists said the repo rate \
+                    could fall if retail inflation eases; retail inflation was 5.1% in June, according to data.";
+        let k = extract_keyphrases(text, 4);
+        // Longer phrases of repeated words win; ties keep text order.
+        assert_eq!(k, vec!["repo rate unchanged", "retail inflation eases", "repo rate", "retail inflation"]);
+        assert!(extract_keyphrases(text, 20).iter().all(|p| !p.contains("according") && !p.chars().any(|c| c.is_ascii_digit())));
+    }
+
+    #[test]
+    fn test_rule_industries_with_title_weight() {
+        let title = "Insurer posts record premium income";
+        let text = "The life insurer said premium income rose 20%, while its bank partners sold more policies.";
+        assert_eq!(classify_industries(title, text), vec!["INSURANCE"]);
+        assert!(classify_industries("Markets", "The committee met on Tuesday to review the agenda.").is_empty());
+    }
+
+    #[test]
+    fn test_model_label_selection() {
+        let labels: Vec<String> = ["BANKS", "INSURANCE", "IT_SERVICES"].iter().map(|l| l.to_string()).collect();
+        assert_eq!(select_labels(&label_scores(&[2.0, -3.0, 1.0], true), &labels, 0.5), vec!["BANKS", "IT_SERVICES"]);
+        assert_eq!(select_labels(&label_scores(&[2.0, -3.0, 1.0], false), &labels, 0.5), vec!["BANKS"]);
+    }
+
+    #[test]
+    fn test_source_values_kept() {
+        let mut keywords = vec!["RBI".to_string(), "Repo Rate".to_string()];
+        append_distinct(&mut keywords, vec!["repo rate".to_string(), "liquidity".to_string()]);
+        assert_eq!(keywords, vec!["RBI", "Repo Rate", "liquidity"]);
+    }
+}
@@ -7,15 +7,34 @@
 // its Hamming distance to a previously seen document is within a small threshold.
 //
 // Behaviour is non-destructive: duplicates are tagged (classification["near_duplicate"]
-// = "true" with the matched unique_id) and still forwarded, so downstream persistence and
+// = "true" and classification["duplicate_of"] = the matched document's unique_id, when it has
+// one) and still forwarded, so downstream persistence and
 // the completed-urls table remain consistent. The document URL is also canonicalized.
+//
+// Story clusters persist across runs: every document's SimHash and cluster id are stored
+// (`store::story_clusters`) with a banded LSH index, so a story republished days later is
+// looked up without a scan and joins the original's cluster. Each checked document is tagged
+// with classification["cluster_id"] — the cluster of its closest earlier near-duplicate
+// within the time window, else its own doc id — which `mod_mentions` records as its story
+// cluster. If the store cannot be opened, matching falls back to this run's documents only.
+//
+// Config keys:
+//   window_days        - How far apart (days) two documents may be published and still be
+//                        near-duplicates (default 7).
+//   simhash_threshold  - Maximum Hamming distance of near-duplicates (default 3; above 3 the
+//                        band index no longer guarantees every match is found).
 
 use std::collections::HashMap;
 use std::sync::mpsc::{Receiver, Sender};
 use std::sync::{Arc, Mutex};
 use config::Config;
 use log::{error, info};
+use rusqlite::Connection;
+use crate::cfg::get_database_filename;
 use crate::document::Document;
+use crate::get_plugin_cfg;
+use crate::plugins::mod_mentions::doc_id_for;
+use crate::store::story_clusters::{cluster_of, find_near_duplicate, save_signature};
 use crate::utils::canonicalize_url;
 
 pub(crate) const PLUGIN_NAME: &str = "mod_dedupe";
@@ -25,17 +44,33 @@ pub(crate) const PLUGIN_NAME: &str = "mod_dedupe";
 const SIMHASH_THRESHOLD: u32 = 3;
 /// Documents shorter than this (chars) are not near-dup checked — too little signal.
 const MIN_TEXT_FOR_SIMHASH: usize = 200;
+/// Default publication-time window for near-duplicates.
+const DEFAULT_WINDOW_DAYS: i64 = 7;
+const DAY_MS: i64 = 86_400_000;
 
 pub(crate) fn process_data(
     tx: Sender<Document>,
     rx: Receiver<Document>,
-    _config: &Config,
+    config: &Config,
     _api_mutexes: &mut HashMap<String, Arc<Mutex<isize>>>,
 ) {
-    info!("{}: Starting near-duplicate detection.", PLUGIN_NAME);
+    let window_days = get_plugin_cfg!(PLUGIN_NAME, "window_days", config)
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_WINDOW_DAYS);
+    let threshold = get_plugin_cfg!(PLUGIN_NAME, "simhash_threshold", config)
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(SIMHASH_THRESHOLD);
+    info!("{}: Starting near-duplicate detection (window={} days, threshold={}).", PLUGIN_NAME, window_days, threshold);
 
-    // Seen SimHashes paired with the unique_id of the document that produced them.
-    let mut seen: Vec<(u64, String)> = Vec::new();
+    let db_path = get_database_filename(config);
+    let conn = match crate::store::open(&db_path) {
+        Ok(c) => Some(c),
+        Err(e) => {
+            error!("{}: cannot open store '{}': {} — clustering within this run only.", PLUGIN_NAME, db_path, e);
+            None
+        }
+    };
+    let mut index = StoryIndex::new(conn, threshold, window_days * DAY_MS);
     let mut dup_count: usize = 0;
 
     for mut doc in rx {
@@ -46,21 +81,20 @@ pub(crate) fn process_data(
 
         if doc.text.len() >= MIN_TEXT_FOR_SIMHASH {
             let hash = simhash(&doc.text);
-            if let Some(matched_id) = seen
-                .iter()
-                .find(|(h, _)| hamming_distance(*h, hash) <= SIMHASH_THRESHOLD)
-                .map(|(_, id)| id.clone())
-            {
+            let date_ms = if doc.publish_date_ms > 0 { doc.publish_date_ms } else { chrono::Utc::now().timestamp_millis() };
+            let (cluster_id, duplicate_of) = index.assign(&doc_id_for(&doc), &doc.unique_id, hash, date_ms);
+            if let Some(matched_unique_id) = duplicate_of {
                 dup_count += 1;
                 doc.classification.insert("near_duplicate".to_string(), "true".to_string());
-                doc.classification.insert("duplicate_of".to_string(), matched_id.clone());
                 info!(
                     "{}: Near-duplicate detected (url={}) of unique_id={}",
-                    PLUGIN_NAME, doc.url, matched_id
+                    PLUGIN_NAME, doc.url, matched_unique_id
                 );
-            } else {
-                seen.push((hash, doc.unique_id.clone()));
+                if !matched_unique_id.is_empty() {
+                    doc.classification.insert("duplicate_of".to_string(), matched_unique_id);
+                }
             }
+            doc.classification.insert("cluster_id".to_string(), cluster_id);
         }
 
         if let Err(e) = tx.send(doc) {
@@ -71,6 +105,51 @@ pub(crate) fn process_data(
     info!("{}: Completed. Flagged {} near-duplicate(s).", PLUGIN_NAME, dup_count);
 }
 
+/// Story clusters of the documents seen so far: the persistent store when available, else
+/// this run's documents.
+struct StoryIndex {
+    conn: Option<Connection>,
+    /// (SimHash, unique_id, cluster id) of this run's documents, used without a store.
+    seen: Vec<(u64, String, String)>,
+    threshold: u32,
+    window_ms: i64,
+}
+
+impl StoryIndex {
+    fn new(conn: Option<Connection>, threshold: u32, window_ms: i64) -> Self {
+        StoryIndex { conn, seen: Vec::new(), threshold, window_ms }
+    }
+
+    /// Cluster id for a document, and the unique_id of the near-duplicate it matched if any
+    /// (empty if that document had none). A document already stored keeps its cluster.
+    fn assign(&mut self, doc_id: &str, unique_id: &str, hash: u64, date_ms: i64) -> (String, Option<String>) {
+        let Some(conn) = self.conn.as_ref() else {
+            if let Some((_, matched, cluster)) = self.seen.iter().find(|(h, _, _)| hamming_distance(*h, hash) <= self.threshold) {
+                return (cluster.clone(), Some(matched.clone()));
+            }
+            self.seen.push((hash, unique_id.trim().to_string(), doc_id.to_string()));
+            return (doc_id.to_string(), None);
+        };
+        let result = (|| -> Result<(String, Option<String>), String> {
+            if let Some(cluster) = cluster_of(conn, doc_id)? {
+                return Ok((cluster, None));
+            }
+            let found = find_near_duplicate(conn, hash, date_ms, self.window_ms, self.threshold)?;
+            let (cluster, matched) = match found {
+                Some(m) => (m.cluster_id, Some(m.unique_id.unwrap_or_default())),
+                None => (doc_id.to_string(), None),
+            };
+            save_signature(conn, doc_id, unique_id, &cluster, hash, date_ms)?;
+            Ok((cluster, matched))
+        })();
+        result.unwrap_or_else(|e| {
+            error!("{}: {}", PLUGIN_NAME, e);
+            crate::metrics::record_db_error();
+            (doc_id.to_string(), None)
+        })
+    }
+}
+
 /// Compute a 64-bit SimHash over whitespace-delimited word tokens of `text`.
 pub fn simhash(text: &str) -> u64 {
     let mut counts = [0i32; 64];
@@ -154,6 +233,33 @@ mod tests {
         assert!(dist > SIMHASH_THRESHOLD, "expected distinct, distance was {}", dist);
     }
 
+    #[test]
+    fn test_story_index_keeps_clusters_stable() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::store::migrate(&conn).unwrap();
+        let day0 = 1_750_000_000_000;
+        let a: u64 = 0x0123_4567_89ab_cdef;
+        let mut index = StoryIndex::new(Some(conn), SIMHASH_THRESHOLD, DEFAULT_WINDOW_DAYS * DAY_MS);
+        let some = |id: &str| Some(id.to_string());
+        assert_eq!(index.assign("D1", "REP-1", a, day0), ("D1".to_string(), None));
+        // Next day's republication (and a copy of that) join the first document's cluster and
+        // name it by its unique_id.
+        assert_eq!(index.assign("D2", "", a ^ 0b11, day0 + DAY_MS), ("D1".to_string(), some("REP-1")));
+        assert_eq!(index.assign("D3", "REP-3", a ^ 0b111, day0 + 2 * DAY_MS).0, "D1");
+        // A re-processed document keeps its cluster and is not its own duplicate.
+        assert_eq!(index.assign("D1", "REP-1", a, day0), ("D1".to_string(), None));
+        // Outside the window it starts a new cluster.
+        assert_eq!(index.assign("D4", "REP-4", a, day0 + 30 * DAY_MS), ("D4".to_string(), None));
+        // A match without a unique_id is reported with an empty one.
+        let b = !a;
+        assert_eq!(index.assign("u0001", "", b, day0).0, "u0001");
+        assert_eq!(index.assign("D5", "REP-5", b ^ 1, day0), ("u0001".to_string(), some("")));
+
+        let mut in_run = StoryIndex::new(None, SIMHASH_THRESHOLD, DEFAULT_WINDOW_DAYS * DAY_MS);
+        assert_eq!(in_run.assign("D1", "REP-1", a, day0).0, "D1");
+        assert_eq!(in_run.assign("D2", "REP-2", a ^ 1, day0), ("D1".to_string(), some("REP-1")));
+    }
+
     #[test]
     fn test_token_hash_stable() {
         // Must be deterministic across calls (and processes).
@@ -18,6 +18,10 @@ pub const PLUGIN_NAME: &str = "mod_doc_type";
 /// - "mod_en_in_rbi" or "rbi_new" → classify_rbi_document_type
 /// - "mod_en_in_sebi" or "sebi"   → classify_sebi_document_type
 /// - "mod_en_in_irdai" or "irdai" → classify_irdai_document_type
+/// - "mod_en_in_mca"              → classify_mca_document_type
+/// - "mod_en_in_pfrda"            → classify_pfrda_document_type
+/// - "mod_en_in_ifsca"            → classify_ifsca_document_type
+/// - "mod_en_in_fiu"              → classify_fiu_document_type
 ///
 /// # Arguments
 ///
@@ -43,6 +47,18 @@ pub fn process_data(tx: Sender<document::Document>, rx: Receiver<document::Docum
         } else if doc.module.eq_ignore_ascii_case("mod_en_in_irdai") || doc.module.eq_ignore_ascii_case("irdai") {
             let doc_type = classify_irdai_document_type(doc.title.as_str(), doc.url.as_str(), doc.section_name.as_str());
             doc.classification.insert("doc_type".to_string(), doc_type.to_string());
+        } else if doc.module.eq_ignore_ascii_case("mod_en_in_mca") {
+            let doc_type = classify_mca_document_type(doc.title.as_str(), doc.section_name.as_str());
+            doc.classification.insert("doc_type".to_string(), doc_type.to_string());
+        } else if doc.module.eq_ignore_ascii_case("mod_en_in_pfrda") {
+            let doc_type = classify_pfrda_document_type(doc.title.as_str(), doc.section_name.as_str());
+            doc.classification.insert("doc_type".to_string(), doc_type.to_string());
+        } else if doc.module.eq_ignore_ascii_case("mod_en_in_ifsca") {
+            let doc_type = classify_ifsca_document_type(doc.title.as_str(), doc.section_name.as_str());
+            doc.classification.insert("doc_type".to_string(), doc_type.to_string());
+        } else if doc.module.eq_ignore_ascii_case("mod_en_in_fiu") {
+            let doc_type = classify_fiu_document_type(doc.title.as_str(), doc.section_name.as_str());
+            doc.classification.insert("doc_type".to_string(), doc_type.to_string());
         }
 
         // for future use, add categorisation rules for other modules/websites:
@@ -134,6 +150,71 @@ pub(crate) fn classify_sebi_document_type(title: &str, url: &str, section_name:
     "regulatory-notification"
 }
 
+/// Drafts and papers released for public comment.
+fn is_consultation(title: &str, section_name: &str) -> bool {
+    let consultation_pattn: Regex = Regex::new(r"(?i)(Exposure Draft|Consultation Paper|Discussion Paper|Draft Rules|Draft Regulations|inviting (public )?comments|seeking comments)").unwrap();
+    consultation_pattn.is_match(title) || consultation_pattn.is_match(section_name)
+}
+
+/// Orders passed against a named entity or person (adjudication, penalty, compounding, etc.).
+fn is_individual_order(title: &str) -> bool {
+    let order_pattn: Regex = Regex::new(r"(?i)(Adjudication Order|Order in the matter of|Order dated .* against|Order against|Order under Section|Penalty imposed|imposition of penalty|Compounding Order|Cancellation of registration of|Order of cancellation)").unwrap();
+    order_pattn.is_match(title)
+}
+
+pub(crate) fn classify_mca_document_type(title: &str, section_name: &str) -> &'static str {
+    if is_consultation(title, section_name) {
+        return "consultation";
+    }
+    let strike_off_pattn: Regex = Regex::new(r"(?i)(Strike[ -]off|Striking off|Section 248|Notice of removal of names)").unwrap();
+    if strike_off_pattn.is_match(title) {
+        return "individual_notice";
+    }
+    if is_individual_order(title) || section_name == "Orders" {
+        return "individual_order";
+    }
+    // default type regulatory-notification (rules, amendments, general circulars):
+    "regulatory-notification"
+}
+
+pub(crate) fn classify_pfrda_document_type(title: &str, section_name: &str) -> &'static str {
+    if is_consultation(title, section_name) {
+        return "consultation";
+    }
+    if is_individual_order(title) {
+        return "individual_order";
+    }
+    let market_data_pattn: Regex = Regex::new(r"(?i)(NPS Subscribers? (data|statistics)|Monthly (NPS|Pension) (Bulletin|Statistics)|Scheme-wise returns)").unwrap();
+    if market_data_pattn.is_match(title) {
+        return "market_data";
+    }
+    "regulatory-notification"
+}
+
+pub(crate) fn classify_ifsca_document_type(title: &str, section_name: &str) -> &'static str {
+    if is_consultation(title, section_name) {
+        return "consultation";
+    }
+    if is_individual_order(title) || section_name == "Orders" {
+        return "individual_order";
+    }
+    "regulatory-notification"
+}
+
+pub(crate) fn classify_fiu_document_type(title: &str, section_name: &str) -> &'static str {
+    let notice_pattn: Regex = Regex::new(r"(?i)(Show Cause Notice|Public Notice regarding|Notice under Section)").unwrap();
+    if notice_pattn.is_match(title) {
+        return "individual_notice";
+    }
+    if is_individual_order(title) || section_name == "Compliance Orders" {
+        return "individual_order";
+    }
+    if is_consultation(title, section_name) {
+        return "consultation";
+    }
+    "regulatory-notification"
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -194,4 +275,33 @@ mod tests {
     fn test_classify_irdai_default() {
         assert_eq!(classify_irdai_document_type("Any IRDAI document", "https://irdai.gov.in/circulars/1", "Circulars"), "regulatory-notification");
     }
+
+    #[test]
+    fn test_classify_mca_document_type() {
+        assert_eq!(classify_mca_document_type("Companies (Accounts) Amendment Rules, 2025", "Notifications"), "regulatory-notification");
+        assert_eq!(classify_mca_document_type("Draft Rules on Related Party Transactions - inviting comments", "Notifications"), "consultation");
+        assert_eq!(classify_mca_document_type("Adjudication Order in the matter of XYZ Pvt Ltd", "Notifications"), "individual_order");
+        assert_eq!(classify_mca_document_type("Notice of removal of names of companies u/s 248", "Notifications"), "individual_notice");
+    }
+
+    #[test]
+    fn test_classify_pfrda_document_type() {
+        assert_eq!(classify_pfrda_document_type("Circular on Multiple Scheme Framework under NPS", "Circulars"), "regulatory-notification");
+        assert_eq!(classify_pfrda_document_type("Exposure Draft on Unified Pension Scheme regulations", "Circulars"), "consultation");
+        assert_eq!(classify_pfrda_document_type("Order in the matter of ABC Pension Fund", "Orders"), "individual_order");
+    }
+
+    #[test]
+    fn test_classify_ifsca_document_type() {
+        assert_eq!(classify_ifsca_document_type("Framework for Global Access in IFSC", "Circulars"), "regulatory-notification");
+        assert_eq!(classify_ifsca_document_type("Consultation Paper on Fund Management Regulations", "Consultation Papers"), "consultation");
+        assert_eq!(classify_ifsca_document_type("Settlement of proceedings with DEF IFSC Ltd", "Orders"), "individual_order");
+    }
+
+    #[test]
+    fn test_classify_fiu_document_type() {
+        assert_eq!(classify_fiu_document_type("Order dated 12.02.2025 against ABC Bank Ltd.", "Compliance Orders"), "individual_order");
+        assert_eq!(classify_fiu_document_type("Show Cause Notice to offshore VDA service providers", "Notices"), "individual_notice");
+        assert_eq!(classify_fiu_document_type("Guidelines for detecting suspicious transactions", "Guidelines"), "regulatory-notification");
+    }
 }
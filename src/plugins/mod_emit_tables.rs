@@ -3,10 +3,12 @@
 //   Phase-1 emitter (roadmap Stage 5 / F1). Persists the structured facts that the upstream
 //   extractors (`mod_extract_quant`, `mod_themes`, `mod_tone`, and later NER/geo) have placed
 //   on `doc.analysis` into the canonical fact tables (`amounts`/`counts`/`dates_ref`/`themes`/
-//   `gcam`) via the store layer. Writes are *batched*: documents are buffered and flushed in a
-//   single SQLite transaction once the buffer fills or the stream ends, reducing disk I/O
-//   (roadmap point 9). This plugin is the canonical-table sink and runs near the end of the
+//   `gcam`/`quotes`) via the store layer. Writes are *batched*: documents are buffered and
+//   flushed in a single SQLite transaction once the buffer fills or the stream ends, reducing
+//   disk I/O (roadmap point 9). This plugin is the canonical-table sink and runs near the end of the
 //   data_processor chain, after all enrichment but before vectorstore.
+//   Tables reconstructed from PDF circulars (`generated_content["tables"]`, see `pdf_tables`)
+//   are buffered alongside and written to the `pdf_tables` table in the same manner.
 
 use std::collections::HashMap;
 use std::sync::mpsc::{Receiver, Sender};
@@ -18,8 +20,9 @@ use log::{error, info};
 use crate::analysis::DocAnalysis;
 use crate::cfg::get_database_filename;
 use crate::document::Document;
+use crate::pdf_tables::{tables_from_document, PdfTable};
 use crate::plugins::mod_mentions::doc_id_for;
-use crate::store::records::emit_analysis;
+use crate::store::records::{emit_analysis, upsert_pdf_table};
 
 pub const PLUGIN_NAME: &str = "mod_emit_tables";
 
@@ -44,6 +47,9 @@ pub fn process_data(
 
     // Buffer of (doc_id, analysis) awaiting a transactional flush.
     let mut buffer: Vec<(String, DocAnalysis)> = Vec::with_capacity(BATCH_SIZE);
+    // Buffer of (doc_id, tables) extracted from PDFs, flushed alongside.
+    let mut table_buffer: Vec<(String, Vec<PdfTable>)> = Vec::new();
+    let mut total_tables = 0usize;
     let mut total_facts = 0usize;
     let mut total_docs = 0usize;
 
@@ -62,6 +68,15 @@ pub fn process_data(
                     }
                 }
             }
+            let tables = tables_from_document(&doc);
+            if !tables.is_empty() {
+                table_buffer.push((doc_id_for(&doc), tables));
+                if table_buffer.len() >= BATCH_SIZE {
+                    if let Some(ref mut c) = conn {
+                        total_tables += flush_tables(c, &mut table_buffer);
+                    }
+                }
+            }
         }
         if let Err(e) = tx.send(doc) {
             error!("{}: when forwarding doc: {}", PLUGIN_NAME, e);
@@ -73,8 +88,10 @@ pub fn process_data(
         let (d, f) = flush(c, &mut buffer);
         total_docs += d;
         total_facts += f;
+        total_tables += flush_tables(c, &mut table_buffer);
     }
-    info!("{}: Completed. Persisted {} fact(s) for {} document(s).", PLUGIN_NAME, total_facts, total_docs);
+    info!("{}: Completed. Persisted {} fact(s) for {} document(s), and {} PDF table(s).",
+        PLUGIN_NAME, total_facts, total_docs, total_tables);
 }
 
 /// Flush the buffer in a single transaction. Returns (documents_written, facts_written).
@@ -106,6 +123,33 @@ fn flush(conn: &mut rusqlite::Connection, buffer: &mut Vec<(String, DocAnalysis)
     (docs, facts)
 }
 
+/// Flush buffered PDF tables in a single transaction. Returns the number of tables written.
+/// Like `flush`, the buffer is cleared even on error.
+fn flush_tables(conn: &mut rusqlite::Connection, buffer: &mut Vec<(String, Vec<PdfTable>)>) -> usize {
+    if buffer.is_empty() {
+        return 0;
+    }
+    let mut written = 0usize;
+    let result = (|| -> Result<(), String> {
+        let tx = conn.transaction().map_err(|e| format!("begin tx: {}", e))?;
+        for (doc_id, tables) in buffer.iter() {
+            for (idx, table) in tables.iter().enumerate() {
+                written += upsert_pdf_table(&tx, doc_id, idx, table)?;
+            }
+        }
+        tx.commit().map_err(|e| format!("commit tx: {}", e))?;
+        Ok(())
+    })();
+    buffer.clear();
+    if let Err(e) = result {
+        error!("{}: PDF table flush failed: {}", PLUGIN_NAME, e);
+        crate::metrics::record_db_error();
+        return 0;
+    }
+    crate::metrics::record_db_writes(written as u64);
+    written
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -146,6 +190,22 @@ mod tests {
         assert_eq!(themes, 2);
     }
 
+    #[test]
+    fn test_flush_tables_writes_each_table() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        store::migrate(&conn).unwrap();
+        let table = PdfTable {
+            page: 1, n_rows: 1, n_cols: 2,
+            rows: vec![vec!["Margin".into(), "12.5%".into()]],
+            csv_file: String::new(),
+        };
+        let mut buffer = vec![("D1".to_string(), vec![table.clone(), table])];
+        assert_eq!(flush_tables(&mut conn, &mut buffer), 2);
+        assert!(buffer.is_empty());
+        let n: i64 = conn.query_row("SELECT COUNT(*) FROM pdf_tables WHERE doc_id='D1'", [], |r| r.get(0)).unwrap();
+        assert_eq!(n, 2);
+    }
+
     #[test]
     fn test_flush_empty_is_noop() {
         let mut conn = rusqlite::Connection::open_in_memory().unwrap();
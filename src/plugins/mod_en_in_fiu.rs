@@ -0,0 +1,159 @@
+// file: mod_en_in_fiu
+// Purpose: Retrieve notices, guidelines and compliance orders published by FIU-IND (Financial
+// Intelligence Unit - India) under the PMLA. The FIU-IND pages are plain lists of PDF links
+// rather than tables, and the date usually appears in the link text or next to it
+// (e.g. "Order dated 12.02.2025 against ..."), so the date is located with a regex.
+
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, OnceLock};
+use regex::Regex;
+use crate::document;
+use crate::document::Document;
+use crate::plugins::regulator_listing::{self, RegulatorSite};
+use crate::utils::{check_and_fix_url, clean_text, get_text_from_element, parse_date_with_formats, to_local_datetime};
+
+pub(crate) const PLUGIN_NAME: &str = "mod_en_in_fiu";
+const PUBLISHER_NAME: &str = "Financial Intelligence Unit - India";
+const BASE_URL: &str = "https://fiuindia.gov.in/";
+
+/// Date formats seen in FIU-IND link texts, after extraction by `date_regex`.
+const DATE_FORMATS: &[&str] = &["%d.%m.%Y", "%d/%m/%Y", "%d-%m-%Y", "%d %B %Y", "%d %b %Y", "%B %d, %Y"];
+
+/// Listing pages of this site, retrieved by `regulator_listing::run`.
+const SITE: RegulatorSite = RegulatorSite {
+    plugin_name: PLUGIN_NAME,
+    publisher_name: PUBLISHER_NAME,
+    base_url: BASE_URL,
+    listing_urls: &[
+        ("https://fiuindia.gov.in/files/Notices/notices.html", "Notices"),
+        ("https://fiuindia.gov.in/files/Compliance_Orders/orders.html", "Compliance Orders"),
+        ("https://fiuindia.gov.in/files/AML_Legislation/guidelines.html", "Guidelines"),
+    ],
+    extract_docs: extract_docs_from_listing,
+};
+
+/// Executes this function of the module in the separate thread launched by the pipeline/queue module
+///
+/// # Arguments
+///
+/// * `tx`: The channel to transmit newly identified or web scraped documents
+/// * `app_config`: The application configuration object to be used to get various config parameters
+///
+/// returns: ()
+pub(crate) fn run_worker_thread(tx: Sender<document::Document>, app_config: Arc<config::Config>) {
+    regulator_listing::run(tx, app_config, &SITE);
+}
+
+/// Matches the dates FIU-IND writes in link texts: "12.02.2025", "12/02/2025",
+/// "12 February 2025", "February 12, 2025".
+fn date_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"\b(\d{1,2}[./-]\d{1,2}[./-]\d{4}|\d{1,2}\s+[A-Z][a-z]+\s+\d{4}|[A-Z][a-z]+\s+\d{1,2},\s*\d{4})\b",
+        )
+        .expect("FIU date regex")
+    })
+}
+
+/// Find the first parseable date in a piece of text.
+fn find_date(text: &str) -> Option<chrono::NaiveDate> {
+    date_regex().find_iter(text).find_map(|m| parse_date_with_formats(m.as_str(), DATE_FORMATS))
+}
+
+/// Extract one document per PDF link on an FIU-IND listing page. The date is taken from the
+/// link text, or else from the text of the enclosing list item / table row.
+pub(crate) fn extract_docs_from_listing(content: &str, url_listing_page: &str) -> Vec<Document> {
+    let html_document = scraper::Html::parse_document(content);
+    let alink_selector = scraper::Selector::parse("a[href]").unwrap();
+    let mut docs: Vec<Document> = Vec::new();
+
+    for alink_elem in html_document.select(&alink_selector) {
+        let Some(href) = alink_elem.value().attr("href") else { continue };
+        if !href.to_lowercase().ends_with(".pdf") {
+            continue;
+        }
+        let Some(url) = absolute_url(href, url_listing_page) else { continue };
+        if docs.iter().any(|d| d.url == url) {
+            continue;
+        }
+        let title = clean_text(get_text_from_element(alink_elem));
+        if title.is_empty() {
+            continue;
+        }
+        let mut this_new_doc = SITE.new_document();
+        this_new_doc.title = title;
+        this_new_doc.url = url.clone();
+        this_new_doc.pdf_url = url;
+        this_new_doc.links_inward = vec![url_listing_page.to_string()];
+
+        let context_text = alink_elem.parent()
+            .and_then(scraper::ElementRef::wrap)
+            .map(|parent| clean_text(get_text_from_element(parent)))
+            .unwrap_or_default();
+        if let Some(naive_date) = find_date(&this_new_doc.title).or_else(|| find_date(&context_text)) {
+            this_new_doc.publish_date_ms = to_local_datetime(naive_date).timestamp();
+            this_new_doc.publish_date = naive_date.format("%Y-%m-%d").to_string();
+        }
+        docs.push(this_new_doc);
+    }
+    docs
+}
+
+/// Resolve a listing link against the listing page's folder (FIU-IND links are relative to
+/// the page, e.g. "Order_XYZ.pdf" under "/files/Compliance_Orders/").
+fn absolute_url(href: &str, url_listing_page: &str) -> Option<String> {
+    let href = href.trim();
+    if href.starts_with("http://") || href.starts_with("https://") {
+        return Some(href.to_string());
+    }
+    if href.starts_with('/') {
+        return check_and_fix_url(href, BASE_URL);
+    }
+    let folder = match url_listing_page.rfind('/') {
+        Some(pos) => &url_listing_page[..=pos],
+        None => BASE_URL,
+    };
+    let mut resolved = format!("{}{}", folder, href.trim_start_matches("./"));
+    // collapse "../" segments:
+    while let Some(pos) = resolved.find("/../") {
+        let prefix_end = resolved[..pos].rfind('/').unwrap_or(pos);
+        resolved.replace_range(prefix_end..pos + 3, "");
+    }
+    check_and_fix_url(resolved.as_str(), BASE_URL)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Representative of the FIU-IND compliance orders page.
+    const LISTING_FIXTURE: &str = r#"
+    <div id="content">
+      <h2>Compliance Orders</h2>
+      <ul>
+        <li><a href="Order_ABC_Bank_12022025.pdf">Order dated 12.02.2025 against ABC Bank Ltd. under Section 13 of PMLA</a></li>
+        <li>Published on 03/01/2025: <a href="../Compliance_Orders/Order_XYZ_Casino.pdf">Order against XYZ Casino</a></li>
+        <li><a href="Order_ABC_Bank_12022025.pdf">Order dated 12.02.2025 (duplicate link)</a></li>
+        <li><a href="/index.html">Home</a></li>
+      </ul>
+    </div>"#;
+
+    #[test]
+    fn test_extract_docs_from_listing_fixture() {
+        let docs = extract_docs_from_listing(LISTING_FIXTURE, "https://fiuindia.gov.in/files/Compliance_Orders/orders.html");
+        assert_eq!(docs.len(), 2, "non-PDF and duplicate links must be skipped");
+        assert_eq!(docs[0].url, "https://fiuindia.gov.in/files/Compliance_Orders/Order_ABC_Bank_12022025.pdf");
+        assert_eq!(docs[0].publish_date, "2025-02-12");
+        assert_eq!(docs[1].title, "Order against XYZ Casino");
+        assert_eq!(docs[1].url, "https://fiuindia.gov.in/files/Compliance_Orders/Order_XYZ_Casino.pdf");
+        assert_eq!(docs[1].publish_date, "2025-01-03", "date taken from the enclosing list item");
+    }
+
+    #[test]
+    fn test_find_date_formats() {
+        assert_eq!(find_date("Notice dated 5 March 2024 on STR filing"), chrono::NaiveDate::from_ymd_opt(2024, 3, 5));
+        assert_eq!(find_date("Guidelines issued on March 5, 2024"), chrono::NaiveDate::from_ymd_opt(2024, 3, 5));
+        assert_eq!(find_date("Annual report of FIU-IND"), None);
+    }
+}
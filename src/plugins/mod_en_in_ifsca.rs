@@ -0,0 +1,167 @@
+// file: mod_en_in_ifsca
+// Purpose: Retrieve circulars, regulations, consultation papers and orders published by IFSCA
+// (International Financial Services Centres Authority, GIFT City). Each listing row links to a
+// PDF; the circular reference number (e.g. "IFSCA/2025/CPD/001") becomes the unique_id.
+
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use log::warn;
+use scraper::ElementRef;
+use crate::document;
+use crate::document::Document;
+use crate::plugins::regulator_listing::{self, RegulatorSite};
+use crate::utils::{clean_text, get_text_from_element, parse_date_with_formats, to_local_datetime};
+
+pub(crate) const PLUGIN_NAME: &str = "mod_en_in_ifsca";
+const PUBLISHER_NAME: &str = "International Financial Services Centres Authority";
+const BASE_URL: &str = "https://ifsca.gov.in/";
+
+/// Date formats seen in the IFSCA listing tables.
+const DATE_FORMATS: &[&str] = &["%b %d, %Y", "%d/%m/%Y", "%d-%m-%Y", "%d %b %Y", "%d-%b-%Y"];
+
+/// Column positions of a listing table, located from its header row.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ListingColumns {
+    date: Option<usize>,
+    title: Option<usize>,
+    reference: Option<usize>,
+}
+
+/// Listing pages of this site, retrieved by `regulator_listing::run`.
+const SITE: RegulatorSite = RegulatorSite {
+    plugin_name: PLUGIN_NAME,
+    publisher_name: PUBLISHER_NAME,
+    base_url: BASE_URL,
+    listing_urls: &[
+        ("https://ifsca.gov.in/Legal/Index/Circulars", "Circulars"),
+        ("https://ifsca.gov.in/Legal/Index/Regulations", "Regulations"),
+        ("https://ifsca.gov.in/Legal/Index/Notifications", "Notifications"),
+        ("https://ifsca.gov.in/Legal/Index/ConsultationPapers", "Consultation Papers"),
+        ("https://ifsca.gov.in/Legal/Index/Orders", "Orders"),
+    ],
+    extract_docs: extract_docs_from_listing,
+};
+
+/// Executes this function of the module in the separate thread launched by the pipeline/queue module
+///
+/// # Arguments
+///
+/// * `tx`: The channel to transmit newly identified or web scraped documents
+/// * `app_config`: The application configuration object to be used to get various config parameters
+///
+/// returns: ()
+pub(crate) fn run_worker_thread(tx: Sender<document::Document>, app_config: Arc<config::Config>) {
+    regulator_listing::run(tx, app_config, &SITE);
+}
+
+/// Extract one document per data row of the IFSCA listing table, locating the date, title and
+/// reference number columns from the table header.
+pub(crate) fn extract_docs_from_listing(content: &str, url_listing_page: &str) -> Vec<Document> {
+    let html_document = scraper::Html::parse_document(content);
+    let header_selector = scraper::Selector::parse("table thead th").unwrap();
+    let rows_selector = scraper::Selector::parse("table tbody tr").unwrap();
+
+    let headers: Vec<String> = html_document.select(&header_selector)
+        .map(|th| clean_text(get_text_from_element(th)).to_lowercase())
+        .collect();
+    let columns = locate_columns(&headers);
+
+    html_document.select(&rows_selector)
+        .filter_map(|row| extract_docinfo_from_row(row, &columns, url_listing_page))
+        .collect()
+}
+
+fn locate_columns(headers: &[String]) -> ListingColumns {
+    let find = |keys: &[&str]| headers.iter().position(|h| keys.iter().any(|k| h.contains(k)));
+    ListingColumns {
+        date: find(&["date"]),
+        title: find(&["subject", "title", "description"]),
+        reference: find(&["reference", "circular no", "number", "ref."]),
+    }
+}
+
+fn extract_docinfo_from_row(row_each: ElementRef, columns: &ListingColumns, source_url: &str) -> Option<Document> {
+    let cell_selector = scraper::Selector::parse("td").unwrap();
+    let alink_selector = scraper::Selector::parse("a[href]").unwrap();
+
+    let cells: Vec<ElementRef> = row_each.select(&cell_selector).collect();
+    let alink_elem = row_each.select(&alink_selector).next()?;
+    let url = SITE.absolute_url(alink_elem.value().attr("href")?)?;
+
+    let mut this_new_doc = SITE.new_document();
+    this_new_doc.url = url.clone();
+    if url.to_lowercase().contains(".pdf") {
+        this_new_doc.pdf_url = url;
+    }
+
+    let cell_text = |idx: Option<usize>| idx
+        .and_then(|i| cells.get(i))
+        .map(|cell| clean_text(get_text_from_element(*cell)));
+
+    this_new_doc.title = cell_text(columns.title)
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| clean_text(get_text_from_element(alink_elem)));
+    if let Some(reference) = cell_text(columns.reference) {
+        this_new_doc.unique_id = reference;
+    }
+    if let Some(date_str) = cell_text(columns.date) {
+        match parse_date_with_formats(&date_str, DATE_FORMATS) {
+            Some(naive_date) => {
+                this_new_doc.publish_date_ms = to_local_datetime(naive_date).timestamp();
+                this_new_doc.publish_date = naive_date.format("%Y-%m-%d").to_string();
+            }
+            None => warn!("{}: Could not parse date '{}'", PLUGIN_NAME, date_str),
+        }
+    }
+    if this_new_doc.title.is_empty() {
+        return None;
+    }
+    this_new_doc.links_inward = vec![source_url.to_string()];
+    Some(this_new_doc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Representative of the IFSCA legal-framework listing table.
+    const LISTING_FIXTURE: &str = r#"
+    <table id="tblLegal" class="table">
+      <thead><tr><th>Sr. No.</th><th>Date</th><th>Reference Number</th><th>Subject</th><th>Download</th></tr></thead>
+      <tbody>
+        <tr>
+          <td>1</td><td>Mar 06, 2026</td><td>IFSCA-CPD/7/2026-Banking</td>
+          <td>Framework for Global Access in IFSC</td>
+          <td><a href="/Document/Legal/framework-for-global-access06032026.pdf">Download (1.2 MB)</a></td>
+        </tr>
+        <tr>
+          <td>2</td><td>Feb 20, 2026</td><td>IFSCA/Ord/2026/02</td>
+          <td>Order in the matter of ABC Capital IFSC Pvt Ltd</td>
+          <td><a href="https://ifsca.gov.in/Document/Legal/order-abc20022026.pdf">Download</a></td>
+        </tr>
+      </tbody>
+    </table>"#;
+
+    #[test]
+    fn test_extract_docs_from_listing_fixture() {
+        let docs = extract_docs_from_listing(LISTING_FIXTURE, "https://ifsca.gov.in/Legal/Index/Circulars");
+        assert_eq!(docs.len(), 2);
+        assert_eq!(docs[0].title, "Framework for Global Access in IFSC");
+        assert_eq!(docs[0].unique_id, "IFSCA-CPD/7/2026-Banking");
+        assert_eq!(docs[0].publish_date, "2026-03-06");
+        assert_eq!(docs[0].pdf_url, "https://ifsca.gov.in/Document/Legal/framework-for-global-access06032026.pdf");
+        assert_eq!(docs[1].url, "https://ifsca.gov.in/Document/Legal/order-abc20022026.pdf");
+        assert_eq!(docs[1].publish_date, "2026-02-20");
+    }
+
+    #[test]
+    fn test_locate_columns_without_header() {
+        let columns = locate_columns(&[]);
+        assert_eq!(columns, ListingColumns { date: None, title: None, reference: None });
+        // Without a header the link text is used as the title.
+        let fragment = r#"<table><tbody><tr><td><a href="a.pdf">Press release on IFSC banking units</a></td></tr></tbody></table>"#;
+        let docs = extract_docs_from_listing(fragment, "https://ifsca.gov.in/");
+        assert_eq!(docs[0].title, "Press release on IFSC banking units");
+        assert_eq!(docs[0].url, "https://ifsca.gov.in/a.pdf");
+    }
+}
@@ -0,0 +1,146 @@
+// file: mod_en_in_mca
+// Purpose: Retrieve notifications, circulars and orders published by the Ministry of Corporate
+// Affairs (MCA). Listing rows link to the document store (`/bin/dms/getdocument?mds=...`),
+// which serves the PDF directly; the text is read via `load_pdf_content`.
+
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, OnceLock};
+use log::debug;
+use regex::Regex;
+use scraper::ElementRef;
+use crate::document;
+use crate::document::Document;
+use crate::plugins::regulator_listing::{self, RegulatorSite};
+use crate::utils::{clean_text, get_text_from_element, parse_date_with_formats, to_local_datetime};
+
+pub(crate) const PLUGIN_NAME: &str = "mod_en_in_mca";
+const PUBLISHER_NAME: &str = "Ministry of Corporate Affairs";
+const BASE_URL: &str = "https://www.mca.gov.in/";
+
+/// Date formats seen in the MCA listing tables.
+const DATE_FORMATS: &[&str] = &["%d/%m/%Y", "%d-%m-%Y", "%d %b %Y", "%d-%b-%Y", "%d %B %Y"];
+
+/// Listing pages of this site, retrieved by `regulator_listing::run`.
+const SITE: RegulatorSite = RegulatorSite {
+    plugin_name: PLUGIN_NAME,
+    publisher_name: PUBLISHER_NAME,
+    base_url: BASE_URL,
+    listing_urls: &[
+        ("https://www.mca.gov.in/content/mca/global/en/acts-rules/ebooks/notifications.html", "Notifications"),
+        ("https://www.mca.gov.in/content/mca/global/en/acts-rules/ebooks/circulars.html", "Circulars"),
+        ("https://www.mca.gov.in/content/mca/global/en/acts-rules/ebooks/orders.html", "Orders"),
+        ("https://www.mca.gov.in/content/mca/global/en/acts-rules/ebooks/draft-rules.html", "Draft Rules"),
+    ],
+    extract_docs: extract_docs_from_listing,
+};
+
+/// Executes this function of the module in the separate thread launched by the pipeline/queue module
+///
+/// # Arguments
+///
+/// * `tx`: The channel to transmit newly identified or web scraped documents
+/// * `app_config`: The application configuration object to be used to get various config parameters
+///
+/// returns: ()
+pub(crate) fn run_worker_thread(tx: Sender<document::Document>, app_config: Arc<config::Config>) {
+    regulator_listing::run(tx, app_config, &SITE);
+}
+
+/// Extract one document per row of the MCA listing table. Rows without a link are skipped.
+/// The notification/circular number, when the row has one, becomes the unique_id.
+pub(crate) fn extract_docs_from_listing(content: &str, url_listing_page: &str) -> Vec<Document> {
+    let html_document = scraper::Html::parse_document(content);
+    let rows_selector = scraper::Selector::parse("table tbody tr").unwrap();
+    html_document.select(&rows_selector)
+        .filter_map(|row| extract_docinfo_from_row(row, url_listing_page))
+        .collect()
+}
+
+fn extract_docinfo_from_row(row_each: ElementRef, source_url: &str) -> Option<Document> {
+    let alink_selector = scraper::Selector::parse("a[href]").unwrap();
+    let cell_selector = scraper::Selector::parse("td").unwrap();
+
+    let alink_elem = row_each.select(&alink_selector).next()?;
+    let href = alink_elem.value().attr("href")?;
+    let url = SITE.absolute_url(href)?;
+
+    let mut this_new_doc = SITE.new_document();
+    this_new_doc.title = clean_text(get_text_from_element(alink_elem));
+    this_new_doc.url = url.clone();
+    let lower_url = url.to_lowercase();
+    if lower_url.ends_with(".pdf") || lower_url.contains("/getdocument") {
+        this_new_doc.pdf_url = url;
+    }
+    for cell in row_each.select(&cell_selector) {
+        let cell_text = clean_text(get_text_from_element(cell));
+        if notification_number_regex().is_match(&cell_text) && this_new_doc.unique_id.is_empty() {
+            this_new_doc.unique_id = cell_text.clone();
+            continue;
+        }
+        match parse_date_with_formats(&cell_text, DATE_FORMATS) {
+            Some(naive_date) => {
+                this_new_doc.publish_date_ms = to_local_datetime(naive_date).timestamp();
+                this_new_doc.publish_date = naive_date.format("%Y-%m-%d").to_string();
+            }
+            None => debug!("{}: cell is not a date: '{}'", PLUGIN_NAME, cell_text),
+        }
+    }
+    if this_new_doc.title.is_empty() {
+        return None;
+    }
+    this_new_doc.links_inward = vec![source_url.to_string()];
+    Some(this_new_doc)
+}
+
+/// Matches MCA document numbers such as "G.S.R. 123(E)", "S.O. 4567(E)" or
+/// "General Circular No. 09/2025".
+fn notification_number_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"^(?:G\.S\.R\.|S\.O\.|GSR|SO)\s*\d+\s*\(E\)|^(?:General\s+)?Circular\s+No\.?\s*\d+")
+            .expect("MCA notification number regex")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Representative of the MCA notifications listing table.
+    const LISTING_FIXTURE: &str = r#"
+    <table class="table notification-table">
+      <thead><tr><th>Notification No.</th><th>Subject</th><th>Date</th></tr></thead>
+      <tbody>
+        <tr>
+          <td>G.S.R. 612(E)</td>
+          <td><a href="/bin/dms/getdocument?mds=Xk2%252FqJ7w%253D%253D&amp;type=open">Companies (Accounts) Amendment Rules, 2025</a></td>
+          <td>08/09/2025</td>
+        </tr>
+        <tr>
+          <td>General Circular No. 09/2025</td>
+          <td><a href="https://www.mca.gov.in/bin/dms/getdocument?mds=Qa1&amp;type=open">Clarification on holding of AGM through video conferencing</a></td>
+          <td>22 Sep 2025</td>
+        </tr>
+      </tbody>
+    </table>"#;
+
+    #[test]
+    fn test_extract_docs_from_listing_fixture() {
+        let docs = extract_docs_from_listing(LISTING_FIXTURE, "https://www.mca.gov.in/notifications.html");
+        assert_eq!(docs.len(), 2);
+        assert_eq!(docs[0].title, "Companies (Accounts) Amendment Rules, 2025");
+        assert_eq!(docs[0].unique_id, "G.S.R. 612(E)");
+        assert_eq!(docs[0].publish_date, "2025-09-08");
+        assert_eq!(docs[0].pdf_url, "https://www.mca.gov.in/bin/dms/getdocument?mds=Xk2%252FqJ7w%253D%253D&type=open");
+        assert_eq!(docs[1].unique_id, "General Circular No. 09/2025");
+        assert_eq!(docs[1].publish_date, "2025-09-22");
+        assert_eq!(docs[1].module, PLUGIN_NAME);
+    }
+
+    #[test]
+    fn test_notification_number_regex() {
+        assert!(notification_number_regex().is_match("S.O. 4567(E)"));
+        assert!(notification_number_regex().is_match("GSR 12 (E)"));
+        assert!(!notification_number_regex().is_match("Companies (Accounts) Amendment Rules, 2025"));
+    }
+}
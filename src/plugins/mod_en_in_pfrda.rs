@@ -0,0 +1,129 @@
+// file: mod_en_in_pfrda
+// Purpose: Retrieve circulars, regulations and guidelines published by PFRDA (Pension Fund
+// Regulatory and Development Authority). The listing pages link straight to the PDF of each
+// document, so the PDF is the document: its text is read via `load_pdf_content`.
+
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use log::debug;
+use scraper::ElementRef;
+use crate::document;
+use crate::document::Document;
+use crate::plugins::regulator_listing::{self, RegulatorSite};
+use crate::utils::{clean_text, get_text_from_element, parse_date_with_formats, to_local_datetime};
+
+pub(crate) const PLUGIN_NAME: &str = "mod_en_in_pfrda";
+const PUBLISHER_NAME: &str = "Pension Fund Regulatory and Development Authority";
+const BASE_URL: &str = "https://www.pfrda.org.in/";
+
+/// Date formats seen in the PFRDA listing tables.
+const DATE_FORMATS: &[&str] = &["%d/%m/%Y", "%d-%m-%Y", "%d.%m.%Y", "%d %b %Y"];
+
+/// Listing pages of this site, retrieved by `regulator_listing::run`.
+const SITE: RegulatorSite = RegulatorSite {
+    plugin_name: PLUGIN_NAME,
+    publisher_name: PUBLISHER_NAME,
+    base_url: BASE_URL,
+    listing_urls: &[
+        ("https://www.pfrda.org.in/index1.cshtml?lsid=1177", "Circulars"),
+        ("https://www.pfrda.org.in/index1.cshtml?lsid=1174", "Regulations"),
+        ("https://www.pfrda.org.in/index1.cshtml?lsid=1176", "Guidelines"),
+        ("https://www.pfrda.org.in/index1.cshtml?lsid=1175", "Notifications"),
+        ("https://www.pfrda.org.in/index1.cshtml?lsid=1183", "Exposure Draft"),
+        ("https://www.pfrda.org.in/index1.cshtml?lsid=1190", "Orders"),
+    ],
+    extract_docs: extract_docs_from_listing,
+};
+
+/// Executes this function of the module in the separate thread launched by the pipeline/queue module
+///
+/// # Arguments
+///
+/// * `tx`: The channel to transmit newly identified or web scraped documents
+/// * `app_config`: The application configuration object to be used to get various config parameters
+///
+/// returns: ()
+pub(crate) fn run_worker_thread(tx: Sender<document::Document>, app_config: Arc<config::Config>) {
+    regulator_listing::run(tx, app_config, &SITE);
+}
+
+/// Extract one document per row of the PFRDA listing table. Rows without a link are skipped.
+pub(crate) fn extract_docs_from_listing(content: &str, url_listing_page: &str) -> Vec<Document> {
+    let html_document = scraper::Html::parse_document(content);
+    let rows_selector = scraper::Selector::parse("table tbody tr").unwrap();
+    html_document.select(&rows_selector)
+        .filter_map(|row| extract_docinfo_from_row(row, url_listing_page))
+        .collect()
+}
+
+fn extract_docinfo_from_row(row_each: ElementRef, source_url: &str) -> Option<Document> {
+    let alink_selector = scraper::Selector::parse("a[href]").unwrap();
+    let cell_selector = scraper::Selector::parse("td").unwrap();
+
+    let alink_elem = row_each.select(&alink_selector).next()?;
+    let href = alink_elem.value().attr("href")?;
+    let url = SITE.absolute_url(href)?;
+
+    let mut this_new_doc = SITE.new_document();
+    this_new_doc.title = clean_text(get_text_from_element(alink_elem));
+    this_new_doc.url = url.clone();
+    if url.to_lowercase().ends_with(".pdf") {
+        this_new_doc.pdf_url = url;
+    }
+    for cell in row_each.select(&cell_selector) {
+        let cell_text = clean_text(get_text_from_element(cell));
+        match parse_date_with_formats(&cell_text, DATE_FORMATS) {
+            Some(naive_date) => {
+                this_new_doc.publish_date_ms = to_local_datetime(naive_date).timestamp();
+                this_new_doc.publish_date = naive_date.format("%Y-%m-%d").to_string();
+            }
+            None => debug!("{}: cell is not a date: '{}'", PLUGIN_NAME, cell_text),
+        }
+    }
+    if this_new_doc.title.is_empty() {
+        return None;
+    }
+    this_new_doc.links_inward = vec![source_url.to_string()];
+    Some(this_new_doc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Representative of the PFRDA circulars listing table.
+    const LISTING_FIXTURE: &str = r#"
+    <table class="table table-bordered">
+      <thead><tr><th>S.No.</th><th>Date</th><th>Subject</th></tr></thead>
+      <tbody>
+        <tr>
+          <td>1</td><td>03/10/2025</td>
+          <td><a href="writereaddata/links/Circular%20on%20Multiple%20Scheme%20Framework.pdf" target="_blank">Circular on Multiple Scheme Framework under NPS</a></td>
+        </tr>
+        <tr>
+          <td>2</td><td>19-09-2025</td>
+          <td><a href="/writereaddata/links/Exposure%20Draft%20UPS.pdf">Exposure Draft on Unified Pension Scheme regulations</a></td>
+        </tr>
+        <tr><td colspan="3">No more records</td></tr>
+      </tbody>
+    </table>"#;
+
+    #[test]
+    fn test_extract_docs_from_listing_fixture() {
+        let docs = extract_docs_from_listing(LISTING_FIXTURE, "https://www.pfrda.org.in/index1.cshtml?lsid=1177");
+        assert_eq!(docs.len(), 2, "the row without a link must be skipped");
+        assert_eq!(docs[0].title, "Circular on Multiple Scheme Framework under NPS");
+        assert_eq!(docs[0].url, "https://www.pfrda.org.in/writereaddata/links/Circular%20on%20Multiple%20Scheme%20Framework.pdf");
+        assert_eq!(docs[0].pdf_url, docs[0].url);
+        assert_eq!(docs[0].publish_date, "2025-10-03");
+        assert_eq!(docs[1].publish_date, "2025-09-19");
+        assert_eq!(docs[1].module, PLUGIN_NAME);
+        assert_eq!(docs[1].classification.get("doc_type").map(String::as_str), Some("regulatory-notification"));
+    }
+
+    #[test]
+    fn test_absolute_url() {
+        assert_eq!(SITE.absolute_url("./a/b.pdf"), Some("https://www.pfrda.org.in/a/b.pdf".to_string()));
+        assert_eq!(SITE.absolute_url("https://npscra.nsdl.co.in/x.pdf"), Some("https://npscra.nsdl.co.in/x.pdf".to_string()));
+    }
+}
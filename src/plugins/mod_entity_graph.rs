@@ -2,9 +2,10 @@
 // Purpose:
 //   Phase-2/3 entity co-occurrence graph builder (roadmap Stage 6/7 / D2). For every document
 //   it links the organisations (and persons) that are mentioned together into undirected
-//   co-occurrence edges in `entity_edges`, stamped with the document's date and tone. Aggregating
-//   these edges over the corpus yields the entity-relationship graph that powers
-//   `mod_emit_graph` (GEXF) and downstream network analytics. Runs after `mod_ner` so the
+//   co-occurrence edges in `entity_edges`, stamped with the document's date and tone — the mean
+//   targeted sentiment of the two entities (`EntityMention::sentiment`) where scored, else the
+//   document tone. Aggregating these edges over the corpus yields the entity-relationship graph
+//   that powers `mod_emit_graph` (GEXF) and downstream network analytics. Runs after `mod_ner` so the
 //   organisations are present on `doc.analysis`; writes are batched per N documents in a single
 //   transaction (roadmap point 9).
 
@@ -32,6 +33,21 @@ struct GraphDoc {
     date_ms: i64,
     tone: f64,
     entity_ids: Vec<String>,
+    /// Targeted sentiment of the entities that have one, by entity id.
+    sentiments: HashMap<String, f64>,
+}
+
+impl GraphDoc {
+    /// Edge tone: the mean sentiment of the two entities (or the one scored), else the
+    /// document tone.
+    fn edge_tone(&self, src: &str, dst: &str) -> f64 {
+        let scored: Vec<f64> = [src, dst].iter().filter_map(|id| self.sentiments.get(*id).copied()).collect();
+        if scored.is_empty() {
+            self.tone
+        } else {
+            scored.iter().sum::<f64>() / scored.len() as f64
+        }
+    }
 }
 
 pub fn process_data(
@@ -63,6 +79,7 @@ pub fn process_data(
                         date_ms: doc.publish_date_ms,
                         tone: a.tone.as_ref().map(|t| t.tone).unwrap_or(0.0),
                         entity_ids: ids,
+                        sentiments: sentiments_for(a),
                     });
                     if buffer.len() >= BATCH_SIZE {
                         if let Some(ref mut c) = conn {
@@ -95,6 +112,18 @@ fn entity_ids_for(a: &DocAnalysis) -> Vec<String> {
     ids
 }
 
+/// Targeted sentiment by entity id (the first scored mention of each id).
+fn sentiments_for(a: &DocAnalysis) -> HashMap<String, f64> {
+    let mut out = HashMap::new();
+    for e in a.organizations.iter().chain(a.persons.iter()) {
+        if let Some(s) = e.sentiment {
+            let id = e.entity_id.clone().unwrap_or_else(|| provisional_entity_id(&e.surface_form));
+            out.entry(id).or_insert(s);
+        }
+    }
+    out
+}
+
 /// All unordered pairs from a list of ids, normalised so the lexicographically-smaller id is the
 /// source (keeps the undirected graph canonical and avoids A-B / B-A duplication).
 pub fn unique_pairs(ids: &[String]) -> Vec<(String, String)> {
@@ -125,7 +154,8 @@ fn flush(conn: &mut rusqlite::Connection, buffer: &mut Vec<GraphDoc>) -> usize {
         let tx = conn.transaction().map_err(|e| format!("begin tx: {}", e))?;
         for gd in buffer.iter() {
             for (src, dst) in unique_pairs(&gd.entity_ids) {
-                insert_edge(&tx, &src, &dst, EDGE_TYPE, &gd.doc_id, gd.date_ms, gd.tone, 1.0, PLUGIN_NAME)?;
+                let tone = gd.edge_tone(&src, &dst);
+                insert_edge(&tx, &src, &dst, EDGE_TYPE, &gd.doc_id, gd.date_ms, tone, 1.0, PLUGIN_NAME)?;
                 written += 1;
             }
         }
@@ -156,6 +186,7 @@ mod tests {
             char_offset: 0,
             salience: 1.0,
             entity_id: None,
+            sentiment: None,
         }
     }
 
@@ -192,12 +223,26 @@ mod tests {
                 provisional_entity_id("SEBI surface"),
                 provisional_entity_id("Axis Bank"),
             ],
+            sentiments: HashMap::from([(provisional_entity_id("Axis Bank"), 3.0), (provisional_entity_id("SEBI surface"), 1.0)]),
         }];
         let n = flush(&mut conn, &mut buffer);
         assert_eq!(n, 3, "3 entities -> 3 edges");
         assert!(buffer.is_empty());
         let edges: i64 = conn.query_row("SELECT COUNT(*) FROM entity_edges WHERE edge_type='cooccur'", [], |r| r.get(0)).unwrap();
         assert_eq!(edges, 3);
+        let tones: Vec<f64> = conn
+            .prepare("SELECT tone FROM entity_edges ORDER BY src_entity_id, dst_entity_id").unwrap()
+            .query_map([], |r| r.get(0)).unwrap()
+            .flatten()
+            .collect();
+        // (axis, reserve bank) -> axis only; (axis, sebi) -> mean; (reserve bank, sebi) -> sebi only
+        assert_eq!(tones, vec![3.0, 2.0, 1.0]);
+    }
+
+    #[test]
+    fn test_edge_tone_falls_back_to_document_tone() {
+        let gd = GraphDoc { doc_id: "D1".into(), date_ms: 0, tone: -2.0, entity_ids: Vec::new(), sentiments: HashMap::new() };
+        assert_eq!(gd.edge_tone("a", "b"), -2.0);
     }
 
     #[test]
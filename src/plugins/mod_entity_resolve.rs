@@ -0,0 +1,455 @@
+// file: mod_entity_resolve.rs
+// Purpose:
+//   Entity linking (roadmap Stage 7 / D1). Resolves the organisation mentions `mod_ner` put on
+//   `doc.analysis` to canonical LEI / CIN / ISIN-backed entities in the entity master
+//   (`entities` / `entity_aliases`, loaded by `feed_symbol_master` and `feed_entity_master`).
+//
+//   A mention's candidates are the entities with an open alias matching it, and the kind of
+//   match sets a base confidence:
+//     - exact normalised name                                  0.90
+//     - core name (legal-form words removed on either side)    0.80
+//     - NSE symbol / BSE code, for an upper-case mention       0.65
+//     - acronym, for an upper-case mention                     0.55
+//   Context in the document text adds to it: another alias of the entity appearing in the
+//   text (+0.10), market vocabulary for a listed entity (+0.10), the entity's sector (+0.05),
+//   and, LEI-first, having an LEI (+0.02). The best candidate's id is assigned only when its
+//   confidence reaches `min_confidence` and beats the runner-up by `min_margin`; otherwise
+//   `entity_id` stays `None` — false links are worse than nulls.
+//
+//   Mentions stored earlier under a provisional surface-form id (`name:<norm>`) are
+//   re-resolved by name alone at the end of each run; the ones that resolve are rewritten to
+//   the canonical id in `entity_mentions`, `entity_edges`, `quotes` and `events`, and the
+//   provisional `entities` row is dropped. Runs after `mod_ner` and before `mod_entity_graph` /
+//   `mod_emit_tables`, so new documents are stored with canonical ids directly.
+//
+// Config keys:
+//   min_confidence  - Confidence needed to assign an id (default 0.75).
+//   min_margin      - Lead needed over the second-best candidate (default 0.10).
+
+use std::collections::HashMap;
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+use chrono::Utc;
+use config::Config;
+use log::{error, info};
+use rusqlite::Connection;
+
+use crate::analysis::{norm_name, DocAnalysis};
+use crate::cfg::get_database_filename;
+use crate::document::Document;
+use crate::get_plugin_cfg;
+use crate::store::entity_master::{core_name, derive_missing_aliases, ALIAS_ACRONYM, ALIAS_NAME_CORE};
+use crate::store::securities::{ALIAS_BSE_CODE, ALIAS_NAME, ALIAS_NSE_SYMBOL};
+
+pub const PLUGIN_NAME: &str = "mod_entity_resolve";
+
+const DEFAULT_MIN_CONFIDENCE: f64 = 0.75;
+const DEFAULT_MIN_MARGIN: f64 = 0.10;
+/// Candidate lists cached per normalised mention; the cache is reset beyond this size.
+const CACHE_LIMIT: usize = 50_000;
+/// Words suggesting the text is about a listed company's stock.
+const MARKET_CUES: &[&str] = &[
+    "shares", "stock", "stocks", "nse", "bse", "sensex", "nifty", "listed", "market cap", "scrip",
+];
+
+/// How a mention matched one of an entity's aliases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchKind {
+    Acronym,
+    Symbol,
+    CoreName,
+    Name,
+}
+
+impl MatchKind {
+    fn base_confidence(self) -> f64 {
+        match self {
+            MatchKind::Name => 0.90,
+            MatchKind::CoreName => 0.80,
+            MatchKind::Symbol => 0.65,
+            MatchKind::Acronym => 0.55,
+        }
+    }
+}
+
+/// An entity a mention may refer to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub entity_id: String,
+    pub kind: MatchKind,
+    pub listed: bool,
+    pub has_lei: bool,
+    pub sector: Option<String>,
+    /// The entity's other open aliases (normalised), used as context cues.
+    pub aliases: Vec<String>,
+}
+
+/// True when every letter of `surface` is upper case ("TCS", "HDFC", "M&M").
+fn is_upper(surface: &str) -> bool {
+    surface.chars().any(|c| c.is_alphabetic()) && !surface.chars().any(|c| c.is_lowercase())
+}
+
+/// Entities with an open alias matching `surface`, one per entity with its best match kind.
+pub fn candidates(conn: &Connection, surface: &str) -> Result<Vec<Candidate>, String> {
+    let norm = norm_name(surface);
+    if norm.is_empty() {
+        return Ok(Vec::new());
+    }
+    let core = core_name(surface);
+    let upper = is_upper(surface);
+    let mut stmt = conn
+        .prepare_cached(
+            "SELECT a.entity_id, a.alias_type, a.alias_norm, e.isin, e.lei, e.sector
+             FROM entity_aliases a JOIN entities e ON e.entity_id = a.entity_id
+             WHERE a.valid_to IS NULL AND a.alias_norm IN (?1, ?2)
+               AND COALESCE(e.status, '') <> 'provisional'",
+        )
+        .map_err(|e| format!("prepare candidates: {}", e))?;
+    let rows = stmt
+        .query_map([&norm, &core], |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, String>(1)?,
+                r.get::<_, String>(2)?,
+                r.get::<_, Option<String>>(3)?,
+                r.get::<_, Option<String>>(4)?,
+                r.get::<_, Option<String>>(5)?,
+            ))
+        })
+        .map_err(|e| format!("query candidates: {}", e))?;
+
+    let mut by_entity: HashMap<String, Candidate> = HashMap::new();
+    for (entity_id, alias_type, alias_norm, isin, lei, sector) in rows.flatten() {
+        let kind = match alias_type.as_str() {
+            t if t == ALIAS_NAME && alias_norm == norm => MatchKind::Name,
+            t if t == ALIAS_NAME || t == ALIAS_NAME_CORE => MatchKind::CoreName,
+            t if (t == ALIAS_NSE_SYMBOL || t == ALIAS_BSE_CODE) && upper && alias_norm == norm => MatchKind::Symbol,
+            t if t == ALIAS_ACRONYM && upper && alias_norm == norm => MatchKind::Acronym,
+            _ => continue,
+        };
+        let c = by_entity.entry(entity_id.clone()).or_insert_with(|| Candidate {
+            entity_id,
+            kind,
+            listed: isin.is_some(),
+            has_lei: lei.is_some(),
+            sector: sector.filter(|s| !s.is_empty()),
+            aliases: Vec::new(),
+        });
+        c.kind = c.kind.max(kind);
+    }
+
+    let mut out: Vec<Candidate> = by_entity.into_values().collect();
+    let mut alias_stmt = conn
+        .prepare_cached(
+            "SELECT DISTINCT alias_norm FROM entity_aliases
+             WHERE entity_id=?1 AND valid_to IS NULL AND alias_norm <> ?2 AND LENGTH(alias_norm) >= 3",
+        )
+        .map_err(|e| format!("prepare aliases: {}", e))?;
+    for c in out.iter_mut() {
+        c.aliases = alias_stmt
+            .query_map([&c.entity_id, &norm], |r| r.get(0))
+            .map_err(|e| format!("query aliases({}): {}", c.entity_id, e))?
+            .flatten()
+            .collect();
+    }
+    out.sort_by(|a, b| a.entity_id.cmp(&b.entity_id));
+    Ok(out)
+}
+
+/// Confidence of each candidate given the document text, normalised with `norm_name` and
+/// padded with a space on both sides (" ... "). Sorted best first.
+pub fn score(candidates: &[Candidate], text_norm: &str) -> Vec<(String, f64)> {
+    let contains = |phrase: &str| !phrase.is_empty() && text_norm.contains(&format!(" {} ", phrase));
+    let market = MARKET_CUES.iter().any(|cue| contains(cue));
+    let mut scored: Vec<(String, f64)> = candidates
+        .iter()
+        .map(|c| {
+            let mut confidence = c.kind.base_confidence();
+            if c.aliases.iter().any(|a| contains(a)) {
+                confidence += 0.10;
+            }
+            if c.listed && market {
+                confidence += 0.10;
+            }
+            if let Some(sector) = &c.sector
+                && norm_name(sector).split(' ').any(|w| w.len() >= 5 && contains(w))
+            {
+                confidence += 0.05;
+            }
+            if c.has_lei {
+                confidence += 0.02;
+            }
+            (c.entity_id.clone(), confidence.min(1.0))
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    scored
+}
+
+/// The winning candidate, if it clears `min_confidence` and leads the runner-up by
+/// `min_margin`.
+pub fn pick(scored: &[(String, f64)], min_confidence: f64, min_margin: f64) -> Option<(String, f64)> {
+    let (best, confidence) = scored.first()?;
+    let runner_up = scored.get(1).map(|(_, c)| *c).unwrap_or(0.0);
+    if *confidence >= min_confidence && confidence - runner_up >= min_margin - 1e-9 {
+        Some((best.clone(), *confidence))
+    } else {
+        None
+    }
+}
+
+/// Resolver over the entity master, caching candidate lists per mention.
+pub struct Resolver {
+    conn: Connection,
+    min_confidence: f64,
+    min_margin: f64,
+    cache: HashMap<String, Vec<Candidate>>,
+}
+
+impl Resolver {
+    pub fn new(conn: Connection, min_confidence: f64, min_margin: f64) -> Self {
+        Resolver { conn, min_confidence, min_margin, cache: HashMap::new() }
+    }
+
+    /// Canonical id and confidence for `surface` in a text normalised as for `score`.
+    pub fn resolve(&mut self, surface: &str, text_norm: &str) -> Option<(String, f64)> {
+        let key = norm_name(surface);
+        if !self.cache.contains_key(&key) {
+            if self.cache.len() >= CACHE_LIMIT {
+                self.cache.clear();
+            }
+            let found = candidates(&self.conn, surface).unwrap_or_else(|e| {
+                error!("{}: {}", PLUGIN_NAME, e);
+                Vec::new()
+            });
+            self.cache.insert(key.clone(), found);
+        }
+        let cands = self.cache.get(&key)?;
+        pick(&score(cands, text_norm), self.min_confidence, self.min_margin)
+    }
+
+    /// Assign ids to the unresolved organisation mentions of one document. Returns the number
+    /// resolved.
+    pub fn resolve_document(&mut self, analysis: &mut DocAnalysis, text: &str) -> usize {
+        if analysis.organizations.iter().all(|m| m.entity_id.is_some()) {
+            return 0;
+        }
+        let text_norm = format!(" {} ", norm_name(text));
+        let mut resolved = 0;
+        for m in analysis.organizations.iter_mut().filter(|m| m.entity_id.is_none()) {
+            if let Some((id, _)) = self.resolve(&m.surface_form, &text_norm) {
+                m.entity_id = Some(id);
+                resolved += 1;
+            }
+        }
+        resolved
+    }
+
+    /// Re-resolve provisional organisation entities by name and rewrite their ids in
+    /// `entity_mentions`, `entity_edges`, `quotes` and `events` (see the file header). Returns
+    /// the number of provisional entities rewritten.
+    pub fn rewrite_provisional_ids(&mut self) -> Result<usize, String> {
+        let provisional: Vec<(String, String)> = {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT entity_id, canonical_name FROM entities WHERE status='provisional' AND type='ORG'")
+                .map_err(|e| format!("prepare provisional entities: {}", e))?;
+            let rows = stmt
+                .query_map([], |r| Ok((r.get(0)?, r.get::<_, Option<String>>(1)?.unwrap_or_default())))
+                .map_err(|e| format!("query provisional entities: {}", e))?;
+            rows.flatten().collect()
+        };
+        let rewrites: Vec<(String, String)> = provisional
+            .into_iter()
+            .filter_map(|(old, name)| self.resolve(&name, " ").map(|(new, _)| (old, new)))
+            .collect();
+        if rewrites.is_empty() {
+            return Ok(0);
+        }
+
+        let tx = self.conn.transaction().map_err(|e| format!("begin transaction: {}", e))?;
+        for (old, new) in &rewrites {
+            for sql in [
+                "UPDATE entity_mentions SET entity_id=:new WHERE entity_id=:old",
+                "UPDATE quotes SET speaker_entity_id=:new WHERE speaker_entity_id=:old",
+                "UPDATE events SET actor1=:new WHERE actor1=:old",
+                "UPDATE events SET actor2=:new WHERE actor2=:old",
+                "UPDATE entity_edges SET src_entity_id=:new WHERE src_entity_id=:old",
+                "UPDATE entity_edges SET dst_entity_id=:new WHERE dst_entity_id=:old",
+            ] {
+                tx.execute(sql, rusqlite::named_params! {":old": old, ":new": new})
+                    .map_err(|e| format!("rewrite {} -> {}: {}", old, new, e))?;
+            }
+            // Keep undirected edges canonical (smaller id first) and drop edges that now join
+            // an entity to itself.
+            for sql in [
+                "UPDATE entity_edges SET src_entity_id=dst_entity_id, dst_entity_id=src_entity_id
+                 WHERE (src_entity_id=:new OR dst_entity_id=:new) AND src_entity_id > dst_entity_id",
+                "DELETE FROM entity_edges WHERE src_entity_id=:new AND dst_entity_id=:new",
+            ] {
+                tx.execute(sql, rusqlite::named_params! {":new": new})
+                    .map_err(|e| format!("rewrite {} -> {}: {}", old, new, e))?;
+            }
+            tx.execute("DELETE FROM entities WHERE entity_id=?1 AND status='provisional'", [old])
+                .map_err(|e| format!("delete provisional entity {}: {}", old, e))?;
+        }
+        tx.commit().map_err(|e| format!("commit: {}", e))?;
+        Ok(rewrites.len())
+    }
+}
+
+pub fn process_data(
+    tx: Sender<Document>,
+    rx: Receiver<Document>,
+    config: &Config,
+    _api_mutexes: &mut HashMap<String, Arc<Mutex<isize>>>,
+) {
+    let min_confidence = get_plugin_cfg!(PLUGIN_NAME, "min_confidence", config)
+        .and_then(|v| v.parse::<f64>().ok())
+        .unwrap_or(DEFAULT_MIN_CONFIDENCE);
+    let min_margin = get_plugin_cfg!(PLUGIN_NAME, "min_margin", config)
+        .and_then(|v| v.parse::<f64>().ok())
+        .unwrap_or(DEFAULT_MIN_MARGIN);
+    info!("{}: Starting entity resolution (min_confidence={:.2}, min_margin={:.2}).", PLUGIN_NAME, min_confidence, min_margin);
+
+    let db_path = get_database_filename(config);
+    let mut resolver = match crate::store::open(&db_path) {
+        Ok(conn) => {
+            match derive_missing_aliases(&conn, Utc::now().date_naive()) {
+                Ok(n) if n > 0 => info!("{}: added {} derived alias(es) to the entity master.", PLUGIN_NAME, n),
+                Ok(_) => {}
+                Err(e) => error!("{}: deriving aliases: {}", PLUGIN_NAME, e),
+            }
+            Some(Resolver::new(conn, min_confidence, min_margin))
+        }
+        Err(e) => {
+            error!("{}: cannot open store '{}': {} — forwarding docs unmodified.", PLUGIN_NAME, db_path, e);
+            None
+        }
+    };
+
+    let mut resolved = 0usize;
+    for mut doc in rx {
+        let text = doc.english_text().to_string();
+        if let Some(r) = resolver.as_mut()
+            && let Some(analysis) = doc.analysis.as_mut()
+        {
+            resolved += r.resolve_document(analysis, &text);
+        }
+        if let Err(e) = tx.send(doc) {
+            error!("{}: when forwarding doc: {}", PLUGIN_NAME, e);
+        }
+    }
+
+    let rewritten = match resolver.as_mut().map(|r| r.rewrite_provisional_ids()) {
+        Some(Ok(n)) => n,
+        Some(Err(e)) => {
+            error!("{}: rewriting provisional ids: {}", PLUGIN_NAME, e);
+            crate::metrics::record_db_error();
+            0
+        }
+        None => 0,
+    };
+    info!("{}: Completed. Resolved {} mention(s); rewrote {} provisional entit(ies).", PLUGIN_NAME, resolved, rewritten);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::EntityMention;
+    use crate::store::entity_master::{upsert_reference_entity, ReferenceEntity};
+    use crate::store::securities::{upsert_listing, ListedSecurity};
+    use chrono::NaiveDate;
+
+    fn master() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::store::migrate(&conn).unwrap();
+        let as_of = NaiveDate::from_ymd_opt(2025, 6, 3).unwrap();
+        let listing = ListedSecurity {
+            isin: "INE002A01018".to_string(),
+            name: "Reliance Industries Limited".to_string(),
+            exchange: "NSE".to_string(),
+            symbol: "RELIANCE".to_string(),
+            sector: Some("Refineries & Marketing".to_string()),
+            listing_date: None,
+            canonical_name_source: true,
+        };
+        upsert_listing(&conn, &listing, as_of).unwrap();
+        let rec = |name: &str, cin: &str| ReferenceEntity { name: name.to_string(), cin: Some(cin.to_string()), ..Default::default() };
+        for r in [
+            rec("Sun Pharma Advanced Research Company Limited", "L73100GJ2006PLC047837"),
+            rec("Sun Pharma Laboratories Limited", "U25200MH1997PLC240268"),
+            rec("Sun Pharma Laboratories Private Limited", "U24230GJ1993PTC019050"),
+        ] {
+            upsert_reference_entity(&conn, &r, as_of).unwrap();
+        }
+        derive_missing_aliases(&conn, as_of).unwrap();
+        conn
+    }
+
+    fn text(s: &str) -> String {
+        format!(" {} ", norm_name(s))
+    }
+
+    #[test]
+    fn test_candidates_and_match_kinds() {
+        let conn = master();
+        let kinds = |surface: &str| candidates(&conn, surface).unwrap().into_iter().map(|c| (c.entity_id, c.kind)).collect::<Vec<_>>();
+        assert_eq!(kinds("Reliance Industries Limited"), vec![("isin:INE002A01018".to_string(), MatchKind::Name)]);
+        assert_eq!(kinds("Reliance Industries Ltd"), vec![("isin:INE002A01018".to_string(), MatchKind::CoreName)]);
+        assert_eq!(kinds("RELIANCE"), vec![("isin:INE002A01018".to_string(), MatchKind::Symbol)]);
+        assert!(kinds("Reliance").is_empty(), "lower-case word is not a symbol match");
+        assert_eq!(kinds("SPARC")[0].1, MatchKind::Acronym);
+    }
+
+    #[test]
+    fn test_resolution_thresholds_and_context() {
+        let mut r = Resolver::new(master(), DEFAULT_MIN_CONFIDENCE, DEFAULT_MIN_MARGIN);
+        let plain = text("The company reported results.");
+        assert_eq!(r.resolve("Reliance Industries", &plain).map(|(id, _)| id), Some("isin:INE002A01018".to_string()));
+        // A bare symbol needs context: market vocabulary plus another alias in the text.
+        assert!(r.resolve("RELIANCE", &plain).is_none());
+        let market = text("Reliance Industries Limited shares rose 3% on the NSE; RELIANCE led the Nifty.");
+        assert!(r.resolve("RELIANCE", &market).is_some());
+        // Two entities share the core name: ambiguous, so left unresolved.
+        assert!(r.resolve("Sun Pharma Laboratories", &plain).is_none());
+        // An acronym alone stays below the threshold.
+        assert!(r.resolve("SPARC", &plain).is_none());
+
+        let mut analysis = DocAnalysis::default();
+        analysis.organizations.push(EntityMention { surface_form: "Reliance Industries Ltd".to_string(), entity_type: "ORG".to_string(), ..Default::default() });
+        analysis.organizations.push(EntityMention { surface_form: "Acme Widgets".to_string(), entity_type: "ORG".to_string(), ..Default::default() });
+        assert_eq!(r.resolve_document(&mut analysis, "Reliance Industries Ltd and Acme Widgets signed a deal."), 1);
+        assert_eq!(analysis.organizations[0].entity_id.as_deref(), Some("isin:INE002A01018"));
+        assert_eq!(analysis.organizations[1].entity_id, None);
+    }
+
+    #[test]
+    fn test_rewrite_provisional_ids() {
+        let conn = master();
+        conn.execute_batch(
+            "INSERT INTO entities (entity_id, type, canonical_name, name_norm, status) VALUES
+                ('name:reliance industries ltd', 'ORG', 'Reliance Industries Ltd', 'reliance industries ltd', 'provisional'),
+                ('name:acme widgets', 'ORG', 'Acme Widgets', 'acme widgets', 'provisional');
+             INSERT INTO entity_mentions (doc_id, entity_id, surface_form) VALUES
+                ('d1', 'name:reliance industries ltd', 'Reliance Industries Ltd'), ('d1', 'name:acme widgets', 'Acme Widgets');
+             INSERT INTO entity_edges (src_entity_id, dst_entity_id, edge_type, doc_id) VALUES
+                ('name:acme widgets', 'name:reliance industries ltd', 'cooccur', 'd1'),
+                ('isin:INE002A01018', 'name:reliance industries ltd', 'cooccur', 'd2');",
+        )
+        .unwrap();
+        let mut r = Resolver::new(conn, DEFAULT_MIN_CONFIDENCE, DEFAULT_MIN_MARGIN);
+        assert_eq!(r.rewrite_provisional_ids().unwrap(), 1);
+
+        let conn = &r.conn;
+        let mention: String = conn.query_row("SELECT entity_id FROM entity_mentions WHERE surface_form='Reliance Industries Ltd'", [], |row| row.get(0)).unwrap();
+        assert_eq!(mention, "isin:INE002A01018");
+        let edges: Vec<(String, String)> = conn
+            .prepare("SELECT src_entity_id, dst_entity_id FROM entity_edges").unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?))).unwrap().flatten().collect();
+        assert_eq!(edges, vec![("isin:INE002A01018".to_string(), "name:acme widgets".to_string())], "re-ordered; self-edge dropped");
+        let left: i64 = conn.query_row("SELECT COUNT(*) FROM entities WHERE status='provisional'", [], |row| row.get(0)).unwrap();
+        assert_eq!(left, 1);
+    }
+}
@@ -0,0 +1,293 @@
+// file: mod_events.rs
+// Purpose:
+//   CAMEO-lite event coder (roadmap Stage 5 / D4). Codes actor1–verb–actor2 triples out of
+//   article sentences onto `doc.analysis.events` as `EventRecord`s, which `mod_emit_tables`
+//   persists to the GDELT-style `events` table. Deterministic and dictionary-driven:
+//     * actors are the person / organisation mentions `mod_ner` found, located in each
+//       sentence by their surface form, and recorded by entity id — the canonical id when
+//       `mod_entity_resolve` has set one, else the provisional `name:<norm>` id, which the
+//       resolver later rewrites like any other provisional id;
+//     * the verb dictionary (EVENT_RULES) maps trigger phrases to a CAMEO root category
+//       (`CAMEO_04_CONSULT`, ...) or a regulatory / corporate event type (`REG_PENALTY`,
+//       `REG_LICENCE_CANCELLATION`, `REG_RATE_CHANGE`, `CORP_MERGER`, `CREDIT_DEFAULT`), each
+//       with a Goldstein score (-10..+10) and QuadClass (1 verbal cooperation, 2 material
+//       cooperation, 3 verbal conflict, 4 material conflict). Some rules also need a context
+//       word in the sentence ("cut" is a rate change only next to "repo rate" etc.);
+//     * actor1 is the nearest actor before the trigger and actor2 the nearest after it; in the
+//       passive ("X was fined by Y") the roles swap. A trigger with no actor either side is
+//       not coded.
+//   Earlier rules win where triggers overlap, so the specific regulatory rules come first.
+//   Runs after `mod_ner` / `mod_entity_resolve`.
+
+use std::collections::HashMap;
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+use config::Config;
+use log::{error, info};
+
+use crate::analysis::{provisional_entity_id, EntityMention, EventRecord};
+use crate::document::Document;
+
+pub const PLUGIN_NAME: &str = "mod_events";
+pub const EVENT_RULES_VERSION: &str = "2026.10.1";
+
+const MIN_TEXT_LEN: usize = 40;
+
+/// An event type with its trigger phrases (lowercase, matched on word boundaries), optional
+/// context words that must also appear in the sentence, and its Goldstein score / QuadClass.
+struct EventRule {
+    event_type: &'static str,
+    triggers: &'static [&'static str],
+    context: &'static [&'static str],
+    goldstein: f64,
+    quad_class: u8,
+}
+
+const RATE_WORDS: &[&str] = &["repo rate", "reverse repo", "policy rate", "interest rate", "interest rates", "bank rate", "crr", "slr", "rates"];
+
+const EVENT_RULES: &[EventRule] = &[
+    EventRule { event_type: "REG_LICENCE_CANCELLATION", triggers: &["cancelled the licence", "cancelled the license", "cancelled its licence", "cancelled its license", "revoked the licence", "revoked the license", "revoked its licence", "revoked its license", "cancelled the registration", "cancelled the certificate of registration"], context: &[], goldstein: -8.0, quad_class: 4 },
+    EventRule { event_type: "REG_PENALTY", triggers: &["imposed a penalty", "imposed a monetary penalty", "imposed penalty", "imposed a fine", "levied a penalty", "slapped a penalty", "fined", "penalised", "penalized"], context: &[], goldstein: -5.0, quad_class: 4 },
+    EventRule { event_type: "REG_RATE_CHANGE", triggers: &["raised", "hiked", "cut", "reduced", "lowered", "increased", "slashed"], context: RATE_WORDS, goldstein: 0.0, quad_class: 2 },
+    EventRule { event_type: "CREDIT_DEFAULT", triggers: &["defaulted on", "defaulted", "missed an interest payment", "missed a payment", "failed to repay", "failed to pay"], context: &[], goldstein: -6.0, quad_class: 4 },
+    EventRule { event_type: "CORP_MERGER", triggers: &["acquired", "acquire", "merged with", "merge with", "amalgamated with", "took over", "takes over", "bought"], context: &[], goldstein: 5.0, quad_class: 2 },
+    EventRule { event_type: "CAMEO_17_COERCE", triggers: &["raided", "arrested", "seized", "attached", "froze", "banned", "barred", "debarred"], context: &[], goldstein: -7.0, quad_class: 4 },
+    EventRule { event_type: "CAMEO_16_REDUCE_RELATIONS", triggers: &["suspended", "terminated", "halted", "blacklisted", "cut ties with"], context: &[], goldstein: -4.0, quad_class: 4 },
+    EventRule { event_type: "CAMEO_13_THREATEN", triggers: &["threatened", "threatens"], context: &[], goldstein: -6.0, quad_class: 3 },
+    EventRule { event_type: "CAMEO_12_REJECT", triggers: &["rejected", "refused", "denied", "turned down", "dismissed"], context: &[], goldstein: -4.0, quad_class: 3 },
+    EventRule { event_type: "CAMEO_11_DISAPPROVE", triggers: &["criticised", "criticized", "accused", "blamed", "condemned", "warned"], context: &[], goldstein: -2.0, quad_class: 3 },
+    EventRule { event_type: "CAMEO_10_DEMAND", triggers: &["demanded", "directed", "ordered", "sought"], context: &[], goldstein: -5.0, quad_class: 3 },
+    EventRule { event_type: "CAMEO_09_INVESTIGATE", triggers: &["probed", "investigated", "inspected", "audited", "questioned", "summoned"], context: &[], goldstein: -2.0, quad_class: 3 },
+    EventRule { event_type: "CAMEO_08_YIELD", triggers: &["approved", "allowed", "permitted", "relaxed", "eased", "lifted"], context: &[], goldstein: 5.0, quad_class: 2 },
+    EventRule { event_type: "CAMEO_07_PROVIDE_AID", triggers: &["granted", "disbursed", "sanctioned", "extended a loan", "provided funding"], context: &[], goldstein: 7.0, quad_class: 2 },
+    EventRule { event_type: "CAMEO_06_MATERIAL_COOPERATION", triggers: &["signed", "partnered with", "tied up with", "invested in", "joined hands with"], context: &[], goldstein: 6.0, quad_class: 2 },
+    EventRule { event_type: "CAMEO_05_DIPLOMATIC_COOPERATION", triggers: &["praised", "welcomed", "endorsed", "supported", "backed"], context: &[], goldstein: 3.5, quad_class: 1 },
+    EventRule { event_type: "CAMEO_04_CONSULT", triggers: &["met", "meets", "held talks with", "consulted", "visited", "hosted"], context: &[], goldstein: 1.0, quad_class: 1 },
+    EventRule { event_type: "CAMEO_03_INTEND_COOPERATE", triggers: &["agreed to", "pledged", "promised", "offered", "committed to"], context: &[], goldstein: 4.0, quad_class: 1 },
+];
+
+/// Auxiliaries marking a trigger as passive ("was fined by").
+const PASSIVE_AUX: &[&str] = &["was", "were", "has been", "have been", "had been", "is", "are", "been"];
+
+pub fn process_data(
+    tx: Sender<Document>,
+    rx: Receiver<Document>,
+    _config: &Config,
+    _api_mutexes: &mut HashMap<String, Arc<Mutex<isize>>>,
+) {
+    info!("{}: Starting event coding (rules v{}).", PLUGIN_NAME, EVENT_RULES_VERSION);
+    let mut docs = 0usize;
+    let mut total = 0usize;
+    for mut doc in rx {
+        let text = doc.english_text().to_string();
+        if text.len() >= MIN_TEXT_LEN
+            && let Some(analysis) = doc.analysis.as_mut()
+        {
+            let events = code_events(&text, &analysis.persons, &analysis.organizations);
+            if !events.is_empty() {
+                docs += 1;
+                total += events.len();
+            }
+            analysis.events = events;
+        }
+        if let Err(e) = tx.send(doc) {
+            error!("{}: when forwarding doc: {}", PLUGIN_NAME, e);
+        }
+    }
+    info!("{}: Completed. Coded {} event(s) in {} document(s).", PLUGIN_NAME, total, docs);
+}
+
+// ---------------------------------------------------------------------------
+// Text helpers
+// ---------------------------------------------------------------------------
+
+/// Abbreviations whose trailing period does not end a sentence.
+const ABBREVIATIONS: &[&str] = &["rs", "mr", "mrs", "ms", "dr", "ltd", "co", "no", "vs", "st", "inc", "corp", "pvt"];
+
+/// Byte ranges of the sentences in `text`: split at line breaks, and at '.', '!' or '?'
+/// followed by whitespace unless the period ends an abbreviation or an initial.
+fn sentence_spans(text: &str) -> Vec<(usize, usize)> {
+    let bytes = text.as_bytes();
+    let mut spans = Vec::new();
+    let mut start = 0;
+    for (i, &b) in bytes.iter().enumerate() {
+        let end = match b {
+            b'\n' => Some(i),
+            b'.' | b'!' | b'?' if bytes.get(i + 1).is_none_or(|n| n.is_ascii_whitespace()) => {
+                let word = text[start..i].rsplit(|c: char| !c.is_alphanumeric()).next().unwrap_or_default();
+                let abbreviation = b == b'.' && (ABBREVIATIONS.contains(&word.to_lowercase().as_str()) || (word.len() == 1 && word.chars().all(|c| c.is_uppercase())));
+                (!abbreviation).then_some(i + 1)
+            }
+            _ => None,
+        };
+        if let Some(end) = end {
+            if !text[start..end].trim().is_empty() {
+                spans.push((start, end));
+            }
+            start = i + 1;
+        }
+    }
+    if !text[start..].trim().is_empty() {
+        spans.push((start, text.len()));
+    }
+    spans
+}
+
+fn is_word_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+/// Offsets of every whole-word occurrence of `needle` in `haystack`.
+fn find_all_whole(haystack: &str, needle: &str) -> Vec<usize> {
+    let bytes = haystack.as_bytes();
+    haystack
+        .match_indices(needle)
+        .map(|(i, _)| i)
+        .filter(|&i| {
+            let after = i + needle.len();
+            (i == 0 || !is_word_byte(bytes[i - 1])) && (after >= bytes.len() || !is_word_byte(bytes[after]))
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Coding
+// ---------------------------------------------------------------------------
+
+/// An actor occurrence within a sentence: (start, end, actor id).
+type ActorSpan = (usize, usize, String);
+
+/// Occurrences of the given mentions in `sentence`, longest mention first where they overlap.
+fn actor_spans(sentence: &str, mentions: &[&EntityMention]) -> Vec<ActorSpan> {
+    let mut found: Vec<ActorSpan> = Vec::new();
+    let mut by_length: Vec<&&EntityMention> = mentions.iter().filter(|m| !m.surface_form.trim().is_empty()).collect();
+    by_length.sort_by_key(|m| std::cmp::Reverse(m.surface_form.len()));
+    for m in by_length {
+        let id = m.entity_id.clone().unwrap_or_else(|| provisional_entity_id(&m.surface_form));
+        for start in find_all_whole(sentence, &m.surface_form) {
+            let end = start + m.surface_form.len();
+            if !found.iter().any(|(s, e, _)| start < *e && *s < end) {
+                found.push((start, end, id.clone()));
+            }
+        }
+    }
+    found.sort();
+    found
+}
+
+/// Code the events in `text` whose actors are among the given person / organisation mentions.
+pub fn code_events(text: &str, persons: &[EntityMention], orgs: &[EntityMention]) -> Vec<EventRecord> {
+    let mentions: Vec<&EntityMention> = persons.iter().chain(orgs).collect();
+    let mut out = Vec::new();
+    if mentions.is_empty() {
+        return out;
+    }
+    for (s_start, s_end) in sentence_spans(text) {
+        let sentence = &text[s_start..s_end];
+        let actors = actor_spans(sentence, &mentions);
+        if actors.is_empty() {
+            continue;
+        }
+        let lower = sentence.to_ascii_lowercase();
+        // (start, end, rule) of each trigger, earlier rules first where triggers overlap.
+        let mut hits: Vec<(usize, usize, &EventRule)> = Vec::new();
+        for rule in EVENT_RULES {
+            if !rule.context.is_empty() && !rule.context.iter().any(|c| !find_all_whole(&lower, c).is_empty()) {
+                continue;
+            }
+            for trigger in rule.triggers {
+                for start in find_all_whole(&lower, trigger) {
+                    let end = start + trigger.len();
+                    let inside_actor = actors.iter().any(|(s, e, _)| start < *e && *s < end);
+                    if !inside_actor && !hits.iter().any(|(s, e, _)| start < *e && *s < end) {
+                        hits.push((start, end, rule));
+                    }
+                }
+            }
+        }
+        hits.sort_by_key(|h| h.0);
+
+        for (start, end, rule) in hits {
+            let before = actors.iter().rev().find(|(_, e, _)| *e <= start).map(|a| a.2.clone());
+            let after = actors.iter().find(|(s, _, _)| *s >= end).map(|a| a.2.clone());
+            let head = lower[..start].trim_end();
+            let passive = PASSIVE_AUX.iter().any(|aux| head.ends_with(aux) && head[..head.len() - aux.len()].ends_with(|c: char| !c.is_alphanumeric()));
+            let (actor1, actor2) = if passive {
+                let agent = actors
+                    .iter()
+                    .find(|(s, _, _)| *s >= end && lower[end..*s].split_whitespace().last() == Some("by"))
+                    .map(|a| a.2.clone());
+                (agent, before)
+            } else {
+                (before, after)
+            };
+            if actor1.is_none() && actor2.is_none() {
+                continue;
+            }
+            out.push(EventRecord {
+                event_type: rule.event_type.to_string(),
+                actor1: actor1.unwrap_or_default(),
+                actor2: actor2.unwrap_or_default(),
+                goldstein: Some(rule.goldstein),
+                quad_class: Some(rule.quad_class),
+                char_offset: s_start + start,
+            });
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn org(name: &str, id: Option<&str>) -> EntityMention {
+        EntityMention { surface_form: name.to_string(), entity_type: "ORG".to_string(), entity_id: id.map(str::to_string), ..Default::default() }
+    }
+
+    fn coded(text: &str, orgs: &[EntityMention]) -> Vec<(String, String, String)> {
+        code_events(text, &[], orgs).into_iter().map(|e| (e.event_type, e.actor1, e.actor2)).collect()
+    }
+
+    #[test]
+    fn test_sentence_spans_skip_abbreviations() {
+        let text = "Rs. 5 crore was paid by Mr. R. K. Singh. He left!\nNew line here";
+        let sentences: Vec<&str> = sentence_spans(text).into_iter().map(|(s, e)| text[s..e].trim()).collect();
+        assert_eq!(sentences, vec!["Rs. 5 crore was paid by Mr. R. K. Singh.", "He left!", "New line here"]);
+    }
+
+    #[test]
+    fn test_regulatory_events_active_and_passive() {
+        let orgs = vec![org("Reserve Bank of India", Some("lei:RBI")), org("RBI", Some("lei:RBI")), org("HDFC Bank", None), org("Paytm Payments Bank", None)];
+        let text = "The Reserve Bank of India imposed a monetary penalty of Rs. 1 crore on HDFC Bank. \
+            Paytm Payments Bank was penalised by RBI for KYC lapses. \
+            RBI cut the repo rate by 25 basis points. \
+            The market cut its losses.";
+        assert_eq!(
+            coded(text, &orgs),
+            vec![
+                ("REG_PENALTY".to_string(), "lei:RBI".to_string(), "name:hdfc bank".to_string()),
+                ("REG_PENALTY".to_string(), "lei:RBI".to_string(), "name:paytm payments bank".to_string()),
+                ("REG_RATE_CHANGE".to_string(), "lei:RBI".to_string(), String::new()),
+            ]
+        );
+        let events = code_events(text, &[], &orgs);
+        assert_eq!((events[0].goldstein, events[0].quad_class), (Some(-5.0), Some(4)));
+        assert_eq!(&text[events[0].char_offset..events[0].char_offset + 7], "imposed");
+    }
+
+    #[test]
+    fn test_cameo_events_and_rule_precedence() {
+        let orgs = vec![org("SEBI", None), org("Adani Group", None), org("Tata Steel", None), org("Bhushan Steel", None)];
+        let text = "SEBI barred Adani Group from the market. Tata Steel acquired Bhushan Steel. Tata Steel met analysts.";
+        assert_eq!(
+            coded(text, &orgs),
+            vec![
+                ("CAMEO_17_COERCE".to_string(), "name:sebi".to_string(), "name:adani group".to_string()),
+                ("CORP_MERGER".to_string(), "name:tata steel".to_string(), "name:bhushan steel".to_string()),
+                ("CAMEO_04_CONSULT".to_string(), "name:tata steel".to_string(), String::new()),
+            ]
+        );
+    }
+}
@@ -6,7 +6,9 @@
 //     * amounts  — monetary values, normalised to a base unit (crore=1e7, lakh=1e5,
 //                  million=1e6, billion=1e9, thousand=1e3, trillion=1e12), currency-tagged.
 //     * counts   — "<number> <plural-noun>" quantities (GDELT V1COUNTS analog).
-//     * dates    — calendar dates referenced in the text (distinct from the publish date).
+//     * dates    — calendar dates referenced in the text (distinct from the publish date):
+//                  explicit dates here, and relative / fiscal expressions ("last Friday",
+//                  "Q3FY24", "by March-end") resolved against the publish date by `temporal`.
 //   The canonical-table persistence of these facts is done later by `mod_emit_tables`; this
 //   plugin only enriches `doc.analysis` and forwards the document.
 
@@ -20,6 +22,7 @@ use regex::Regex;
 
 use crate::analysis::{AmountMention, CountMention, DateRef};
 use crate::document::Document;
+use crate::temporal;
 
 pub const PLUGIN_NAME: &str = "mod_extract_quant";
 
@@ -37,10 +40,13 @@ pub fn process_data(
     let mut facts = 0usize;
 
     for mut doc in rx {
-        if doc.text.len() >= MIN_TEXT_LEN {
-            let amounts = extract_amounts(&doc.text);
-            let counts = extract_counts(&doc.text);
-            let dates = extract_dates(&doc.text);
+        let text = doc.english_text();
+        if text.len() >= MIN_TEXT_LEN {
+            let amounts = extract_amounts(text);
+            let counts = extract_counts(text);
+            let mut dates = extract_dates(text);
+            dates.extend(temporal::resolve_expressions(text, temporal::anchor_date(&doc.publish_date, doc.publish_date_ms)));
+            dates.sort_by_key(|d| d.char_offset);
             if !amounts.is_empty() || !counts.is_empty() || !dates.is_empty() {
                 let mut analysis = doc.analysis.take().unwrap_or_default();
                 facts += amounts.len() + counts.len() + dates.len();
@@ -214,6 +220,7 @@ pub fn extract_dates(text: &str) -> Vec<DateRef> {
             month: c[2].parse().unwrap_or(0),
             day: c[3].parse().unwrap_or(0),
             char_offset: c.get(0).map(|m| m.start()).unwrap_or(0),
+            expression: c[0].to_string(),
         });
     }
     for c in dmy_re().captures_iter(text) {
@@ -224,6 +231,7 @@ pub fn extract_dates(text: &str) -> Vec<DateRef> {
                 month,
                 day: c[1].parse().unwrap_or(0),
                 char_offset: c.get(0).map(|m| m.start()).unwrap_or(0),
+                expression: c[0].to_string(),
             });
         }
     }
@@ -235,6 +243,7 @@ pub fn extract_dates(text: &str) -> Vec<DateRef> {
                 month,
                 day: c[2].parse().unwrap_or(0),
                 char_offset: c.get(0).map(|m| m.start()).unwrap_or(0),
+                expression: c[0].to_string(),
             });
         }
     }
@@ -300,5 +309,6 @@ mod tests {
         assert!(d.iter().any(|x| x.year == 2026 && x.month == 6 && x.day == 10));
         assert!(d.iter().any(|x| x.year == 2026 && x.month == 7 && x.day == 1));
         assert!(d.iter().any(|x| x.year == 2026 && x.month == 6 && x.day == 15));
+        assert!(d.iter().any(|x| x.expression == "1 July 2026"));
     }
 }
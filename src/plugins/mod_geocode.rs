@@ -6,24 +6,67 @@
 //   unit-testable; results land on `doc.analysis.locations` and are persisted to the `locations`
 //   table by `mod_emit_tables`.
 //
-//   The embedded gazetteer is a high-precision starter set. The roadmap's full World Cities /
-//   India PIN gazetteer is intended to load via a calamine-backed batch feed into a `geo` table;
-//   that bulk loader is deferred until the data files are available, and `geocode_text` can then
-//   consult that table in addition to this seed list without changing the plugin contract.
+//   The embedded gazetteer is a high-precision seed set; a gazetteer CSV (see `vocab`) can
+//   replace it, and its version is stamped on the `locations` rows it places. When the bulk
+//   gazetteer (`store::geo`, loaded by `feed_gazetteer` from GeoNames and the India Post PIN
+//   directory) is present in the metadata DB, `GeoResolver` also looks up capitalised name runs
+//   there and picks among same-named places ("Aurangabad" in Maharashtra or Bihar, "Hyderabad"
+//   in Telangana or Sindh) by:
+//     - country context: countries of places already resolved in the article, else
+//       `default_country`;
+//     - co-mentioned places: an ADM1 named in the article ("Aurangabad in Bihar"), or a
+//       resolved place within NEAR_KM;
+//     - population and feature rank (capital > ADM1 seat > ADM2 seat > other place).
+//   Unambiguous names are resolved first so they can inform the ambiguous ones. Resolved
+//   mentions carry the feature's GeoNames id / `IN-<PIN>`, ADM1 and ADM2. Places `mod_ner`
+//   already listed (without coordinates) are completed rather than added again, so the two
+//   plugins may run in either order. Seed places keep
+//   priority: a bulk feature matching a seed entry (same country, within SEED_MATCH_KM) takes
+//   its place, and a seed entry without one is kept as is.
+//
+// Config keys:
+//   gazetteer_file   - Gazetteer CSV replacing the embedded one; reloaded when it changes.
+//   default_country  - ISO code favoured when the article names no country (default "IN").
+//   min_population   - Smallest place resolved without supporting context (default 1000).
+//   min_score        - Minimum disambiguation score for a bulk-gazetteer match (default 0.5).
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::mpsc::{Receiver, Sender};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, OnceLock};
 
 use config::Config;
-use log::{error, info};
+use log::{error, info, warn};
+use regex::Regex;
+use rusqlite::Connection;
 
-use crate::analysis::GeoMention;
+use crate::analysis::{norm_name, GeoMention};
+use crate::cfg::get_database_filename;
 use crate::document::Document;
+use crate::get_plugin_cfg;
+use crate::store::geo::{self, GeoFeature};
+use crate::vocab::{self, Gazetteer, VocabSource, Vocabulary};
 
 pub const PLUGIN_NAME: &str = "mod_geocode";
+pub const GAZETTEER_VERSION: &str = "2026.06.1";
 
 const MIN_TEXT_LEN: usize = 40;
+const DEFAULT_COUNTRY: &str = "IN";
+const DEFAULT_MIN_POPULATION: i64 = 1000;
+const DEFAULT_MIN_SCORE: f64 = 0.5;
+/// Foreign places this large resolve without supporting context.
+const MAJOR_CITY_POPULATION: i64 = 1_000_000;
+/// A bulk feature this close to a seed entry of the same country is taken to be it.
+const SEED_MATCH_KM: f64 = 100.0;
+/// Places this close to an already resolved place count as co-mentioned.
+const NEAR_KM: f64 = 200.0;
+/// Longest capitalised name run looked up in the bulk gazetteer, in words.
+const MAX_NAME_WORDS: usize = 4;
+const LOOKUP_CACHE_LIMIT: usize = 50_000;
+/// Capitalised words that are place names somewhere but rarely in news text.
+const NOT_PLACES: &[&str] = &[
+    "january", "february", "march", "april", "may", "june", "july", "august", "september", "october", "november",
+    "december", "monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday", "the", "this", "said",
+];
 
 /// A gazetteer entry: (name, lat, lon, adm1 (state/region), country ISO2).
 struct Place {
@@ -85,19 +128,33 @@ const GAZETTEER: &[Place] = &[
 pub fn process_data(
     tx: Sender<Document>,
     rx: Receiver<Document>,
-    _config: &Config,
+    config: &Config,
     _api_mutexes: &mut HashMap<String, Arc<Mutex<isize>>>,
 ) {
-    info!("{}: Starting geocoding ({} gazetteer entries).", PLUGIN_NAME, GAZETTEER.len());
+    let mut gazetteer = VocabSource::new(PLUGIN_NAME, get_plugin_cfg!(PLUGIN_NAME, "gazetteer_file", config), default_gazetteer().clone());
+    let mut resolver = GeoResolver::open(&get_database_filename(config), config);
+    info!(
+        "{}: Starting geocoding ({} gazetteer entries, v{}; {} bulk gazetteer features).",
+        PLUGIN_NAME,
+        gazetteer.get().places.len(),
+        gazetteer.get().version,
+        resolver.as_ref().map_or(0, |r| r.features)
+    );
     let mut docs = 0usize;
     let mut hits = 0usize;
     for mut doc in rx {
-        if doc.text.len() >= MIN_TEXT_LEN {
-            let locs = geocode_text(&doc.text);
+        gazetteer.refresh();
+        let text = doc.english_text();
+        if text.len() >= MIN_TEXT_LEN {
+            let (locs, version) = match resolver.as_mut() {
+                Some(r) => (r.geocode(gazetteer.get(), text), format!("{}+geo:{}", gazetteer.get().version, r.features)),
+                None => (geocode_text_with(gazetteer.get(), text), gazetteer.get().version.clone()),
+            };
             if !locs.is_empty() {
                 let mut analysis = doc.analysis.take().unwrap_or_default();
                 hits += locs.len();
-                analysis.locations.extend(locs);
+                merge_locations(&mut analysis.locations, locs);
+                analysis.vocab_versions.insert(Gazetteer::KIND.to_string(), version);
                 doc.analysis = Some(analysis);
                 docs += 1;
             }
@@ -109,33 +166,67 @@ pub fn process_data(
     info!("{}: Completed. Geocoded {} place mention(s) across {} document(s).", PLUGIN_NAME, hits, docs);
 }
 
+/// Add geocoded places to a document's locations. A place already listed under the same name
+/// is kept once: an entry without coordinates (from `mod_ner`) is replaced by the geocoded
+/// one, keeping the earlier offset, and a geocoded entry is left as it is.
+pub fn merge_locations(locations: &mut Vec<GeoMention>, found: Vec<GeoMention>) {
+    for mut loc in found {
+        let key = norm_name(&loc.name);
+        match locations.iter_mut().find(|l| norm_name(&l.name) == key) {
+            Some(existing) if existing.lat.is_none() => {
+                loc.char_offset = loc.char_offset.min(existing.char_offset);
+                *existing = loc;
+            }
+            Some(_) => {}
+            None => locations.push(loc),
+        }
+    }
+}
+
+/// The embedded gazetteer.
+pub fn default_gazetteer() -> &'static Gazetteer {
+    static GAZ: OnceLock<Gazetteer> = OnceLock::new();
+    GAZ.get_or_init(|| Gazetteer {
+        version: GAZETTEER_VERSION.to_string(),
+        places: GAZETTEER
+            .iter()
+            .map(|p| vocab::Place { name: p.name.into(), lat: p.lat, lon: p.lon, adm1: p.adm1.into(), country: p.country.into() })
+            .collect(),
+    })
+}
+
+/// Geocode `text` with the embedded gazetteer (see `geocode_text_with`).
+pub fn geocode_text(text: &str) -> Vec<GeoMention> {
+    geocode_text_with(default_gazetteer(), text)
+}
+
 /// Resolve gazetteer place names appearing as whole words in `text`. Each place is reported at
 /// most once (earliest offset). Matching is case-insensitive and boundary-aware so "London" does
 /// not match inside "Londonderry".
-pub fn geocode_text(text: &str) -> Vec<GeoMention> {
+pub fn geocode_text_with(gazetteer: &Gazetteer, text: &str) -> Vec<GeoMention> {
     let lower = text.to_lowercase();
     let bytes = lower.as_bytes();
 
     // Prefer longer names first so multi-word places win over any shorter overlap.
-    let mut order: Vec<&Place> = GAZETTEER.iter().collect();
+    let mut order: Vec<&vocab::Place> = gazetteer.places.iter().collect();
     order.sort_by_key(|p| std::cmp::Reverse(p.name.len()));
 
     let mut out: Vec<GeoMention> = Vec::new();
     let mut seen: Vec<&str> = Vec::new();
     for p in order {
-        if seen.contains(&p.name) {
+        if seen.contains(&p.name.as_str()) {
             continue;
         }
         let needle = p.name.to_lowercase();
         if let Some(off) = find_whole_word(&lower, bytes, &needle) {
-            seen.push(p.name);
+            seen.push(&p.name);
             out.push(GeoMention {
-                name: p.name.to_string(),
+                name: p.name.clone(),
                 feature_id: Some(format!("{}/{}", p.country, p.name.replace(' ', "_"))),
                 lat: Some(p.lat),
                 lon: Some(p.lon),
-                country: p.country.to_string(),
-                adm1: p.adm1.to_string(),
+                country: p.country.clone(),
+                adm1: p.adm1.clone(),
                 adm2: String::new(),
                 char_offset: off,
             });
@@ -145,6 +236,260 @@ pub fn geocode_text(text: &str) -> Vec<GeoMention> {
     out
 }
 
+/// A possible reading of a place-name span.
+struct Candidate {
+    feature: GeoFeature,
+    /// From the seed gazetteer (or the bulk feature matching a seed entry).
+    curated: bool,
+}
+
+/// A place name found in the text with its possible readings.
+struct Span {
+    start: usize,
+    end: usize,
+    candidates: Vec<Candidate>,
+}
+
+/// What the article has established so far: countries, ADM1 names and points of the places
+/// resolved.
+#[derive(Default)]
+struct Context {
+    countries: HashSet<String>,
+    adm1s: HashSet<String>,
+    points: Vec<(f64, f64)>,
+}
+
+impl Context {
+    fn add(&mut self, f: &GeoFeature) {
+        if !f.country.is_empty() {
+            self.countries.insert(f.country.clone());
+        }
+        if !f.adm1.is_empty() {
+            self.adm1s.insert(norm_name(&f.adm1));
+        }
+        if let (Some(lat), Some(lon)) = (f.lat, f.lon) {
+            self.points.push((lat, lon));
+        }
+    }
+}
+
+/// Geocoder over the seed gazetteer and the bulk gazetteer tables.
+pub struct GeoResolver {
+    conn: Connection,
+    /// Features in the bulk gazetteer when it was opened.
+    pub features: i64,
+    default_country: String,
+    min_population: i64,
+    min_score: f64,
+    cache: HashMap<String, Vec<GeoFeature>>,
+}
+
+impl GeoResolver {
+    pub fn new(conn: Connection, default_country: &str, min_population: i64, min_score: f64) -> Self {
+        let features = geo::feature_count(&conn);
+        GeoResolver { conn, features, default_country: default_country.to_string(), min_population, min_score, cache: HashMap::new() }
+    }
+
+    /// Resolver over the metadata DB's bulk gazetteer, or None if it is unavailable or empty
+    /// (the seed gazetteer is then used alone).
+    fn open(db_path: &str, config: &Config) -> Option<Self> {
+        let conn = match crate::store::open(db_path) {
+            Ok(c) => c,
+            Err(e) => {
+                warn!("{}: cannot open store '{}': {} — using the seed gazetteer only.", PLUGIN_NAME, db_path, e);
+                return None;
+            }
+        };
+        let number = |key: &str| get_plugin_cfg!(PLUGIN_NAME, key, config).and_then(|v| v.parse::<f64>().ok());
+        let resolver = GeoResolver::new(
+            conn,
+            &get_plugin_cfg!(PLUGIN_NAME, "default_country", config).unwrap_or(DEFAULT_COUNTRY.to_string()),
+            number("min_population").map_or(DEFAULT_MIN_POPULATION, |v| v as i64),
+            number("min_score").unwrap_or(DEFAULT_MIN_SCORE),
+        );
+        (resolver.features > 0).then_some(resolver)
+    }
+
+    /// Bulk gazetteer features named `name`, most populous first.
+    fn lookup(&mut self, name: &str) -> Vec<GeoFeature> {
+        let key = norm_name(name);
+        if let Some(found) = self.cache.get(&key) {
+            return found.clone();
+        }
+        if self.cache.len() >= LOOKUP_CACHE_LIMIT {
+            self.cache.clear();
+        }
+        let found = geo::lookup(&self.conn, name).unwrap_or_else(|e| {
+            error!("{}: {}", PLUGIN_NAME, e);
+            Vec::new()
+        });
+        self.cache.insert(key, found.clone());
+        found
+    }
+
+    /// Seed gazetteer matches, each with the bulk features of the same name as alternative
+    /// readings.
+    fn seed_spans(&mut self, gazetteer: &Gazetteer, text: &str) -> Vec<Span> {
+        let mut spans = Vec::new();
+        for m in geocode_text_with(gazetteer, text) {
+            let mut candidates: Vec<Candidate> =
+                self.lookup(&m.name).into_iter().map(|feature| Candidate { feature, curated: false }).collect();
+            let seed_point = m.lat.zip(m.lon);
+            let same = candidates.iter_mut().find(|c| {
+                c.feature.country == m.country
+                    && seed_point.zip(c.feature.lat.zip(c.feature.lon)).is_some_and(|(a, b)| distance_km(a, b) <= SEED_MATCH_KM)
+            });
+            match same {
+                Some(c) => c.curated = true,
+                None => candidates.push(Candidate {
+                    feature: GeoFeature {
+                        feature_id: m.feature_id.clone().unwrap_or_default(),
+                        name: m.name.clone(),
+                        lat: m.lat,
+                        lon: m.lon,
+                        country: m.country.clone(),
+                        adm1: m.adm1.clone(),
+                        ..Default::default()
+                    },
+                    curated: true,
+                }),
+            }
+            spans.push(Span { start: m.char_offset, end: m.char_offset + m.name.len(), candidates });
+        }
+        spans
+    }
+
+    /// Runs of capitalised words outside `taken` found in the bulk gazetteer, longest name
+    /// first ("Navi Mumbai" before "Mumbai").
+    fn name_spans(&mut self, text: &str, taken: &[Span]) -> Vec<Span> {
+        static WORD: OnceLock<Regex> = OnceLock::new();
+        let word = WORD.get_or_init(|| Regex::new(r"\p{Lu}[\p{L}\p{M}'’-]*").expect("capitalised word regex"));
+        let mut runs: Vec<Vec<(usize, usize)>> = Vec::new();
+        for m in word.find_iter(text) {
+            match runs.last_mut() {
+                Some(run) if &text[run[run.len() - 1].1..m.start()] == " " => run.push((m.start(), m.end())),
+                _ => runs.push(vec![(m.start(), m.end())]),
+            }
+        }
+        let mut spans = Vec::new();
+        for run in runs {
+            let mut i = 0;
+            while i < run.len() {
+                let mut step = 1;
+                for n in (1..=MAX_NAME_WORDS.min(run.len() - i)).rev() {
+                    let (start, end) = (run[i].0, run[i + n - 1].1);
+                    let surface = &text[start..end];
+                    if taken.iter().any(|s| start < s.end && s.start < end)
+                        || n == 1 && (surface.chars().all(|c| !c.is_lowercase()) || NOT_PLACES.contains(&surface.to_lowercase().as_str()))
+                    {
+                        continue;
+                    }
+                    let found = self.lookup(surface);
+                    if !found.is_empty() {
+                        let candidates = found.into_iter().map(|feature| Candidate { feature, curated: false }).collect();
+                        spans.push(Span { start, end, candidates });
+                        step = n;
+                        break;
+                    }
+                }
+                i += step;
+            }
+        }
+        spans
+    }
+
+    /// Score a candidate in context; the flag is whether a co-mentioned place supports it.
+    fn score(&self, c: &Candidate, ctx: &Context) -> (f64, bool) {
+        let f = &c.feature;
+        let mut score = ((f.population.max(0) + 1) as f64).log10() / 7.0;
+        score += match f.feature_code.as_str() {
+            "PPLC" | "PCLI" => 0.3,
+            "PPLA" | "ADM1" => 0.2,
+            "PPLA2" | "ADM2" => 0.1,
+            _ => 0.0,
+        };
+        if c.curated {
+            score += 0.3;
+        }
+        if ctx.countries.contains(&f.country) {
+            score += 0.3;
+        } else if f.country == self.default_country {
+            score += 0.2;
+        }
+        let in_adm1 = !f.adm1.is_empty() && ctx.adm1s.contains(&norm_name(&f.adm1));
+        let near = f.lat.zip(f.lon).is_some_and(|p| ctx.points.iter().any(|q| distance_km(p, *q) <= NEAR_KM));
+        if in_adm1 {
+            score += 0.4;
+        }
+        if near {
+            score += 0.2;
+        }
+        (score, in_adm1 || near)
+    }
+
+    /// Whether the best-scoring reading of a span is good enough to report.
+    fn accept(&self, c: &Candidate, score: f64, supported: bool, ctx: &Context) -> bool {
+        let f = &c.feature;
+        let in_country = ctx.countries.contains(&f.country) || f.country == self.default_country;
+        c.curated
+            || score >= self.min_score
+                && (supported || in_country && f.population >= self.min_population || f.population >= MAJOR_CITY_POPULATION)
+    }
+
+    /// Resolve the places mentioned in `text`: seed gazetteer names and capitalised names found
+    /// in the bulk gazetteer, each disambiguated by the places resolved before it (unambiguous
+    /// names first). Each place is reported once, at its earliest offset.
+    pub fn geocode(&mut self, gazetteer: &Gazetteer, text: &str) -> Vec<GeoMention> {
+        let mut spans = self.seed_spans(gazetteer, text);
+        let names = self.name_spans(text, &spans);
+        spans.extend(names);
+        spans.sort_by_key(|s| (s.candidates.len(), s.start));
+
+        let mut ctx = Context::default();
+        let mut out: Vec<GeoMention> = Vec::new();
+        for span in &spans {
+            let mut scored: Vec<(f64, bool, &Candidate)> = span
+                .candidates
+                .iter()
+                .map(|c| {
+                    let (score, supported) = self.score(c, &ctx);
+                    (score, supported, c)
+                })
+                .collect();
+            scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+            let Some(&(score, supported, best)) = scored.first() else { continue };
+            if !self.accept(best, score, supported, &ctx) {
+                continue;
+            }
+            let f = &best.feature;
+            ctx.add(f);
+            match out.iter_mut().find(|m| m.feature_id.as_deref() == Some(f.feature_id.as_str())) {
+                Some(m) => m.char_offset = m.char_offset.min(span.start),
+                None => out.push(GeoMention {
+                    name: f.name.clone(),
+                    feature_id: Some(f.feature_id.clone()),
+                    lat: f.lat,
+                    lon: f.lon,
+                    country: f.country.clone(),
+                    adm1: f.adm1.clone(),
+                    adm2: f.adm2.clone(),
+                    char_offset: span.start,
+                }),
+            }
+        }
+        out.sort_by_key(|m| m.char_offset);
+        out
+    }
+}
+
+/// Great-circle distance between two (lat, lon) points.
+fn distance_km(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (lat1, lat2) = (a.0.to_radians(), b.0.to_radians());
+    let (dlat, dlon) = (lat2 - lat1, (b.1 - a.1).to_radians());
+    let h = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * 6371.0 * h.sqrt().asin()
+}
+
 fn find_whole_word(haystack: &str, bytes: &[u8], needle: &str) -> Option<usize> {
     let nlen = needle.len();
     let mut start = 0;
@@ -209,6 +554,122 @@ mod tests {
         assert_eq!(g.iter().filter(|m| m.name == "Mumbai").count(), 1);
     }
 
+    #[test]
+    fn test_ner_and_geocode_in_either_order_list_each_place_once() {
+        use crate::analysis::{DocAnalysis, EntityMention};
+        use crate::plugins::mod_ner::RecognisedEntities;
+
+        let text = "The RBI headquarters in Mumbai issued a circular; Navi Mumbai offices reacted.";
+        let recognised = || RecognisedEntities {
+            locations: ["Mumbai", "Navi Mumbai"]
+                .iter()
+                .map(|name| EntityMention {
+                    surface_form: name.to_string(),
+                    entity_type: "LOC".to_string(),
+                    char_offset: text.find(name).unwrap(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        let mut geocode_first = DocAnalysis::default();
+        merge_locations(&mut geocode_first.locations, geocode_text(text));
+        recognised().apply_to(&mut geocode_first);
+        let mut ner_first = DocAnalysis::default();
+        recognised().apply_to(&mut ner_first);
+        merge_locations(&mut ner_first.locations, geocode_text(text));
+
+        for analysis in [&geocode_first, &ner_first] {
+            let mut names: Vec<&str> = analysis.locations.iter().map(|l| l.name.as_str()).collect();
+            names.sort();
+            assert_eq!(names, vec!["Mumbai", "Navi Mumbai"]);
+            let mumbai = analysis.locations.iter().find(|l| l.name == "Mumbai").unwrap();
+            assert_eq!((mumbai.country.as_str(), mumbai.char_offset), ("IN", text.find("Mumbai").unwrap()));
+        }
+    }
+
+    #[test]
+    fn test_loaded_gazetteer() {
+        let csv = "# version: geo-1\nname,lat,lon,adm1,country\nThane,19.2183,72.9781,Maharashtra,IN\n";
+        let gaz = Gazetteer::parse(csv, vocab::Format::Csv, "places").unwrap().vocab;
+        let g = geocode_text_with(&gaz, "Flooding in Thane and Mumbai disrupted suburban rail services.");
+        assert_eq!(g.iter().map(|m| m.name.as_str()).collect::<Vec<_>>(), vec!["Thane"]);
+        assert_eq!(g[0].feature_id.as_deref(), Some("IN/Thane"));
+    }
+
+    fn resolver() -> GeoResolver {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::store::migrate(&conn).unwrap();
+        let place = |id: &str, name: &str, (lat, lon): (f64, f64), code: &str, country: &str, adm1: &str, adm2: &str, population: i64| GeoFeature {
+            feature_id: id.into(),
+            name: name.into(),
+            lat: Some(lat),
+            lon: Some(lon),
+            feature_code: code.into(),
+            country: country.into(),
+            adm1: adm1.into(),
+            adm2: adm2.into(),
+            population,
+            source: "geonames".into(),
+        };
+        for f in [
+            place("1275339", "Mumbai", (19.07283, 72.88261), "PPLA", "IN", "Maharashtra", "Mumbai", 12_691_836),
+            place("1278149", "Aurangabad", (19.87757, 75.34226), "PPLA2", "IN", "Maharashtra", "Aurangabad", 1_175_116),
+            place("1278148", "Aurangabad", (24.75204, 84.3742), "PPLA2", "IN", "Bihar", "Aurangabad", 95_929),
+            place("1271439", "Gaya", (24.79686, 85.00385), "PPLA2", "IN", "Bihar", "Gaya", 463_454),
+            place("1275715", "Bihar", (25.5, 85.5), "ADM1", "IN", "Bihar", "", 104_099_452),
+            place("1176734", "Hyderabad", (25.39242, 68.37366), "PPLA2", "PK", "Sindh", "Hyderabad", 1_386_330),
+            place("1174872", "Karachi", (24.8608, 67.0104), "PPLA", "PK", "Sindh", "Karachi", 11_624_219),
+        ] {
+            geo::upsert_feature(&conn, &f, &[]).unwrap();
+        }
+        GeoResolver::new(conn, "IN", DEFAULT_MIN_POPULATION, DEFAULT_MIN_SCORE)
+    }
+
+    fn resolved(text: &str) -> Vec<(String, String, String)> {
+        resolver()
+            .geocode(default_gazetteer(), text)
+            .into_iter()
+            .map(|m| (m.feature_id.unwrap_or_default(), m.country, m.adm1))
+            .collect()
+    }
+
+    #[test]
+    fn test_co_mentioned_adm1_disambiguates() {
+        let g = resolved("Floods hit Aurangabad in Bihar; relief reached Gaya by the evening.");
+        let ids: Vec<&str> = g.iter().map(|(id, _, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["1278148", "1275715", "1271439"]);
+        assert_eq!(g[0].2, "Bihar");
+    }
+
+    #[test]
+    fn test_population_and_default_country_without_context() {
+        let mut r = resolver();
+        let g = r.geocode(default_gazetteer(), "Auto makers in Aurangabad reported higher dispatches this month.");
+        assert_eq!(g.len(), 1);
+        assert_eq!((g[0].feature_id.as_deref(), g[0].adm1.as_str(), g[0].adm2.as_str()), (Some("1278149"), "Maharashtra", "Aurangabad"));
+    }
+
+    #[test]
+    fn test_country_context_overrides_seed() {
+        // The seed list has Hyderabad (Telangana); Karachi puts the article in Sindh.
+        let g = resolved("Protests in Karachi spread to Hyderabad as traders shut their shops.");
+        assert_eq!(
+            g,
+            vec![("1174872".into(), "PK".into(), "Sindh".into()), ("1176734".into(), "PK".into(), "Sindh".into())]
+        );
+    }
+
+    #[test]
+    fn test_seed_places_kept_and_matched() {
+        let g = resolved("Mumbai and London markets opened higher after the RBI policy announcement.");
+        assert_eq!(
+            g,
+            vec![("1275339".into(), "IN".into(), "Maharashtra".into()), ("GB/London".into(), "GB".into(), "England".into())]
+        );
+    }
+
     #[test]
     fn test_no_match_neutral_text() {
         assert!(geocode_text("The committee reviewed the quarterly report and adjourned the meeting.").is_empty());
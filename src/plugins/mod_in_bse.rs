@@ -1,151 +1,336 @@
 // file: mod_in_bse.rs
-// Download BSE (Bombay Stock Exchange) equity bhavcopy and push it through the pipeline.
+// Retrieve exchange notices/circulars and listed-company corporate announcements from BSE
+// (Bombay Stock Exchange).
 //
-// As of the SEBI "Common Equity Bhavcopy" (UDiFF) rollout, BSE publishes the daily
-// equity bhavcopy as a *plain, uncompressed CSV* (not a ZIP) at:
-//   https://www.bseindia.com/download/BhavCopy/Equity/BhavCopy_BSE_CM_0_0_0_{YYYYMMDD}_F_0000.CSV
-// The older  EQ{DD}{MM}{YY}_CSV.ZIP  endpoint was discontinued and now returns an HTML
-// error page (the cause of the recurring "returned an HTML page instead of a zip" logs).
+// Corporate announcements come from the JSON API at api.bseindia.com, which, like NSE, answers
+// only with the session cookies and Referer of www.bseindia.com; the landing pages are loaded
+// first and again whenever an API call returns HTML instead of JSON. Each announcement carries
+// the company's BSE scrip code in `classification["scrip_code"]`, and its attachment PDF under
+// /xml-data/corpfiling/AttachLive/ is downloaded and read. Exchange notices are listed on an
+// HTML page whose notice numbers ("20251003-12") start with the notice date.
 //
-// The current day's file does not exist until after market close, and no file is
-// published on weekends/holidays, so we walk back over recent business days and use the
-// most recent one that returns CSV data.
+// The equity bhavcopy download that used to live here is the batch feed
+// src/feeds/feed_bse_bhavcopy.rs.
 
+use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 use std::sync::mpsc::Sender;
 
-use chrono::{NaiveDate, Utc};
+use chrono::{Duration, NaiveDate, Utc};
 use log::{error, info, warn};
+use serde_json::Value;
 
-use crate::cfg::get_database_filename;
+use crate::{document, get_plugin_cfg};
+use crate::cfg::{get_data_folder, get_database_filename, get_pdf_data_folder};
 use crate::document::Document;
-use crate::network::{http_get_binary, make_http_client, read_network_parameters};
-use crate::utils::{get_urls_from_database, recent_business_days};
+use crate::network::{establish_session, http_get, http_get_json_with_session, make_http_client, read_network_parameters};
+use crate::plugins::mod_in_nse::{announcement_category, json_records, json_str, load_attachment};
+use crate::utils::{clean_text, get_text_from_element, get_urls_from_database, make_unique_filename, parse_date_with_formats, to_local_datetime};
 
 pub(crate) const PLUGIN_NAME: &str = "mod_in_bse";
 const PUBLISHER_NAME: &str = "Bombay Stock Exchange";
 const BASE_URL: &str = "https://www.bseindia.com/";
-// BSE serves the bhavcopy only after market close; check this many recent business
-// days (most recent first) to tolerate weekends, holidays, and early-in-day runs.
-const LOOKBACK_BUSINESS_DAYS: usize = 5;
-
-/// Build the BSE UDiFF equity bhavcopy CSV URL for a given date.
-fn bhavcopy_url_for(date: NaiveDate) -> String {
-    let date_compact = date.format("%Y%m%d").to_string();
-    format!(
-        "{}download/BhavCopy/Equity/BhavCopy_BSE_CM_0_0_0_{}_F_0000.CSV",
-        BASE_URL, date_compact
-    )
-}
-
-/// Returns true if the downloaded bytes look like a real bhavcopy CSV rather than an
-/// HTML error page or an empty body.
-fn looks_like_csv(bytes: &[u8]) -> bool {
-    if bytes.is_empty() {
-        return false;
-    }
-    // HTML error pages start with '<' (e.g. "<!DOCTYPE", "<html").
-    if bytes.first() == Some(&b'<') {
-        return false;
-    }
-    // The UDiFF bhavcopy header begins with "TradDt,". Accept any comma-delimited text as
-    // a fallback in case the header layout changes.
-    let prefix = &bytes[..bytes.len().min(64)];
-    let prefix_str = String::from_utf8_lossy(prefix);
-    prefix_str.starts_with("TradDt") || prefix_str.contains(',')
-}
+const API_URL: &str = "https://api.bseindia.com/BseIndiaAPI/api/";
+/// Folder from which announcement attachments are served.
+const ATTACHMENT_URL: &str = "https://www.bseindia.com/xml-data/corpfiling/AttachLive/";
+const NOTICES_URL: &str = "https://www.bseindia.com/markets/MarketInfo/NoticesCirculars.aspx";
+/// Pages loaded to obtain the session cookies required by the API.
+const SESSION_PAGES: &[&str] = &[
+    "https://www.bseindia.com/",
+    "https://www.bseindia.com/corporates/ann.html",
+];
+/// Days of announcements requested from the API, unless configured otherwise.
+const DEFAULT_LOOKBACK_DAYS: i64 = 3;
+/// Date formats used in the BSE API ("2025-10-03T18:47:12.1").
+const DATE_FORMATS: &[&str] = &["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S", "%d/%m/%Y", "%Y%m%d"];
 
 pub(crate) fn run_worker_thread(tx: Sender<Document>, app_config: Arc<config::Config>) {
     info!("{}: Starting plugin.", PLUGIN_NAME);
 
+    let database_filename = get_database_filename(&app_config);
+    let data_folder = get_data_folder(&app_config);
+    let data_folder_str = data_folder.to_str().unwrap_or("data").to_string();
+    let pdf_folder = get_pdf_data_folder(&app_config);
+    let pdf_folder_str = pdf_folder.to_str().unwrap_or("data/master_data").to_string();
+
     let mut netw_params = read_network_parameters(&app_config);
     netw_params.referrer_url = Some(BASE_URL.to_string());
     let client = make_http_client(&netw_params);
 
-    let database_filename = get_database_filename(&app_config);
-    let already_retrieved = get_urls_from_database(&database_filename, PLUGIN_NAME);
+    if establish_session(SESSION_PAGES, &client, netw_params.wait_time_min) {
+        info!("{}: Established BSE session via landing page.", PLUGIN_NAME);
+    } else {
+        warn!("{}: Could not load BSE landing page to establish session cookies.", PLUGIN_NAME);
+    }
 
-    // BSE requires a desktop browser User-Agent to serve the bhavcopy file.
-    let bhavcopy_client = reqwest::blocking::Client::builder()
-        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
-        .timeout(std::time::Duration::from_secs(60))
-        .build()
-        .unwrap_or_else(|_| client.clone());
+    let max_pages: u64 = get_plugin_cfg!(PLUGIN_NAME, "max_pages", &app_config)
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(5);
+    let lookback_days = get_plugin_cfg!(PLUGIN_NAME, "lookback_days", &app_config)
+        .and_then(|v| v.parse::<i64>().ok())
+        .map_or(DEFAULT_LOOKBACK_DAYS, |n| n.max(0));
+    let to_date = Utc::now().date_naive();
+    let from_date = to_date - Duration::days(lookback_days);
 
-    let candidate_days = recent_business_days(Utc::now().date_naive(), LOOKBACK_BUSINESS_DAYS);
+    let mut already_retrieved_urls = get_urls_from_database(&database_filename, PLUGIN_NAME);
+    info!("{}: Got {} previously retrieved urls.", PLUGIN_NAME, already_retrieved_urls.len());
 
-    for date in candidate_days {
-        let bhavcopy_url = bhavcopy_url_for(date);
-        let date_str = date.format("%Y-%m-%d").to_string();
+    let mut new_docs: Vec<Document> = Vec::new();
 
-        if already_retrieved.contains(&bhavcopy_url) {
-            info!("{}: BSE bhavcopy for {} already retrieved, stopping.", PLUGIN_NAME, date_str);
-            return;
+    // Corporate announcements, paginated:
+    for pageno in 1..=max_pages {
+        let api_url = announcements_api_url(pageno, from_date, to_date);
+        let Some(json_val) = http_get_json_with_session(&api_url, SESSION_PAGES, &client, netw_params.retry_times, netw_params.wait_time_min) else {
+            warn!("{}: No corporate announcements from {}", PLUGIN_NAME, api_url);
+            break;
+        };
+        let page_docs = docs_from_announcements_json(&json_val);
+        if page_docs.is_empty() {
+            break;
         }
+        new_docs.extend(page_docs);
+    }
 
-        info!("{}: Trying BSE bhavcopy for {}: {}", PLUGIN_NAME, date_str, bhavcopy_url);
-        let body = http_get_binary(&bhavcopy_url, &bhavcopy_client);
+    // Exchange notices and circulars:
+    let content = http_get(&NOTICES_URL.to_string(), &client, netw_params.retry_times, netw_params.wait_time_min);
+    if content.is_empty() {
+        warn!("{}: Empty response from {}", PLUGIN_NAME, NOTICES_URL);
+    }
+    for mut notice in extract_docs_from_notices_listing(&content, NOTICES_URL) {
+        if already_retrieved_urls.contains(&notice.url) {
+            continue;
+        }
+        let notice_page = http_get(&notice.url, &client, netw_params.retry_times, netw_params.wait_time_min);
+        let (text, pdf_url) = notice_details_from_page(&notice_page);
+        notice.text = text;
+        if let Some(pdf_url) = pdf_url {
+            notice.pdf_url = pdf_url;
+        }
+        new_docs.push(notice);
+    }
 
-        if !looks_like_csv(body.as_ref()) {
-            warn!("{}: No bhavcopy CSV for {} (not yet published / non-trading day), trying earlier day.",
-                PLUGIN_NAME, date_str);
+    let mut counter: usize = 0;
+    for mut doc in new_docs {
+        if already_retrieved_urls.contains(&doc.url) {
             continue;
         }
+        _ = already_retrieved_urls.insert(doc.url.clone());
 
-        let csv_content = String::from_utf8_lossy(body.as_ref()).into_owned();
-        let row_count = csv_content.lines().count().saturating_sub(1);
-        info!("{}: Retrieved BSE bhavcopy for {} with {} data rows.", PLUGIN_NAME, date_str, row_count);
-
-        let mut doc = Document::default();
-        doc.module = PLUGIN_NAME.to_string();
-        doc.plugin_name = PUBLISHER_NAME.to_string();
-        doc.source_author = PUBLISHER_NAME.to_string();
-        doc.section_name = "Equity Bhavcopy".to_string();
-        doc.url = bhavcopy_url.clone();
-        doc.title = format!("BSE Equity Bhavcopy {}", date_str);
-        doc.publish_date = date_str.clone();
-        doc.publish_date_ms = crate::utils::to_local_datetime(date).timestamp();
-        doc.text = csv_content;
+        let filename = make_unique_filename(&doc, "json");
+        let json_file_path = Path::new(data_folder_str.as_str()).join(filename);
+        doc.filename = String::from(
+            json_file_path.as_path().to_str().expect("Not able to convert path to string")
+        );
+        load_attachment(&mut doc, &client, pdf_folder_str.as_str());
 
         match tx.send(doc) {
-            Ok(_) => info!("{}: Sent BSE bhavcopy document for {} ({} rows).", PLUGIN_NAME, date_str, row_count),
+            Ok(_) => counter += 1,
             Err(e) => error!("{}: Channel send error: {}", PLUGIN_NAME, e),
         }
-        info!("{}: Completed.", PLUGIN_NAME);
-        return;
     }
+    info!("{}: Completed, retrieved {} documents.", PLUGIN_NAME, counter);
+}
 
-    warn!("{}: No BSE bhavcopy found in the last {} business days.", PLUGIN_NAME, LOOKBACK_BUSINESS_DAYS);
-    info!("{}: Completed with 0 documents.", PLUGIN_NAME);
+/// URL of one page of the corporate announcements API for a date range (all categories).
+fn announcements_api_url(pageno: u64, from_date: NaiveDate, to_date: NaiveDate) -> String {
+    format!(
+        "{}AnnSubCategoryGetData/w?pageno={}&strCat=-1&strPrevDate={}&strScrip=&strSearch=P&strToDate={}&strType=C&subcategory=-1",
+        API_URL, pageno, from_date.format("%Y%m%d"), to_date.format("%Y%m%d")
+    )
+}
+
+/// Build one document per record of the announcements API response (under `Table`), with the
+/// company's scrip code attached.
+pub(crate) fn docs_from_announcements_json(json_val: &Value) -> Vec<Document> {
+    let mut docs = Vec::new();
+    for item in json_records(json_val) {
+        let news_id = json_str(item, &["NEWSID"]);
+        let scrip_code = json_str(item, &["SCRIP_CD"]);
+        if news_id.is_empty() || scrip_code.is_empty() {
+            continue;
+        }
+        let subject = json_str(item, &["NEWSSUB", "HEADLINE"]);
+        let category = format!("{} {} {}",
+            json_str(item, &["CATEGORYNAME"]), json_str(item, &["SUBCATNAME"]), subject);
+
+        let mut doc = new_document(announcement_category(&category), "corporate-announcement");
+        doc.title = subject;
+        doc.text = json_str(item, &["HEADLINE"]);
+        doc.unique_id = format!("BSE-ANN-{}", news_id);
+        let attachment = json_str(item, &["ATTACHMENTNAME"]);
+        if attachment.is_empty() {
+            doc.url = format!("{}corporates/ann.html?newsid={}", BASE_URL, news_id);
+        } else {
+            doc.url = format!("{}{}", ATTACHMENT_URL, attachment);
+            if attachment.to_lowercase().ends_with(".pdf") {
+                doc.pdf_url = doc.url.clone();
+            }
+        }
+        if let Some(naive_date) = parse_date_with_formats(&json_str(item, &["NEWS_DT", "DT_TM"]), DATE_FORMATS) {
+            doc.publish_date = naive_date.format("%Y-%m-%d").to_string();
+            doc.publish_date_ms = to_local_datetime(naive_date).timestamp();
+        }
+        doc.classification.insert("exchange".to_string(), "BSE".to_string());
+        doc.classification.insert("scrip_code".to_string(), scrip_code);
+        doc.classification.insert("company".to_string(), json_str(item, &["SLONGNAME"]));
+        docs.push(doc);
+    }
+    docs
+}
+
+/// Extract one document per notice row of the notices/circulars listing. Rows are recognised by
+/// their notice number "YYYYMMDD-N", which also gives the notice date.
+pub(crate) fn extract_docs_from_notices_listing(content: &str, listing_url: &str) -> Vec<Document> {
+    let html = scraper::Html::parse_document(content);
+    let row_sel = scraper::Selector::parse("tr").unwrap();
+    let cell_sel = scraper::Selector::parse("td").unwrap();
+    let link_sel = scraper::Selector::parse("a[href]").unwrap();
+    let mut docs: Vec<Document> = Vec::new();
+
+    for row in html.select(&row_sel) {
+        let cells: Vec<String> = row.select(&cell_sel)
+            .map(|cell| clean_text(get_text_from_element(cell)))
+            .collect();
+        let Some(notice_no) = cells.iter().find(|c| is_notice_number(c)) else { continue };
+        let Some(alink) = row.select(&link_sel).next() else { continue };
+        let Some(url) = alink.value().attr("href").and_then(|href| absolute_url(href, listing_url)) else { continue };
+        if docs.iter().any(|d| d.url == url) {
+            continue;
+        }
+        let mut doc = new_document("Notices", "exchange-circular");
+        doc.title = clean_text(get_text_from_element(alink));
+        doc.unique_id = notice_no.clone();
+        if let Some(naive_date) = parse_date_with_formats(&notice_no[..8], DATE_FORMATS) {
+            doc.publish_date = naive_date.format("%Y-%m-%d").to_string();
+            doc.publish_date_ms = to_local_datetime(naive_date).timestamp();
+        }
+        // the segment and category columns follow the subject:
+        if let Some(segment) = cells.get(2).filter(|c| !c.is_empty()) {
+            doc.classification.insert("segment".to_string(), segment.clone());
+        }
+        doc.url = url;
+        doc.links_inward = vec![listing_url.to_string()];
+        docs.push(doc);
+    }
+    docs
+}
+
+/// BSE notice numbers are the notice date followed by a serial, e.g. "20251003-12".
+fn is_notice_number(text: &str) -> bool {
+    let mut parts = text.splitn(2, '-');
+    let date_part = parts.next().unwrap_or("");
+    let serial = parts.next().unwrap_or("");
+    date_part.len() == 8 && date_part.chars().all(|c| c.is_ascii_digit())
+        && !serial.is_empty() && serial.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Text and attachment PDF link of a notice detail page.
+pub(crate) fn notice_details_from_page(content: &str) -> (String, Option<String>) {
+    let html = scraper::Html::parse_document(content);
+    let body_sel = scraper::Selector::parse("#ContentPlaceHolder1_tdNotice, td.TTRow_leftnotices, div.noticebody").unwrap();
+    let link_sel = scraper::Selector::parse("a[href]").unwrap();
+
+    let text = html.select(&body_sel)
+        .map(|elem| clean_text(get_text_from_element(elem)))
+        .collect::<Vec<String>>()
+        .join("\n");
+    let pdf_url = html.select(&link_sel)
+        .filter_map(|alink| alink.value().attr("href"))
+        .find(|href| href.to_lowercase().ends_with(".pdf"))
+        .and_then(|href| absolute_url(href, NOTICES_URL));
+    (text, pdf_url)
+}
+
+/// Resolve a link on a BSE page: absolute links are kept, root-relative ones are joined to the
+/// site root and page-relative ones to the folder of the page.
+fn absolute_url(href: &str, page_url: &str) -> Option<String> {
+    let href = href.trim();
+    if href.is_empty() || href.starts_with("javascript:") || href.starts_with('#') {
+        return None;
+    }
+    if href.starts_with("http://") || href.starts_with("https://") {
+        return Some(href.to_string());
+    }
+    if let Some(path) = href.strip_prefix('/') {
+        return Some(format!("{}{}", BASE_URL, path));
+    }
+    let folder = &page_url[..=page_url.rfind('/')?];
+    Some(format!("{}{}", folder, href.trim_start_matches("./")))
+}
+
+fn new_document(section_name: &str, doc_type: &str) -> Document {
+    Document {
+        module: PLUGIN_NAME.to_string(),
+        plugin_name: PUBLISHER_NAME.to_string(),
+        source_author: PUBLISHER_NAME.to_string(),
+        section_name: section_name.to_string(),
+        data_proc_flags: document::DATA_PROC_CLASSIFY_INDUSTRY
+            | document::DATA_PROC_EXTRACT_NAME_ENTITY
+            | document::DATA_PROC_SUMMARIZE,
+        classification: HashMap::from([
+            ("doc_type".to_string(), doc_type.to_string()),
+        ]),
+        ..Default::default()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_bhavcopy_url_format() {
-        let date = NaiveDate::from_ymd_opt(2025, 6, 3).unwrap();
-        assert_eq!(
-            bhavcopy_url_for(date),
-            "https://www.bseindia.com/download/BhavCopy/Equity/BhavCopy_BSE_CM_0_0_0_20250603_F_0000.CSV"
-        );
-    }
+    // Representative of the AnnSubCategoryGetData response.
+    const ANNOUNCEMENTS_FIXTURE: &str = r#"{"Table": [
+      {"NEWSID": "a1b2c3d4-0001", "SCRIP_CD": 500325, "XML_NAME": "", "NEWSSUB": "Reliance Industries Ltd - 500325 - Outcome of Board Meeting",
+       "DT_TM": "2025-10-03T18:47:12.1", "NEWS_DT": "2025-10-03T18:47:12.1", "CATEGORYNAME": "Board Meeting",
+       "SUBCATNAME": "Outcome of Board Meeting", "ATTACHMENTNAME": "8c7e6b5a-1234.pdf",
+       "HEADLINE": "Approved the unaudited financial results for the quarter.", "SLONGNAME": "Reliance Industries Ltd"},
+      {"NEWSID": "a1b2c3d4-0002", "SCRIP_CD": 532540, "NEWSSUB": "Tata Consultancy Services Ltd - 532540 - Shareholding for the Period Ended September 30, 2025",
+       "NEWS_DT": "2025-10-04T10:00:00", "CATEGORYNAME": "Company Update", "ATTACHMENTNAME": "", "SLONGNAME": "Tata Consultancy Services Ltd"}
+    ], "Table1": [{"ROWCNT": 2}]}"#;
 
     #[test]
-    fn test_looks_like_csv_accepts_udiff_header() {
-        let body = b"TradDt,BizDt,Sgmt,Src,FinInstrmTp\n2025-06-03,2025-06-03,CM,BSE,STK";
-        assert!(looks_like_csv(body));
+    fn test_docs_from_announcements_json() {
+        let json_val: Value = serde_json::from_str(ANNOUNCEMENTS_FIXTURE).unwrap();
+        let docs = docs_from_announcements_json(&json_val);
+        assert_eq!(docs.len(), 2);
+        assert_eq!(docs[0].classification["scrip_code"], "500325");
+        assert_eq!(docs[0].section_name, "Board Meeting Outcome");
+        assert_eq!(docs[0].pdf_url, "https://www.bseindia.com/xml-data/corpfiling/AttachLive/8c7e6b5a-1234.pdf");
+        assert_eq!(docs[0].publish_date, "2025-10-03");
+        assert_eq!(docs[0].unique_id, "BSE-ANN-a1b2c3d4-0001");
+        assert_eq!(docs[1].section_name, "Shareholding Pattern");
+        assert!(docs[1].pdf_url.is_empty());
+        assert_eq!(docs[1].url, "https://www.bseindia.com/corporates/ann.html?newsid=a1b2c3d4-0002");
     }
 
+    // Representative of the notices and circulars listing.
+    const NOTICES_FIXTURE: &str = r#"
+    <table>
+      <tr><td>Notice No.</td><td>Subject</td><td>Segment Name</td><td>Category Name</td></tr>
+      <tr><td>20251003-12</td>
+          <td><a href="DispNewNoticesCirculars.aspx?page=abc123">Listing of new securities of XYZ Ltd</a></td>
+          <td>Equity</td><td>Company related</td></tr>
+      <tr><td>not a notice</td><td><a href="/index.html">Home</a></td></tr>
+    </table>"#;
+
     #[test]
-    fn test_looks_like_csv_rejects_html() {
-        assert!(!looks_like_csv(b"<!DOCTYPE html><html><body>error</body></html>"));
-        assert!(!looks_like_csv(b"<html>"));
+    fn test_extract_docs_from_notices_listing() {
+        let docs = extract_docs_from_notices_listing(NOTICES_FIXTURE, NOTICES_URL);
+        assert_eq!(docs.len(), 1);
+        assert_eq!(docs[0].url, "https://www.bseindia.com/markets/MarketInfo/DispNewNoticesCirculars.aspx?page=abc123");
+        assert_eq!(docs[0].unique_id, "20251003-12");
+        assert_eq!(docs[0].publish_date, "2025-10-03");
+        assert_eq!(docs[0].classification["segment"], "Equity");
     }
 
     #[test]
-    fn test_looks_like_csv_rejects_empty() {
-        assert!(!looks_like_csv(b""));
+    fn test_notice_details_from_page() {
+        let page = r#"<html><body><table><tr><td id="ContentPlaceHolder1_tdNotice">Trading members are
+            requested to note the following.</td></tr></table>
+            <a href="/downloads/Notice_20251003_12.pdf">Attachment</a></body></html>"#;
+        let (text, pdf_url) = notice_details_from_page(page);
+        assert_eq!(text, "Trading members are requested to note the following.");
+        assert_eq!(pdf_url.as_deref(), Some("https://www.bseindia.com/downloads/Notice_20251003_12.pdf"));
     }
 }
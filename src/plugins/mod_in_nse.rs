@@ -1,60 +1,99 @@
 // file: mod_in_nse.rs
-// Retrieve regulatory circulars and announcements from NSE India
-
-use std::collections::{HashMap, HashSet};
-use std::io::Read;
+// Retrieve exchange circulars and listed-company corporate announcements from NSE India.
+//
+// NSE serves its data through JSON endpoints under /api/ that answer only when the session
+// cookies set by the landing pages are present; otherwise an HTML "access denied" page comes
+// back. The shared client keeps a cookie store, so the landing pages are loaded first and are
+// loaded again whenever an API call does not return JSON (see `http_get_json_with_session`).
+// Each announcement carries the company's NSE symbol and ISIN in `classification`, and the
+// attached PDF is downloaded and its text read via `load_pdf_content`.
+//
+// The equity bhavcopy download that used to live here is the batch feed
+// src/feeds/feed_nse_bhavcopy.rs.
+
+use std::collections::HashMap;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use std::path::Path;
 use std::sync::mpsc::Sender;
 use std::sync::Arc;
-use chrono::{NaiveDate, Utc};
+use chrono::{Duration, Utc};
 use log::{error, info, warn};
 use serde_json::Value;
-use zip::ZipArchive;
 
 use crate::{document, get_plugin_cfg};
-use crate::cfg::{get_data_folder, get_database_filename};
+use crate::cfg::{get_data_folder, get_database_filename, get_pdf_data_folder};
 use crate::document::Document;
-use crate::network::{http_get, http_get_binary, make_http_client, read_network_parameters};
-use crate::utils::{check_and_fix_url, clean_text, get_urls_from_database, recent_business_days, to_local_datetime};
+use crate::network::{establish_session, http_get, http_get_json_with_session, make_http_client, read_network_parameters};
+use crate::utils::{check_and_fix_url, clean_text, get_urls_from_database, load_pdf_content, make_unique_filename, parse_date_with_formats, to_local_datetime};
 
 pub(crate) const PLUGIN_NAME: &str = "mod_in_nse";
 const PUBLISHER_NAME: &str = "National Stock Exchange of India";
 const BASE_URL: &str = "https://www.nseindia.com/";
-// Walk back this many business days to find the most recent published bhavcopy.
-const NSE_LOOKBACK_BUSINESS_DAYS: usize = 5;
+/// Pages loaded to obtain the session cookies required by the /api/ endpoints.
+const SESSION_PAGES: &[&str] = &[
+    "https://www.nseindia.com/",
+    "https://www.nseindia.com/companies-listing/corporate-filings-announcements",
+];
+/// Days of announcements and circulars requested from the API, unless configured otherwise.
+const DEFAULT_LOOKBACK_DAYS: i64 = 3;
+/// Date formats used in NSE API responses ("03-Oct-2025 18:47:00", "20251003", ...).
+const DATE_FORMATS: &[&str] = &[
+    "%d-%b-%Y %H:%M:%S", "%d-%b-%Y", "%Y%m%d", "%d-%m-%Y", "%Y-%m-%d %H:%M:%S", "%Y-%m-%d", "%B %d, %Y",
+];
 
 pub(crate) fn run_worker_thread(tx: Sender<Document>, app_config: Arc<config::Config>) {
 
     info!("{}: Starting plugin.", PLUGIN_NAME);
     let database_filename = get_database_filename(&app_config);
+    let data_folder = get_data_folder(&app_config);
+    let data_folder_str = data_folder.to_str().unwrap_or("data").to_string();
+    let pdf_folder = get_pdf_data_folder(&app_config);
+    let pdf_folder_str = pdf_folder.to_str().unwrap_or("data/master_data").to_string();
+
     let mut netw_params = read_network_parameters(&app_config);
     // NSE requires browser-like headers
     netw_params.referrer_url = Some(BASE_URL.to_string());
     let client = make_http_client(&netw_params);
 
-    // Warm up the session: NSE sets cookies on the landing page that are required for its
-    // JSON API and listing endpoints. The shared client has a cookie store, so this primes it.
-    let warmup = http_get(&BASE_URL.to_string(), &client, 1, netw_params.wait_time_min);
-    if warmup.is_empty() {
-        warn!("{}: Could not load NSE landing page to establish session cookies.", PLUGIN_NAME);
-    } else {
+    if establish_session(SESSION_PAGES, &client, netw_params.wait_time_min) {
         info!("{}: Established NSE session via landing page.", PLUGIN_NAME);
+    } else {
+        warn!("{}: Could not load NSE landing page to establish session cookies.", PLUGIN_NAME);
     }
 
-    let mut max_pages: u64 = 2;
-    if let Some(v) = get_plugin_cfg!(PLUGIN_NAME, "max_pages", &app_config) {
-        if let Ok(n) = v.parse::<u64>() { max_pages = n; }
-    }
-
-    let already_retrieved_urls = get_urls_from_database(database_filename.as_str(), PLUGIN_NAME);
+    let max_pages: u64 = get_plugin_cfg!(PLUGIN_NAME, "max_pages", &app_config)
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(2);
+    let lookback_days = get_plugin_cfg!(PLUGIN_NAME, "lookback_days", &app_config)
+        .and_then(|v| v.parse::<i64>().ok())
+        .map_or(DEFAULT_LOOKBACK_DAYS, |n| n.max(0));
+    let to_date = Utc::now().date_naive();
+    let from_date = to_date - Duration::days(lookback_days);
+    let (from_str, to_str) = (from_date.format("%d-%m-%Y").to_string(), to_date.format("%d-%m-%Y").to_string());
+
+    let mut already_retrieved_urls = get_urls_from_database(database_filename.as_str(), PLUGIN_NAME);
     info!("{}: Got {} previously retrieved urls.", PLUGIN_NAME, already_retrieved_urls.len());
 
-    // NSE publishes circulars at a paginated HTML listing
+    let mut new_docs: Vec<Document> = Vec::new();
+
+    // Exchange circulars, from the dated API and the latest-circulars API:
+    let circular_apis = [
+        format!("{}api/circulars?fromDate={}&toDate={}", BASE_URL, from_str, to_str),
+        format!("{}api/latest-circular?index=equities", BASE_URL),
+    ];
+    for api_url in circular_apis {
+        match http_get_json_with_session(&api_url, SESSION_PAGES, &client, netw_params.retry_times, netw_params.wait_time_min) {
+            Some(json_val) => new_docs.extend(docs_from_circulars_json(&json_val)),
+            None => warn!("{}: No circulars data from {}", PLUGIN_NAME, api_url),
+        }
+    }
+
+    // NSE also publishes circulars at a paginated HTML listing
     let listing_sections = vec![
         ("https://www.nseindia.com/regulations/circulars", "Circulars"),
         ("https://www.nseindia.com/regulations/notices", "Notices"),
     ];
-
-    let mut counter: usize = 0;
     for (base_section_url, section_name) in listing_sections {
         for pageno in 0..max_pages {
             let listing_url = if pageno == 0 {
@@ -62,194 +101,243 @@ pub(crate) fn run_worker_thread(tx: Sender<Document>, app_config: Arc<config::Co
             } else {
                 format!("{}?page={}", base_section_url, pageno)
             };
-
             let content = http_get(&listing_url, &client, netw_params.retry_times, netw_params.wait_time_min);
             if content.is_empty() {
                 warn!("{}: Empty response from {}", PLUGIN_NAME, listing_url);
                 continue;
             }
-
-            let count = extract_docs_from_nse_listing(
-                content,
-                &tx,
-                &listing_url,
-                section_name,
-                &already_retrieved_urls,
-                &client,
-                &netw_params,
-            );
-            counter += count;
+            new_docs.extend(extract_docs_from_nse_listing(&content, &listing_url, section_name));
         }
     }
 
-    // Also try NSE's JSON API endpoint for latest circulars (may require session cookies)
-    let api_url = "https://www.nseindia.com/api/latest-circular?index=equities".to_string();
-    let api_content = http_get(&api_url, &client, netw_params.retry_times, netw_params.wait_time_min);
-    if !api_content.is_empty() {
-        if let Ok(json_val) = serde_json::from_str::<Value>(&api_content) {
-            if let Some(data_arr) = json_val.as_array() {
-                for item in data_arr {
-                    if let Some(url_str) = item.get("filePath").and_then(|v| v.as_str()) {
-                        if already_retrieved_urls.contains(url_str) {
-                            continue;
-                        }
-                        let mut doc = Document::default();
-                        doc.module = PLUGIN_NAME.to_string();
-                        doc.plugin_name = PUBLISHER_NAME.to_string();
-                        doc.source_author = PUBLISHER_NAME.to_string();
-                        doc.section_name = "Circulars".to_string();
-                        doc.url = format!("{}{}", BASE_URL.trim_end_matches('/'), url_str);
-                        doc.title = item.get("subject")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("NSE Circular")
-                            .to_string();
-
-                        if let Some(date_str) = item.get("notif_dt").and_then(|v| v.as_str()) {
-                            if let Ok(d) = NaiveDate::parse_from_str(date_str, "%d-%b-%Y") {
-                                doc.publish_date = d.format("%Y-%m-%d").to_string();
-                                doc.publish_date_ms = to_local_datetime(d).timestamp();
-                            }
-                        }
-                        if doc.publish_date == "1970-01-01" {
-                            doc.publish_date = Utc::now().format("%Y-%m-%d").to_string();
-                        }
-                        doc.unique_id = item.get("nseCircularNumber")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("")
-                            .to_string();
-                        doc.data_proc_flags = document::DATA_PROC_CLASSIFY_INDUSTRY
-                            | document::DATA_PROC_EXTRACT_NAME_ENTITY
-                            | document::DATA_PROC_SUMMARIZE;
-
-                        match tx.send(doc) {
-                            Ok(_) => counter += 1,
-                            Err(e) => error!("{}: Channel send error: {}", PLUGIN_NAME, e),
-                        }
-                    }
-                }
-            }
-        }
+    // Corporate announcements (board meeting outcomes, results, shareholding, ...):
+    let announcements_api = format!("{}api/corporate-announcements?index=equities&from_date={}&to_date={}",
+        BASE_URL, from_str, to_str);
+    match http_get_json_with_session(&announcements_api, SESSION_PAGES, &client, netw_params.retry_times, netw_params.wait_time_min) {
+        Some(json_val) => new_docs.extend(docs_from_announcements_json(&json_val)),
+        None => warn!("{}: No corporate announcements from {}", PLUGIN_NAME, announcements_api),
     }
 
-    // Also download today's NSE equity bhavcopy CSV.
-    download_nse_bhavcopy(&tx, &client, &already_retrieved_urls);
+    // Insider trading disclosures under the SEBI (PIT) Regulations:
+    let insider_api = format!("{}api/corporates-pit?index=equities&from_date={}&to_date={}",
+        BASE_URL, from_str, to_str);
+    match http_get_json_with_session(&insider_api, SESSION_PAGES, &client, netw_params.retry_times, netw_params.wait_time_min) {
+        Some(json_val) => new_docs.extend(docs_from_insider_trading_json(&json_val)),
+        None => warn!("{}: No insider trading disclosures from {}", PLUGIN_NAME, insider_api),
+    }
+
+    let mut counter: usize = 0;
+    for mut doc in new_docs {
+        if already_retrieved_urls.contains(&doc.url) {
+            continue;
+        }
+        _ = already_retrieved_urls.insert(doc.url.clone());
+
+        let filename = make_unique_filename(&doc, "json");
+        let json_file_path = Path::new(data_folder_str.as_str()).join(filename);
+        doc.filename = String::from(
+            json_file_path.as_path().to_str().expect("Not able to convert path to string")
+        );
+        load_attachment(&mut doc, &client, pdf_folder_str.as_str());
+
+        match tx.send(doc) {
+            Ok(_) => counter += 1,
+            Err(e) => error!("{}: Channel send error: {}", PLUGIN_NAME, e),
+        }
+    }
 
     info!("{}: Completed, retrieved {} documents.", PLUGIN_NAME, counter);
 }
 
-/// NSE equity bhavcopy URL (UDiFF zip) for a given date.
-///   https://nsearchives.nseindia.com/content/cm/BhavCopy_NSE_CM_0_0_0_{YYYYMMDD}_F_0000.csv.zip
-fn nse_bhavcopy_url_for(date: NaiveDate) -> String {
-    let date_compact = date.format("%Y%m%d").to_string();
-    format!(
-        "https://nsearchives.nseindia.com/content/cm/BhavCopy_NSE_CM_0_0_0_{}_F_0000.csv.zip",
-        date_compact
-    )
+/// Download the attached PDF of a document and read its text. The announcement summary given
+/// by the exchange is kept as the text when the PDF cannot be read.
+pub(crate) fn load_attachment(doc: &mut Document, client: &reqwest::blocking::Client, pdf_folder: &str) {
+    if doc.pdf_url.is_empty() {
+        return;
+    }
+    let summary = doc.text.clone();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        load_pdf_content(doc, client, pdf_folder);
+    }));
+    if let Err(errvar) = result {
+        error!("When reading PDF of document '{}' the error was: {:?}", doc.title, errvar);
+    }
+    if doc.text.trim().is_empty() {
+        doc.text = summary;
+    }
 }
 
-/// Download the most recent available NSE equity bhavcopy and send it as a document.
-///
-/// The current day's file is published only after market close and not at all on
-/// weekends/holidays, so we walk back over recent business days and use the first that
-/// returns a valid zip.
-fn download_nse_bhavcopy(
-    tx: &Sender<Document>,
-    client: &reqwest::blocking::Client,
-    already_retrieved: &HashSet<String>,
-) {
-    // NSE requires a desktop Chrome User-Agent for bhavcopy downloads.
-    let bhavcopy_client = reqwest::blocking::Client::builder()
-        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
-        .timeout(std::time::Duration::from_secs(60))
-        .build()
-        .unwrap_or_else(|_| client.clone());
-
-    let candidate_days = recent_business_days(Utc::now().date_naive(), NSE_LOOKBACK_BUSINESS_DAYS);
-
-    for date in candidate_days {
-        let bhavcopy_url = nse_bhavcopy_url_for(date);
-        let date_str = date.format("%Y-%m-%d").to_string();
-
-        if already_retrieved.contains(&bhavcopy_url) {
-            info!("{}: NSE bhavcopy for {} already retrieved, stopping.", PLUGIN_NAME, date_str);
-            return;
-        }
+/// Read a field of an API record as text, trying each key in turn. Numbers (e.g. BSE scrip
+/// codes) are returned in their decimal form.
+pub(crate) fn json_str(item: &Value, keys: &[&str]) -> String {
+    keys.iter()
+        .filter_map(|key| match item.get(*key) {
+            Some(Value::String(s)) => Some(clean_text(s.to_string())),
+            Some(Value::Number(n)) => Some(n.to_string()),
+            _ => None,
+        })
+        .find(|s| !s.is_empty() && s != "-")
+        .unwrap_or_default()
+}
 
-        info!("{}: Trying NSE bhavcopy for {}: {}", PLUGIN_NAME, date_str, bhavcopy_url);
-        let zip_bytes = http_get_binary(&bhavcopy_url, &bhavcopy_client);
+/// The records of an API response, which is either an array or an object holding the array
+/// under `data` (NSE) or `Table` (BSE).
+pub(crate) fn json_records(json_val: &Value) -> Vec<&Value> {
+    let array = json_val.as_array()
+        .or_else(|| json_val.get("data").and_then(Value::as_array))
+        .or_else(|| json_val.get("Table").and_then(Value::as_array));
+    array.map(|a| a.iter().collect()).unwrap_or_default()
+}
 
-        // Empty or HTML (starts with '<') means the file is not published for this date.
-        if zip_bytes.is_empty() || zip_bytes.first() == Some(&b'<') {
-            warn!("{}: No bhavcopy for {} (not yet published / non-trading day), trying earlier day.",
-                PLUGIN_NAME, date_str);
-            continue;
-        }
+/// Resolve a link from a listing or API record: absolute links (NSE serves files from
+/// nsearchives.nseindia.com) are kept, root-relative ones are joined to the site root.
+fn absolute_url(link: &str) -> Option<String> {
+    let link = link.trim();
+    if link.starts_with("http://") || link.starts_with("https://") {
+        return Some(link.to_string());
+    }
+    check_and_fix_url(link, BASE_URL)
+}
 
-        let cursor = std::io::Cursor::new(zip_bytes.as_ref());
-        let mut archive = match ZipArchive::new(cursor) {
-            Ok(a) => a,
-            Err(e) => {
-                warn!("{}: NSE bhavcopy zip for {} invalid: {}; trying earlier day.", PLUGIN_NAME, date_str, e);
-                continue;
-            }
-        };
+/// Section name of a corporate announcement, from its subject/category text.
+pub(crate) fn announcement_category(subject: &str) -> &'static str {
+    let subject = subject.to_lowercase();
+    let has_word = |word: &str| subject.split(|c: char| !c.is_alphanumeric()).any(|w| w == word);
+    if subject.contains("insider trading") || has_word("sast") || subject.contains("prohibition of insider") {
+        "Insider Trading"
+    } else if subject.contains("shareholding") {
+        "Shareholding Pattern"
+    } else if has_word("result") || has_word("results") {
+        "Financial Results"
+    } else if subject.contains("board meeting") && (subject.contains("outcome") || subject.contains("held")) {
+        "Board Meeting Outcome"
+    } else if subject.contains("board meeting") {
+        "Board Meeting Intimation"
+    } else {
+        "Corporate Announcements"
+    }
+}
 
-        let mut csv_content = String::new();
-        let mut found = false;
-        for i in 0..archive.len() {
-            let mut entry = match archive.by_index(i) {
-                Ok(e) => e,
-                Err(e) => { warn!("{}: Zip entry error: {}", PLUGIN_NAME, e); continue; }
-            };
-            if entry.name().to_lowercase().ends_with(".csv") {
-                if let Err(e) = entry.read_to_string(&mut csv_content) {
-                    error!("{}: Failed reading NSE CSV: {}", PLUGIN_NAME, e);
-                } else {
-                    found = true;
-                }
-                break;
-            }
+/// Set the publish date of a document from a date text, if it parses.
+fn set_publish_date(doc: &mut Document, date_text: &str) {
+    if let Some(naive_date) = parse_date_with_formats(date_text, DATE_FORMATS) {
+        doc.publish_date = naive_date.format("%Y-%m-%d").to_string();
+        doc.publish_date_ms = to_local_datetime(naive_date).timestamp();
+    }
+}
+
+/// Build circular documents from the `api/circulars` or `api/latest-circular` response.
+pub(crate) fn docs_from_circulars_json(json_val: &Value) -> Vec<Document> {
+    let mut docs = Vec::new();
+    for item in json_records(json_val) {
+        let link = json_str(item, &["circFilelink", "filePath", "fileLink"]);
+        let Some(url) = absolute_url(&link) else { continue };
+        let mut doc = new_document("Circulars", "exchange-circular");
+        doc.title = json_str(item, &["sub", "subject"]);
+        if doc.title.is_empty() {
+            doc.title = "NSE Circular".to_string();
+        }
+        doc.unique_id = json_str(item, &["circDisplayNo", "nseCircularNumber", "circNumber"]);
+        set_publish_date(&mut doc, &json_str(item, &["cirDisplayDate", "notif_dt", "cirDate", "circDate"]));
+        let department = json_str(item, &["circDepartment"]);
+        if !department.is_empty() {
+            doc.classification.insert("department".to_string(), department);
+        }
+        if url.to_lowercase().ends_with(".pdf") {
+            doc.pdf_url = url.clone();
         }
+        doc.url = url;
+        docs.push(doc);
+    }
+    docs
+}
 
-        if !found || csv_content.is_empty() {
-            warn!("{}: No CSV inside NSE bhavcopy zip for {}; trying earlier day.", PLUGIN_NAME, date_str);
+/// Build one document per record of the `api/corporate-announcements` response, with the
+/// company's symbol and ISIN attached.
+pub(crate) fn docs_from_announcements_json(json_val: &Value) -> Vec<Document> {
+    let mut docs = Vec::new();
+    for item in json_records(json_val) {
+        let symbol = json_str(item, &["symbol"]);
+        let seq_id = json_str(item, &["seq_id"]);
+        if symbol.is_empty() || seq_id.is_empty() {
             continue;
         }
+        let company = json_str(item, &["sm_name"]);
+        let subject = json_str(item, &["desc"]);
+        let attachment = json_str(item, &["attchmntFile"]);
+
+        let mut doc = new_document(announcement_category(&subject), "corporate-announcement");
+        doc.title = format!("{}: {}", if company.is_empty() { &symbol } else { &company }, subject);
+        doc.text = json_str(item, &["attchmntText"]);
+        doc.unique_id = format!("NSE-ANN-{}", seq_id);
+        doc.url = match absolute_url(&attachment) {
+            Some(url) => {
+                if url.to_lowercase().ends_with(".pdf") {
+                    doc.pdf_url = url.clone();
+                }
+                url
+            }
+            None => format!("{}companies-listing/corporate-filings-announcements?symbol={}&seq_id={}", BASE_URL, symbol, seq_id),
+        };
+        set_publish_date(&mut doc, &json_str(item, &["an_dt", "sort_date"]));
+        doc.classification.insert("exchange".to_string(), "NSE".to_string());
+        doc.classification.insert("symbol".to_string(), symbol);
+        doc.classification.insert("company".to_string(), company);
+        doc.classification.insert("isin".to_string(), json_str(item, &["sm_isin"]));
+        docs.push(doc);
+    }
+    docs
+}
 
-        let row_count = csv_content.lines().count().saturating_sub(1);
-
-        let mut doc = Document::default();
-        doc.module = PLUGIN_NAME.to_string();
-        doc.plugin_name = PUBLISHER_NAME.to_string();
-        doc.source_author = PUBLISHER_NAME.to_string();
-        doc.section_name = "Equity Bhavcopy".to_string();
-        doc.url = bhavcopy_url;
-        doc.title = format!("NSE Equity Bhavcopy {}", date_str);
-        doc.publish_date = date_str.clone();
-        doc.publish_date_ms = to_local_datetime(date).timestamp();
-        doc.text = csv_content;
-
-        match tx.send(doc) {
-            Ok(_) => info!("{}: Sent NSE bhavcopy document for {} ({} rows).", PLUGIN_NAME, date_str, row_count),
-            Err(e) => error!("{}: Channel send error: {}", PLUGIN_NAME, e),
+/// Build one document per insider trading disclosure in the `api/corporates-pit` response.
+/// These have no attachment, so the text is composed from the disclosed fields.
+pub(crate) fn docs_from_insider_trading_json(json_val: &Value) -> Vec<Document> {
+    let mut docs = Vec::new();
+    for item in json_records(json_val) {
+        let symbol = json_str(item, &["symbol"]);
+        if symbol.is_empty() {
+            continue;
         }
-        return;
+        let company = json_str(item, &["company"]);
+        let acquirer = json_str(item, &["acqName"]);
+        let category = json_str(item, &["personCategory"]);
+        let transaction = json_str(item, &["tdpTransactionType"]);
+        let quantity = json_str(item, &["secAcq"]);
+        let security = json_str(item, &["secType"]);
+        let value = json_str(item, &["secVal"]);
+        let mode = json_str(item, &["acqMode"]);
+        let (from_date, to_date) = (json_str(item, &["acqfromDt"]), json_str(item, &["acqtoDt"]));
+        let disclosure_id = json_str(item, &["did", "pid"]);
+
+        let mut doc = new_document("Insider Trading", "insider-trading-disclosure");
+        doc.title = format!("{}: {} ({}) {} {} {}", company, acquirer, category, transaction, quantity, security);
+        doc.text = format!(
+            "{} ({}) of {} ({}) reported a {} of {} {} valued at Rs. {} by way of {}, between {} and {}.",
+            acquirer, category, company, symbol, transaction.to_lowercase(), quantity, security, value, mode,
+            from_date, to_date
+        );
+        let xbrl = json_str(item, &["xbrl"]);
+        doc.url = match absolute_url(&xbrl) {
+            Some(url) => url,
+            None => format!("{}companies-listing/corporate-filings-insider-trading?symbol={}&did={}",
+                BASE_URL, symbol, if disclosure_id.is_empty() { doc.title.replace(' ', "_") } else { disclosure_id.clone() }),
+        };
+        if !disclosure_id.is_empty() {
+            doc.unique_id = format!("NSE-PIT-{}", disclosure_id);
+        }
+        set_publish_date(&mut doc, &json_str(item, &["intimDt", "date"]));
+        doc.classification.insert("exchange".to_string(), "NSE".to_string());
+        doc.classification.insert("symbol".to_string(), symbol);
+        doc.classification.insert("company".to_string(), company);
+        docs.push(doc);
     }
-
-    warn!("{}: No NSE bhavcopy found in the last {} business days.", PLUGIN_NAME, NSE_LOOKBACK_BUSINESS_DAYS);
+    docs
 }
 
-fn extract_docs_from_nse_listing(
-    content: String,
-    tx: &Sender<Document>,
-    listing_url: &str,
-    section_name: &str,
-    already_retrieved_urls: &HashSet<String>,
-    client: &reqwest::blocking::Client,
-    netw_params: &crate::network::NetworkParameters,
-) -> usize {
-    let mut counter = 0;
-    let html = scraper::Html::parse_document(&content);
+/// Extract circular documents from an NSE HTML listing page (table rows or list items with a link).
+pub(crate) fn extract_docs_from_nse_listing(content: &str, listing_url: &str, section_name: &str) -> Vec<Document> {
+    let mut docs = Vec::new();
+    let html = scraper::Html::parse_document(content);
     let row_sel = scraper::Selector::parse("table tbody tr, div.circular-item, li.circular-row").unwrap();
     let link_sel = scraper::Selector::parse("a").unwrap();
 
@@ -267,47 +355,128 @@ fn extract_docs_from_nse_listing(
         if url.is_empty() || title.is_empty() {
             continue;
         }
-        if let Some(fixed_url) = check_and_fix_url(&url, BASE_URL) {
-            url = fixed_url;
-        } else {
-            continue;
-        }
-        if already_retrieved_urls.contains(&url) {
-            continue;
-        }
+        let Some(fixed_url) = absolute_url(&url) else { continue };
 
-        let mut doc = Document::default();
-        doc.module = PLUGIN_NAME.to_string();
-        doc.plugin_name = PUBLISHER_NAME.to_string();
-        doc.source_author = PUBLISHER_NAME.to_string();
-        doc.section_name = section_name.to_string();
-        doc.url = url.clone();
+        let mut doc = new_document(section_name, "exchange-circular");
         doc.title = title;
+        if fixed_url.to_lowercase().ends_with(".pdf") {
+            doc.pdf_url = fixed_url.clone();
+        }
+        doc.url = fixed_url;
         doc.publish_date = Utc::now().format("%Y-%m-%d").to_string();
         doc.publish_date_ms = Utc::now().timestamp();
         doc.links_inward = vec![listing_url.to_string()];
-        doc.data_proc_flags = document::DATA_PROC_CLASSIFY_INDUSTRY
-            | document::DATA_PROC_EXTRACT_NAME_ENTITY
-            | document::DATA_PROC_SUMMARIZE;
+        docs.push(doc);
+    }
+    docs
+}
 
-        match tx.send(doc) {
-            Ok(_) => counter += 1,
-            Err(e) => error!("{}: Channel send error: {}", PLUGIN_NAME, e),
-        }
+fn new_document(section_name: &str, doc_type: &str) -> Document {
+    Document {
+        module: PLUGIN_NAME.to_string(),
+        plugin_name: PUBLISHER_NAME.to_string(),
+        source_author: PUBLISHER_NAME.to_string(),
+        section_name: section_name.to_string(),
+        data_proc_flags: document::DATA_PROC_CLASSIFY_INDUSTRY
+            | document::DATA_PROC_EXTRACT_NAME_ENTITY
+            | document::DATA_PROC_SUMMARIZE,
+        classification: HashMap::from([
+            ("doc_type".to_string(), doc_type.to_string()),
+        ]),
+        ..Default::default()
     }
-    counter
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    // Representative of the api/corporate-announcements response.
+    const ANNOUNCEMENTS_FIXTURE: &str = r#"[
+      {"symbol": "RELIANCE", "desc": "Outcome of Board Meeting", "dt": "03102025184700",
+       "attchmntFile": "https://nsearchives.nseindia.com/corporate/RELIANCE_03102025184700_Outcome.pdf",
+       "sm_name": "Reliance Industries Limited", "sm_isin": "INE002A01018", "an_dt": "03-Oct-2025 18:47:00",
+       "sort_date": "2025-10-03 18:47:00", "seq_id": "104123456",
+       "attchmntText": "Reliance Industries Limited has informed the Exchange about the outcome of the board meeting."},
+      {"symbol": "TCS", "desc": "Financial Result Updates", "attchmntFile": "-", "sm_name": "Tata Consultancy Services Limited",
+       "sm_isin": "INE467B01029", "an_dt": "09-Oct-2025 16:05:12", "seq_id": "104123999",
+       "attchmntText": "Results for the quarter ended September 30, 2025."},
+      {"symbol": "", "desc": "No symbol", "seq_id": "1"}
+    ]"#;
+
     #[test]
-    fn test_nse_bhavcopy_url_format() {
-        let date = NaiveDate::from_ymd_opt(2025, 6, 3).unwrap();
-        assert_eq!(
-            nse_bhavcopy_url_for(date),
-            "https://nsearchives.nseindia.com/content/cm/BhavCopy_NSE_CM_0_0_0_20250603_F_0000.csv.zip"
-        );
+    fn test_docs_from_announcements_json() {
+        let json_val: Value = serde_json::from_str(ANNOUNCEMENTS_FIXTURE).unwrap();
+        let docs = docs_from_announcements_json(&json_val);
+        assert_eq!(docs.len(), 2, "records without a symbol are skipped");
+        assert_eq!(docs[0].title, "Reliance Industries Limited: Outcome of Board Meeting");
+        assert_eq!(docs[0].section_name, "Board Meeting Outcome");
+        assert_eq!(docs[0].classification["symbol"], "RELIANCE");
+        assert_eq!(docs[0].classification["isin"], "INE002A01018");
+        assert_eq!(docs[0].pdf_url, docs[0].url);
+        assert_eq!(docs[0].publish_date, "2025-10-03");
+        assert_eq!(docs[0].unique_id, "NSE-ANN-104123456");
+        assert_eq!(docs[1].section_name, "Financial Results");
+        assert!(docs[1].pdf_url.is_empty(), "'-' means no attachment");
+        assert!(docs[1].url.contains("seq_id=104123999"));
+    }
+
+    #[test]
+    fn test_docs_from_circulars_json() {
+        let json_val: Value = serde_json::from_str(r#"{"data": [
+            {"circDisplayNo": "NSE/CML/70123", "sub": "Listing of equity shares of XYZ Limited",
+             "cirDisplayDate": "03-Oct-2025", "circDepartment": "Listing",
+             "circFilelink": "https://nsearchives.nseindia.com/content/circulars/CML70123.pdf"},
+            {"nseCircularNumber": "NSE/FAOP/70124", "subject": "Contract specifications",
+             "notif_dt": "04-Oct-2025", "filePath": "/content/circulars/FAOP70124.zip"}
+        ]}"#).unwrap();
+        let docs = docs_from_circulars_json(&json_val);
+        assert_eq!(docs.len(), 2);
+        assert_eq!(docs[0].unique_id, "NSE/CML/70123");
+        assert_eq!(docs[0].publish_date, "2025-10-03");
+        assert_eq!(docs[0].classification["department"], "Listing");
+        assert!(!docs[0].pdf_url.is_empty());
+        assert_eq!(docs[1].url, "https://www.nseindia.com/content/circulars/FAOP70124.zip");
+        assert!(docs[1].pdf_url.is_empty());
+    }
+
+    #[test]
+    fn test_docs_from_insider_trading_json() {
+        let json_val: Value = serde_json::from_str(r#"{"data": [
+            {"symbol": "INFY", "company": "Infosys Limited", "acqName": "A Promoter", "personCategory": "Promoters",
+             "secType": "Equity Shares", "secAcq": "10000", "secVal": "15000000", "tdpTransactionType": "Buy",
+             "acqMode": "Market Purchase", "acqfromDt": "01-Oct-2025", "acqtoDt": "01-Oct-2025",
+             "intimDt": "03-Oct-2025", "did": "998877", "xbrl": "-"}
+        ]}"#).unwrap();
+        let docs = docs_from_insider_trading_json(&json_val);
+        assert_eq!(docs.len(), 1);
+        assert_eq!(docs[0].section_name, "Insider Trading");
+        assert_eq!(docs[0].classification["symbol"], "INFY");
+        assert_eq!(docs[0].unique_id, "NSE-PIT-998877");
+        assert!(docs[0].text.contains("reported a buy of 10000 Equity Shares"));
+        assert_eq!(docs[0].publish_date, "2025-10-03");
+    }
+
+    #[test]
+    fn test_extract_docs_from_nse_listing() {
+        let content = r#"<table><tbody>
+            <tr><td>03-Oct-2025</td><td><a href="https://nsearchives.nseindia.com/content/circulars/CMTR70125.pdf">Revision in trading hours</a></td></tr>
+            <tr><td>No link here</td></tr>
+        </tbody></table>"#;
+        let docs = extract_docs_from_nse_listing(content, "https://www.nseindia.com/regulations/circulars", "Circulars");
+        assert_eq!(docs.len(), 1);
+        assert_eq!(docs[0].title, "Revision in trading hours");
+        assert_eq!(docs[0].pdf_url, "https://nsearchives.nseindia.com/content/circulars/CMTR70125.pdf");
+    }
+
+    #[test]
+    fn test_announcement_category() {
+        assert_eq!(announcement_category("Board Meeting Intimation for Fund Raising"), "Board Meeting Intimation");
+        assert_eq!(announcement_category("Shareholding Pattern for the quarter"), "Shareholding Pattern");
+        assert_eq!(announcement_category("Disclosure under SEBI (Prohibition of Insider Trading) Regulations"), "Insider Trading");
+        assert_eq!(announcement_category("Result"), "Financial Results");
+        assert_eq!(announcement_category("Press Release"), "Corporate Announcements");
+        assert_eq!(announcement_category("Disclosures under Reg. 29(2) of SEBI (SAST) Regulations, 2011"), "Insider Trading");
+        assert_eq!(announcement_category("Update on disaster recovery site"), "Corporate Announcements");
     }
 }
@@ -0,0 +1,548 @@
+// file: mod_lang.rs
+// Purpose:
+//   Offline language identification and Hindi/English pairing. Every document gets
+//   `analysis.lang` (ISO 639-1, "und" when undetermined) from a two-step guess:
+//     1. the dominant Unicode script of its letters (Devanagari, Bengali, Tamil, Latin, ...), and
+//     2. for scripts shared by several languages, a score against small character-trigram
+//        profiles (Hindi vs Marathi for Devanagari, English vs anything else for Latin).
+//   RBI, SEBI and the Gazette publish many circulars as separate Hindi and English documents.
+//   For the regulator retrievers this plugin holds such documents back and pairs them by their
+//   circular reference number (falling back to module + date + section at end of stream), so that
+//   the Hindi document carries the English text in `analysis.text_en` and the English document
+//   carries the Hindi text in `generated_content["text_hi"]`. Single documents printed in both
+//   languages are split by script the same way, and their Latin-script lines are kept in
+//   `analysis.text_en` whichever language dominates. Downstream plugins should read
+//   `Document::english_text()`.
+
+use std::collections::HashMap;
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use config::Config;
+use log::{debug, error, info};
+use regex::Regex;
+
+use crate::document::Document;
+
+pub const PLUGIN_NAME: &str = "mod_lang";
+
+/// Key in `generated_content` holding the Hindi text of an English document.
+pub const TEXT_HI_KEY: &str = "text_hi";
+/// Key in `generated_content` holding the url of the other-language version of a document.
+pub const PAIRED_URL_KEY: &str = "paired_doc_url";
+
+/// Modules whose documents are published as separate Hindi and English versions.
+const PAIRED_MODULES: &[&str] = &[
+    "mod_en_in_rbi", "rbi_new", "mod_en_in_sebi", "sebi", "mod_en_in_irdai", "irdai",
+    "mod_en_in_mca", "mod_en_in_pfrda", "mod_en_in_ifsca", "mod_en_in_fiu",
+];
+
+/// Upper bound on documents held back while waiting for their other-language version; the
+/// oldest is forwarded unpaired once this is exceeded.
+const MAX_PENDING: usize = 500;
+/// Number of characters at the start of the text searched for a reference number.
+const REF_SEARCH_CHARS: usize = 3000;
+/// Each of two scripts must have at least this share of the letters for a document to be
+/// treated as printed in both languages.
+const BILINGUAL_MIN_SHARE: f32 = 0.2;
+/// Latin text with fewer letters than this is too short to score and is taken as English.
+const MIN_SCORED_LETTERS: usize = 40;
+/// Minimum share of trigrams found in the English profile for Latin text to be called English.
+const MIN_ENGLISH_SCORE: f32 = 0.08;
+
+/// Frequent character trigrams of Hindi (function words and inflections), space-padded.
+const HINDI_TRIGRAMS: &[&str] = &[
+    " के", "के ", " की", "की ", " का", "का ", " है", "है ", "हैं", "ैं ", " मे", "में", "ें ",
+    " और", "और", "और ", " से", "से ", " को", "को ", " ने", "ने ", " कि", "िया", " पर", "पर ",
+    "ता ", "ती ", "ते ", "ों ", "गया", "ारा", "्वा", "वार", " लि", "लिए", "िए ", " यह", "यह ",
+    " इस", "इस ", "ेगा", "नही", "हीं", "ीं ",
+];
+
+/// Frequent character trigrams of Marathi, chosen to be rare in Hindi.
+const MARATHI_TRIGRAMS: &[&str] = &[
+    " आह", "आहे", "ाहे", "हे ", "हेत", "ेत ", " आण", "आणि", "णि ", "च्य", " व ", "ाचे", "चे ",
+    "ाची", "ची ", "ाचा", "चा ", "ाला", "ला ", " मध", "मध्", "्ये", "ून ", "ण्य", "ल्य", "ांन",
+    "ंना", "करण", "रणे", "नाह", "ाही", "साठ", "ाठी", "ठी ", "ेले", "ले ", "यात", "ात ",
+];
+
+/// Frequent character trigrams of English.
+const ENGLISH_TRIGRAMS: &[&str] = &[
+    " th", "the", "he ", " of", "of ", "and", "nd ", " an", " to", "to ", " in", "in ", "ing",
+    "ng ", "ion", "tio", " re", "ed ", " is", "is ", "for", " fo", "or ", "ent", "ati", " be",
+    "er ", "es ", "ll ", "ith", " wi", "hat", "tha", " sh", "al ", " co", "on ", "ons", "ank",
+];
+
+/// Writing systems distinguished by the script step of identification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Script {
+    Latin,
+    Devanagari,
+    Bengali,
+    Gurmukhi,
+    Gujarati,
+    Oriya,
+    Tamil,
+    Telugu,
+    Kannada,
+    Malayalam,
+    Arabic,
+}
+
+fn script_of(c: char) -> Option<Script> {
+    match c as u32 {
+        0x41..=0x5A | 0x61..=0x7A | 0xC0..=0x24F => Some(Script::Latin).filter(|_| c.is_alphabetic()),
+        0x0900..=0x097F | 0xA8E0..=0xA8FF => Some(Script::Devanagari),
+        0x0980..=0x09FF => Some(Script::Bengali),
+        0x0A00..=0x0A7F => Some(Script::Gurmukhi),
+        0x0A80..=0x0AFF => Some(Script::Gujarati),
+        0x0B00..=0x0B7F => Some(Script::Oriya),
+        0x0B80..=0x0BFF => Some(Script::Tamil),
+        0x0C00..=0x0C7F => Some(Script::Telugu),
+        0x0C80..=0x0CFF => Some(Script::Kannada),
+        0x0D00..=0x0D7F => Some(Script::Malayalam),
+        0x0600..=0x06FF | 0x0750..=0x077F => Some(Script::Arabic),
+        _ => None,
+    }.filter(|_| !is_indic_digit(c))
+}
+
+/// Devanagari and other Indic digits sit inside the script blocks but say nothing about language.
+fn is_indic_digit(c: char) -> bool {
+    matches!(c as u32, 0x0966..=0x096F | 0x09E6..=0x09EF | 0x0A66..=0x0A6F | 0x0AE6..=0x0AEF
+        | 0x0B66..=0x0B6F | 0x0BE6..=0x0BEF | 0x0C66..=0x0C6F | 0x0CE6..=0x0CEF | 0x0D66..=0x0D6F)
+}
+
+/// Result of language identification.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LangGuess {
+    /// ISO 639-1 code, or "und" when the language could not be determined.
+    pub lang: String,
+    /// 0..1: the dominant script's share of letters times the n-gram margin.
+    pub confidence: f32,
+    /// Share of letters in Devanagari script.
+    pub devanagari_share: f32,
+    /// Share of letters in Latin script.
+    pub latin_share: f32,
+}
+
+impl LangGuess {
+    /// True when the text has a substantial part in both Devanagari and Latin script.
+    pub fn is_bilingual(&self) -> bool {
+        self.devanagari_share >= BILINGUAL_MIN_SHARE && self.latin_share >= BILINGUAL_MIN_SHARE
+    }
+}
+
+/// Identify the language of a text without any network or model: the dominant script decides,
+/// and character-trigram profiles separate languages that share a script.
+pub fn detect_language(text: &str) -> LangGuess {
+    let mut counts: HashMap<Script, usize> = HashMap::new();
+    for c in text.chars() {
+        if let Some(script) = script_of(c) {
+            *counts.entry(script).or_insert(0) += 1;
+        }
+    }
+    let total: usize = counts.values().sum();
+    let share = |s: Script| if total == 0 { 0.0 } else { *counts.get(&s).unwrap_or(&0) as f32 / total as f32 };
+    let devanagari_share = share(Script::Devanagari);
+    let latin_share = share(Script::Latin);
+
+    let Some((&dominant, &dominant_count)) = counts.iter().max_by_key(|(s, n)| (**n, **s == Script::Latin)) else {
+        return LangGuess { lang: "und".to_string(), confidence: 0.0, devanagari_share, latin_share };
+    };
+    let script_share = dominant_count as f32 / total as f32;
+
+    let (lang, margin) = match dominant {
+        Script::Devanagari => {
+            let hi = trigram_score(text, Script::Devanagari, HINDI_TRIGRAMS);
+            let mr = trigram_score(text, Script::Devanagari, MARATHI_TRIGRAMS);
+            if hi + mr == 0.0 {
+                ("hi", 0.5)
+            } else if mr > hi {
+                ("mr", mr / (hi + mr))
+            } else {
+                ("hi", hi / (hi + mr))
+            }
+        }
+        Script::Latin => {
+            if dominant_count < MIN_SCORED_LETTERS {
+                ("en", 0.5)
+            } else {
+                let en = trigram_score(text, Script::Latin, ENGLISH_TRIGRAMS);
+                if en >= MIN_ENGLISH_SCORE {
+                    ("en", (en / (2.0 * MIN_ENGLISH_SCORE)).min(1.0))
+                } else {
+                    ("und", 0.0)
+                }
+            }
+        }
+        Script::Bengali => ("bn", 1.0),
+        Script::Gurmukhi => ("pa", 1.0),
+        Script::Gujarati => ("gu", 1.0),
+        Script::Oriya => ("or", 1.0),
+        Script::Tamil => ("ta", 1.0),
+        Script::Telugu => ("te", 1.0),
+        Script::Kannada => ("kn", 1.0),
+        Script::Malayalam => ("ml", 1.0),
+        Script::Arabic => ("ur", 1.0),
+    };
+    LangGuess { lang: lang.to_string(), confidence: script_share * margin, devanagari_share, latin_share }
+}
+
+/// Share of the space-padded character trigrams of the words written in `script` that occur
+/// in `profile`.
+fn trigram_score(text: &str, script: Script, profile: &[&str]) -> f32 {
+    let mut total = 0usize;
+    let mut hits = 0usize;
+    let lowered = text.to_lowercase();
+    for word in lowered.split(|c: char| script_of(c) != Some(script) && !is_combining_mark(c)) {
+        if word.is_empty() {
+            continue;
+        }
+        let padded: Vec<char> = std::iter::once(' ').chain(word.chars()).chain(std::iter::once(' ')).collect();
+        for window in padded.windows(3) {
+            total += 1;
+            let trigram: String = window.iter().collect();
+            if profile.contains(&trigram.as_str()) {
+                hits += 1;
+            }
+        }
+    }
+    if total == 0 { 0.0 } else { hits as f32 / total as f32 }
+}
+
+/// Zero-width joiners used inside Indic conjuncts must not split a word.
+fn is_combining_mark(c: char) -> bool {
+    matches!(c, '\u{200C}' | '\u{200D}')
+}
+
+/// Split a text printed in both Devanagari and Latin script into its Devanagari and Latin
+/// lines. Lines without letters (numbers, separators) are dropped.
+pub fn split_by_script(text: &str) -> (String, String) {
+    let mut devanagari: Vec<&str> = Vec::new();
+    let mut latin: Vec<&str> = Vec::new();
+    for line in text.lines() {
+        let (mut dev, mut lat) = (0usize, 0usize);
+        for c in line.chars() {
+            match script_of(c) {
+                Some(Script::Devanagari) => dev += 1,
+                Some(Script::Latin) => lat += 1,
+                _ => {}
+            }
+        }
+        if dev == 0 && lat == 0 {
+            continue;
+        }
+        if dev >= lat {
+            devanagari.push(line.trim());
+        } else {
+            latin.push(line.trim());
+        }
+    }
+    (devanagari.join("\n"), latin.join("\n"))
+}
+
+/// Matches reference numbers made of three or more "/"-separated parts, as used by RBI
+/// ("RBI/2024-25/123", "DOR.CRE.REC.No.12/21.01.023/2024-25") and SEBI
+/// ("SEBI/HO/MRD/MRD-PoD-1/P/CIR/2024/123"), in either script.
+fn reference_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"[\p{L}\p{M}\p{N}.()\-]+(?:/[\p{L}\p{M}\p{N}.()\-]+){2,}").expect("reference regex")
+    })
+}
+
+/// Matches Gazette notification numbers: "G.S.R. 612(E)" / "सा.का.नि. 612(अ)" and
+/// "S.O. 1234(E)" / "का.आ. 1234(अ)".
+fn gazette_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(G\.\s*S\.\s*R\.|सा\.\s*का\.\s*नि\.|S\.\s*O\.|का\.\s*आ\.)\s*(\d+)").expect("gazette regex")
+    })
+}
+
+fn digit_runs_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\d+").expect("digit regex"))
+}
+
+/// Replace Devanagari digits with ASCII digits so both versions of a number compare equal.
+fn normalise_digits(text: &str) -> String {
+    text.chars()
+        .map(|c| match c as u32 {
+            0x0966..=0x096F => char::from_digit(c as u32 - 0x0966, 10).unwrap_or(c),
+            _ => c,
+        })
+        .collect()
+}
+
+/// Language-neutral key of the reference number in a piece of text. The words of a reference
+/// are translated in the Hindi version, so the key keeps only its digit groups
+/// ("RBI/2024-25/123" → "2024-25-123"); a reference must still contain a letter, which keeps
+/// plain dates like "12/02/2025" out.
+fn reference_key(text: &str) -> Option<String> {
+    let text = normalise_digits(text);
+    if let Some(caps) = gazette_regex().captures(&text) {
+        let series = if caps[1].contains('G') || caps[1].starts_with("सा") { "GSR" } else { "SO" };
+        return Some(format!("{}-{}", series, &caps[2]));
+    }
+    reference_regex().find_iter(&text)
+        .map(|m| m.as_str())
+        .filter(|r| r.chars().any(char::is_alphabetic))
+        .map(|r| digit_runs_regex().find_iter(r).map(|d| d.as_str()).collect::<Vec<_>>())
+        .find(|runs| runs.len() >= 2)
+        .map(|runs| runs.join("-"))
+}
+
+/// The reference key of a document, looked up in its unique id, its title and then the start
+/// of its text.
+pub fn pair_key(doc: &Document) -> Option<String> {
+    let head: String = doc.text.chars().take(REF_SEARCH_CHARS).collect();
+    [doc.unique_id.as_str(), doc.title.as_str(), head.as_str()]
+        .into_iter()
+        .find_map(reference_key)
+}
+
+fn doc_lang(doc: &Document) -> &str {
+    doc.analysis.as_ref().map(|a| a.lang.as_str()).unwrap_or("")
+}
+
+/// Link a Hindi document and its English version to each other.
+fn link_pair(hi: &mut Document, en: &mut Document) {
+    let mut analysis = hi.analysis.take().unwrap_or_default();
+    analysis.text_en = en.text.clone();
+    hi.analysis = Some(analysis);
+    hi.generated_content.insert(PAIRED_URL_KEY.to_string(), en.url.clone());
+    en.generated_content.insert(TEXT_HI_KEY.to_string(), hi.text.clone());
+    en.generated_content.insert(PAIRED_URL_KEY.to_string(), hi.url.clone());
+}
+
+/// Holds back Hindi and English regulator documents until their other-language version
+/// arrives. Documents come out in arrival order, except that a paired document leaves together
+/// with its partner.
+#[derive(Default)]
+pub struct PairingBuffer {
+    pending: Vec<(Document, Option<String>)>,
+}
+
+impl PairingBuffer {
+    /// Add a language-tagged document; returns the documents ready to be forwarded.
+    pub fn push(&mut self, mut doc: Document) -> Vec<Document> {
+        let lang = doc_lang(&doc).to_string();
+        let already_paired = doc.generated_content.contains_key(PAIRED_URL_KEY)
+            || doc.generated_content.contains_key(TEXT_HI_KEY)
+            || doc.analysis.as_ref().is_some_and(|a| !a.text_en.is_empty());
+        if (lang != "hi" && lang != "en") || already_paired
+            || !PAIRED_MODULES.iter().any(|m| doc.module.eq_ignore_ascii_case(m)) {
+            return vec![doc];
+        }
+        let key = pair_key(&doc);
+        if let Some(k) = key.as_deref() {
+            let partner = self.pending.iter().position(|(other, other_key)| {
+                other.module == doc.module && other_key.as_deref() == Some(k)
+                    && doc_lang(other) != lang
+            });
+            if let Some(idx) = partner {
+                let (mut other, _) = self.pending.remove(idx);
+                if lang == "hi" { link_pair(&mut doc, &mut other) } else { link_pair(&mut other, &mut doc) }
+                debug!("{}: paired '{}' with '{}' by reference {}", PLUGIN_NAME, other.url, doc.url, k);
+                return vec![other, doc];
+            }
+        }
+        self.pending.push((doc, key));
+        if self.pending.len() > MAX_PENDING {
+            return vec![self.pending.remove(0).0];
+        }
+        Vec::new()
+    }
+
+    /// End of stream: pair the remaining documents that are the only Hindi and the only English
+    /// document of their module, publish date and section, then return everything still held.
+    pub fn drain(&mut self) -> Vec<Document> {
+        let mut groups: HashMap<(String, String, String), Vec<usize>> = HashMap::new();
+        for (idx, (doc, _)) in self.pending.iter().enumerate() {
+            if !doc.publish_date.is_empty() {
+                groups.entry((doc.module.clone(), doc.publish_date.clone(), doc.section_name.clone()))
+                    .or_default()
+                    .push(idx);
+            }
+        }
+        for members in groups.values() {
+            let hi: Vec<usize> = members.iter().copied().filter(|i| doc_lang(&self.pending[*i].0) == "hi").collect();
+            let en: Vec<usize> = members.iter().copied().filter(|i| doc_lang(&self.pending[*i].0) == "en").collect();
+            if hi.len() != 1 || en.len() != 1 {
+                continue;
+            }
+            let (hi_idx, en_idx) = (hi[0], en[0]);
+            // Two different reference numbers mean two different circulars.
+            if matches!((&self.pending[hi_idx].1, &self.pending[en_idx].1), (Some(a), Some(b)) if a != b) {
+                continue;
+            }
+            let mut en_doc = std::mem::take(&mut self.pending[en_idx].0);
+            link_pair(&mut self.pending[hi_idx].0, &mut en_doc);
+            self.pending[en_idx].0 = en_doc;
+        }
+        self.pending.drain(..).map(|(doc, _)| doc).collect()
+    }
+}
+
+/// Tag one document with its language, and split the text of a document printed in both
+/// Hindi and English.
+pub fn identify_language(doc: &mut Document) {
+    let sample = if doc.text.trim().is_empty() { doc.title.as_str() } else { doc.text.as_str() };
+    let guess = detect_language(sample);
+    let mut analysis = doc.analysis.take().unwrap_or_default();
+    if guess.is_bilingual() {
+        let (devanagari, latin) = split_by_script(&doc.text);
+        if guess.lang == "en" {
+            doc.generated_content.insert(TEXT_HI_KEY.to_string(), devanagari);
+        }
+        if analysis.text_en.is_empty() {
+            analysis.text_en = latin;
+        }
+    }
+    analysis.lang = guess.lang;
+    doc.analysis = Some(analysis);
+}
+
+/// Executes this function of the module in the separate thread launched by the pipeline to tag
+/// each document with its language and pair Hindi and English versions of regulator documents.
+///
+/// Documents of the regulator modules may be held until their other-language version arrives
+/// or the input channel closes; all others pass straight through.
+///
+/// # Arguments
+///
+/// * `tx`: Queue transmitter for the next thread
+/// * `rx`: Queue receiver for this thread
+/// * `_app_config`: The application's configuration object
+/// * `_api_mutexes`: Shared API rate-limit counters (unused)
+pub fn process_data(
+    tx: Sender<Document>,
+    rx: Receiver<Document>,
+    _app_config: &Config,
+    _api_mutexes: &mut HashMap<String, Arc<Mutex<isize>>>,
+) {
+    info!("{}: Starting language identification.", PLUGIN_NAME);
+    let mut doc_counter: u32 = 0;
+    let mut paired: u32 = 0;
+    let mut buffer = PairingBuffer::default();
+
+    let forward = |docs: Vec<Document>, doc_counter: &mut u32, paired: &mut u32| {
+        for doc in docs {
+            if doc.generated_content.contains_key(PAIRED_URL_KEY) {
+                *paired += 1;
+            }
+            match tx.send(doc) {
+                Ok(_) => *doc_counter += 1,
+                Err(e) => error!("{}: When sending processed doc via tx: {}", PLUGIN_NAME, e),
+            }
+        }
+    };
+
+    for mut doc in rx {
+        identify_language(&mut doc);
+        forward(buffer.push(doc), &mut doc_counter, &mut paired);
+    }
+    forward(buffer.drain(), &mut doc_counter, &mut paired);
+    info!("{}: Completed processing {} documents, {} of them in Hindi/English pairs.",
+        PLUGIN_NAME, doc_counter, paired);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENGLISH: &str = "The Reserve Bank of India has decided to revise the limits for the \
+        issue of prepaid payment instruments. All banks are advised to comply with these instructions.";
+    const HINDI: &str = "भारतीय रिज़र्व बैंक ने प्रीपेड भुगतान लिखतों के लिए सीमा में संशोधन करने का \
+        निर्णय लिया है। सभी बैंकों को इन निर्देशों का पालन करने की सलाह दी जाती है।";
+    const MARATHI: &str = "भारतीय रिझर्व्ह बँकेने प्रीपेड पेमेंट साधनांच्या मर्यादेत सुधारणा करण्याचा \
+        निर्णय घेतला आहे आणि सर्व बँकांना या सूचनांचे पालन करण्याचा सल्ला देण्यात आला आहे.";
+
+    fn regulator_doc(lang_text: &str, title: &str, url: &str) -> Document {
+        let mut doc = Document {
+            module: "mod_en_in_rbi".to_string(),
+            title: title.to_string(),
+            url: url.to_string(),
+            text: lang_text.to_string(),
+            publish_date: "2025-02-12".to_string(),
+            section_name: "Notifications".to_string(),
+            ..Default::default()
+        };
+        identify_language(&mut doc);
+        doc
+    }
+
+    #[test]
+    fn test_detect_language_by_script_and_ngrams() {
+        assert_eq!(detect_language(ENGLISH).lang, "en");
+        assert_eq!(detect_language(HINDI).lang, "hi");
+        assert_eq!(detect_language(MARATHI).lang, "mr");
+        assert_eq!(detect_language("தமிழ்நாடு அரசு அறிவிப்பு").lang, "ta");
+        assert_eq!(detect_language("12345 -- 678").lang, "und");
+        assert!(detect_language(ENGLISH).confidence > 0.5);
+    }
+
+    #[test]
+    fn test_reference_key_is_language_neutral() {
+        assert_eq!(reference_key("RBI/2024-25/123 dated Feb 12"), Some("2024-25-123".to_string()));
+        assert_eq!(reference_key("आरबीआई/२०२४-२५/१२३"), Some("2024-25-123".to_string()));
+        assert_eq!(reference_key("G.S.R. 612(E)"), reference_key("सा.का.नि. 612(अ)"));
+        assert_eq!(reference_key("dated 12/02/2025"), None, "plain dates are not references");
+    }
+
+    #[test]
+    fn test_pairing_by_reference_number() {
+        let mut buffer = PairingBuffer::default();
+        let en = regulator_doc(&format!("RBI/2024-25/123\n{}", ENGLISH), "Prepaid Payment Instruments", "https://rbi.org.in/en/123");
+        let hi = regulator_doc(&format!("आरबीआई/2024-25/123\n{}", HINDI), "प्रीपेड भुगतान लिखत", "https://rbi.org.in/hi/123");
+        assert!(buffer.push(en).is_empty(), "first version is held for its partner");
+        let out = buffer.push(hi);
+        assert_eq!(out.len(), 2);
+        let hi = &out[1];
+        assert_eq!(hi.analysis.as_ref().unwrap().lang, "hi");
+        assert!(hi.english_text().starts_with("RBI/2024-25/123"));
+        assert_eq!(out[0].generated_content[PAIRED_URL_KEY], "https://rbi.org.in/hi/123");
+        assert!(out[0].generated_content[TEXT_HI_KEY].contains("रिज़र्व"));
+        assert!(buffer.drain().is_empty());
+    }
+
+    #[test]
+    fn test_pairing_falls_back_to_date_and_section() {
+        let mut buffer = PairingBuffer::default();
+        assert!(buffer.push(regulator_doc(ENGLISH, "Prepaid Payment Instruments", "https://rbi.org.in/en/1")).is_empty());
+        assert!(buffer.push(regulator_doc(HINDI, "प्रीपेड भुगतान लिखत", "https://rbi.org.in/hi/1")).is_empty());
+        let mut news = regulator_doc(ENGLISH, "Unrelated", "https://example.com/news");
+        news.module = "mod_en_bbc".to_string();
+        assert_eq!(buffer.push(news).len(), 1, "non-regulator documents pass straight through");
+        let out = buffer.drain();
+        assert_eq!(out.len(), 2);
+        assert_eq!(out[1].analysis.as_ref().unwrap().text_en, ENGLISH);
+        assert_eq!(out[0].generated_content[PAIRED_URL_KEY], "https://rbi.org.in/hi/1");
+    }
+
+    #[test]
+    fn test_bilingual_document_is_split_by_script() {
+        let english_summary = "Prepaid payment instruments: revised limits.";
+        let mut doc = Document { text: format!("{}\n{}\n", HINDI, english_summary), ..Default::default() };
+        identify_language(&mut doc);
+        let analysis = doc.analysis.as_ref().unwrap();
+        assert_eq!(analysis.lang, "hi");
+        assert_eq!(analysis.text_en, english_summary);
+        assert_eq!(doc.english_text(), english_summary);
+
+        let mut doc = Document { text: format!("{}\n{}\n", ENGLISH, HINDI), ..Default::default() };
+        identify_language(&mut doc);
+        assert_eq!(doc.analysis.as_ref().unwrap().lang, "en");
+        assert_eq!(doc.generated_content[TEXT_HI_KEY], HINDI);
+    }
+
+    #[test]
+    fn test_english_dominant_bilingual_document_reads_without_hindi_lines() {
+        let mut doc = Document { text: format!("{}\n{}\n", ENGLISH, HINDI), ..Default::default() };
+        identify_language(&mut doc);
+        assert_eq!(doc.analysis.as_ref().unwrap().lang, "en");
+        assert_eq!(doc.english_text(), ENGLISH);
+        assert!(!doc.english_text().contains("रिज़र्व"));
+        assert!(doc.text.contains("रिज़र्व"), "the source text is kept as published");
+    }
+}
@@ -11,19 +11,26 @@
 //
 //   This stage is intentionally dependency-free and deterministic (good for tests). The tone
 //   lexicon is a small finance/news starter set; mod_tone (Stage 5) supersedes it with GCAM.
+//   A lexicon file (see `vocab`) can replace it; its version is stamped on `documents` rows.
+//
+// Config keys:
+//   lexicon_file    - Tone lexicon replacing the embedded one; reloaded when it changes.
 
 use std::collections::HashMap;
 use std::sync::mpsc::{Receiver, Sender};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, OnceLock};
 
 use config::Config;
 use log::{error, info};
 
 use crate::cfg::get_database_filename;
 use crate::document::Document;
+use crate::get_plugin_cfg;
 use crate::store::records::{insert_mention, upsert_document, DocumentRow};
+use crate::vocab::{ToneLexicon, VocabSource};
 
 pub const PLUGIN_NAME: &str = "mod_mentions";
+pub const LEXICON_VERSION: &str = "2026.06.1";
 
 /// Minimum text length (chars) before we bother computing tone / a mention.
 const MIN_TEXT_LEN: usize = 80;
@@ -36,6 +43,7 @@ pub fn process_data(
 ) {
     info!("{}: Starting mentions + tone extraction.", PLUGIN_NAME);
     let db_path = get_database_filename(config);
+    let mut lexicon = VocabSource::new(PLUGIN_NAME, get_plugin_cfg!(PLUGIN_NAME, "lexicon_file", config), default_lexicon().clone());
 
     // Open one connection for the lifetime of this stage.
     let conn = match crate::store::open(&db_path) {
@@ -48,9 +56,10 @@ pub fn process_data(
 
     let mut count: usize = 0;
     for mut doc in rx {
+        lexicon.refresh();
         if let Some(ref conn) = conn {
-            if doc.text.len() >= MIN_TEXT_LEN {
-                let (tone, word_count) = lexicon_tone(&doc.text);
+            if doc.english_text().len() >= MIN_TEXT_LEN {
+                let (tone, word_count) = lexicon_tone_with(lexicon.get(), doc.english_text());
                 let did = doc_id_for(&doc);
                 let cluster = cluster_id_for(&doc, &did);
 
@@ -67,7 +76,7 @@ pub fn process_data(
                     url: doc.url.clone(),
                     source: doc.source_name.first().cloned().unwrap_or_default(),
                     title: doc.title.clone(),
-                    lang: String::new(),
+                    lang: doc.analysis.as_ref().map(|a| a.lang.clone()).unwrap_or_default(),
                     pubdate_ms: doc.publish_date_ms,
                     pubdate: doc.publish_date.clone(),
                     plugin: doc.module.clone(),
@@ -75,6 +84,7 @@ pub fn process_data(
                     cluster_id: cluster.clone(),
                     tone,
                     word_count: word_count as i64,
+                    vocab_version: Some(lexicon.get().version.clone()),
                 };
                 if let Err(e) = upsert_document(conn, &row) {
                     error!("{}: {}", PLUGIN_NAME, e);
@@ -102,14 +112,18 @@ pub fn doc_id_for(doc: &Document) -> String {
     format!("u{:016x}", fnv1a(&doc.url))
 }
 
-/// The cluster (story/event) a document belongs to. mod_dedupe tags near-duplicates with
-/// `duplicate_of` = the unique_id of the representative document; non-duplicates form their
-/// own singleton cluster keyed by their own doc_id.
+/// The cluster (story/event) a document belongs to. mod_dedupe tags each document with its
+/// persistent `cluster_id` (stable across runs), and near-duplicates with `duplicate_of` = the
+/// unique_id of the document they matched; otherwise a document forms its own singleton
+/// cluster keyed by its own doc_id.
 pub fn cluster_id_for(doc: &Document, did: &str) -> String {
-    match doc.classification.get("duplicate_of") {
-        Some(rep) if !rep.trim().is_empty() => rep.trim().to_string(),
-        _ => did.to_string(),
-    }
+    ["cluster_id", "duplicate_of"]
+        .iter()
+        .filter_map(|key| doc.classification.get(*key))
+        .map(|id| id.trim())
+        .find(|id| !id.is_empty())
+        .unwrap_or(did)
+        .to_string()
 }
 
 fn fnv1a(s: &str) -> u64 {
@@ -137,9 +151,20 @@ const NEGATIVE: &[&str] = &[
     "plunge", "plunged", "crash", "slowdown", "lawsuit", "scam", "breach", "violation", "miss",
 ];
 
+/// The embedded starter lexicon.
+pub fn default_lexicon() -> &'static ToneLexicon {
+    static LEXICON: OnceLock<ToneLexicon> = OnceLock::new();
+    LEXICON.get_or_init(|| ToneLexicon::from_static("starter", LEXICON_VERSION, POSITIVE, NEGATIVE, &[]))
+}
+
+/// Cheap tone with the embedded lexicon (see `lexicon_tone_with`).
+pub fn lexicon_tone(text: &str) -> (f64, u64) {
+    lexicon_tone_with(default_lexicon(), text)
+}
+
 /// Cheap lexicon tone in [-10, 10] plus the word count.
 /// tone = 10 * (pos - neg) / (pos + neg); 0 when no lexicon words are present.
-pub fn lexicon_tone(text: &str) -> (f64, u64) {
+pub fn lexicon_tone_with(lexicon: &ToneLexicon, text: &str) -> (f64, u64) {
     let mut pos = 0u64;
     let mut neg = 0u64;
     let mut words = 0u64;
@@ -148,11 +173,10 @@ pub fn lexicon_tone(text: &str) -> (f64, u64) {
             continue;
         }
         words += 1;
-        let w = raw.to_lowercase();
-        if POSITIVE.contains(&w.as_str()) {
-            pos += 1;
-        } else if NEGATIVE.contains(&w.as_str()) {
-            neg += 1;
+        match lexicon.polarity(&raw.to_lowercase()) {
+            1 => pos += 1,
+            -1 => neg += 1,
+            _ => {}
         }
     }
     let tone = if pos + neg == 0 {
@@ -209,6 +233,9 @@ mod tests {
 
         let d2 = Document::default();
         assert_eq!(cluster_id_for(&d2, "D9"), "D9", "singleton clusters on own id");
+
+        d.classification.insert("cluster_id".into(), "REP-0".into());
+        assert_eq!(cluster_id_for(&d, "D9"), "REP-0", "persistent cluster wins");
     }
 
     #[test]
@@ -1,32 +1,59 @@
 // file: mod_ner.rs
 // Purpose:
-//   Phase-3 organization recogniser (roadmap Stage 7 / D1), deterministic rule-based variant.
-//   A model-backed NER (ONNX/LLM-JSON) is planned, but this dependency-free recogniser gives the
-//   entity graph and resolver something to work on now and is fully unit-testable. It finds
-//   organizations two ways:
-//     1. a curated gazetteer of Indian regulators / exchanges / major financial entities
-//        (acronyms like RBI/SEBI and full names), and
-//     2. corporate-suffix patterns ("<Capitalised words> Ltd/Limited/Bank/Corporation/...").
-//   Each organization becomes an `EntityMention` on `doc.analysis.organizations` (and its surface
-//   form is added to `all_names`). The canonical `entity_id` stays `None` here — false links are
-//   worse than nulls — and a *provisional* surface-key id is assigned only at persistence time
-//   (see `provisional_entity_id`) so `mod_entity_graph` / `mod_emit_tables` can group mentions
-//   before `mod_entity_resolve` (LEI/CIN) supplies real ids.
-
-use std::collections::HashMap;
+//   Phase-3 named-entity recogniser (roadmap Stage 7 / D1). Two layers:
+//     1. deterministic organisation rules, always on: a curated gazetteer of Indian
+//        regulators / exchanges / major financial entities (acronyms like RBI/SEBI and full
+//        names), and corporate-suffix patterns ("<Capitalised words> Ltd/Limited/Bank/...");
+//     2. optionally, an ONNX token-classification model (BERT-style, BIO labels such as
+//        B-PER / I-ORG / B-LOC / B-MISC, CoNLL or OntoNotes label sets) run through the same
+//        `ort` session loader and `BertTokenizer` as `mod_vectorstore`. It finds persons,
+//        organisations, locations and other proper names. Model organisations overlapping a
+//        rule hit are dropped, so gazetteer canonical names win ("RBI" and "Reserve Bank of
+//        India" stay one entity).
+//   Results land on `doc.analysis`: `organizations` and `persons` as `EntityMention`s (one per
+//   distinct entity, earliest offset, salience = occurrences / max-occurrences of that type,
+//   and the entity's targeted tone from `mod_tone::mention_tone` as `sentiment`), `locations`
+//   as unresolved `GeoMention`s (names `mod_geocode` already placed are not repeated), and
+//   every surface form in `all_names`. The canonical `entity_id` stays `None`
+//   here — false links are worse than nulls — and a *provisional* surface-key id is assigned
+//   only at persistence time (see `provisional_entity_id`) so `mod_entity_graph` /
+//   `mod_emit_tables` can group mentions before `mod_entity_resolve` (LEI/CIN) supplies real
+//   ids.
+//
+// Config keys:
+//   ner_model_dir   - Dir with model.onnx, vocab.txt and config.json (`id2label`); an optional
+//                     tokenizer_config.json sets `do_lower_case`. Unset: rules only.
+//   ner_min_score   - Mean label probability below which a model span is dropped (default 0.5).
+//   org_gazetteer_file - Organisation gazetteer CSV (see `vocab`) replacing KNOWN_ORGS; reloaded
+//                     when it changes. Its version is stamped on organisation mention rows.
+//                     Targeted sentiment uses `mod_tone`'s `lexicon_file`.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
 use std::sync::mpsc::{Receiver, Sender};
 use std::sync::{Arc, Mutex, OnceLock};
 
 use config::Config;
-use log::{error, info};
+use log::{error, info, warn};
+use ort::session::Session;
+use ort::value::Tensor;
 use regex::Regex;
+use serde_json::Value;
 
-use crate::analysis::{norm_name, provisional_entity_id, EntityMention};
+use crate::analysis::{norm_name, DocAnalysis, EntityMention, GeoMention};
 use crate::document::Document;
+use crate::get_plugin_cfg;
+use crate::plugins::mod_tone::{configured_lexicon, default_lexicon, mention_tone_with};
+use crate::vocab::{OrgGazetteer, ToneLexicon, VocabSource, Vocabulary};
+use crate::plugins::mod_vectorstore::{load_session, word_spans, BertTokenizer};
 
 pub const PLUGIN_NAME: &str = "mod_ner";
 
 const MIN_TEXT_LEN: usize = 40;
+/// Word pieces per model window; longer texts are classified window by window.
+const MAX_SEQ_LEN: usize = 256;
+const DEFAULT_MIN_SCORE: f32 = 0.5;
+pub const KNOWN_ORGS_VERSION: &str = "2026.06.1";
 
 /// Curated organisation gazetteer: (surface/acronym, canonical name). Matched whole-word,
 /// case-insensitively. Canonical name is used for the provisional id so "RBI" and
@@ -59,24 +86,38 @@ const KNOWN_ORGS: &[(&str, &str)] = &[
 pub fn process_data(
     tx: Sender<Document>,
     rx: Receiver<Document>,
-    _config: &Config,
+    app_config: &Config,
     _api_mutexes: &mut HashMap<String, Arc<Mutex<isize>>>,
 ) {
-    info!("{}: Starting rule-based organization NER.", PLUGIN_NAME);
+    let mut model = get_plugin_cfg!(PLUGIN_NAME, "ner_model_dir", app_config)
+        .filter(|dir| !dir.trim().is_empty())
+        .and_then(|dir| NerModel::load(&dir));
+    let min_score = get_plugin_cfg!(PLUGIN_NAME, "ner_min_score", app_config)
+        .and_then(|v| v.parse::<f32>().ok())
+        .unwrap_or(DEFAULT_MIN_SCORE);
+    let mut known_orgs = VocabSource::new(PLUGIN_NAME, get_plugin_cfg!(PLUGIN_NAME, "org_gazetteer_file", app_config), default_orgs().clone());
+    let mut lexicon = configured_lexicon(PLUGIN_NAME, app_config);
+    match &model {
+        Some(m) => info!("{}: Starting model-backed NER ({} labels) with organization rules.", PLUGIN_NAME, m.labels.len()),
+        None => info!("{}: Starting rule-based organization NER.", PLUGIN_NAME),
+    }
     let mut docs = 0usize;
     let mut ents = 0usize;
     for mut doc in rx {
-        if doc.text.len() >= MIN_TEXT_LEN {
-            let orgs = extract_orgs(&doc.text);
-            if !orgs.is_empty() {
+        known_orgs.refresh();
+        lexicon.refresh();
+        let text = doc.english_text();
+        if text.len() >= MIN_TEXT_LEN {
+            let spans = match model.as_mut() {
+                Some(m) => m.predict(text, min_score),
+                None => Vec::new(),
+            };
+            let found = merge_entities_with(known_orgs.get(), lexicon.get(), text, &spans);
+            if !found.is_empty() {
                 let mut analysis = doc.analysis.take().unwrap_or_default();
-                ents += orgs.len();
-                for o in &orgs {
-                    if !analysis.all_names.contains(&o.surface_form) {
-                        analysis.all_names.push(o.surface_form.clone());
-                    }
-                }
-                analysis.organizations.extend(orgs);
+                ents += found.len();
+                found.apply_to(&mut analysis);
+                analysis.vocab_versions.insert(OrgGazetteer::KIND.to_string(), known_orgs.get().version.clone());
                 doc.analysis = Some(analysis);
                 docs += 1;
             }
@@ -85,7 +126,307 @@ pub fn process_data(
             error!("{}: when forwarding doc: {}", PLUGIN_NAME, e);
         }
     }
-    info!("{}: Completed. Found {} organization mention(s) in {} document(s).", PLUGIN_NAME, ents, docs);
+    info!("{}: Completed. Found {} entity mention(s) in {} document(s).", PLUGIN_NAME, ents, docs);
+}
+
+/// Entities recognised in one text, one entry per distinct entity of each kind.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecognisedEntities {
+    pub persons: Vec<EntityMention>,
+    pub organizations: Vec<EntityMention>,
+    pub locations: Vec<EntityMention>,
+    /// Other proper names: MISC, or NORP / EVENT / LAW / PRODUCT ... in OntoNotes label sets.
+    pub others: Vec<EntityMention>,
+}
+
+impl RecognisedEntities {
+    pub fn len(&self) -> usize {
+        self.persons.len() + self.organizations.len() + self.locations.len() + self.others.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Add these entities to a document's analysis. Locations already present (e.g. from
+    /// `mod_geocode`) are kept as they are; new ones are added without coordinates, which
+    /// `mod_geocode::merge_locations` fills in if the geocoder runs afterwards.
+    pub fn apply_to(self, analysis: &mut DocAnalysis) {
+        for m in self.persons.iter().chain(&self.organizations).chain(&self.others) {
+            if !analysis.all_names.contains(&m.surface_form) {
+                analysis.all_names.push(m.surface_form.clone());
+            }
+        }
+        for loc in self.locations {
+            let key = norm_name(&loc.surface_form);
+            if !analysis.locations.iter().any(|l| norm_name(&l.name) == key) {
+                analysis.locations.push(GeoMention {
+                    name: loc.surface_form,
+                    char_offset: loc.char_offset,
+                    ..Default::default()
+                });
+            }
+        }
+        analysis.persons.extend(self.persons);
+        analysis.organizations.extend(self.organizations);
+    }
+}
+
+/// An entity span predicted by the model: byte range in the text, entity label without its
+/// BIO prefix ("PER", "ORG", "LOC", ...) and the mean probability of its words' labels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamedSpan {
+    pub start: usize,
+    pub end: usize,
+    pub label: String,
+    pub score: f32,
+}
+
+/// ONNX token-classification model with its tokenizer and label set.
+pub struct NerModel {
+    session: Session,
+    tokenizer: BertTokenizer,
+    labels: Vec<String>,
+    lowercase: bool,
+    /// Whether the exported graph takes a third `token_type_ids` input.
+    token_types: bool,
+}
+
+impl NerModel {
+    /// Load model.onnx, vocab.txt and the label map from `model_dir`. None (with a warning)
+    /// if any of them is missing, in which case only the rules run.
+    pub fn load(model_dir: &str) -> Option<Self> {
+        let config_path = format!("{}/config.json", model_dir);
+        let labels = fs::read_to_string(&config_path)
+            .ok()
+            .and_then(|t| serde_json::from_str::<Value>(&t).ok())
+            .and_then(|v| labels_from_config(&v));
+        let labels = match labels {
+            Some(l) => l,
+            None => {
+                warn!("{}: no id2label map in '{}'; using rules only.", PLUGIN_NAME, config_path);
+                return None;
+            }
+        };
+        let tokenizer = BertTokenizer::from_dir(model_dir)?;
+        let session = load_session(model_dir, PLUGIN_NAME)?;
+        let lowercase = fs::read_to_string(format!("{}/tokenizer_config.json", model_dir))
+            .ok()
+            .and_then(|t| serde_json::from_str::<Value>(&t).ok())
+            .and_then(|v| v.get("do_lower_case").and_then(Value::as_bool))
+            .unwrap_or(false);
+        let token_types = session.inputs().len() > 2;
+        Some(NerModel { session, tokenizer, labels, lowercase, token_types })
+    }
+
+    /// Entity spans in `text`, without those whose mean label probability is below
+    /// `min_score`. Each word takes the label of its first word piece.
+    pub fn predict(&mut self, text: &str, min_score: f32) -> Vec<NamedSpan> {
+        let spans = word_spans(text);
+        let words: Vec<String> = spans
+            .iter()
+            .map(|&(s, e)| if self.lowercase { text[s..e].to_lowercase() } else { text[s..e].to_string() })
+            .collect();
+        let mut word_labels: Vec<(String, f32)> = vec![("O".to_string(), 1.0); words.len()];
+
+        let mut next = 0;
+        while next < words.len() {
+            let window: Vec<&str> = words[next..].iter().take(MAX_SEQ_LEN).map(String::as_str).collect();
+            let (ids, word_of) = self.tokenizer.encode_words(&window, MAX_SEQ_LEN);
+            let consumed = word_of.iter().flatten().max().map(|w| w + 1).unwrap_or(0);
+            if consumed == 0 {
+                // A single word longer than the window: leave it unlabelled.
+                next += 1;
+                continue;
+            }
+            let token_labels = match self.classify(&ids) {
+                Ok(l) => l,
+                Err(e) => {
+                    warn!("{}: {}; using rules only for this document.", PLUGIN_NAME, e);
+                    return Vec::new();
+                }
+            };
+            for (t, w) in word_of.iter().enumerate() {
+                if let Some(w) = w
+                    && word_of[t - 1] != Some(*w)
+                    && let Some(label) = token_labels.get(t)
+                {
+                    word_labels[next + w] = label.clone();
+                }
+            }
+            next += consumed;
+        }
+        decode_bio(&spans, &word_labels).into_iter().filter(|s| s.score >= min_score).collect()
+    }
+
+    /// Most likely label and its probability for each token of one window.
+    fn classify(&mut self, ids: &[i64]) -> Result<Vec<(String, f32)>, String> {
+        let len = ids.len();
+        let tensor = |values: Vec<i64>| Tensor::<i64>::from_array(([1, len], values)).map_err(|e| format!("tensor build failed: {}", e));
+        let (ids_tensor, mask_tensor) = (tensor(ids.to_vec())?, tensor(vec![1; len])?);
+        let outputs = if self.token_types {
+            let types_tensor = tensor(vec![0; len])?;
+            self.session.run(ort::inputs![ids_tensor, mask_tensor, types_tensor])
+        } else {
+            self.session.run(ort::inputs![ids_tensor, mask_tensor])
+        }
+        .map_err(|e| format!("ONNX inference failed: {}", e))?;
+
+        // Output 0: logits [1, seq_len, num_labels]
+        let (shape, logits) = outputs[0].try_extract_tensor::<f32>().map_err(|e| format!("tensor extract failed: {}", e))?;
+        if shape.len() < 3 || shape[1] as usize != len {
+            return Err(format!("unexpected output shape {:?}", shape));
+        }
+        let n_labels = shape[2] as usize;
+        Ok((0..len).map(|t| best_label(&logits[t * n_labels..(t + 1) * n_labels], &self.labels)).collect())
+    }
+}
+
+/// Label list indexed by class id, from a Hugging Face `config.json` (`id2label`).
+pub(crate) fn labels_from_config(config: &Value) -> Option<Vec<String>> {
+    let map = config.get("id2label")?.as_object()?;
+    let mut labels = vec!["O".to_string(); map.len()];
+    for (id, label) in map {
+        let slot = labels.get_mut(id.parse::<usize>().ok()?)?;
+        *slot = label.as_str()?.to_string();
+    }
+    if labels.is_empty() { None } else { Some(labels) }
+}
+
+/// Softmax arg-max over one token's logits.
+fn best_label(logits: &[f32], labels: &[String]) -> (String, f32) {
+    let max = logits.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+    let sum: f32 = logits.iter().map(|l| (l - max).exp()).sum();
+    let (best, logit) = logits
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .unwrap_or((0, &max));
+    let label = labels.get(best).cloned().unwrap_or_else(|| "O".to_string());
+    (label, (logit - max).exp() / sum)
+}
+
+/// Group per-word BIO labels into entity spans. `word_labels[i]` is the (label, probability)
+/// of the word at byte span `words[i]`. A span runs over consecutive words of one entity type
+/// and ends at "O", at a "B-"/"S-" label, or where the type changes; a label without a prefix
+/// ("PER") continues like "I-".
+pub fn decode_bio(words: &[(usize, usize)], word_labels: &[(String, f32)]) -> Vec<NamedSpan> {
+    let mut out = Vec::new();
+    // (span, sum of word probabilities, word count)
+    let mut current: Option<(NamedSpan, f32, usize)> = None;
+    let flush = |current: &mut Option<(NamedSpan, f32, usize)>, out: &mut Vec<NamedSpan>| {
+        if let Some((mut span, sum, n)) = current.take() {
+            span.score = sum / n as f32;
+            out.push(span);
+        }
+    };
+    for (&(start, end), (label, p)) in words.iter().zip(word_labels) {
+        let (prefix, kind) = match label.split_once('-') {
+            Some((pre, kind)) if pre.len() == 1 => (pre, kind),
+            _ => ("I", label.as_str()),
+        };
+        if kind.eq_ignore_ascii_case("O") {
+            flush(&mut current, &mut out);
+            continue;
+        }
+        match current.as_mut() {
+            Some((span, sum, n)) if !matches!(prefix, "B" | "S") && span.label == kind => {
+                span.end = end;
+                *sum += p;
+                *n += 1;
+            }
+            _ => {
+                flush(&mut current, &mut out);
+                current = Some((NamedSpan { start, end, label: kind.to_string(), score: 0.0 }, *p, 1));
+            }
+        }
+    }
+    flush(&mut current, &mut out);
+    out
+}
+
+/// Entity category of a model label.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Person,
+    Org,
+    Location,
+    Other,
+}
+
+/// Category of a label; None for the numeric / temporal classes of OntoNotes label sets,
+/// which other extractors handle.
+fn kind_of(label: &str) -> Option<Kind> {
+    match label.to_ascii_uppercase().as_str() {
+        "PER" | "PERSON" => Some(Kind::Person),
+        "ORG" => Some(Kind::Org),
+        "LOC" | "GPE" | "FAC" => Some(Kind::Location),
+        "DATE" | "TIME" | "MONEY" | "PERCENT" | "QUANTITY" | "CARDINAL" | "ORDINAL" => None,
+        _ => Some(Kind::Other),
+    }
+}
+
+/// One occurrence of an entity: normalised key, display name, byte range.
+#[derive(Debug, Clone)]
+struct Hit {
+    key: String,
+    name: String,
+    start: usize,
+    end: usize,
+}
+
+/// Merge model spans with the organisation rules, using the embedded gazetteer and lexicon
+/// (see `merge_entities_with`).
+pub fn merge_entities(text: &str, spans: &[NamedSpan]) -> RecognisedEntities {
+    merge_entities_with(default_orgs(), default_lexicon(), text, spans)
+}
+
+/// Merge model spans with the organisation rules. Model organisations overlapping a rule hit
+/// are dropped; a person named by surname only ("Das") joins the full name mentioned
+/// elsewhere in the text ("Shaktikanta Das") when exactly one such name exists. Targeted
+/// sentiment is scored with `lexicon`.
+pub fn merge_entities_with(known_orgs: &OrgGazetteer, lexicon: &ToneLexicon, text: &str, spans: &[NamedSpan]) -> RecognisedEntities {
+    let rule_hits = rule_org_hits(known_orgs, text);
+    let mut orgs = rule_hits.clone();
+    let (mut persons, mut locations, mut others) = (Vec::new(), Vec::new(), Vec::new());
+    for span in spans {
+        let Some(surface) = text.get(span.start..span.end) else { continue };
+        let key = norm_name(surface);
+        if key.is_empty() {
+            continue;
+        }
+        let hit = Hit { key, name: surface.to_string(), start: span.start, end: span.end };
+        match kind_of(&span.label) {
+            Some(Kind::Org) if rule_hits.iter().any(|h| h.start < hit.end && hit.start < h.end) => {}
+            Some(Kind::Org) => orgs.push(hit),
+            Some(Kind::Person) => persons.push(hit),
+            Some(Kind::Location) => locations.push(hit),
+            Some(Kind::Other) => others.push(hit),
+            None => {}
+        }
+    }
+
+    let full_names: Vec<(String, String)> = persons
+        .iter()
+        .filter(|h| h.key.contains(' '))
+        .map(|h| (h.key.clone(), h.name.clone()))
+        .collect();
+    for p in persons.iter_mut().filter(|h| !h.key.contains(' ')) {
+        let suffix = format!(" {}", p.key);
+        let mut owners: Vec<&(String, String)> = full_names.iter().filter(|(k, _)| k.ends_with(&suffix)).collect();
+        owners.dedup_by(|a, b| a.0 == b.0);
+        if let [(key, name)] = owners.as_slice() {
+            p.key = key.clone();
+            p.name = name.clone();
+        }
+    }
+
+    RecognisedEntities {
+        persons: aggregate(lexicon, text, persons, "PERSON"),
+        organizations: aggregate(lexicon, text, orgs, "ORG"),
+        locations: aggregate(lexicon, text, locations, "LOC"),
+        others: aggregate(lexicon, text, others, "MISC"),
+    }
 }
 
 /// Matches "<Capitalised words> <corporate suffix>", e.g. "Tata Consultancy Services Ltd",
@@ -100,17 +441,31 @@ fn org_suffix_re() -> &'static Regex {
     })
 }
 
-/// Extract organisation mentions. Each distinct organisation (by normalised canonical name) is
-/// returned once, at its earliest offset, with salience = occurrences / max-occurrences.
+/// Extract organisation mentions with the rules alone. Each distinct organisation (by
+/// normalised canonical name) is returned once, at its earliest offset, with salience =
+/// occurrences / max-occurrences.
 pub fn extract_orgs(text: &str) -> Vec<EntityMention> {
-    // canonical-norm -> (surface_form, earliest_offset, count)
-    let mut found: HashMap<String, (String, usize, u32)> = HashMap::new();
+    aggregate(default_lexicon(), text, rule_org_hits(default_orgs(), text), "ORG")
+}
+
+/// The embedded organisation gazetteer.
+pub fn default_orgs() -> &'static OrgGazetteer {
+    static ORGS: OnceLock<OrgGazetteer> = OnceLock::new();
+    ORGS.get_or_init(|| OrgGazetteer::from_static(KNOWN_ORGS_VERSION, KNOWN_ORGS))
+}
+
+/// Organisation occurrences found by the gazetteer and the corporate-suffix patterns. A
+/// suffix match overlapping a gazetteer hit is dropped ("Reserve Bank" inside "Reserve Bank
+/// of India"). Gazetteer matching lowercases ASCII letters only, so that hit offsets are
+/// byte offsets into `text` itself.
+fn rule_org_hits(known_orgs: &OrgGazetteer, text: &str) -> Vec<Hit> {
+    let mut hits: Vec<Hit> = Vec::new();
 
     // 1. Gazetteer hits.
-    let lower = text.to_lowercase();
+    let lower = text.to_ascii_lowercase();
     let bytes = lower.as_bytes();
-    for (surface, canonical) in KNOWN_ORGS {
-        let needle = surface.to_lowercase();
+    for (surface, canonical) in &known_orgs.orgs {
+        let needle = surface.to_ascii_lowercase();
         let mut start = 0;
         while let Some(rel) = lower[start..].find(&needle) {
             let idx = start + rel;
@@ -118,41 +473,48 @@ pub fn extract_orgs(text: &str) -> Vec<EntityMention> {
             let after = idx + needle.len();
             let after_ok = after >= bytes.len() || !is_word_byte(bytes[after]);
             if before_ok && after_ok {
-                let key = norm_name(canonical);
-                let e = found.entry(key).or_insert(((*canonical).to_string(), idx, 0));
-                e.1 = e.1.min(idx);
-                e.2 += 1;
+                hits.push(Hit { key: norm_name(canonical), name: canonical.clone(), start: idx, end: after });
             }
-            start = idx + 1;
+            start = idx + lower[idx..].chars().next().map_or(1, char::len_utf8);
             if start >= lower.len() {
                 break;
             }
         }
     }
+    let gazetteer_hits = hits.len();
 
     // 2. Corporate-suffix patterns.
     for caps in org_suffix_re().captures_iter(text) {
         if let Some(m) = caps.get(1) {
             let surface = m.as_str().trim_end_matches('.').trim().to_string();
             let key = norm_name(&surface);
-            if key.is_empty() {
-                continue;
+            let overlaps = hits[..gazetteer_hits].iter().any(|h| h.start < m.end() && m.start() < h.end);
+            if !key.is_empty() && !overlaps {
+                hits.push(Hit { key, name: surface, start: m.start(), end: m.end() });
             }
-            let e = found.entry(key).or_insert((surface, m.start(), 0));
-            e.1 = e.1.min(m.start());
-            e.2 += 1;
         }
     }
+    hits
+}
 
-    let max_count = found.values().map(|(_, _, c)| *c).max().unwrap_or(1).max(1);
+/// Collapse occurrences into one mention per key: display name of the first occurrence
+/// found, earliest offset, salience = distinct occurrences / max-occurrences, and the
+/// targeted tone around all its occurrences (`mod_tone::mention_tone_with`).
+fn aggregate(lexicon: &ToneLexicon, text: &str, hits: Vec<Hit>, entity_type: &str) -> Vec<EntityMention> {
+    let mut found: HashMap<String, (String, BTreeSet<usize>)> = HashMap::new();
+    for h in hits {
+        found.entry(h.key).or_insert_with(|| (h.name, BTreeSet::new())).1.insert(h.start);
+    }
+    let max_count = found.values().map(|(_, offsets)| offsets.len()).max().unwrap_or(1).max(1);
     let mut out: Vec<EntityMention> = found
         .into_values()
-        .map(|(surface, offset, count)| EntityMention {
+        .map(|(surface, offsets)| EntityMention {
             surface_form: surface,
-            entity_type: "ORG".to_string(),
-            char_offset: offset,
-            salience: count as f64 / max_count as f64,
+            entity_type: entity_type.to_string(),
+            char_offset: offsets.first().copied().unwrap_or(0),
+            salience: offsets.len() as f64 / max_count as f64,
             entity_id: None,
+            sentiment: mention_tone_with(lexicon, text, &offsets.iter().copied().collect::<Vec<_>>()),
         })
         .collect();
     out.sort_by_key(|m| m.char_offset);
@@ -166,6 +528,7 @@ fn is_word_byte(b: u8) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::analysis::provisional_entity_id;
 
     fn surfaces(text: &str) -> Vec<String> {
         extract_orgs(text).into_iter().map(|m| m.surface_form).collect()
@@ -200,8 +563,92 @@ mod tests {
         assert_eq!(sbi.salience, 1.0, "most frequent org has salience 1.0");
     }
 
+    #[test]
+    fn test_gazetteer_offsets_index_the_original_text() {
+        // "İ" grows by a byte when fully lowercased, which used to shift the offsets after it.
+        let text = "İstanbul bankers met the Reserve Bank of India and the RBI governor.";
+        let found: Vec<&str> = rule_org_hits(default_orgs(), text)
+            .iter()
+            .filter(|h| h.name == "Reserve Bank of India")
+            .map(|h| &text[h.start..h.end])
+            .collect();
+        assert_eq!(found, vec!["RBI", "Reserve Bank of India"]);
+    }
+
     #[test]
     fn test_no_orgs_in_plain_text() {
         assert!(extract_orgs("the weather was calm and the river flowed gently past the village").is_empty());
     }
+
+    fn labelled(text: &str, labels: &[&str]) -> Vec<NamedSpan> {
+        let words = word_spans(text);
+        assert_eq!(words.len(), labels.len(), "one label per word");
+        let word_labels: Vec<(String, f32)> = labels.iter().map(|l| (l.to_string(), 0.9)).collect();
+        decode_bio(&words, &word_labels)
+    }
+
+    #[test]
+    fn test_decode_bio_groups_words() {
+        let text = "Shaktikanta Das met Tata Steel in Mumbai";
+        let spans = labelled(text, &["B-PER", "I-PER", "O", "B-ORG", "I-ORG", "O", "B-LOC"]);
+        let got: Vec<(&str, &str)> = spans.iter().map(|s| (&text[s.start..s.end], s.label.as_str())).collect();
+        assert_eq!(got, vec![("Shaktikanta Das", "PER"), ("Tata Steel", "ORG"), ("Mumbai", "LOC")]);
+        assert!((spans[0].score - 0.9).abs() < 1e-6);
+        // B- starts a new span even within the same type; a type change ends one.
+        assert_eq!(labelled("Infosys Wipro", &["B-ORG", "B-ORG"]).len(), 2);
+        assert_eq!(labelled("Paris Hilton", &["B-LOC", "I-PER"]).len(), 2);
+    }
+
+    #[test]
+    fn test_merge_entities_with_model_spans() {
+        let text = "Shaktikanta Das said the Reserve Bank of India will act. Das spoke in Mumbai about the Union Budget and Zerodha.";
+        let span = |needle: &str, len: usize, label: &str| {
+            let start = text.find(needle).unwrap();
+            NamedSpan { start, end: start + len, label: label.to_string(), score: 0.9 }
+        };
+        let spans = vec![
+            span("Shaktikanta Das", 15, "PER"),
+            span("Reserve Bank", 12, "ORG"),
+            span("Das spoke", 3, "PER"),
+            span("Mumbai", 6, "LOC"),
+            span("Union Budget", 12, "MISC"),
+            span("Zerodha", 7, "ORG"),
+        ];
+
+        let found = merge_entities(text, &spans);
+        let names = |ms: &[EntityMention]| ms.iter().map(|m| m.surface_form.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&found.persons), vec!["Shaktikanta Das"]);
+        assert_eq!(found.persons[0].salience, 1.0);
+        assert_eq!(names(&found.organizations), vec!["Reserve Bank of India", "Zerodha"], "gazetteer name wins over the overlapping model span");
+        assert_eq!(names(&found.locations), vec!["Mumbai"]);
+        assert_eq!(names(&found.others), vec!["Union Budget"]);
+
+        let mut analysis = DocAnalysis::default();
+        analysis.locations.push(GeoMention { name: "Mumbai".to_string(), lat: Some(19.07), ..Default::default() });
+        found.apply_to(&mut analysis);
+        assert_eq!(analysis.locations.len(), 1, "geocoded location is not repeated");
+        assert_eq!(analysis.persons.len(), 1);
+        assert!(analysis.all_names.contains(&"Union Budget".to_string()));
+        assert!(analysis.all_names.contains(&"Zerodha".to_string()));
+    }
+
+    #[test]
+    fn test_loaded_org_gazetteer() {
+        let csv = "surface,canonical\nSEBI,Securities and Exchange Board of India\nPFRDA,Pension Fund Regulatory and Development Authority\n";
+        let orgs = OrgGazetteer::parse(csv, crate::vocab::Format::Csv, "orgs").unwrap().vocab;
+        let found = merge_entities_with(&orgs, default_lexicon(), "PFRDA and SEBI met; the RBI was not present at the meeting.", &[]);
+        let names: Vec<&str> = found.organizations.iter().map(|m| m.surface_form.as_str()).collect();
+        assert_eq!(names, vec!["Pension Fund Regulatory and Development Authority", "Securities and Exchange Board of India"]);
+    }
+
+    #[test]
+    fn test_labels_from_config_and_best_label() {
+        let cfg: Value = serde_json::from_str(r#"{"id2label": {"0": "O", "2": "I-PER", "1": "B-PER"}}"#).unwrap();
+        let labels = labels_from_config(&cfg).unwrap();
+        assert_eq!(labels, vec!["O", "B-PER", "I-PER"]);
+        assert!(labels_from_config(&serde_json::json!({"model_type": "bert"})).is_none());
+        let (label, p) = best_label(&[0.0, 3.0, 0.0], &labels);
+        assert_eq!(label, "B-PER");
+        assert!(p > 0.9 && p < 1.0);
+    }
 }
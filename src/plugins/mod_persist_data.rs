@@ -221,6 +221,7 @@ fn save_to_zip(mut received: document::Document, data_folder_name: &str) -> docu
 ///
 /// Only runs when:
 ///  - `doc.module` is `"mod_in_nse"` or `"mod_in_bse"`, AND
+///  - `doc.section_name` is `"Equity Bhavcopy"` (circulars and announcements are skipped), AND
 ///  - `doc.text` begins with a non-empty line that contains at least one comma.
 ///
 /// The target database path is read from the `market_data_db` config key; when
@@ -231,6 +232,9 @@ fn save_market_data_to_sqlite(doc: &document::Document, app_config: &Config) {
     if doc.module != "mod_in_nse" && doc.module != "mod_in_bse" {
         return;
     }
+    if doc.section_name != "Equity Bhavcopy" {
+        return;
+    }
 
     // Check that doc.text starts with a CSV-like line (non-empty and contains a comma).
     let first_line = doc.text.lines().next().unwrap_or("").trim();
@@ -0,0 +1,376 @@
+// file: mod_quotes.rs
+// Purpose:
+//   Quotation and speaker extraction (roadmap Stage 5 / D5). Finds direct quotes in article
+//   text — straight ("...") or curly (“...”) double quotes, including multi-paragraph quotes
+//   where each continuing paragraph re-opens the quote without closing the previous one — and
+//   their attribution, written onto `doc.analysis.quotes` as `Quotation`s:
+//     * after the quote:  "...," said Governor Shaktikanta Das / "...," Das said /
+//                         "...," according to a senior RBI official
+//     * before the quote: Das told reporters: "..." / According to the ministry, "..."
+//   The reporting verb ("said", "told", "warned", "according to", ...) is kept as found.
+//   The speaker is linked to the person / organisation mention `mod_ner` found that it names
+//   ("Governor Shaktikanta Das" → "Shaktikanta Das"), or whose surname it is ("Das said"); a
+//   pronoun speaker ("he said") is the nearest person mentioned between the sentence that
+//   attributed the previous quote and this one, else the previous quote's speaker if this
+//   quote follows it closely, else the nearest person mentioned before the quote. A linked speaker takes the mention's surface form and
+//   `entity_id` (which `mod_entity_resolve` may have set); `mod_emit_tables` persists the
+//   quotes to the `quotes` table, keying a linked but unresolved speaker by its provisional id.
+//   An unattributed quote is kept only when it directly follows an attributed one (the usual
+//   `"...," he said. "..."` continuation); otherwise it is skipped as a scare quote or title.
+//   Single quotes (‘...’) are not read, as they cannot be told apart from apostrophes.
+//   Runs after `mod_ner` / `mod_entity_resolve`.
+
+use std::collections::HashMap;
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use config::Config;
+use log::{error, info};
+use regex::Regex;
+
+use crate::analysis::{norm_name, EntityMention, Quotation};
+use crate::document::Document;
+
+pub const PLUGIN_NAME: &str = "mod_quotes";
+
+/// Minimum text length before extraction is attempted.
+const MIN_TEXT_LEN: usize = 40;
+/// Quotes shorter than this many words are treated as scare quotes / titles.
+const MIN_QUOTE_WORDS: usize = 4;
+/// Bytes of text either side of a quote searched for its attribution.
+const ATTRIBUTION_WINDOW: usize = 200;
+/// An unattributed or pronoun-attributed quote inherits the previous quote's speaker when it
+/// starts within this many bytes of the previous quote's end.
+const CONTINUATION_GAP: usize = 120;
+
+pub fn process_data(
+    tx: Sender<Document>,
+    rx: Receiver<Document>,
+    _config: &Config,
+    _api_mutexes: &mut HashMap<String, Arc<Mutex<isize>>>,
+) {
+    info!("{}: Starting quotation extraction.", PLUGIN_NAME);
+    let mut docs = 0usize;
+    let mut total = 0usize;
+
+    for mut doc in rx {
+        let text = doc.english_text().to_string();
+        if text.len() >= MIN_TEXT_LEN {
+            let mut analysis = doc.analysis.take().unwrap_or_default();
+            let quotes = extract_quotes(&text, &analysis.persons, &analysis.organizations);
+            if !quotes.is_empty() {
+                docs += 1;
+                total += quotes.len();
+            }
+            analysis.quotes = quotes;
+            doc.analysis = Some(analysis);
+        }
+        if let Err(e) = tx.send(doc) {
+            error!("{}: when forwarding doc: {}", PLUGIN_NAME, e);
+        }
+    }
+    info!("{}: Completed. Extracted {} quote(s) from {} document(s).", PLUGIN_NAME, total, docs);
+}
+
+// ---------------------------------------------------------------------------
+// Quote spans
+// ---------------------------------------------------------------------------
+
+/// A quote in the text: `open` is the byte offset of the opening quote mark, `end` the offset
+/// just past the closing one, and `parts` the quoted text of each paragraph.
+#[derive(Debug, Clone, PartialEq)]
+struct QuoteSpan {
+    open: usize,
+    end: usize,
+    parts: Vec<(usize, usize)>,
+}
+
+fn is_open_quote(c: char) -> bool {
+    c == '"' || c == '“'
+}
+
+fn is_close_quote(c: char) -> bool {
+    c == '"' || c == '”'
+}
+
+/// Quote spans in `text`. A paragraph break inside a quote continues it when the next
+/// paragraph re-opens the quote; otherwise the unterminated quote is dropped.
+fn quote_spans(text: &str) -> Vec<QuoteSpan> {
+    let mut spans = Vec::new();
+    // The quote being read, and the start of its current paragraph's part.
+    let mut current: Option<(QuoteSpan, usize)> = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let Some((span, start)) = current.as_mut() else {
+            if is_open_quote(c) {
+                current = Some((QuoteSpan { open: i, end: i, parts: Vec::new() }, i + c.len_utf8()));
+            }
+            continue;
+        };
+        if is_close_quote(c) {
+            span.parts.push((*start, i));
+            span.end = i + c.len_utf8();
+            spans.extend(current.take().map(|(span, _)| span));
+        } else if c == '\n' {
+            let rest = &text[i..];
+            let next = rest.trim_start();
+            match next.chars().next() {
+                Some(q) if is_open_quote(q) => {
+                    span.parts.push((*start, i));
+                    *start = i + (rest.len() - next.len()) + q.len_utf8();
+                    while chars.peek().is_some_and(|(j, _)| *j < *start) {
+                        chars.next();
+                    }
+                }
+                _ => current = None,
+            }
+        }
+    }
+    spans
+}
+
+// ---------------------------------------------------------------------------
+// Attribution
+// ---------------------------------------------------------------------------
+
+const VERB: &str = r"(?:said|says|added|adds|told|tells|stated|noted|explained|asserted|claimed|warned|remarked|observed|stressed|emphasised|emphasized|announced|argued|wrote|cautioned|insisted|maintained|pointed\s+out|reiterated|commented|replied|acknowledged|admitted|declared|mentioned)\b";
+
+/// A pronoun, a described role ("a senior RBI official") or a capitalised name with
+/// connectives ("Governor of the Reserve Bank of India", "R. K. Singh").
+fn speaker_pattern() -> String {
+    let token = r"(?:[A-Z]\.|Dr\.|Mr\.|Mrs\.|Ms\.|[A-Z][\w&'’-]*)";
+    format!(
+        r"(?:(?:[Hh]e|[Ss]he|[Tt]hey)\b|(?i:(?:the|an?)\s+)?(?:[\w-]+\s+){{0,3}}?(?i:spokesperson|spokesman|spokeswoman|officials?|analysts?|executives?|economists?|sources?)\b|{t}(?:\s+(?:(?:of|for|and|&|the)\s+)*{t})*)",
+        t = token
+    )
+}
+
+/// Attribution right after a quote: `, said X` / `, X said` / `, according to X`.
+fn after_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        let s = speaker_pattern();
+        Regex::new(&format!(
+            r"^\s*,?\s*(?:(?P<v1>{v})\s+(?P<s1>{s})|(?P<s2>{s})\s+(?:has\s+|had\s+)?(?P<v2>{v})|(?P<v3>according\s+to)\s+(?P<s3>{s}))",
+            v = VERB,
+            s = s
+        ))
+        .expect("after-quote attribution regex")
+    })
+}
+
+/// Attribution leading into a quote: `X said:` / `X told reporters,` / `According to X,`.
+fn before_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        let s = speaker_pattern();
+        Regex::new(&format!(
+            r"(?:\b(?P<s1>{s})\s+(?:has\s+|had\s+)?(?P<v1>{v})(?:\s+(?:reporters|journalists|analysts|investors|shareholders|PTI|ANI|Reuters|that))?|\b(?P<v2>[Aa]ccording\s+to)\s+(?P<s2>{s}))\s*[:,]?\s*$",
+            v = VERB,
+            s = s
+        ))
+        .expect("before-quote attribution regex")
+    })
+}
+
+/// Largest char boundary of `text` not above `i`.
+fn floor_boundary(text: &str, mut i: usize) -> usize {
+    while !text.is_char_boundary(i) {
+        i -= 1;
+    }
+    i
+}
+
+/// (speaker, verb) attributed to the quote `span`, as written in the text.
+fn attribution(text: &str, span: &QuoteSpan) -> Option<(String, String)> {
+    let tail_end = floor_boundary(text, (span.end + ATTRIBUTION_WINDOW).min(text.len()));
+    let tail = &text[span.end..tail_end];
+    let tail = tail.split('\n').next().unwrap_or_default();
+    let found = after_re().captures(tail).and_then(|c| {
+        ["1", "2", "3"].iter().find_map(|n| Some((c.name(&format!("s{}", n))?, c.name(&format!("v{}", n))?)))
+    });
+    let found = found.or_else(|| {
+        let line_start = text[..span.open].rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let head_start = floor_boundary(text, span.open.saturating_sub(ATTRIBUTION_WINDOW)).max(line_start);
+        before_re().captures(&text[head_start..span.open]).and_then(|c| {
+            ["1", "2"].iter().find_map(|n| Some((c.name(&format!("s{}", n))?, c.name(&format!("v{}", n))?)))
+        })
+    })?;
+    let (speaker, verb) = (found.0.as_str().trim(), found.1.as_str());
+    let speaker = match speaker.strip_prefix("The ") {
+        Some(rest) if rest.starts_with(char::is_uppercase) => rest,
+        _ => speaker,
+    };
+    let verb = verb.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    Some((speaker.to_string(), verb))
+}
+
+fn is_pronoun(speaker: &str) -> bool {
+    matches!(speaker.to_lowercase().as_str(), "he" | "she" | "they")
+}
+
+/// The person / organisation mention an attribution names: a mention contained in it
+/// (longest first), else a person whose surname it ends with.
+fn link_speaker<'a>(speaker: &str, persons: &'a [EntityMention], orgs: &'a [EntityMention]) -> Option<&'a EntityMention> {
+    let key = format!(" {} ", norm_name(speaker));
+    persons
+        .iter()
+        .chain(orgs)
+        .filter(|m| {
+            let name = norm_name(&m.surface_form);
+            !name.is_empty() && key.contains(&format!(" {} ", name))
+        })
+        .max_by_key(|m| m.surface_form.len())
+        .or_else(|| {
+            let last = key.split_whitespace().last()?;
+            persons.iter().find(|m| norm_name(&m.surface_form).rsplit(' ').next() == Some(last))
+        })
+}
+
+// ---------------------------------------------------------------------------
+// Extraction
+// ---------------------------------------------------------------------------
+
+/// Attributed quotes in `text`, with speakers linked to the given person / organisation
+/// mentions where possible (see the file header).
+pub fn extract_quotes(text: &str, persons: &[EntityMention], orgs: &[EntityMention]) -> Vec<Quotation> {
+    let mut out: Vec<Quotation> = Vec::new();
+    // End offset of the previous kept quote, for continuation quotes.
+    let mut prev_end: Option<usize> = None;
+    for span in quote_spans(text) {
+        let quote = span
+            .parts
+            .iter()
+            .map(|&(s, e)| text[s..e].trim())
+            .filter(|p| !p.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let quote = quote.trim_end_matches([',', ' ']).to_string();
+        if quote.split_whitespace().count() < MIN_QUOTE_WORDS {
+            continue;
+        }
+        let char_offset = span.parts.first().map(|p| p.0).unwrap_or(span.open);
+
+        let (speaker, speaker_entity_id, verb) = match attribution(text, &span) {
+            Some((pronoun, verb)) if is_pronoun(&pronoun) => {
+                // People named after the sentence that attributed the previous quote.
+                let after = prev_end
+                    .map(|end| text[end..].find(['.', '!', '?', '\n']).map_or(text.len(), |i| end + i + 1))
+                    .unwrap_or(0);
+                let nearest = |from: usize| {
+                    persons
+                        .iter()
+                        .filter(|m| m.char_offset >= from && m.char_offset < span.open)
+                        .max_by_key(|m| m.char_offset)
+                        .map(|m| (m.surface_form.clone(), m.entity_id.clone()))
+                };
+                let previous = || match (out.last(), prev_end) {
+                    (Some(q), Some(end)) if span.open.saturating_sub(end) <= CONTINUATION_GAP => {
+                        Some((q.speaker.clone(), q.speaker_entity_id.clone()))
+                    }
+                    _ => None,
+                };
+                match nearest(after).or_else(previous).or_else(|| nearest(0)) {
+                    Some((speaker, id)) => (speaker, id, verb),
+                    None => (pronoun, None, verb),
+                }
+            }
+            Some((speaker, verb)) => match link_speaker(&speaker, persons, orgs) {
+                Some(m) => (m.surface_form.clone(), m.entity_id.clone(), verb),
+                None => (speaker, None, verb),
+            },
+            None => match (out.last(), prev_end) {
+                (Some(q), Some(end)) if span.open.saturating_sub(end) <= CONTINUATION_GAP => {
+                    (q.speaker.clone(), q.speaker_entity_id.clone(), q.verb.clone())
+                }
+                _ => continue,
+            },
+        };
+        out.push(Quotation { speaker, speaker_entity_id, verb, quote, char_offset });
+        prev_end = Some(span.end);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn person(name: &str, offset: usize, id: Option<&str>) -> EntityMention {
+        EntityMention {
+            surface_form: name.to_string(),
+            entity_type: "PERSON".to_string(),
+            char_offset: offset,
+            salience: 1.0,
+            entity_id: id.map(str::to_string),
+            sentiment: None,
+        }
+    }
+
+    #[test]
+    fn test_quote_spans_straight_curly_and_multi_paragraph() {
+        let text = "He said \"one two\" and “three four”.\n\n“First paragraph goes on,\n\n“and ends here.”\n\"Unterminated\nNext para.";
+        let spans = quote_spans(text);
+        let quoted: Vec<String> = spans
+            .iter()
+            .map(|s| s.parts.iter().map(|&(a, b)| &text[a..b]).collect::<Vec<_>>().join("|"))
+            .collect();
+        assert_eq!(quoted, vec!["one two", "three four", "First paragraph goes on,|and ends here."]);
+    }
+
+    #[test]
+    fn test_attribution_forms_and_speaker_linking() {
+        let text = "The central bank held rates. “Inflation remains a concern for us,” said Governor Shaktikanta Das. \
+            “We will stay watchful on prices.” \
+            Speaking to reporters, Das said: \"Growth is robust this year.\" \
+            \"The rupee is stable against the dollar,\" according to a senior RBI official. \
+            Nothing was \"really new\" there.";
+        let persons = vec![person("Shaktikanta Das", 0, Some("lei:RBI-GOV"))];
+        let quotes = extract_quotes(text, &persons, &[]);
+        let got: Vec<(&str, Option<&str>, &str, &str)> = quotes
+            .iter()
+            .map(|q| (q.speaker.as_str(), q.speaker_entity_id.as_deref(), q.verb.as_str(), q.quote.as_str()))
+            .collect();
+        assert_eq!(
+            got,
+            vec![
+                ("Shaktikanta Das", Some("lei:RBI-GOV"), "said", "Inflation remains a concern for us"),
+                ("Shaktikanta Das", Some("lei:RBI-GOV"), "said", "We will stay watchful on prices."),
+                ("Shaktikanta Das", Some("lei:RBI-GOV"), "said", "Growth is robust this year."),
+                ("a senior RBI official", None, "according to", "The rupee is stable against the dollar"),
+            ]
+        );
+        assert_eq!(&text[quotes[0].char_offset..quotes[0].char_offset + 9], "Inflation");
+    }
+
+    #[test]
+    fn test_pronoun_speaker_resolves_to_nearest_person() {
+        let text = "Nirmala Sitharaman presented the budget on Saturday. \
+            \"The fiscal deficit target will be met this year,\" she told Parliament.";
+        let persons = vec![person("Nirmala Sitharaman", 0, None)];
+        let quotes = extract_quotes(text, &persons, &[]);
+        assert_eq!(quotes.len(), 1);
+        assert_eq!(quotes[0].speaker, "Nirmala Sitharaman");
+        assert_eq!(quotes[0].speaker_entity_id, None);
+        assert_eq!(quotes[0].verb, "told");
+    }
+
+    #[test]
+    fn test_pronoun_speaker_prefers_person_named_since_previous_quote() {
+        let text = "\"Rates will stay on hold for now,\" said Das. Sitharaman then spoke. \
+            \"The fiscal deficit target will be met,\" she said. \"Borrowing will also come down next year,\" she added.";
+        let persons = vec![
+            person("Das", text.find("Das").unwrap(), None),
+            person("Sitharaman", text.find("Sitharaman").unwrap(), None),
+        ];
+        let quotes = extract_quotes(text, &persons, &[]);
+        let speakers: Vec<&str> = quotes.iter().map(|q| q.speaker.as_str()).collect();
+        assert_eq!(speakers, vec!["Das", "Sitharaman", "Sitharaman"]);
+
+        // Without a new name, the pronoun is the previous speaker only when the quotes are close.
+        let text = "\"Rates will stay on hold for now,\" said Das. \"Inflation is easing across the board,\" he said.";
+        let persons = vec![person("Das", text.find("Das").unwrap(), None)];
+        let quotes = extract_quotes(text, &persons, &[]);
+        assert_eq!(quotes[1].speaker, "Das");
+    }
+}
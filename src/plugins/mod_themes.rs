@@ -8,17 +8,23 @@
 //
 //   The themebook is intentionally finance/regulatory-leaning (the corpus is Indian financial
 //   news + regulator circulars). It is versioned via THEMEBOOK_VERSION so downstream consumers
-//   can reason about vocabulary drift.
+//   can reason about vocabulary drift. Analysts can replace it with a TOML/CSV themebook (see
+//   `vocab` for the formats); the version in use is stamped on every `themes` row.
+//
+// Config keys:
+//   themebook_file  - Themebook file replacing the embedded one; reloaded when it changes.
 
 use std::collections::HashMap;
 use std::sync::mpsc::{Receiver, Sender};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, OnceLock};
 
 use config::Config;
 use log::{error, info};
 
 use crate::analysis::ThemeMention;
 use crate::document::Document;
+use crate::get_plugin_cfg;
+use crate::vocab::{Themebook, VocabSource, Vocabulary};
 
 pub const PLUGIN_NAME: &str = "mod_themes";
 pub const THEMEBOOK_VERSION: &str = "2026.06.1";
@@ -48,19 +54,23 @@ const THEMEBOOK: &[(&str, &[&str])] = &[
 pub fn process_data(
     tx: Sender<Document>,
     rx: Receiver<Document>,
-    _config: &Config,
+    config: &Config,
     _api_mutexes: &mut HashMap<String, Arc<Mutex<isize>>>,
 ) {
-    info!("{}: Starting theme tagging (themebook v{}).", PLUGIN_NAME, THEMEBOOK_VERSION);
+    let mut book = VocabSource::new(PLUGIN_NAME, get_plugin_cfg!(PLUGIN_NAME, "themebook_file", config), default_themebook().clone());
+    info!("{}: Starting theme tagging (themebook v{}).", PLUGIN_NAME, book.get().version);
     let mut docs = 0usize;
     let mut tags = 0usize;
     for mut doc in rx {
-        if doc.text.len() >= MIN_TEXT_LEN {
-            let themes = tag_themes(&doc.text);
+        book.refresh();
+        let text = doc.english_text();
+        if text.len() >= MIN_TEXT_LEN {
+            let themes = tag_themes_with(book.get(), text);
             if !themes.is_empty() {
                 let mut analysis = doc.analysis.take().unwrap_or_default();
                 tags += themes.len();
                 analysis.themes.extend(themes);
+                analysis.vocab_versions.insert(Themebook::KIND.to_string(), book.get().version.clone());
                 doc.analysis = Some(analysis);
                 docs += 1;
             }
@@ -72,21 +82,32 @@ pub fn process_data(
     info!("{}: Completed. Tagged {} document(s) with {} theme mention(s).", PLUGIN_NAME, docs, tags);
 }
 
+/// The embedded themebook.
+pub fn default_themebook() -> &'static Themebook {
+    static BOOK: OnceLock<Themebook> = OnceLock::new();
+    BOOK.get_or_init(|| Themebook::from_static(THEMEBOOK_VERSION, THEMEBOOK))
+}
+
+/// Tag `text` with the embedded themebook (see `tag_themes_with`).
+pub fn tag_themes(text: &str) -> Vec<ThemeMention> {
+    tag_themes_with(default_themebook(), text)
+}
+
 /// Return one `ThemeMention` per (theme, first-occurrence) found in the text. A theme is
 /// recorded at most once (at its earliest offset) even if several of its phrases match.
-pub fn tag_themes(text: &str) -> Vec<ThemeMention> {
+pub fn tag_themes_with(book: &Themebook, text: &str) -> Vec<ThemeMention> {
     let lower = text.to_lowercase();
     let bytes = lower.as_bytes();
     let mut out: Vec<ThemeMention> = Vec::new();
-    for (theme, phrases) in THEMEBOOK {
+    for (theme, phrases) in &book.themes {
         let mut best: Option<usize> = None;
-        for phrase in *phrases {
+        for phrase in phrases {
             if let Some(off) = find_whole_word(&lower, bytes, phrase) {
                 best = Some(best.map_or(off, |b| b.min(off)));
             }
         }
         if let Some(off) = best {
-            out.push(ThemeMention { theme: (*theme).to_string(), char_offset: off });
+            out.push(ThemeMention { theme: theme.clone(), char_offset: off });
         }
     }
     out.sort_by_key(|t| t.char_offset);
@@ -157,6 +178,16 @@ mod tests {
         assert_eq!(banking.len(), 1, "theme should appear once");
     }
 
+    #[test]
+    fn test_loaded_themebook_replaces_embedded() {
+        let csv = "# version: 2026.10.1\ntheme,phrase\nECON_TRADE,tariff\nECON_TRADE,trade deficit\n";
+        let book = Themebook::parse(csv, crate::vocab::Format::Csv, "themes").unwrap().vocab;
+        let t = tag_themes_with(&book, "The bank flagged a widening trade deficit after the tariff hike.");
+        assert_eq!(t.iter().map(|m| m.theme.as_str()).collect::<Vec<_>>(), vec!["ECON_TRADE"]);
+        assert_eq!(t[0].char_offset, 28, "earliest phrase wins");
+        assert_eq!(default_themebook().version, THEMEBOOK_VERSION);
+    }
+
     #[test]
     fn test_no_themes_neutral_text() {
         assert!(tag_themes("The weather was pleasant and the children played in the park.").is_empty());
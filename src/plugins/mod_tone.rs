@@ -6,18 +6,37 @@
 //   lexicons, plus a small GCAM-style sub-panel emitted as `GcamScore` rows. Deterministic and
 //   dependency-free. Writes onto `doc.analysis.tone` and `doc.analysis.gcam`; persistence is
 //   done later by `mod_emit_tables`.
+//
+//   `mention_tone` scores the same lexicons for one entity only (targeted sentiment), over
+//   the clauses around its occurrences — the sentence, cut at contrast words such as "but" /
+//   "while", so "X's profit surged while Y posted losses" is positive for X and negative for
+//   Y — with a sentiment word preceded by a negator ("not", "never", "didn't", ...) within
+//   three words counted with the opposite polarity. `mod_ner` stores it on each
+//   `EntityMention`.
+//
+//   The embedded lexicons below are the default; a lexicon file (see `vocab`: TOML, a
+//   word,category CSV, or the Loughran–McDonald master dictionary as published) replaces the
+//   positive/negative lists, and its name becomes the GCAM `dict_id`. The version in use is
+//   stamped on the `gcam` rows.
+//
+// Config keys:
+//   lexicon_file    - Tone lexicon replacing the embedded one; reloaded when it changes. Also
+//                     used by `mod_ner` for targeted sentiment.
 
 use std::collections::HashMap;
 use std::sync::mpsc::{Receiver, Sender};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, OnceLock};
 
 use config::Config;
 use log::{error, info};
 
 use crate::analysis::{GcamScore, ToneScores};
 use crate::document::Document;
+use crate::get_plugin_cfg;
+use crate::vocab::{ToneLexicon, VocabSource, Vocabulary};
 
 pub const PLUGIN_NAME: &str = "mod_tone";
+pub const LEXICON_VERSION: &str = "2026.06.1";
 
 const MIN_TEXT_LEN: usize = 40;
 
@@ -51,18 +70,23 @@ const ACTIVE: &[&str] = &[
 pub fn process_data(
     tx: Sender<Document>,
     rx: Receiver<Document>,
-    _config: &Config,
+    config: &Config,
     _api_mutexes: &mut HashMap<String, Arc<Mutex<isize>>>,
 ) {
-    info!("{}: Starting tone-panel scoring.", PLUGIN_NAME);
+    let mut lexicon = configured_lexicon(PLUGIN_NAME, config);
+    info!("{}: Starting tone-panel scoring ({} lexicon v{}).", PLUGIN_NAME, lexicon.get().name, lexicon.get().version);
     let mut docs = 0usize;
     for mut doc in rx {
-        if doc.text.len() >= MIN_TEXT_LEN {
-            let scores = score_tone(&doc.text);
-            let gcam = gcam_for(&scores);
+        lexicon.refresh();
+        let text = doc.english_text();
+        if text.len() >= MIN_TEXT_LEN {
+            let lex = lexicon.get();
+            let scores = score_tone_with(lex, text);
+            let gcam = gcam_for(&lex.name, &scores);
             let mut analysis = doc.analysis.take().unwrap_or_default();
             analysis.tone = Some(scores);
             analysis.gcam.extend(gcam);
+            analysis.vocab_versions.insert(ToneLexicon::KIND.to_string(), lex.version.clone());
             doc.analysis = Some(analysis);
             docs += 1;
         }
@@ -73,10 +97,27 @@ pub fn process_data(
     info!("{}: Completed. Scored tone for {} document(s).", PLUGIN_NAME, docs);
 }
 
+/// The embedded finance-news lexicon.
+pub fn default_lexicon() -> &'static ToneLexicon {
+    static LEXICON: OnceLock<ToneLexicon> = OnceLock::new();
+    LEXICON.get_or_init(|| ToneLexicon::from_static("finlex", LEXICON_VERSION, POSITIVE, NEGATIVE, ACTIVE))
+}
+
+/// The tone lexicon named by `mod_tone`'s `lexicon_file` config key, for `plugin` (this one,
+/// or `mod_ner` scoring targeted sentiment); the embedded lexicon if unset or invalid.
+pub fn configured_lexicon(plugin: &str, config: &Config) -> VocabSource<ToneLexicon> {
+    VocabSource::new(plugin, get_plugin_cfg!(PLUGIN_NAME, "lexicon_file", config), default_lexicon().clone())
+}
+
+/// The tone panel of `text` with the embedded lexicon (see `score_tone_with`).
+pub fn score_tone(text: &str) -> ToneScores {
+    score_tone_with(default_lexicon(), text)
+}
+
 /// Compute the six-field tone panel. Percentages are over total word count.
 /// tone = positive% - negative% (in [-100, 100], then rescaled to GDELT's -10..10 convention
 /// is left to consumers; we keep the percentage-point form consistent with GKG V1.5TONE).
-pub fn score_tone(text: &str) -> ToneScores {
+pub fn score_tone_with(lexicon: &ToneLexicon, text: &str) -> ToneScores {
     let mut words = 0usize;
     let mut pos = 0usize;
     let mut neg = 0usize;
@@ -87,12 +128,12 @@ pub fn score_tone(text: &str) -> ToneScores {
         }
         words += 1;
         let w = raw.to_lowercase();
-        if POSITIVE.contains(&w.as_str()) {
-            pos += 1;
-        } else if NEGATIVE.contains(&w.as_str()) {
-            neg += 1;
+        match lexicon.polarity(&w) {
+            1 => pos += 1,
+            -1 => neg += 1,
+            _ => {}
         }
-        if ACTIVE.contains(&w.as_str()) {
+        if lexicon.is_active(&w) {
             act += 1;
         }
     }
@@ -113,16 +154,91 @@ pub fn score_tone(text: &str) -> ToneScores {
     }
 }
 
+/// Words that flip the polarity of a sentiment word up to NEGATION_SCOPE words after them.
+/// Contractions are split at the apostrophe, so "didn't" is seen as "didn" + "t".
+const NEGATORS: &[&str] = &[
+    "not", "no", "never", "without", "neither", "nor", "hardly", "barely", "cannot", "didn",
+    "doesn", "don", "isn", "wasn", "aren", "weren", "won", "couldn", "wouldn", "shouldn",
+    "hasn", "haven", "hadn",
+];
+const NEGATION_SCOPE: usize = 3;
+/// Words starting a contrasting clause; targeted sentiment does not cross them.
+const CONTRAST_WORDS: &[&str] = &["but", "while", "whereas", "although", "though", "however", "unlike", "even as"];
+
+/// Byte range of the clause around `offset`: its sentence (bounded by . ! ? ; or a line
+/// break), cut at the nearest contrast words either side. An offset inside a multi-byte
+/// character is taken as the start of that character.
+fn clause_around(text: &str, offset: usize) -> (usize, usize) {
+    let is_break = |c: char| matches!(c, '.' | '!' | '?' | ';' | '\n');
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let mut start = text[..offset].rfind(is_break).map(|i| i + 1).unwrap_or(0);
+    let mut end = text[offset..].find(is_break).map(|i| offset + i).unwrap_or(text.len());
+    let lower = text[start..end].to_ascii_lowercase();
+    for word in CONTRAST_WORDS {
+        for (i, _) in lower.match_indices(word) {
+            let at = start + i;
+            let bounded = (i == 0 || !lower.as_bytes()[i - 1].is_ascii_alphanumeric())
+                && lower.as_bytes().get(i + word.len()).is_none_or(|b| !b.is_ascii_alphanumeric());
+            if !bounded {
+                continue;
+            }
+            if at <= offset && at > start {
+                start = at;
+            } else if at > offset && at < end {
+                end = at;
+            }
+        }
+    }
+    (start, end)
+}
+
+/// Targeted tone with the embedded lexicon (see `mention_tone_with`).
+pub fn mention_tone(text: &str, offsets: &[usize]) -> Option<f64> {
+    mention_tone_with(default_lexicon(), text, offsets)
+}
+
+/// Targeted tone of an entity occurring at the given byte offsets of `text`: positive% -
+/// negative% (as `ToneScores::tone`) over the words of the clauses around its occurrences,
+/// with negated sentiment words counted with the opposite polarity. None if there are no
+/// occurrences.
+pub fn mention_tone_with(lexicon: &ToneLexicon, text: &str, offsets: &[usize]) -> Option<f64> {
+    let mut clauses: Vec<(usize, usize)> = offsets.iter().map(|&o| clause_around(text, o)).collect();
+    clauses.sort();
+    clauses.dedup();
+    if clauses.is_empty() {
+        return None;
+    }
+    let (mut words, mut score) = (0usize, 0i64);
+    for (start, end) in clauses {
+        let tokens: Vec<String> = text[start..end]
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(|w| w.to_lowercase())
+            .collect();
+        words += tokens.len();
+        for (i, w) in tokens.iter().enumerate() {
+            let polarity = lexicon.polarity(w);
+            if polarity == 0 {
+                continue;
+            }
+            let negated = tokens[i.saturating_sub(NEGATION_SCOPE)..i].iter().any(|t| NEGATORS.contains(&t.as_str()));
+            score += if negated { -polarity } else { polarity };
+        }
+    }
+    Some(if words == 0 { 0.0 } else { 100.0 * score as f64 / words as f64 })
+}
+
 /// Emit a small GCAM-style sub-panel mirroring the tone fields, so the `gcam` table is
 /// populated in the same GDELT-compatible `dict_id`/`dim_id`/`key`(c|v) shape used later.
-fn gcam_for(s: &ToneScores) -> Vec<GcamScore> {
-    vec![
-        GcamScore { dict_id: "finlex".into(), dim_id: "wc".into(), key: "c".into(), score: s.word_count as f64 },
-        GcamScore { dict_id: "finlex".into(), dim_id: "tone".into(), key: "v".into(), score: s.tone },
-        GcamScore { dict_id: "finlex".into(), dim_id: "pos".into(), key: "v".into(), score: s.positive },
-        GcamScore { dict_id: "finlex".into(), dim_id: "neg".into(), key: "v".into(), score: s.negative },
-        GcamScore { dict_id: "finlex".into(), dim_id: "act".into(), key: "v".into(), score: s.activity },
-    ]
+/// `dict_id` is the lexicon's name.
+fn gcam_for(dict_id: &str, s: &ToneScores) -> Vec<GcamScore> {
+    [("wc", "c", s.word_count as f64), ("tone", "v", s.tone), ("pos", "v", s.positive), ("neg", "v", s.negative), ("act", "v", s.activity)]
+        .into_iter()
+        .map(|(dim_id, key, score)| GcamScore { dict_id: dict_id.into(), dim_id: dim_id.into(), key: key.into(), score })
+        .collect()
 }
 
 #[cfg(test)]
@@ -151,12 +267,46 @@ mod tests {
         assert!((s.polarity - (s.positive + s.negative)).abs() < 1e-9);
     }
 
+    #[test]
+    fn test_mention_tone_targets_each_entity() {
+        let text = "HDFC Bank reported strong profit growth, while Yes Bank posted losses. \
+                    Axis Bank did not default on its bonds.";
+        let at = |name: &str| vec![text.find(name).unwrap()];
+        let hdfc = mention_tone(text, &at("HDFC Bank")).unwrap();
+        let yes = mention_tone(text, &at("Yes Bank")).unwrap();
+        let axis = mention_tone(text, &at("Axis Bank")).unwrap();
+        assert!(hdfc > 0.0, "HDFC: {}", hdfc);
+        assert!(yes < 0.0, "Yes Bank: {}", yes);
+        assert!(axis > 0.0, "negated 'default' reads positive: {}", axis);
+        assert!(score_tone(text).tone > 0.0 && mention_tone(text, &[]).is_none());
+    }
+
+    #[test]
+    fn test_clause_around_snaps_to_char_boundary() {
+        let text = "Société Générale reported strong profit. Losses at the unit hurt.";
+        let inside_e = text.find('é').unwrap() + 1;
+        assert_eq!(clause_around(text, inside_e), (0, text.find('.').unwrap()));
+        assert!(mention_tone(text, &[inside_e]).unwrap() > 0.0);
+    }
+
     #[test]
     fn test_gcam_panel_shape() {
         let s = score_tone("strong growth and profit reported by the company this quarter today");
-        let g = gcam_for(&s);
+        let g = gcam_for("finlex", &s);
         assert!(g.iter().any(|x| x.dim_id == "tone" && x.key == "v"));
         assert!(g.iter().any(|x| x.dim_id == "wc" && x.key == "c"));
         assert_eq!(g.len(), 5);
     }
+
+    #[test]
+    fn test_loughran_mcdonald_lexicon() {
+        let lm = "Word,Seq_num,Negative,Positive\nIMPAIRMENT,1,2009,0\nRESTATED,2,2009,0\nACHIEVED,3,0,2009\n";
+        let lex = ToneLexicon::parse(lm, crate::vocab::Format::Csv, "LM_MasterDictionary").unwrap().vocab;
+        let text = "The auditor restated earnings after an impairment; the bank achieved its targets.";
+        let s = score_tone_with(&lex, text);
+        assert!(s.negative > s.positive && s.positive > 0.0, "{:?}", s);
+        assert_eq!(score_tone(text).negative, 0.0, "embedded lexicon lacks these words");
+        assert_eq!(gcam_for(&lex.name, &s)[0].dict_id, "lm");
+        assert!(mention_tone_with(&lex, text, &[text.find("bank").unwrap()]).unwrap() > 0.0);
+    }
 }
@@ -32,24 +32,26 @@ pub const PLUGIN_NAME: &str = "mod_vectorstore";
 const EMBED_DIM: usize = 768;
 const MAX_SEQ_LEN: usize = 512;
 
-fn load_session(model_dir: &str) -> Option<Session> {
+/// Load `<model_dir>/model.onnx` into an ONNX Runtime session. Also used by `mod_ner` for its
+/// token-classification model; `plugin_name` prefixes the log messages.
+pub fn load_session(model_dir: &str, plugin_name: &str) -> Option<Session> {
     let model_path = format!("{}/model.onnx", model_dir);
     if !std::path::Path::new(&model_path).exists() {
         warn!(
-            "{}: ONNX model not found at '{}'. Vector embedding disabled.",
-            PLUGIN_NAME, model_path
+            "{}: ONNX model not found at '{}'. Model inference disabled.",
+            plugin_name, model_path
         );
         return None;
     }
     match Session::builder().and_then(|mut b| b.commit_from_file(&model_path)) {
         Ok(s) => {
-            info!("{}: Loaded ONNX model from '{}'.", PLUGIN_NAME, model_path);
+            info!("{}: Loaded ONNX model from '{}'.", plugin_name, model_path);
             Some(s)
         }
         Err(e) => {
             warn!(
-                "{}: Failed to load ONNX model '{}': {}. Embedding disabled.",
-                PLUGIN_NAME, model_path, e
+                "{}: Failed to load ONNX model '{}': {}. Model inference disabled.",
+                plugin_name, model_path, e
             );
             None
         }
@@ -90,7 +92,7 @@ pub fn process_data(
         warn!("{}: Tokenizer unavailable — chunks split but not embedded.", PLUGIN_NAME);
     }
 
-    let mut session = load_session(&params.model_dir);
+    let mut session = load_session(&params.model_dir, PLUGIN_NAME);
 
     let db_path = format!("{}/vectors.db", params.vectorstore_path);
     let db_conn = match open_vector_db(&db_path) {
@@ -260,6 +262,26 @@ impl BertTokenizer {
         (ids, vec![1i64; len])
     }
 
+    /// Word-piece ids of already split `words` framed by [CLS]/[SEP], with the index of the
+    /// word each id came from (None for [CLS]/[SEP]), for token classification. Stops before
+    /// the first word that would exceed `max_length`. Words are looked up as given, so the
+    /// caller lowercases them for an uncased vocabulary.
+    pub fn encode_words(&self, words: &[&str], max_length: usize) -> (Vec<i64>, Vec<Option<usize>>) {
+        let mut ids: Vec<i64> = vec![self.cls_id];
+        let mut word_of: Vec<Option<usize>> = vec![None];
+        for (i, word) in words.iter().enumerate() {
+            let wp = self.wordpiece(word);
+            if ids.len() + wp.len() + 1 > max_length {
+                break;
+            }
+            word_of.extend(std::iter::repeat_n(Some(i), wp.len()));
+            ids.extend_from_slice(&wp);
+        }
+        ids.push(self.sep_id);
+        word_of.push(None);
+        (ids, word_of)
+    }
+
     fn wordpiece(&self, token: &str) -> Vec<i64> {
         if token.is_empty() {
             return vec![];
@@ -322,6 +344,29 @@ fn basic_tokenize(text: &str) -> Vec<String> {
     tokens
 }
 
+/// Byte spans in `text` of the words `basic_tokenize` splits it into (whitespace-separated
+/// runs, with each punctuation character a word of its own).
+pub fn word_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start: Option<usize> = None;
+    for (i, ch) in text.char_indices() {
+        if ch.is_whitespace() || is_punctuation(ch) {
+            if let Some(s) = start.take() {
+                spans.push((s, i));
+            }
+            if !ch.is_whitespace() {
+                spans.push((i, i + ch.len_utf8()));
+            }
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+    if let Some(s) = start {
+        spans.push((s, text.len()));
+    }
+    spans
+}
+
 fn is_punctuation(c: char) -> bool {
     let cp = c as u32;
     (cp >= 33 && cp <= 47)
@@ -827,6 +872,14 @@ mod tests {
         assert!(tokens.contains(&",".to_string()));
     }
 
+    #[test]
+    fn test_word_spans_match_basic_tokenize() {
+        let text = "Shaktikanta Das, RBI's governor";
+        let words: Vec<&str> = word_spans(text).iter().map(|&(s, e)| &text[s..e]).collect();
+        assert_eq!(words, vec!["Shaktikanta", "Das", ",", "RBI", "'", "s", "governor"]);
+        assert_eq!(words.iter().map(|w| w.to_lowercase()).collect::<Vec<_>>(), basic_tokenize(&text.to_lowercase()));
+    }
+
     #[test]
     fn test_basic_tokenize_empty() {
         assert!(basic_tokenize("").is_empty());
@@ -853,6 +906,14 @@ mod tests {
         assert!(mask.iter().all(|&m| m == 1));
     }
 
+    #[test]
+    fn test_encode_words_maps_ids_to_words() {
+        let tok = minimal_tokenizer();
+        let (ids, word_of) = tok.encode_words(&["hello", "world", "hello"], 4);
+        assert_eq!(ids, vec![101, 7592, 2088, 102], "third word does not fit");
+        assert_eq!(word_of, vec![None, Some(0), Some(1), None]);
+    }
+
     #[test]
     fn test_tokenize_truncates_at_max_length() {
         let mut vocab = HashMap::new();
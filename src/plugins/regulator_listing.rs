@@ -0,0 +1,211 @@
+// file: regulator_listing.rs
+// Purpose: Shared retrieval loop for the regulator plugins (MCA, PFRDA, IFSCA, FIU-IND) whose
+// listing pages link straight to one PDF per document. Each plugin declares a `RegulatorSite`
+// with its listing URLs and a function that turns a listing page into documents; this module
+// fetches the listings, skips already retrieved URLs, reads each PDF via `load_pdf_content`
+// and sends the documents down the pipeline.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use log::{error, info};
+use rand::RngExt;
+use crate::document;
+use crate::cfg::{get_data_folder, get_database_filename, get_pdf_data_folder};
+use crate::document::Document;
+use crate::network::{http_get, make_http_client, read_network_parameters};
+use crate::utils::{check_and_fix_url, get_urls_from_database, load_pdf_content, make_unique_filename};
+
+/// Describes one regulator's website for `run`.
+pub struct RegulatorSite {
+    /// Internal plugin id, e.g. "mod_en_in_mca". Used for logging and the completed-urls table.
+    pub plugin_name: &'static str,
+    /// Publisher name stored on each document as its plugin name and source author.
+    pub publisher_name: &'static str,
+    /// Site root, used as the referrer and to resolve relative links.
+    pub base_url: &'static str,
+    /// Listing pages to scrape, each paired with the section name given to its documents.
+    pub listing_urls: &'static [(&'static str, &'static str)],
+    /// Turns the HTML of a listing page (and the page's URL) into one document per entry.
+    pub extract_docs: fn(&str, &str) -> Vec<Document>,
+}
+
+impl RegulatorSite {
+    /// A new document for this site, initialised with the regulatory-notification defaults.
+    pub fn new_document(&self) -> Document {
+        Document {
+            module: self.plugin_name.to_string(),
+            plugin_name: self.publisher_name.to_string(),
+            source_author: self.publisher_name.to_string(),
+            data_proc_flags: document::DATA_PROC_CLASSIFY_INDUSTRY |
+                document::DATA_PROC_CLASSIFY_MARKET | document::DATA_PROC_CLASSIFY_PRODUCT |
+                document::DATA_PROC_EXTRACT_NAME_ENTITY | document::DATA_PROC_SUMMARIZE |
+                document::DATA_PROC_EXTRACT_ACTIONS,
+            // Init document with default "others" categories in classification field.
+            classification: HashMap::from([
+                ("channel".to_string(), "other".to_string()),
+                ("customer_type".to_string(), "other".to_string()),
+                ("function".to_string(), "other".to_string()),
+                ("market_type".to_string(), "other".to_string()),
+                ("occupation".to_string(), "other".to_string()),
+                ("product_type".to_string(), "other".to_string()),
+                ("risk_type".to_string(), "other".to_string()),
+                ("doc_type".to_string(), "regulatory-notification".to_string()),
+            ]),
+            ..Default::default()
+        }
+    }
+
+    /// Resolve a listing link (absolute, root-relative or page-relative) against the site root.
+    pub fn absolute_url(&self, href: &str) -> Option<String> {
+        let href = href.trim();
+        if href.starts_with("http://") || href.starts_with("https://") {
+            return Some(href.to_string());
+        }
+        let path = format!("/{}", href.trim_start_matches("./").trim_start_matches('/'));
+        check_and_fix_url(path.as_str(), self.base_url)
+    }
+}
+
+/// Executes the retrieval for one regulator site in the separate thread launched by the
+/// pipeline/queue module.
+///
+/// # Arguments
+///
+/// * `tx`: The channel to transmit newly identified or web scraped documents
+/// * `app_config`: The application configuration object to be used to get various config parameters
+/// * `site`: The regulator's listing pages and extraction function
+///
+/// returns: ()
+pub fn run(tx: Sender<document::Document>, app_config: Arc<config::Config>, site: &RegulatorSite) {
+
+    info!("{}: Starting plugin.", site.plugin_name);
+
+    let database_filename = get_database_filename(&app_config);
+    let data_folder = get_data_folder(&app_config);
+    let data_folder_str = data_folder.to_str().unwrap_or("data").to_string();
+    let pdf_folder = get_pdf_data_folder(&app_config);
+    let pdf_folder_str = pdf_folder.to_str().unwrap_or("data/master_data").to_string();
+
+    let mut counter = 0;
+    let mut netw_params = read_network_parameters(&app_config);
+    netw_params.referrer_url = Some(site.base_url.to_string());
+    let client = make_http_client(&netw_params);
+
+    let mut already_retrieved_urls = get_urls_from_database(database_filename.as_str(), site.plugin_name);
+    info!("For Plugin {}: Got {} previously retrieved urls from table.", site.plugin_name, already_retrieved_urls.len());
+
+    let mut rng = rand::rng();
+
+    for (listing_url, section_name) in site.listing_urls {
+        info!("{}: identifying listing from section: {}", site.plugin_name, section_name);
+        let content = http_get(
+            &listing_url.to_string(),
+            &client,
+            netw_params.retry_times,
+            rng.random_range(netw_params.wait_time_min..=(netw_params.wait_time_min * 3))
+        );
+        info!("{}: Retrieving url listing from: {}", site.plugin_name, listing_url);
+        for this_new_doc in new_docs_from_listing(site, &content, listing_url, section_name, &mut already_retrieved_urls) {
+            if send_pdf_document(site, this_new_doc, &tx, &client, data_folder_str.as_str(), pdf_folder_str.as_str()) {
+                counter += 1;
+            }
+        }
+    }
+    info!("{}: Completed retrieving {} documents.", site.plugin_name, counter);
+}
+
+/// Extract the documents on a listing page that were not retrieved before, tagging them with
+/// the section name and recording their URLs as retrieved.
+fn new_docs_from_listing(
+    site: &RegulatorSite,
+    content: &str,
+    url_listing_page: &str,
+    section_name: &str,
+    already_retrieved_urls: &mut HashSet<String>) -> Vec<Document>
+{
+    let mut new_docs = Vec::new();
+    for mut this_new_doc in (site.extract_docs)(content, url_listing_page) {
+        if !already_retrieved_urls.insert(this_new_doc.url.clone()) {
+            info!("{}: Ignoring already retrieved url: {}", site.plugin_name, this_new_doc.url);
+            continue;
+        }
+        this_new_doc.section_name = section_name.to_string();
+        new_docs.push(this_new_doc);
+    }
+    new_docs
+}
+
+/// Set the output filename of a document, read the text of its PDF and send it down the
+/// channel. Returns true if the document was sent.
+fn send_pdf_document(
+    site: &RegulatorSite,
+    mut this_new_doc: Document,
+    tx: &Sender<document::Document>,
+    client: &reqwest::blocking::Client,
+    data_folder: &str,
+    pdf_folder: &str) -> bool
+{
+    let filename = make_unique_filename(&this_new_doc, "json");
+    let json_file_path = Path::new(data_folder).join(filename);
+    this_new_doc.filename = String::from(
+        json_file_path.as_path().to_str().expect("Not able to convert path to string")
+    );
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        load_pdf_content(&mut this_new_doc, client, pdf_folder);
+    }));
+    if let Err(errvar) = result {
+        error!("{}: When reading PDF of document '{}' the error was: {:?}", site.plugin_name, this_new_doc.title, errvar);
+    }
+
+    match tx.send(this_new_doc) {
+        Result::Ok(_res) => true,
+        Err(e) => {
+            error!("{}: When sending document via channel: {}", site.plugin_name, e);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extract_two_docs(_content: &str, url_listing_page: &str) -> Vec<Document> {
+        ["https://example.gov.in/a.pdf", "https://example.gov.in/b.pdf"].iter()
+            .map(|url| Document { url: url.to_string(), links_inward: vec![url_listing_page.to_string()], ..SITE.new_document() })
+            .collect()
+    }
+
+    const SITE: RegulatorSite = RegulatorSite {
+        plugin_name: "mod_test_regulator",
+        publisher_name: "Test Regulator",
+        base_url: "https://example.gov.in/",
+        listing_urls: &[("https://example.gov.in/circulars.html", "Circulars")],
+        extract_docs: extract_two_docs,
+    };
+
+    #[test]
+    fn test_new_docs_from_listing_skips_retrieved_urls() {
+        let mut retrieved = HashSet::from(["https://example.gov.in/a.pdf".to_string()]);
+        let docs = new_docs_from_listing(&SITE, "", "https://example.gov.in/circulars.html", "Circulars", &mut retrieved);
+        assert_eq!(docs.len(), 1);
+        assert_eq!(docs[0].url, "https://example.gov.in/b.pdf");
+        assert_eq!(docs[0].section_name, "Circulars");
+        assert_eq!(docs[0].module, "mod_test_regulator");
+        assert_eq!(docs[0].source_author, "Test Regulator");
+        assert_eq!(docs[0].classification["doc_type"], "regulatory-notification");
+        assert!(retrieved.contains("https://example.gov.in/b.pdf"));
+    }
+
+    #[test]
+    fn test_absolute_url() {
+        assert_eq!(SITE.absolute_url("./a/b.pdf"), Some("https://example.gov.in/a/b.pdf".to_string()));
+        assert_eq!(SITE.absolute_url("/c.pdf"), Some("https://example.gov.in/c.pdf".to_string()));
+        assert_eq!(SITE.absolute_url("https://other.in/x.pdf"), Some("https://other.in/x.pdf".to_string()));
+    }
+}
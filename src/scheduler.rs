@@ -0,0 +1,482 @@
+// file: scheduler.rs
+// Purpose:
+//   Daemon mode: a long-running process that runs each retriever and each batch feed on its
+//   own schedule instead of relying on external cron. A plugin is scheduled by a 5-field cron
+//   expression ("schedule"="30 18 * * 1-5", server local time) or a fixed interval
+//   ("interval_minutes"=60) in its config entry. Without either, retrievers fall back to the
+//   top-level `daemon_default_interval_minutes` (default 360) and batch feeds to their
+//   `frequency_days`.
+//
+//   A job never overlaps itself: a run that falls due while the previous one is still going
+//   is dropped and the job waits for its next slot. At most `daemon_max_parallel_jobs`
+//   (default 4) jobs run at once; a due job waits for a free slot. Last/next run times are
+//   kept in `schedule_state` (main metadata DB) and the next run is written as a run starts,
+//   so restarting the daemon neither repeats runs nor loses one that fell due while it was
+//   down (that one runs once, straight away).
+
+use std::collections::BinaryHeap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration as StdDuration;
+
+use chrono::{Datelike, Duration, Local, NaiveDateTime, TimeZone, Timelike, Utc};
+use log::{error, info, warn};
+use rusqlite::Connection;
+
+use crate::cfg::{get_database_filename, get_market_data_db};
+use crate::get_plugin_cfg;
+use crate::pipeline::{self, DataProcPlugin, PipelineRun, RetrieverPlugin};
+use crate::store::batch_log::RunStatus;
+use crate::store::schedule_state;
+use crate::web_api::SharedStatus;
+
+const DEFAULT_RETRIEVER_INTERVAL_MINUTES: i64 = 360;
+const DEFAULT_MAX_PARALLEL_JOBS: usize = 4;
+/// Longest the daemon sleeps between checks for due jobs.
+const MAX_TICK_SECS: i64 = 30;
+
+/// A cron expression: minute, hour, day of month, month, day of week (0 or 7 = Sunday).
+/// Fields accept `*`, numbers, ranges `a-b`, lists `a,b` and steps `*/n` or `a-b/n`. As in
+/// cron, when both day fields are restricted a day matching either one qualifies.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronExpr {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    dom_any: bool,
+    dow_any: bool,
+}
+
+fn parse_cron_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let number = |s: &str| s.parse::<u32>().map_err(|_| format!("invalid value '{}' in '{}'", s, field));
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => match number(step)? {
+                0 => return Err(format!("zero step in '{}'", field)),
+                n => (range, n),
+            },
+            None => (part, 1),
+        };
+        let (lo, hi) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (number(a)?, number(b)?)
+        } else {
+            // "5/15" means every 15 starting at 5.
+            let v = number(range)?;
+            (v, if step > 1 { max } else { v })
+        };
+        if lo < min || hi > max || lo > hi {
+            return Err(format!("'{}' is outside {}-{}", part, min, max));
+        }
+        for v in (lo..=hi).step_by(step as usize) {
+            bits |= 1 << v;
+        }
+    }
+    Ok(bits)
+}
+
+fn has_bit(bits: u64, v: u32) -> bool {
+    bits & (1 << v) != 0
+}
+
+impl CronExpr {
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let expr = match expr.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!("expected 5 fields in cron expression '{}'", expr));
+        }
+        let mut days_of_week = parse_cron_field(fields[4], 0, 7)?;
+        if has_bit(days_of_week, 7) {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+        Ok(CronExpr {
+            minutes: parse_cron_field(fields[0], 0, 59)?,
+            hours: parse_cron_field(fields[1], 0, 23)?,
+            days_of_month: parse_cron_field(fields[2], 1, 31)?,
+            months: parse_cron_field(fields[3], 1, 12)?,
+            days_of_week,
+            dom_any: fields[2].starts_with('*'),
+            dow_any: fields[4].starts_with('*'),
+        })
+    }
+
+    fn day_matches(&self, t: &NaiveDateTime) -> bool {
+        if !has_bit(self.months, t.month()) {
+            return false;
+        }
+        let dom = has_bit(self.days_of_month, t.day());
+        let dow = has_bit(self.days_of_week, t.weekday().num_days_from_sunday());
+        match (self.dom_any, self.dow_any) {
+            (true, true) => true,
+            (true, false) => dow,
+            (false, true) => dom,
+            (false, false) => dom || dow,
+        }
+    }
+
+    /// The first matching minute strictly after `after`, searching up to five years ahead
+    /// (None for expressions that never match, such as "0 0 31 2 *").
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut t = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = t + Duration::days(5 * 366);
+        while t <= limit {
+            if !self.day_matches(&t) {
+                t = t.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+            } else if !has_bit(self.hours, t.hour()) {
+                t = t.date().and_hms_opt(t.hour(), 0, 0)? + Duration::hours(1);
+            } else if !has_bit(self.minutes, t.minute()) {
+                t += Duration::minutes(1);
+            } else {
+                return Some(t);
+            }
+        }
+        None
+    }
+}
+
+/// When a job runs.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schedule {
+    /// Every so many seconds, counted from the start of the previous run.
+    Interval(i64),
+    /// At the times matched by a cron expression, in the server's local time.
+    Cron(CronExpr),
+}
+
+impl Schedule {
+    /// Build a schedule from a plugin's "schedule" (cron) or "interval_minutes" setting; the
+    /// cron expression wins if both are given. Ok(None) if neither is set.
+    pub fn from_settings(cron: Option<&str>, interval_minutes: Option<&str>) -> Result<Option<Schedule>, String> {
+        if let Some(expr) = cron.filter(|c| !c.trim().is_empty()) {
+            return CronExpr::parse(expr).map(|c| Some(Schedule::Cron(c)));
+        }
+        match interval_minutes {
+            Some(m) => match m.trim().parse::<i64>() {
+                Ok(m) if m > 0 => Ok(Some(Schedule::Interval(m * 60))),
+                _ => Err(format!("invalid interval_minutes '{}'", m)),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// The next run time (epoch seconds) after `ts`.
+    pub fn next_after(&self, ts: i64) -> Option<i64> {
+        match self {
+            Schedule::Interval(secs) => Some(ts + secs),
+            Schedule::Cron(cron) => {
+                let mut t = Local.timestamp_opt(ts, 0).single()?.naive_local();
+                // A local time skipped by a DST change does not exist; take the next match.
+                for _ in 0..1440 {
+                    t = cron.next_after(t)?;
+                    if let Some(dt) = Local.from_local_datetime(&t).earliest() {
+                        return Some(dt.timestamp());
+                    }
+                }
+                None
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobKind {
+    Retriever,
+    BatchFeed,
+}
+
+impl JobKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobKind::Retriever => "retriever",
+            JobKind::BatchFeed => "batch_feed",
+        }
+    }
+}
+
+/// A scheduled plugin.
+pub struct Job {
+    pub name: String,
+    pub kind: JobKind,
+    pub schedule: Schedule,
+    /// Retriever entry point; None for batch feeds.
+    retriever: Option<fn(std::sync::mpsc::Sender<crate::document::Document>, Arc<config::Config>)>,
+    next_run_ts: i64,
+    running: Arc<AtomicBool>,
+}
+
+fn plugin_schedule(name: &str, app_config: &config::Config, fallback_secs: i64) -> Option<Schedule> {
+    let cron = get_plugin_cfg!(name, "schedule", app_config);
+    let interval = get_plugin_cfg!(name, "interval_minutes", app_config);
+    match Schedule::from_settings(cron.as_deref(), interval.as_deref()) {
+        Ok(Some(s)) => Some(s),
+        Ok(None) => Some(Schedule::Interval(fallback_secs)),
+        Err(e) => {
+            error!("scheduler: '{}' has an invalid schedule ({}); it will not be run.", name, e);
+            None
+        }
+    }
+}
+
+/// Build the jobs for all enabled retrievers and batch feeds in the config.
+pub fn load_jobs(app_config: Arc<config::Config>) -> Vec<Job> {
+    let retriever_default = app_config
+        .get_int("daemon_default_interval_minutes")
+        .unwrap_or(DEFAULT_RETRIEVER_INTERVAL_MINUTES)
+        .max(1)
+        * 60;
+    let mut jobs = Vec::new();
+    for plugin in pipeline::load_retriever_plugins(app_config.clone()).into_iter().filter(|p| p.enabled) {
+        if let Some(schedule) = plugin_schedule(&plugin.name, &app_config, retriever_default) {
+            jobs.push(Job {
+                name: plugin.name,
+                kind: JobKind::Retriever,
+                schedule,
+                retriever: Some(plugin.method),
+                next_run_ts: 0,
+                running: Arc::new(AtomicBool::new(false)),
+            });
+        }
+    }
+    for feed in crate::feeds::load_batch_feeds(&app_config).into_iter().filter(|f| f.enabled) {
+        let fallback = feed.frequency_days.max(1) as i64 * 86_400;
+        if let Some(schedule) = plugin_schedule(&feed.name, &app_config, fallback) {
+            jobs.push(Job {
+                name: feed.name,
+                kind: JobKind::BatchFeed,
+                schedule,
+                retriever: None,
+                next_run_ts: 0,
+                running: Arc::new(AtomicBool::new(false)),
+            });
+        }
+    }
+    jobs
+}
+
+/// First run time of `job` when the daemon starts at `now_ts`. A stored next run is kept (if
+/// already past, the job is due now), unless the schedule now gives an earlier slot because
+/// the config changed. A job never scheduled before runs now if it is interval-based, or at
+/// its next cron slot.
+fn initial_next_run(conn: &Connection, job: &Job, now_ts: i64) -> i64 {
+    let upcoming = job.schedule.next_after(now_ts).unwrap_or(i64::MAX);
+    match schedule_state::load(conn, &job.name).and_then(|s| s.next_run_ts) {
+        Some(stored) => stored.min(upcoming),
+        None => match job.schedule {
+            Schedule::Interval(_) => now_ts,
+            Schedule::Cron(_) => upcoming,
+        },
+    }
+}
+
+/// Run one job to completion; returns (status, message) for `schedule_state`.
+fn run_job(
+    name: &str,
+    kind: JobKind,
+    retriever: Option<fn(std::sync::mpsc::Sender<crate::document::Document>, Arc<config::Config>)>,
+    app_config: Arc<config::Config>,
+    status_tracker: Option<SharedStatus>,
+) -> (String, String) {
+    match (kind, retriever) {
+        (JobKind::Retriever, Some(method)) => {
+            let plugin = RetrieverPlugin { name: name.to_string(), priority: 1, enabled: true, method };
+            let data_proc_plugins: BinaryHeap<DataProcPlugin> =
+                pipeline::load_dataproc_plugins(app_config.clone(), pipeline::create_api_mutexes());
+            let run = pipeline::run_data_pipeline(vec![plugin], data_proc_plugins, app_config, status_tracker);
+            retriever_run_result(&run)
+        }
+        _ => {
+            let db_path = get_market_data_db(&app_config);
+            let outcome = crate::feeds::run_scheduled_feed(app_config, &db_path, name);
+            (outcome.status.as_str().to_string(), outcome.message)
+        }
+    }
+}
+
+/// (status, message) for a retriever job: a failure if its retriever could not start or
+/// panicked, as feed jobs record their own failures.
+fn retriever_run_result(run: &PipelineRun) -> (String, String) {
+    let processed = format!("{} document(s) processed", run.docs.len());
+    if run.failed_retrievers.is_empty() {
+        return (RunStatus::Success.as_str().to_string(), processed);
+    }
+    let reasons: Vec<String> = run.failed_retrievers.iter()
+        .map(|(name, reason)| format!("'{}' {}", name, reason))
+        .collect();
+    (RunStatus::Failure.as_str().to_string(), format!("{}; {}", reasons.join("; "), processed))
+}
+
+/// Run the daemon: schedule every enabled retriever and batch feed and run each when due,
+/// until the process is stopped. Returns straight away if there is nothing to schedule.
+/// The web API, if enabled, is started by the caller and keeps serving between runs.
+pub fn run_daemon(app_config: Arc<config::Config>, status_tracker: Option<SharedStatus>) {
+    let db_path = get_database_filename(&app_config);
+    let conn = match crate::store::open_and_migrate(&db_path) {
+        Ok(c) => c,
+        Err(e) => {
+            error!("scheduler: cannot open schedule state in '{}': {}", db_path, e);
+            return;
+        }
+    };
+    let max_parallel = app_config
+        .get_int("daemon_max_parallel_jobs")
+        .map(|n| n.max(1) as usize)
+        .unwrap_or(DEFAULT_MAX_PARALLEL_JOBS);
+
+    let mut jobs = load_jobs(app_config.clone());
+    if jobs.is_empty() {
+        warn!("scheduler: no enabled retrievers or batch feeds to schedule.");
+        return;
+    }
+    let now_ts = Utc::now().timestamp();
+    for job in jobs.iter_mut() {
+        job.next_run_ts = initial_next_run(&conn, job, now_ts);
+        schedule_state::set_next_run(&conn, &job.name, job.kind.as_str(), job.next_run_ts);
+        info!("scheduler: '{}' ({}) next runs at {}.", job.name, job.kind.as_str(),
+            Local.timestamp_opt(job.next_run_ts, 0).single().map(|t| t.to_rfc3339()).unwrap_or_default());
+    }
+    info!("scheduler: daemon started with {} job(s), at most {} running at once.", jobs.len(), max_parallel);
+
+    let active = Arc::new(AtomicUsize::new(0));
+    loop {
+        let now_ts = Utc::now().timestamp();
+        for job in jobs.iter_mut().filter(|j| j.next_run_ts <= now_ts) {
+            let next = job.schedule.next_after(now_ts).unwrap_or(i64::MAX);
+            if job.running.load(Ordering::SeqCst) {
+                warn!("scheduler: '{}' is still running; skipping this run.", job.name);
+                job.next_run_ts = next;
+                schedule_state::set_next_run(&conn, &job.name, job.kind.as_str(), next);
+                continue;
+            }
+            if active.load(Ordering::SeqCst) >= max_parallel {
+                // Leave it due; it starts once a running job finishes.
+                continue;
+            }
+
+            job.running.store(true, Ordering::SeqCst);
+            active.fetch_add(1, Ordering::SeqCst);
+            job.next_run_ts = next;
+            schedule_state::record_start(&conn, &job.name, job.kind.as_str(), now_ts, next);
+
+            let (name, kind, retriever) = (job.name.clone(), job.kind, job.retriever);
+            let (running, active_jobs) = (job.running.clone(), active.clone());
+            let (cfg, tracker, state_db) = (app_config.clone(), status_tracker.clone(), db_path.clone());
+            let spawned = thread::Builder::new().name(format!("sched_{}", name)).spawn(move || {
+                info!("scheduler: starting '{}'.", name);
+                let (status, message) = run_job(&name, kind, retriever, cfg, tracker);
+                info!("scheduler: '{}' finished: {} — {}", name, status, message);
+                if let Ok(c) = crate::store::open(&state_db) {
+                    schedule_state::record_finish(&c, &name, &status, &message);
+                }
+                running.store(false, Ordering::SeqCst);
+                active_jobs.fetch_sub(1, Ordering::SeqCst);
+            });
+            if let Err(e) = spawned {
+                error!("scheduler: could not start '{}': {}", job.name, e);
+                schedule_state::record_finish(&conn, &job.name, "failure", &e.to_string());
+                job.running.store(false, Ordering::SeqCst);
+                active.fetch_sub(1, Ordering::SeqCst);
+            }
+        }
+
+        let now_ts = Utc::now().timestamp();
+        let earliest = jobs.iter().map(|j| j.next_run_ts).min().unwrap_or(now_ts + MAX_TICK_SECS);
+        let wait = (earliest - now_ts).clamp(1, MAX_TICK_SECS);
+        thread::sleep(StdDuration::from_secs(wait as u64));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use crate::document::Document;
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap().and_hms_opt(h, min, 0).unwrap()
+    }
+
+    #[test]
+    fn test_cron_next_after() {
+        // 18:30 on weekdays; 2025-06-06 is a Friday.
+        let c = CronExpr::parse("30 18 * * 1-5").unwrap();
+        assert_eq!(c.next_after(at(2025, 6, 6, 9, 0)), Some(at(2025, 6, 6, 18, 30)));
+        assert_eq!(c.next_after(at(2025, 6, 6, 18, 30)), Some(at(2025, 6, 9, 18, 30)), "skips the weekend");
+
+        let every_15 = CronExpr::parse("*/15 * * * *").unwrap();
+        assert_eq!(every_15.next_after(at(2025, 6, 6, 23, 50)), Some(at(2025, 6, 7, 0, 0)));
+
+        // Both day fields restricted: the 1st of the month OR any Sunday.
+        let c = CronExpr::parse("0 6 1 * 7").unwrap();
+        assert_eq!(c.next_after(at(2025, 6, 2, 0, 0)), Some(at(2025, 6, 8, 6, 0)));
+        assert_eq!(CronExpr::parse("@monthly").unwrap().next_after(at(2025, 6, 2, 0, 0)), Some(at(2025, 7, 1, 0, 0)));
+        assert_eq!(CronExpr::parse("0 0 31 2 *").unwrap().next_after(at(2025, 1, 1, 0, 0)), None);
+    }
+
+    #[test]
+    fn test_schedule_settings() {
+        assert!(CronExpr::parse("30 18 * *").is_err());
+        assert!(CronExpr::parse("60 * * * *").is_err());
+        assert!(CronExpr::parse("*/0 * * * *").is_err());
+        assert_eq!(Schedule::from_settings(None, Some("90")).unwrap(), Some(Schedule::Interval(5400)));
+        assert!(matches!(Schedule::from_settings(Some("0 9 * * *"), Some("90")).unwrap(), Some(Schedule::Cron(_))));
+        assert_eq!(Schedule::from_settings(None, None).unwrap(), None);
+        assert!(Schedule::from_settings(None, Some("0")).is_err());
+        assert_eq!(Schedule::Interval(600).next_after(1_000), Some(1_600));
+    }
+
+    #[test]
+    fn test_initial_next_run_uses_persisted_state() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::store::migrate(&conn).unwrap();
+        let job = Job {
+            name: "feed_nse_bhavcopy".to_string(),
+            kind: JobKind::BatchFeed,
+            schedule: Schedule::Interval(3_600),
+            retriever: None,
+            next_run_ts: 0,
+            running: Arc::new(AtomicBool::new(false)),
+        };
+        let now = 1_000_000;
+        assert_eq!(initial_next_run(&conn, &job, now), now, "a new interval job runs straight away");
+
+        // Ran 10 minutes before a restart: wait for the rest of the interval.
+        schedule_state::record_start(&conn, &job.name, "batch_feed", now - 600, now + 3_000);
+        assert_eq!(initial_next_run(&conn, &job, now), now + 3_000);
+        // Fell due while the daemon was down: run once, now.
+        schedule_state::set_next_run(&conn, &job.name, "batch_feed", now - 7_200);
+        assert!(initial_next_run(&conn, &job, now) <= now);
+    }
+
+    #[test]
+    fn test_failed_retriever_run_is_recorded_as_failure() {
+        let ok = PipelineRun { docs: vec![Document::default(), Document::default()], failed_retrievers: vec![] };
+        assert_eq!(retriever_run_result(&ok), ("success".to_string(), "2 document(s) processed".to_string()));
+
+        let failed = PipelineRun {
+            docs: vec![],
+            failed_retrievers: vec![("mod_en_in_rbi".to_string(), "panicked: listing page changed".to_string())],
+        };
+        let (status, message) = retriever_run_result(&failed);
+        assert_eq!(status, "failure");
+        assert_eq!(message, "'mod_en_in_rbi' panicked: listing page changed; 0 document(s) processed");
+
+        let conn = Connection::open_in_memory().unwrap();
+        crate::store::migrate(&conn).unwrap();
+        schedule_state::record_start(&conn, "mod_en_in_rbi", "retriever", 1_000, 4_600);
+        schedule_state::record_finish(&conn, "mod_en_in_rbi", &status, &message);
+        let state = schedule_state::load(&conn, "mod_en_in_rbi").unwrap();
+        assert_eq!(state.last_status.as_deref(), Some("failure"));
+    }
+}
@@ -13,13 +13,16 @@ use rusqlite::Connection;
 pub enum RunStatus {
     Success,
     Failure,
+    /// Not attempted because a feed it depends on failed or was itself skipped.
+    Skipped,
 }
 
 impl RunStatus {
-    fn as_str(&self) -> &'static str {
+    pub fn as_str(&self) -> &'static str {
         match self {
             RunStatus::Success => "success",
             RunStatus::Failure => "failure",
+            RunStatus::Skipped => "skipped",
         }
     }
 }
@@ -55,6 +58,31 @@ pub fn should_skip(
     }
 }
 
+/// Returns true if `(source, dataset)` has ever completed successfully. Used by date-range
+/// backfills, where each date is its own dataset and never needs re-loading once done.
+pub fn has_succeeded(conn: &Connection, source: &str, dataset: &str) -> bool {
+    conn.query_row(
+        "SELECT last_success_ts FROM batch_run_log WHERE source=?1 AND dataset=?2",
+        rusqlite::params![source, dataset],
+        |r| r.get::<_, Option<i64>>(0),
+    )
+    .ok()
+    .flatten()
+    .is_some()
+}
+
+/// Status of the latest attempt of `(source, dataset)` ("running", "success", "failure" or
+/// "skipped"), or None if it has never run.
+pub fn last_status(conn: &Connection, source: &str, dataset: &str) -> Option<String> {
+    conn.query_row(
+        "SELECT status FROM batch_run_log WHERE source=?1 AND dataset=?2",
+        rusqlite::params![source, dataset],
+        |r| r.get::<_, Option<String>>(0),
+    )
+    .ok()
+    .flatten()
+}
+
 /// Record the start of an attempt (sets `last_attempt_ts`, status='running'), upserting the
 /// row if it does not yet exist.
 pub fn record_attempt(conn: &Connection, source: &str, dataset: &str, now_ts: i64) {
@@ -69,7 +97,8 @@ pub fn record_attempt(conn: &Connection, source: &str, dataset: &str, now_ts: i6
     }
 }
 
-/// Record the outcome of an attempt. On success, also updates `last_success_ts`.
+/// Record the outcome of an attempt. On success, also updates `last_success_ts`; failures and
+/// skips leave it untouched so the next invocation tries again.
 pub fn record_result(
     conn: &Connection,
     source: &str,
@@ -87,7 +116,7 @@ pub fn record_result(
              ON CONFLICT(source, dataset) DO UPDATE SET
                 last_attempt_ts=?3, last_success_ts=?3, status=?4, rows_ingested=?5, message=?6"
         }
-        RunStatus::Failure => {
+        RunStatus::Failure | RunStatus::Skipped => {
             "INSERT INTO batch_run_log
                 (source, dataset, last_attempt_ts, status, rows_ingested, message)
              VALUES (?1, ?2, ?3, ?4, ?5, ?6)
@@ -166,4 +195,37 @@ mod tests {
             .unwrap();
         assert_eq!(status, "failure");
     }
+
+    #[test]
+    fn test_skipped_run_is_recorded_and_retried() {
+        let c = db();
+        record_result(&c, "feed_index_constituents", "feed_index_constituents", RunStatus::Skipped, 0,
+            "prerequisite 'feed_symbol_master' failed", 10 * DAY);
+        assert!(!should_skip(&c, "feed_index_constituents", "feed_index_constituents", 7, 10 * DAY + 1));
+        let status: String = c
+            .query_row("SELECT status FROM batch_run_log WHERE source='feed_index_constituents'", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(status, "skipped");
+    }
+
+    #[test]
+    fn test_last_status_tracks_latest_attempt() {
+        let c = db();
+        assert_eq!(last_status(&c, "feed_symbol_master", "feed_symbol_master"), None);
+        record_attempt(&c, "feed_symbol_master", "feed_symbol_master", 10 * DAY);
+        assert_eq!(last_status(&c, "feed_symbol_master", "feed_symbol_master").as_deref(), Some("running"));
+        record_result(&c, "feed_symbol_master", "feed_symbol_master", RunStatus::Success, 2000, "ok", 10 * DAY + 60);
+        assert_eq!(last_status(&c, "feed_symbol_master", "feed_symbol_master").as_deref(), Some("success"));
+    }
+
+    #[test]
+    fn test_has_succeeded_only_after_success() {
+        let c = db();
+        assert!(!has_succeeded(&c, "feed_nse_bhavcopy", "date:2024-01-02"));
+        record_attempt(&c, "feed_nse_bhavcopy", "date:2024-01-02", DAY);
+        record_result(&c, "feed_nse_bhavcopy", "date:2024-01-02", RunStatus::Failure, 0, "timeout", DAY + 1);
+        assert!(!has_succeeded(&c, "feed_nse_bhavcopy", "date:2024-01-02"));
+        record_result(&c, "feed_nse_bhavcopy", "date:2024-01-02", RunStatus::Success, 2000, "ok", DAY + 2);
+        assert!(has_succeeded(&c, "feed_nse_bhavcopy", "date:2024-01-02"));
+    }
 }
@@ -0,0 +1,109 @@
+// file: store/dq_findings.rs
+// Purpose:
+//   Helpers over the `dq_findings` table (migration 0005): the data-quality findings raised
+//   when a batch feed validates a downloaded file (see `feeds::dq`). Findings are kept per
+//   (source, trade_date); re-validating a date replaces its earlier findings, so the table
+//   always reflects the latest attempt.
+
+use rusqlite::Connection;
+
+/// One stored data-quality finding.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DqFindingRow {
+    pub source: String,
+    pub trade_date: String,
+    pub check_name: String,
+    /// "error" or "warning".
+    pub severity: String,
+    pub instrument: Option<String>,
+    pub message: String,
+}
+
+/// Replace the findings of `(source, trade_date)` with `findings` in one transaction.
+/// Returns the number of rows written.
+pub fn replace_findings(
+    conn: &mut Connection,
+    source: &str,
+    trade_date: &str,
+    findings: &[DqFindingRow],
+    now_ts: i64,
+) -> Result<usize, String> {
+    let tx = conn.transaction().map_err(|e| format!("begin transaction: {}", e))?;
+    tx.execute(
+        "DELETE FROM dq_findings WHERE source=?1 AND trade_date=?2",
+        rusqlite::params![source, trade_date],
+    )
+    .map_err(|e| format!("clear dq_findings({}, {}): {}", source, trade_date, e))?;
+    {
+        let mut stmt = tx
+            .prepare(
+                "INSERT INTO dq_findings
+                    (source, trade_date, check_name, severity, instrument, message, created_ts)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )
+            .map_err(|e| format!("prepare dq_findings insert: {}", e))?;
+        for f in findings {
+            stmt.execute(rusqlite::params![
+                source, trade_date, f.check_name, f.severity, f.instrument, f.message, now_ts
+            ])
+            .map_err(|e| format!("insert dq finding({}, {}): {}", source, trade_date, e))?;
+        }
+    }
+    tx.commit().map_err(|e| format!("commit dq_findings: {}", e))?;
+    Ok(findings.len())
+}
+
+/// Findings recorded for `(source, trade_date)`, errors first.
+pub fn findings_for(conn: &Connection, source: &str, trade_date: &str) -> Result<Vec<DqFindingRow>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT source, trade_date, check_name, severity, instrument, message FROM dq_findings
+             WHERE source=?1 AND trade_date=?2
+             ORDER BY CASE severity WHEN 'error' THEN 0 ELSE 1 END, id",
+        )
+        .map_err(|e| format!("prepare dq_findings query: {}", e))?;
+    let rows = stmt
+        .query_map(rusqlite::params![source, trade_date], |r| {
+            Ok(DqFindingRow {
+                source: r.get(0)?,
+                trade_date: r.get(1)?,
+                check_name: r.get(2)?,
+                severity: r.get(3)?,
+                instrument: r.get(4)?,
+                message: r.get(5)?,
+            })
+        })
+        .map_err(|e| format!("query dq_findings: {}", e))?;
+    Ok(rows.flatten().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store;
+
+    fn finding(check: &str, severity: &str) -> DqFindingRow {
+        DqFindingRow {
+            source: "feed_nse_bhavcopy".to_string(),
+            trade_date: "2025-06-03".to_string(),
+            check_name: check.to_string(),
+            severity: severity.to_string(),
+            instrument: None,
+            message: format!("{} finding", check),
+        }
+    }
+
+    #[test]
+    fn test_replace_findings_overwrites_previous_attempt() {
+        let mut c = Connection::open_in_memory().unwrap();
+        store::migrate(&c).unwrap();
+        let first = vec![finding("day_move", "warning"), finding("schema", "error")];
+        assert_eq!(replace_findings(&mut c, "feed_nse_bhavcopy", "2025-06-03", &first, 1).unwrap(), 2);
+        let stored = findings_for(&c, "feed_nse_bhavcopy", "2025-06-03").unwrap();
+        assert_eq!(stored[0].check_name, "schema", "errors are listed first");
+
+        replace_findings(&mut c, "feed_nse_bhavcopy", "2025-06-03", &[finding("day_move", "warning")], 2).unwrap();
+        assert_eq!(findings_for(&c, "feed_nse_bhavcopy", "2025-06-03").unwrap().len(), 1);
+        assert!(findings_for(&c, "feed_bse_bhavcopy", "2025-06-03").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,321 @@
+// file: store/entity_master.rs
+// Purpose:
+//   Reference-entity master over `entities` / `entity_aliases` for entity resolution
+//   (`mod_entity_resolve`): legal entities from the GLEIF LEI file and the MCA company master,
+//   loaded by `feed_entity_master`. A company appears once: a record is merged into the entity
+//   already holding its LEI, one of its ISINs (GLEIF's LEI–ISIN map) or its CIN, or — for a
+//   listed company (CIN starting with 'L') — the single listed entity with the same name.
+//   Otherwise it is keyed `lei:<LEI>` or `cin:<CIN>`.
+//
+//   Besides the exact `name` aliases, every name gets derived aliases the resolver matches on:
+//   `name_core` (legal-form words such as "Limited" / "Pvt" / "Company" removed) and `acronym`
+//   (initials of names of three or more words, "State Bank of India" → "sbi").
+
+use chrono::NaiveDate;
+use rusqlite::{Connection, OptionalExtension};
+
+use crate::analysis::norm_name;
+use crate::store::securities::ALIAS_NAME;
+
+pub const ALIAS_NAME_CORE: &str = "name_core";
+pub const ALIAS_ACRONYM: &str = "acronym";
+
+/// Trailing legal-form words dropped from a name's core.
+const LEGAL_FORM_WORDS: &[&str] = &[
+    "limited", "ltd", "pvt", "private", "plc", "llp", "inc", "incorporated", "co", "company",
+    "corp", "corporation",
+];
+/// Words skipped when forming an acronym.
+const ACRONYM_STOPWORDS: &[&str] = &["of", "and", "the", "for", "in"];
+
+/// A legal entity from a reference file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReferenceEntity {
+    pub name: String,
+    /// Other legal / trading / transliterated names.
+    pub other_names: Vec<String>,
+    pub lei: Option<String>,
+    pub cin: Option<String>,
+    /// ISINs issued by the entity (GLEIF LEI–ISIN map).
+    pub isins: Vec<String>,
+    /// Registration status as published, e.g. "ACTIVE", "Active", "Strike Off".
+    pub status: Option<String>,
+    pub legal_form: Option<String>,
+}
+
+fn day(d: NaiveDate) -> String {
+    d.format("%Y-%m-%d").to_string()
+}
+
+/// Normalised name without its leading "the" and trailing legal-form words.
+pub fn core_name(name: &str) -> String {
+    let norm = norm_name(name);
+    let mut words: Vec<&str> = norm.split(' ').filter(|w| !w.is_empty()).collect();
+    if words.first() == Some(&"the") {
+        words.remove(0);
+    }
+    while words.len() > 1 && LEGAL_FORM_WORDS.contains(words.last().unwrap_or(&"")) {
+        words.pop();
+    }
+    words.join(" ")
+}
+
+/// Acronyms of a name of three or more significant words: the initials of the name without
+/// "Limited"-type suffixes, and also without a trailing "of India" ("Life Insurance
+/// Corporation of India" → "lici", "lic").
+pub fn acronyms(name: &str) -> Vec<String> {
+    let norm = norm_name(name);
+    let mut words: Vec<&str> = norm.split(' ').filter(|w| !w.is_empty()).collect();
+    while words.last().is_some_and(|w| ["limited", "ltd", "pvt", "private", "plc", "llp", "inc"].contains(w)) {
+        words.pop();
+    }
+    let initials = |words: &[&str]| -> Option<String> {
+        let significant: Vec<&str> = words.iter().copied().filter(|w| !ACRONYM_STOPWORDS.contains(w)).collect();
+        if significant.len() < 3 {
+            return None;
+        }
+        Some(significant.iter().filter_map(|w| w.chars().next()).collect())
+    };
+    let mut out: Vec<String> = initials(&words).into_iter().collect();
+    if words.len() > 2 && words[words.len() - 2..] == ["of", "india"] {
+        out.extend(initials(&words[..words.len() - 2]));
+    }
+    out.dedup();
+    out
+}
+
+/// Add an open alias unless the entity already has one of this type and normalised form.
+/// Returns true if a row was added.
+fn add_alias(conn: &Connection, entity_id: &str, alias_type: &str, alias: &str, alias_norm: &str, from: NaiveDate) -> Result<bool, String> {
+    if alias_norm.is_empty() {
+        return Ok(false);
+    }
+    let exists: bool = conn
+        .query_row(
+            "SELECT 1 FROM entity_aliases
+             WHERE entity_id=?1 AND alias_type=?2 AND alias_norm=?3 AND valid_to IS NULL",
+            rusqlite::params![entity_id, alias_type, alias_norm],
+            |_| Ok(true),
+        )
+        .optional()
+        .map_err(|e| format!("read alias({}, {}): {}", entity_id, alias, e))?
+        .unwrap_or(false);
+    if exists {
+        return Ok(false);
+    }
+    conn.execute(
+        "INSERT INTO entity_aliases (entity_id, alias, alias_norm, alias_type, lang, valid_from)
+         VALUES (?1, ?2, ?3, ?4, 'en', ?5)",
+        rusqlite::params![entity_id, alias, alias_norm, alias_type, day(from)],
+    )
+    .map_err(|e| format!("insert alias({}, {}): {}", entity_id, alias, e))?;
+    Ok(true)
+}
+
+/// Add `name` to an entity with its derived core-name and acronym aliases. Returns the number
+/// of alias rows added.
+pub fn add_name_aliases(conn: &Connection, entity_id: &str, name: &str, from: NaiveDate) -> Result<usize, String> {
+    let mut added = usize::from(add_alias(conn, entity_id, ALIAS_NAME, name, &norm_name(name), from)?);
+    let core = core_name(name);
+    if core != norm_name(name) {
+        added += usize::from(add_alias(conn, entity_id, ALIAS_NAME_CORE, name, &core, from)?);
+    }
+    for acronym in acronyms(name) {
+        added += usize::from(add_alias(conn, entity_id, ALIAS_ACRONYM, name, &acronym, from)?);
+    }
+    Ok(added)
+}
+
+/// Add the derived aliases of every open `name` alias that does not have them yet (names
+/// loaded by the symbol-master feed, for instance). Returns the number of rows added.
+pub fn derive_missing_aliases(conn: &Connection, as_of: NaiveDate) -> Result<usize, String> {
+    let names: Vec<(String, String)> = {
+        let mut stmt = conn
+            .prepare(
+                "SELECT a.entity_id, a.alias FROM entity_aliases a
+                 WHERE a.alias_type=?1 AND a.valid_to IS NULL
+                   AND NOT EXISTS (SELECT 1 FROM entity_aliases d
+                                   WHERE d.entity_id=a.entity_id AND d.alias=a.alias
+                                     AND d.alias_type IN (?2, ?3))",
+            )
+            .map_err(|e| format!("prepare name aliases: {}", e))?;
+        let rows = stmt
+            .query_map(rusqlite::params![ALIAS_NAME, ALIAS_NAME_CORE, ALIAS_ACRONYM], |r| Ok((r.get(0)?, r.get(1)?)))
+            .map_err(|e| format!("query name aliases: {}", e))?;
+        rows.flatten().collect()
+    };
+    let mut added = 0;
+    for (entity_id, name) in names {
+        added += add_name_aliases(conn, &entity_id, &name, as_of)?;
+    }
+    Ok(added)
+}
+
+/// The existing entity a reference record belongs to, if any (see the file header).
+fn merge_target(conn: &Connection, rec: &ReferenceEntity) -> Result<Option<String>, String> {
+    let by = |column: &str, value: &str| -> Result<Option<String>, String> {
+        conn.query_row(
+            &format!("SELECT entity_id FROM entities WHERE {}=?1 ORDER BY entity_id LIMIT 1", column),
+            [value],
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("find entity by {} {}: {}", column, value, e))
+    };
+    if let Some(lei) = &rec.lei
+        && let Some(id) = by("lei", lei)?
+    {
+        return Ok(Some(id));
+    }
+    for isin in &rec.isins {
+        if let Some(id) = by("isin", isin)? {
+            return Ok(Some(id));
+        }
+    }
+    if let Some(cin) = &rec.cin {
+        if let Some(id) = by("cin", cin)? {
+            return Ok(Some(id));
+        }
+        if cin.starts_with('L') {
+            let listed: Vec<String> = {
+                let mut stmt = conn
+                    .prepare(
+                        "SELECT DISTINCT e.entity_id FROM entity_aliases a JOIN entities e ON e.entity_id=a.entity_id
+                         WHERE a.alias_type=?1 AND a.alias_norm=?2 AND e.isin IS NOT NULL AND e.cin IS NULL",
+                    )
+                    .map_err(|e| format!("prepare listed name lookup: {}", e))?;
+                let rows = stmt
+                    .query_map(rusqlite::params![ALIAS_NAME, norm_name(&rec.name)], |r| r.get(0))
+                    .map_err(|e| format!("query listed name lookup: {}", e))?;
+                rows.flatten().collect()
+            };
+            if let [id] = listed.as_slice() {
+                return Ok(Some(id.clone()));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Insert or merge one reference entity as loaded on `as_of`, with its names as aliases.
+/// Identifiers already set on a merged entity are kept; its status is only filled in when
+/// empty (a listed company stays "listed"). Returns the entity id.
+pub fn upsert_reference_entity(conn: &Connection, rec: &ReferenceEntity, as_of: NaiveDate) -> Result<String, String> {
+    let entity_id = match merge_target(conn, rec)? {
+        Some(id) => {
+            conn.execute(
+                "UPDATE entities SET
+                    lei=COALESCE(lei, ?2), cin=COALESCE(cin, ?3), legal_form=COALESCE(legal_form, ?4),
+                    status=COALESCE(status, ?5), last_update=?6
+                 WHERE entity_id=?1",
+                rusqlite::params![id, rec.lei, rec.cin, rec.legal_form, rec.status, day(as_of)],
+            )
+            .map_err(|e| format!("merge entity({}): {}", id, e))?;
+            id
+        }
+        None => {
+            let id = match (&rec.lei, &rec.cin) {
+                (Some(lei), _) => format!("lei:{}", lei),
+                (None, Some(cin)) => format!("cin:{}", cin),
+                (None, None) => return Err(format!("reference entity '{}' has neither LEI nor CIN", rec.name)),
+            };
+            conn.execute(
+                "INSERT INTO entities
+                    (entity_id, type, canonical_name, name_norm, lei, cin, isin, legal_form, status, valid_from, last_update)
+                 VALUES (?1, 'ORG', ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?9)
+                 ON CONFLICT(entity_id) DO UPDATE SET
+                    canonical_name=excluded.canonical_name, name_norm=excluded.name_norm,
+                    legal_form=excluded.legal_form, status=excluded.status, last_update=excluded.last_update",
+                rusqlite::params![
+                    id, rec.name, norm_name(&rec.name), rec.lei, rec.cin, rec.isins.first(),
+                    rec.legal_form, rec.status, day(as_of)
+                ],
+            )
+            .map_err(|e| format!("insert entity({}): {}", id, e))?;
+            id
+        }
+    };
+    for name in std::iter::once(&rec.name).chain(&rec.other_names) {
+        add_name_aliases(conn, &entity_id, name, as_of)?;
+    }
+    Ok(entity_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store;
+    use crate::store::securities::{upsert_listing, ListedSecurity};
+
+    fn db() -> Connection {
+        let c = Connection::open_in_memory().unwrap();
+        store::migrate(&c).unwrap();
+        c
+    }
+
+    fn d() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 6, 1).unwrap()
+    }
+
+    #[test]
+    fn test_core_name_and_acronyms() {
+        assert_eq!(core_name("The Tata Power Company Limited"), "tata power");
+        assert_eq!(core_name("Infosys Private Ltd."), "infosys");
+        assert_eq!(core_name("Limited"), "limited");
+        assert_eq!(acronyms("Tata Motors Limited"), Vec::<String>::new(), "only two significant words");
+        assert_eq!(acronyms("State Bank of India"), vec!["sbi"]);
+        assert_eq!(acronyms("Housing Development Finance Corporation Limited"), vec!["hdfc"]);
+        assert_eq!(acronyms("Life Insurance Corporation of India"), vec!["lici", "lic"]);
+    }
+
+    #[test]
+    fn test_reference_records_merge_into_one_entity() {
+        let c = db();
+        let listing = ListedSecurity {
+            isin: "INE002A01018".to_string(),
+            name: "Reliance Industries Limited".to_string(),
+            exchange: "NSE".to_string(),
+            symbol: "RELIANCE".to_string(),
+            sector: None,
+            listing_date: None,
+            canonical_name_source: true,
+        };
+        upsert_listing(&c, &listing, d()).unwrap();
+
+        // MCA listed company with the same name joins the listed entity...
+        let mca = ReferenceEntity {
+            name: "RELIANCE INDUSTRIES LIMITED".to_string(),
+            cin: Some("L17110MH1973PLC019786".to_string()),
+            status: Some("Active".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(upsert_reference_entity(&c, &mca, d()).unwrap(), "isin:INE002A01018");
+        // ...and so does the GLEIF record through its CIN, adding the LEI and another name.
+        let gleif = ReferenceEntity {
+            name: "RELIANCE INDUSTRIES LIMITED".to_string(),
+            other_names: vec!["RIL".to_string()],
+            lei: Some("5493003CWGDTZCDMI489".to_string()),
+            cin: Some("L17110MH1973PLC019786".to_string()),
+            status: Some("ACTIVE".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(upsert_reference_entity(&c, &gleif, d()).unwrap(), "isin:INE002A01018");
+        let (lei, cin, status): (String, String, String) = c
+            .query_row("SELECT lei, cin, status FROM entities WHERE entity_id='isin:INE002A01018'", [], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+            .unwrap();
+        assert_eq!((lei.as_str(), cin.as_str(), status.as_str()), ("5493003CWGDTZCDMI489", "L17110MH1973PLC019786", "listed"));
+        let core: String = c
+            .query_row("SELECT entity_id FROM entity_aliases WHERE alias_type='name_core' AND alias_norm='reliance industries'", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(core, "isin:INE002A01018");
+
+        // An unlisted company with no match gets its own CIN-keyed entity.
+        let private = ReferenceEntity {
+            name: "Zerodha Broking Limited".to_string(),
+            cin: Some("U65929KA2018PLC116815".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(upsert_reference_entity(&c, &private, d()).unwrap(), "cin:U65929KA2018PLC116815");
+        assert!(upsert_reference_entity(&c, &ReferenceEntity { name: "Nameless".to_string(), ..Default::default() }, d()).is_err());
+    }
+}
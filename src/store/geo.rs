@@ -0,0 +1,123 @@
+// file: store/geo.rs
+// Purpose:
+//   Bulk gazetteer over the `geo` / `geo_names` tables (migration 0013), loaded by
+//   `feed_gazetteer` from GeoNames dumps (populated places, countries and ADM1/ADM2 areas,
+//   keyed by geonameid) and the India Post PIN directory (one feature per PIN, keyed
+//   `IN-<PIN>`, named after its head/sub post office). Every name a feature is known by —
+//   name, ASCII name, Latin-script alternate names, post-office names — is indexed by its
+//   normalised form in `geo_names`, which `mod_geocode` looks place names up in.
+
+use rusqlite::Connection;
+
+use crate::analysis::norm_name;
+
+/// A gazetteer feature.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GeoFeature {
+    /// GeoNames id, or `IN-<PIN>` for a PIN area.
+    pub feature_id: String,
+    pub name: String,
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+    /// GeoNames feature code (PPL, PPLA, PPLC, ADM1, ADM2, PCLI, ...); "PO" for a PIN area.
+    pub feature_code: String,
+    /// ISO 3166-1 alpha-2 country code.
+    pub country: String,
+    pub adm1: String,
+    pub adm2: String,
+    pub population: i64,
+    /// "geonames" | "india_post".
+    pub source: String,
+}
+
+/// Insert or replace a feature with the names it is looked up by (`name` is always one).
+pub fn upsert_feature(conn: &Connection, f: &GeoFeature, names: &[String]) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR REPLACE INTO geo
+            (feature_id, name, lat, lon, feature_code, country, adm1, adm2, population, source)
+         VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9,?10)",
+        rusqlite::params![
+            f.feature_id, f.name, f.lat, f.lon, f.feature_code, f.country, f.adm1, f.adm2, f.population, f.source
+        ],
+    )
+    .map_err(|e| format!("upsert geo({}): {}", f.feature_id, e))?;
+    conn.execute("DELETE FROM geo_names WHERE feature_id=?1", [&f.feature_id])
+        .map_err(|e| format!("clear geo_names({}): {}", f.feature_id, e))?;
+    for name in std::iter::once(&f.name).chain(names) {
+        let norm = norm_name(name);
+        if !norm.is_empty() {
+            conn.execute("INSERT OR IGNORE INTO geo_names (name_norm, feature_id) VALUES (?1, ?2)", rusqlite::params![norm, f.feature_id])
+                .map_err(|e| format!("insert geo_name({}): {}", f.feature_id, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Every feature known by `name` (compared normalised), most populous first.
+pub fn lookup(conn: &Connection, name: &str) -> Result<Vec<GeoFeature>, String> {
+    let mut stmt = conn
+        .prepare_cached(
+            "SELECT g.feature_id, g.name, g.lat, g.lon, g.feature_code, g.country, g.adm1, g.adm2, g.population, g.source
+             FROM geo_names n JOIN geo g ON g.feature_id = n.feature_id
+             WHERE n.name_norm=?1 ORDER BY g.population DESC, g.feature_id",
+        )
+        .map_err(|e| format!("prepare geo lookup: {}", e))?;
+    let rows = stmt
+        .query_map([norm_name(name)], |r| {
+            Ok(GeoFeature {
+                feature_id: r.get(0)?,
+                name: r.get(1)?,
+                lat: r.get(2)?,
+                lon: r.get(3)?,
+                feature_code: r.get(4)?,
+                country: r.get(5)?,
+                adm1: r.get(6)?,
+                adm2: r.get(7)?,
+                population: r.get(8)?,
+                source: r.get(9)?,
+            })
+        })
+        .map_err(|e| format!("geo lookup({}): {}", name, e))?;
+    Ok(rows.flatten().collect())
+}
+
+/// Number of features loaded (0 on a database without the table).
+pub fn feature_count(conn: &Connection) -> i64 {
+    conn.query_row("SELECT COUNT(*) FROM geo", [], |r| r.get(0)).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store;
+
+    #[test]
+    fn test_upsert_and_lookup_by_any_name() {
+        let c = Connection::open_in_memory().unwrap();
+        store::migrate(&c).unwrap();
+        let mumbai = GeoFeature {
+            feature_id: "1275339".into(),
+            name: "Mumbai".into(),
+            lat: Some(19.07283),
+            lon: Some(72.88261),
+            feature_code: "PPLA".into(),
+            country: "IN".into(),
+            adm1: "Maharashtra".into(),
+            population: 12_691_836,
+            source: "geonames".into(),
+            ..Default::default()
+        };
+        upsert_feature(&c, &mumbai, &["Bombay".to_string(), "Mumbai".to_string()]).unwrap();
+        let pin = GeoFeature { feature_id: "IN-400069".into(), name: "Andheri East".into(), country: "IN".into(), source: "india_post".into(), ..Default::default() };
+        upsert_feature(&c, &pin, &["Bombay".to_string()]).unwrap();
+
+        assert_eq!(lookup(&c, "bombay").unwrap().iter().map(|f| f.feature_id.as_str()).collect::<Vec<_>>(), vec!["1275339", "IN-400069"]);
+        assert_eq!(lookup(&c, "MUMBAI").unwrap(), vec![mumbai.clone()]);
+        assert!(lookup(&c, "Pune").unwrap().is_empty());
+
+        // Re-loading a feature replaces its names.
+        upsert_feature(&c, &pin, &[]).unwrap();
+        assert_eq!(lookup(&c, "Bombay").unwrap().len(), 1);
+        assert_eq!(feature_count(&c), 2);
+    }
+}
@@ -18,7 +18,13 @@ use rusqlite::Connection;
 
 pub mod batch_log;
 pub mod batch_writer;
+pub mod dq_findings;
+pub mod entity_master;
+pub mod geo;
 pub mod records;
+pub mod schedule_state;
+pub mod securities;
+pub mod story_clusters;
 
 /// Open (creating if absent) a SQLite database at `db_path` with WAL journaling and the
 /// performance/concurrency pragmas the pipeline relies on. Returns an open connection.
@@ -35,7 +41,23 @@ pub fn open(db_path: &str) -> Result<Connection, String> {
 }
 
 /// Ordered list of schema migrations. Append-only: never edit or reorder an existing entry.
-const MIGRATIONS: &[(i64, &str)] = &[(1, MIGRATION_0001_CANONICAL_SCHEMA)];
+const MIGRATIONS: &[(i64, &str)] = &[
+    (1, MIGRATION_0001_CANONICAL_SCHEMA),
+    (2, MIGRATION_0002_PDF_TABLES),
+    (3, MIGRATION_0003_TRADING_HOLIDAYS),
+    (4, MIGRATION_0004_SECURITIES_MASTER),
+    (5, MIGRATION_0005_DQ_FINDINGS),
+    (6, MIGRATION_0006_CORPORATE_ACTIONS),
+    (7, MIGRATION_0007_SCHEDULE_STATE),
+    (8, MIGRATION_0008_EVENT_STUDY),
+    (9, MIGRATION_0009_ENTITY_RESOLUTION),
+    (10, MIGRATION_0010_STORY_SIGNATURES),
+    (11, MIGRATION_0011_MENTION_SENTIMENT),
+    (12, MIGRATION_0012_VOCAB_VERSIONS),
+    (13, MIGRATION_0013_GEO_GAZETTEER),
+    (14, MIGRATION_0014_DATE_EXPRESSIONS),
+    (15, MIGRATION_0015_SIGNATURE_UNIQUE_ID),
+];
 
 /// Open the database and bring it up to the latest schema version.
 pub fn open_and_migrate(db_path: &str) -> Result<Connection, String> {
@@ -315,6 +337,256 @@ CREATE TABLE IF NOT EXISTS batch_run_log (
 );
 ";
 
+// ---------------------------------------------------------------------------
+// Migration 0002 — tables reconstructed from PDF circulars (`pdf_tables` module). One row
+// per table; cells are kept as a JSON array of rows, with the CSV side file path for bulk
+// loading.
+// ---------------------------------------------------------------------------
+const MIGRATION_0002_PDF_TABLES: &str = "
+CREATE TABLE IF NOT EXISTS pdf_tables (
+    doc_id     TEXT NOT NULL,
+    table_idx  INTEGER NOT NULL,
+    page       INTEGER,
+    n_rows     INTEGER,
+    n_cols     INTEGER,
+    rows_json  TEXT,
+    csv_file   TEXT,
+    PRIMARY KEY (doc_id, table_idx)
+);
+";
+
+// ---------------------------------------------------------------------------
+// Migration 0003 — exchange trading holidays, refreshed by `feed_trading_holidays` and read
+// by `calendar::TradingCalendar`.
+// ---------------------------------------------------------------------------
+const MIGRATION_0003_TRADING_HOLIDAYS: &str = "
+CREATE TABLE IF NOT EXISTS trading_holidays (
+    exchange      TEXT NOT NULL,
+    holiday_date  TEXT NOT NULL,
+    description   TEXT,
+    PRIMARY KEY (exchange, holiday_date)
+);
+";
+
+// ---------------------------------------------------------------------------
+// Migration 0004 — listed-securities master (`store::securities`). Aliases gain a validity
+// window so symbol changes and delistings are kept as history; index membership is tracked
+// the same way. ALTER TABLE is not repeatable, which is fine since `schema_version` ensures
+// each migration runs once.
+// ---------------------------------------------------------------------------
+const MIGRATION_0004_SECURITIES_MASTER: &str = "
+ALTER TABLE entity_aliases ADD COLUMN valid_from TEXT;
+ALTER TABLE entity_aliases ADD COLUMN valid_to TEXT;
+CREATE INDEX IF NOT EXISTS idx_aliases_type ON entity_aliases (alias_type, alias);
+CREATE INDEX IF NOT EXISTS idx_entities_nse_symbol ON entities (nse_symbol);
+CREATE INDEX IF NOT EXISTS idx_entities_bse_code ON entities (bse_code);
+
+CREATE TABLE IF NOT EXISTS index_constituents (
+    index_name  TEXT NOT NULL,
+    entity_id   TEXT NOT NULL,
+    valid_from  TEXT NOT NULL,
+    valid_to    TEXT,
+    PRIMARY KEY (index_name, entity_id, valid_from)
+);
+CREATE INDEX IF NOT EXISTS idx_index_constituents_entity ON index_constituents (entity_id);
+";
+
+// ---------------------------------------------------------------------------
+// Migration 0005 — data-quality findings raised by batch feeds when validating a downloaded
+// file (`feeds::dq`, `store::dq_findings`). `severity` is 'error' (the run was failed and the
+// file not loaded) or 'warning'.
+// ---------------------------------------------------------------------------
+const MIGRATION_0005_DQ_FINDINGS: &str = "
+CREATE TABLE IF NOT EXISTS dq_findings (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    source      TEXT NOT NULL,
+    trade_date  TEXT NOT NULL,
+    check_name  TEXT NOT NULL,
+    severity    TEXT NOT NULL,
+    instrument  TEXT,
+    message     TEXT NOT NULL,
+    created_ts  INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_dq_findings_source_date ON dq_findings (source, trade_date);
+CREATE INDEX IF NOT EXISTS idx_dq_findings_severity ON dq_findings (severity);
+";
+
+// ---------------------------------------------------------------------------
+// Migration 0006 — corporate actions (splits, bonuses, dividends, ...) loaded by
+// `feed_corporate_actions` and used by `market_data::adjusted_ohlcv` to back-adjust prices.
+// `share_ratio` is the number of shares held after the action per share held before it
+// (split or bonus); `cash_amount` is the dividend per share. Both are NULL when the action
+// does not affect prices.
+// ---------------------------------------------------------------------------
+const MIGRATION_0006_CORPORATE_ACTIONS: &str = "
+CREATE TABLE IF NOT EXISTS corporate_actions (
+    exchange     TEXT NOT NULL,
+    symbol       TEXT NOT NULL,
+    isin         TEXT,
+    ex_date      TEXT NOT NULL,
+    action_type  TEXT NOT NULL,
+    subject      TEXT NOT NULL,
+    share_ratio  REAL,
+    cash_amount  REAL,
+    record_date  TEXT,
+    PRIMARY KEY (exchange, symbol, ex_date, subject)
+);
+CREATE INDEX IF NOT EXISTS idx_corporate_actions_isin ON corporate_actions (isin, ex_date);
+CREATE INDEX IF NOT EXISTS idx_corporate_actions_ex_date ON corporate_actions (ex_date);
+";
+
+// ---------------------------------------------------------------------------
+// Migration 0007 — per-job schedule state for daemon mode (`scheduler`, `store::schedule_state`).
+// `next_run_ts` is advanced when a run starts, so a restart neither repeats a run that
+// already happened nor forgets one that fell due while the daemon was down.
+// ---------------------------------------------------------------------------
+const MIGRATION_0007_SCHEDULE_STATE: &str = "
+CREATE TABLE IF NOT EXISTS schedule_state (
+    job          TEXT PRIMARY KEY,
+    kind         TEXT NOT NULL,
+    last_run_ts  INTEGER,
+    next_run_ts  INTEGER,
+    last_status  TEXT,
+    message      TEXT
+);
+";
+
+// ---------------------------------------------------------------------------
+// Migration 0008 — event study results (`event_study`): abnormal returns of listed
+// instruments around the publish date of documents mentioning them, one row per event
+// window, and their averages by theme and tone bucket. Aggregates are rebuilt on every run.
+// ---------------------------------------------------------------------------
+const MIGRATION_0008_EVENT_STUDY: &str = "
+CREATE TABLE IF NOT EXISTS event_study_results (
+    doc_id        TEXT NOT NULL,
+    entity_id     TEXT NOT NULL,
+    symbol        TEXT NOT NULL,
+    event_date    TEXT NOT NULL,
+    window_start  INTEGER NOT NULL,
+    window_end    INTEGER NOT NULL,
+    alpha         REAL,
+    beta          REAL,
+    sigma         REAL,
+    est_obs       INTEGER,
+    car           REAL,
+    t_stat        REAL,
+    computed_ts   INTEGER,
+    PRIMARY KEY (doc_id, entity_id, window_start, window_end)
+);
+CREATE INDEX IF NOT EXISTS idx_event_study_symbol ON event_study_results (symbol, event_date);
+
+CREATE TABLE IF NOT EXISTS event_study_aggregates (
+    group_type     TEXT NOT NULL,
+    group_key      TEXT NOT NULL,
+    window_start   INTEGER NOT NULL,
+    window_end     INTEGER NOT NULL,
+    n              INTEGER,
+    mean_car       REAL,
+    mean_abs_car   REAL,
+    share_positive REAL,
+    mean_t_stat    REAL,
+    computed_ts    INTEGER,
+    PRIMARY KEY (group_type, group_key, window_start, window_end)
+);
+";
+
+// ---------------------------------------------------------------------------
+// Migration 0009 — lookups used by entity resolution (`store::entity_master`,
+// `mod_entity_resolve`): reference records are merged by LEI, and provisional ids are
+// rewritten across `entity_edges`.
+// ---------------------------------------------------------------------------
+const MIGRATION_0009_ENTITY_RESOLUTION: &str = "
+CREATE INDEX IF NOT EXISTS idx_entities_lei ON entities (lei);
+CREATE INDEX IF NOT EXISTS idx_entities_status ON entities (status);
+CREATE INDEX IF NOT EXISTS idx_aliases_type_norm ON entity_aliases (alias_type, alias_norm);
+";
+
+// ---------------------------------------------------------------------------
+// Migration 0010 — persistent story clustering (`store::story_clusters`, `mod_dedupe`): each
+// document's SimHash and story cluster, and a banded LSH index over the SimHashes (one row
+// per band) so near-duplicates from earlier runs are found without a scan.
+// ---------------------------------------------------------------------------
+const MIGRATION_0010_STORY_SIGNATURES: &str = "
+CREATE TABLE IF NOT EXISTS doc_signatures (
+    doc_id      TEXT PRIMARY KEY,
+    cluster_id  TEXT NOT NULL,
+    simhash     INTEGER NOT NULL,
+    date_ms     INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_doc_signatures_cluster ON doc_signatures (cluster_id);
+
+CREATE TABLE IF NOT EXISTS simhash_bands (
+    band        INTEGER NOT NULL,
+    band_value  INTEGER NOT NULL,
+    doc_id      TEXT NOT NULL,
+    date_ms     INTEGER NOT NULL,
+    PRIMARY KEY (band, band_value, doc_id)
+) WITHOUT ROWID;
+";
+
+// ---------------------------------------------------------------------------
+// Migration 0011 — targeted (entity-level) sentiment of each entity mention, from
+// `mod_tone::mention_tone`, for charting sentiment per company over time.
+// ---------------------------------------------------------------------------
+const MIGRATION_0011_MENTION_SENTIMENT: &str = "
+ALTER TABLE entity_mentions ADD COLUMN sentiment REAL;
+";
+
+// ---------------------------------------------------------------------------
+// Migration 0012 — version of the vocabulary (`vocab`) each lexicon-derived row was produced
+// with: themebook for `themes`, tone lexicon for `gcam` and `documents.tone`, place
+// gazetteer for `locations`, organisation gazetteer for `entity_mentions`.
+// ---------------------------------------------------------------------------
+const MIGRATION_0012_VOCAB_VERSIONS: &str = "
+ALTER TABLE themes ADD COLUMN vocab_version TEXT;
+ALTER TABLE gcam ADD COLUMN vocab_version TEXT;
+ALTER TABLE locations ADD COLUMN vocab_version TEXT;
+ALTER TABLE entity_mentions ADD COLUMN vocab_version TEXT;
+ALTER TABLE documents ADD COLUMN vocab_version TEXT;
+";
+
+// ---------------------------------------------------------------------------
+// Migration 0013 — bulk gazetteer (`store::geo`), loaded by `feed_gazetteer` from GeoNames
+// and the India Post PIN directory; `geo_names` indexes every name a feature is known by.
+// ---------------------------------------------------------------------------
+const MIGRATION_0013_GEO_GAZETTEER: &str = "
+CREATE TABLE IF NOT EXISTS geo (
+    feature_id   TEXT PRIMARY KEY,
+    name         TEXT NOT NULL,
+    lat          REAL,
+    lon          REAL,
+    feature_code TEXT NOT NULL DEFAULT '',
+    country      TEXT NOT NULL,
+    adm1         TEXT NOT NULL DEFAULT '',
+    adm2         TEXT NOT NULL DEFAULT '',
+    population   INTEGER NOT NULL DEFAULT 0,
+    source       TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS geo_names (
+    name_norm  TEXT NOT NULL,
+    feature_id TEXT NOT NULL,
+    PRIMARY KEY (name_norm, feature_id)
+) WITHOUT ROWID;
+CREATE INDEX IF NOT EXISTS idx_geo_names_feature ON geo_names (feature_id);
+";
+
+// ---------------------------------------------------------------------------
+// Migration 0014 — the text a referenced date was resolved from (`temporal`), e.g. "FY25" or
+// "last Friday", alongside its resolved period start.
+// ---------------------------------------------------------------------------
+const MIGRATION_0014_DATE_EXPRESSIONS: &str = "
+ALTER TABLE dates_ref ADD COLUMN expression TEXT;
+";
+
+// ---------------------------------------------------------------------------
+// Migration 0015 — the site-provided `unique_id` of each document with a stored signature, so
+// `mod_dedupe` can name the document a near-duplicate matched (`duplicate_of`) by unique_id
+// while clustering on the doc id.
+// ---------------------------------------------------------------------------
+const MIGRATION_0015_SIGNATURE_UNIQUE_ID: &str = "
+ALTER TABLE doc_signatures ADD COLUMN unique_id TEXT;
+";
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -336,6 +608,9 @@ mod tests {
             "documents", "events", "mentions", "entities", "entity_aliases",
             "entity_mentions", "entity_edges", "themes", "counts", "amounts",
             "quotes", "dates_ref", "gcam", "locations", "market_series", "batch_run_log",
+            "pdf_tables", "trading_holidays", "index_constituents", "dq_findings",
+            "corporate_actions", "schedule_state", "event_study_results", "event_study_aggregates",
+            "doc_signatures", "simhash_bands", "geo", "geo_names",
         ] {
             let found: bool = c
                 .query_row(
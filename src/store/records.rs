@@ -8,6 +8,8 @@
 use rusqlite::Connection;
 
 use crate::analysis::{provisional_entity_id, DocAnalysis, EntityMention};
+use crate::pdf_tables::PdfTable;
+use crate::vocab::{Gazetteer, OrgGazetteer, Themebook, ToneLexicon, Vocabulary};
 
 /// A row for the canonical `documents` table.
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -24,6 +26,8 @@ pub struct DocumentRow {
     pub cluster_id: String,
     pub tone: f64,
     pub word_count: i64,
+    /// Version of the lexicon `tone` was scored with (see `vocab`).
+    pub vocab_version: Option<String>,
 }
 
 /// Insert or replace a document row (keyed by `doc_id`), stamping the ingest time.
@@ -31,14 +35,15 @@ pub fn upsert_document(conn: &Connection, d: &DocumentRow) -> Result<usize, Stri
     conn.execute(
         "INSERT INTO documents
             (doc_id, url, source, title, lang, pubdate_ms, pubdate, plugin, section,
-             cluster_id, tone, word_count, ingested_ts)
-         VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9,?10,?11,?12,?13)
+             cluster_id, tone, word_count, ingested_ts, vocab_version)
+         VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9,?10,?11,?12,?13,?14)
          ON CONFLICT(doc_id) DO UPDATE SET
             url=?2, source=?3, title=?4, lang=?5, pubdate_ms=?6, pubdate=?7, plugin=?8,
-            section=?9, cluster_id=?10, tone=?11, word_count=?12, ingested_ts=?13",
+            section=?9, cluster_id=?10, tone=?11, word_count=?12, ingested_ts=?13, vocab_version=?14",
         rusqlite::params![
             d.doc_id, d.url, d.source, d.title, d.lang, d.pubdate_ms, d.pubdate, d.plugin,
             d.section, d.cluster_id, d.tone, d.word_count, chrono::Utc::now().timestamp(),
+            d.vocab_version,
         ],
     )
     .map_err(|e| format!("upsert_document({}): {}", d.doc_id, e))
@@ -62,12 +67,15 @@ pub fn insert_mention(
 }
 
 /// Emit all structured-analysis facts for one document into the canonical fact tables
-/// (`amounts`, `counts`, `dates_ref`, `themes`, `gcam`). Intended to be called inside a
+/// (`amounts`, `counts`, `dates_ref`, `themes`, `gcam`, `locations`, `quotes`, `events`, and
+/// the entity tables), each lexicon-derived row stamped with its vocabulary's version from
+/// `a.vocab_versions`. Intended to be called inside a
 /// transaction owned by `mod_emit_tables` so many documents flush as one batch. The
 /// `documents`/`mentions` rows remain owned by `mod_mentions`; this only writes the per-fact
 /// tables keyed on `doc_id`.
 pub fn emit_analysis(conn: &Connection, doc_id: &str, a: &DocAnalysis) -> Result<usize, String> {
     let mut n = 0usize;
+    let version = |kind: &str| a.vocab_versions.get(kind);
     for m in &a.amounts {
         conn.execute(
             "INSERT INTO amounts (doc_id, value, currency, unit, object, char_offset)
@@ -88,42 +96,77 @@ pub fn emit_analysis(conn: &Connection, doc_id: &str, a: &DocAnalysis) -> Result
     }
     for d in &a.dates_referenced {
         conn.execute(
-            "INSERT INTO dates_ref (doc_id, resolution, year, month, day, char_offset)
-             VALUES (?1,?2,?3,?4,?5,?6)",
-            rusqlite::params![doc_id, d.resolution, d.year, d.month, d.day, d.char_offset as i64],
+            "INSERT INTO dates_ref (doc_id, resolution, year, month, day, char_offset, expression)
+             VALUES (?1,?2,?3,?4,?5,?6,?7)",
+            rusqlite::params![doc_id, d.resolution, d.year, d.month, d.day, d.char_offset as i64, d.expression],
         )
         .map_err(|e| format!("insert date_ref({}): {}", doc_id, e))?;
         n += 1;
     }
     for t in &a.themes {
         conn.execute(
-            "INSERT INTO themes (doc_id, theme, char_offset) VALUES (?1,?2,?3)",
-            rusqlite::params![doc_id, t.theme, t.char_offset as i64],
+            "INSERT INTO themes (doc_id, theme, char_offset, vocab_version) VALUES (?1,?2,?3,?4)",
+            rusqlite::params![doc_id, t.theme, t.char_offset as i64, version(Themebook::KIND)],
         )
         .map_err(|e| format!("insert theme({}): {}", doc_id, e))?;
         n += 1;
     }
     for g in &a.gcam {
         conn.execute(
-            "INSERT INTO gcam (doc_id, dict_id, dim_id, key, score) VALUES (?1,?2,?3,?4,?5)",
-            rusqlite::params![doc_id, g.dict_id, g.dim_id, g.key, g.score],
+            "INSERT INTO gcam (doc_id, dict_id, dim_id, key, score, vocab_version) VALUES (?1,?2,?3,?4,?5,?6)",
+            rusqlite::params![doc_id, g.dict_id, g.dim_id, g.key, g.score, version(ToneLexicon::KIND)],
         )
         .map_err(|e| format!("insert gcam({}): {}", doc_id, e))?;
         n += 1;
     }
     for l in &a.locations {
         conn.execute(
-            "INSERT INTO locations (doc_id, name, feature_id, lat, lon, country, adm1, adm2, char_offset)
-             VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9)",
+            "INSERT INTO locations (doc_id, name, feature_id, lat, lon, country, adm1, adm2, char_offset, vocab_version)
+             VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9,?10)",
             rusqlite::params![
-                doc_id, l.name, l.feature_id, l.lat, l.lon, l.country, l.adm1, l.adm2, l.char_offset as i64
+                doc_id, l.name, l.feature_id, l.lat, l.lon, l.country, l.adm1, l.adm2, l.char_offset as i64,
+                // Only placed mentions come from the gazetteer.
+                l.lat.and(version(Gazetteer::KIND))
             ],
         )
         .map_err(|e| format!("insert location({}): {}", doc_id, e))?;
         n += 1;
     }
-    for e in a.organizations.iter().chain(a.persons.iter()) {
-        n += emit_entity_mention(conn, doc_id, e)?;
+    for e in &a.organizations {
+        n += emit_entity_mention(conn, doc_id, e, version(OrgGazetteer::KIND))?;
+    }
+    for e in &a.persons {
+        n += emit_entity_mention(conn, doc_id, e, None)?;
+    }
+    for q in &a.quotes {
+        // A speaker linked to an unresolved mention shares the mention's provisional id.
+        let speaker_entity_id = q.speaker_entity_id.clone().or_else(|| {
+            let key = crate::analysis::norm_name(&q.speaker);
+            a.organizations
+                .iter()
+                .chain(a.persons.iter())
+                .any(|e| crate::analysis::norm_name(&e.surface_form) == key)
+                .then(|| provisional_entity_id(&q.speaker))
+        });
+        conn.execute(
+            "INSERT INTO quotes (doc_id, speaker_entity_id, speaker, verb, quote, char_offset)
+             VALUES (?1,?2,?3,?4,?5,?6)",
+            rusqlite::params![doc_id, speaker_entity_id, q.speaker, q.verb, q.quote, q.char_offset as i64],
+        )
+        .map_err(|e| format!("insert quote({}): {}", doc_id, e))?;
+        n += 1;
+    }
+    for ev in &a.events {
+        // Events are dated by their document's publication date.
+        conn.execute(
+            "INSERT INTO events (doc_id, event_type, actor1, actor2, goldstein, quad_class, date_ms, char_offset)
+             VALUES (?1,?2,?3,?4,?5,?6,(SELECT pubdate_ms FROM documents WHERE doc_id=?1),?7)",
+            rusqlite::params![
+                doc_id, ev.event_type, ev.actor1, ev.actor2, ev.goldstein, ev.quad_class, ev.char_offset as i64
+            ],
+        )
+        .map_err(|e| format!("insert event({}): {}", doc_id, e))?;
+        n += 1;
     }
     Ok(n)
 }
@@ -131,7 +174,7 @@ pub fn emit_analysis(conn: &Connection, doc_id: &str, a: &DocAnalysis) -> Result
 /// Persist one entity mention plus a minimal `entities` master row. Until `mod_entity_resolve`
 /// supplies a real LEI/CIN-backed id, the entity is keyed by a provisional surface-form id so
 /// co-mentions across documents group consistently.
-fn emit_entity_mention(conn: &Connection, doc_id: &str, e: &EntityMention) -> Result<usize, String> {
+fn emit_entity_mention(conn: &Connection, doc_id: &str, e: &EntityMention, vocab_version: Option<&String>) -> Result<usize, String> {
     let entity_id = e.entity_id.clone().unwrap_or_else(|| provisional_entity_id(&e.surface_form));
     // Minimal master row; INSERT OR IGNORE so a later resolved row is never clobbered here.
     conn.execute(
@@ -146,9 +189,9 @@ fn emit_entity_mention(conn: &Connection, doc_id: &str, e: &EntityMention) -> Re
     )
     .map_err(|err| format!("upsert entity({}): {}", entity_id, err))?;
     conn.execute(
-        "INSERT INTO entity_mentions (doc_id, entity_id, surface_form, char_offset, salience)
-         VALUES (?1, ?2, ?3, ?4, ?5)",
-        rusqlite::params![doc_id, entity_id, e.surface_form, e.char_offset as i64, e.salience],
+        "INSERT INTO entity_mentions (doc_id, entity_id, surface_form, char_offset, salience, sentiment, vocab_version)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![doc_id, entity_id, e.surface_form, e.char_offset as i64, e.salience, e.sentiment, vocab_version],
     )
     .map_err(|err| format!("insert entity_mention({}): {}", doc_id, err))?;
     Ok(2)
@@ -176,6 +219,21 @@ pub fn insert_edge(
     .map_err(|e| format!("insert edge({}->{}): {}", src, dst, e))
 }
 
+/// Insert or replace one PDF table for a document, keyed by its position in the document.
+pub fn upsert_pdf_table(conn: &Connection, doc_id: &str, table_idx: usize, t: &PdfTable) -> Result<usize, String> {
+    let rows_json = serde_json::to_string(&t.rows)
+        .map_err(|e| format!("serialise pdf_table({}, {}): {}", doc_id, table_idx, e))?;
+    conn.execute(
+        "INSERT OR REPLACE INTO pdf_tables
+            (doc_id, table_idx, page, n_rows, n_cols, rows_json, csv_file)
+         VALUES (?1,?2,?3,?4,?5,?6,?7)",
+        rusqlite::params![
+            doc_id, table_idx as i64, t.page, t.n_rows as i64, t.n_cols as i64, rows_json, t.csv_file
+        ],
+    )
+    .map_err(|e| format!("upsert pdf_table({}, {}): {}", doc_id, table_idx, e))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -212,9 +270,10 @@ mod tests {
         let a = DocAnalysis {
             amounts: vec![AmountMention { value: 5e10, currency: "INR".into(), unit: "crore".into(), object: String::new(), char_offset: 3 }],
             counts: vec![CountMention { count_type: String::new(), number: 15.0, object: "banks".into(), char_offset: 7 }],
-            dates_referenced: vec![DateRef { resolution: "day".into(), year: 2026, month: 6, day: 10, char_offset: 1 }],
+            dates_referenced: vec![DateRef { resolution: "day".into(), year: 2026, month: 6, day: 10, char_offset: 1, expression: "2026-06-10".into() }],
             themes: vec![ThemeMention { theme: "FIN_BANKING".into(), char_offset: 0 }],
             gcam: vec![GcamScore { dict_id: "finlex".into(), dim_id: "tone".into(), key: "v".into(), score: 1.2 }],
+            vocab_versions: [("themebook".to_string(), "2026.10.1".to_string())].into(),
             ..Default::default()
         };
         let n = emit_analysis(&c, "D1", &a).unwrap();
@@ -227,6 +286,85 @@ mod tests {
         }
         let v: f64 = c.query_row("SELECT value FROM amounts WHERE doc_id='D1'", [], |r| r.get(0)).unwrap();
         assert_eq!(v, 5e10);
+        let stamps: (Option<String>, Option<String>) = c
+            .query_row("SELECT t.vocab_version, g.vocab_version FROM themes t, gcam g WHERE t.doc_id='D1'", [], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap();
+        assert_eq!(stamps, (Some("2026.10.1".to_string()), None));
+    }
+
+    #[test]
+    fn test_emit_quotes_keys_linked_speakers() {
+        use crate::analysis::Quotation;
+        let c = db();
+        let quote = |speaker: &str, id: Option<&str>| Quotation {
+            speaker: speaker.into(),
+            speaker_entity_id: id.map(str::to_string),
+            verb: "said".into(),
+            quote: "Inflation remains a concern".into(),
+            char_offset: 10,
+        };
+        let a = DocAnalysis {
+            persons: vec![EntityMention { surface_form: "Shaktikanta Das".into(), entity_type: "PERSON".into(), sentiment: Some(-1.5), ..Default::default() }],
+            quotes: vec![
+                quote("Shaktikanta Das", None),
+                quote("a senior official", None),
+                quote("Reserve Bank of India", Some("lei:RBI")),
+            ],
+            ..Default::default()
+        };
+        emit_analysis(&c, "D1", &a).unwrap();
+        let ids: Vec<Option<String>> = c
+            .prepare("SELECT speaker_entity_id FROM quotes WHERE doc_id='D1' ORDER BY rowid").unwrap()
+            .query_map([], |r| r.get(0)).unwrap()
+            .flatten()
+            .collect();
+        assert_eq!(ids, vec![Some("name:shaktikanta das".to_string()), None, Some("lei:RBI".to_string())]);
+        let sentiment: Option<f64> = c.query_row("SELECT sentiment FROM entity_mentions WHERE doc_id='D1'", [], |r| r.get(0)).unwrap();
+        assert_eq!(sentiment, Some(-1.5));
+    }
+
+    #[test]
+    fn test_emit_events_dated_by_document() {
+        use crate::analysis::EventRecord;
+        let c = db();
+        upsert_document(&c, &DocumentRow { doc_id: "D1".into(), pubdate_ms: 1_750_000_000_000, ..Default::default() }).unwrap();
+        let event = EventRecord {
+            event_type: "REG_PENALTY".into(),
+            actor1: "lei:RBI".into(),
+            actor2: "name:hdfc bank".into(),
+            goldstein: Some(-5.0),
+            quad_class: Some(4),
+            char_offset: 4,
+        };
+        let a = DocAnalysis { events: vec![event], ..Default::default() };
+        emit_analysis(&c, "D1", &a).unwrap();
+        emit_analysis(&c, "D2", &a).unwrap();
+        let dates: Vec<Option<i64>> = c
+            .prepare("SELECT date_ms FROM events WHERE quad_class=4 ORDER BY doc_id").unwrap()
+            .query_map([], |r| r.get(0)).unwrap()
+            .flatten()
+            .collect();
+        assert_eq!(dates, vec![Some(1_750_000_000_000), None]);
+    }
+
+    #[test]
+    fn test_upsert_pdf_table_replaces_same_index() {
+        let c = db();
+        let mut t = PdfTable {
+            page: 1, n_rows: 2, n_cols: 2,
+            rows: vec![vec!["Item".into(), "Fee".into()], vec!["Broker".into(), "50,000".into()]],
+            csv_file: "x_table1.csv".into(),
+        };
+        upsert_pdf_table(&c, "D1", 0, &t).unwrap();
+        t.page = 3;
+        upsert_pdf_table(&c, "D1", 0, &t).unwrap();
+        let (n, page, rows): (i64, i64, String) = c
+            .query_row("SELECT COUNT(*), MAX(page), MAX(rows_json) FROM pdf_tables WHERE doc_id='D1'", [], |r| {
+                Ok((r.get(0)?, r.get(1)?, r.get(2)?))
+            })
+            .unwrap();
+        assert_eq!((n, page), (1, 3));
+        assert_eq!(rows, r#"[["Item","Fee"],["Broker","50,000"]]"#);
     }
 
     #[test]
@@ -0,0 +1,103 @@
+// file: store/schedule_state.rs
+// Purpose:
+//   Helpers over the `schedule_state` table (migration 0007): the last and next run time of
+//   every job scheduled by daemon mode (`crate::scheduler`). A job's next run is written when
+//   the run starts, before any work is done, so a daemon restarted mid-run does not start
+//   the same run again.
+
+use log::error;
+use rusqlite::Connection;
+
+/// Persisted schedule of one job.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduleState {
+    pub job: String,
+    /// "retriever" or "batch_feed".
+    pub kind: String,
+    pub last_run_ts: Option<i64>,
+    pub next_run_ts: Option<i64>,
+    /// "running", "success", "failure" or "skipped"; None if the job has not run yet.
+    pub last_status: Option<String>,
+    pub message: Option<String>,
+}
+
+/// The stored state of `job`, if it has been scheduled before.
+pub fn load(conn: &Connection, job: &str) -> Option<ScheduleState> {
+    conn.query_row(
+        "SELECT job, kind, last_run_ts, next_run_ts, last_status, message FROM schedule_state WHERE job=?1",
+        [job],
+        |r| {
+            Ok(ScheduleState {
+                job: r.get(0)?,
+                kind: r.get(1)?,
+                last_run_ts: r.get(2)?,
+                next_run_ts: r.get(3)?,
+                last_status: r.get(4)?,
+                message: r.get(5)?,
+            })
+        },
+    )
+    .ok()
+}
+
+/// Set the next run time of `job` without recording a run (used when a job is first
+/// scheduled, or when a due run is dropped because the previous one is still going).
+pub fn set_next_run(conn: &Connection, job: &str, kind: &str, next_run_ts: i64) {
+    let res = conn.execute(
+        "INSERT INTO schedule_state (job, kind, next_run_ts) VALUES (?1, ?2, ?3)
+         ON CONFLICT(job) DO UPDATE SET kind=?2, next_run_ts=?3",
+        rusqlite::params![job, kind, next_run_ts],
+    );
+    if let Err(e) = res {
+        error!("schedule_state: set_next_run({}): {}", job, e);
+    }
+}
+
+/// Record that a run of `job` started at `started_ts`, and when the one after it is due.
+pub fn record_start(conn: &Connection, job: &str, kind: &str, started_ts: i64, next_run_ts: i64) {
+    let res = conn.execute(
+        "INSERT INTO schedule_state (job, kind, last_run_ts, next_run_ts, last_status, message)
+         VALUES (?1, ?2, ?3, ?4, 'running', NULL)
+         ON CONFLICT(job) DO UPDATE SET
+            kind=?2, last_run_ts=?3, next_run_ts=?4, last_status='running', message=NULL",
+        rusqlite::params![job, kind, started_ts, next_run_ts],
+    );
+    if let Err(e) = res {
+        error!("schedule_state: record_start({}): {}", job, e);
+    }
+}
+
+/// Record how the latest run of `job` ended.
+pub fn record_finish(conn: &Connection, job: &str, status: &str, message: &str) {
+    let res = conn.execute(
+        "UPDATE schedule_state SET last_status=?2, message=?3 WHERE job=?1",
+        rusqlite::params![job, status, message],
+    );
+    if let Err(e) = res {
+        error!("schedule_state: record_finish({}): {}", job, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store;
+
+    #[test]
+    fn test_run_lifecycle_is_persisted() {
+        let c = Connection::open_in_memory().unwrap();
+        store::migrate(&c).unwrap();
+        assert!(load(&c, "mod_en_bbc").is_none());
+
+        set_next_run(&c, "mod_en_bbc", "retriever", 1_000);
+        let s = load(&c, "mod_en_bbc").unwrap();
+        assert_eq!((s.last_run_ts, s.next_run_ts, s.last_status), (None, Some(1_000), None));
+
+        record_start(&c, "mod_en_bbc", "retriever", 1_005, 4_600);
+        record_finish(&c, "mod_en_bbc", "success", "42 documents");
+        let s = load(&c, "mod_en_bbc").unwrap();
+        assert_eq!((s.last_run_ts, s.next_run_ts), (Some(1_005), Some(4_600)));
+        assert_eq!(s.last_status.as_deref(), Some("success"));
+        assert_eq!(s.message.as_deref(), Some("42 documents"));
+    }
+}
@@ -0,0 +1,364 @@
+// file: store/securities.rs
+// Purpose:
+//   Listed-securities master over `entities`, `entity_aliases` and `index_constituents`,
+//   populated by the symbol-master and index-constituent batch feeds. A listed company is one
+//   entity keyed by ISIN (`isin:<ISIN>`); each exchange listing is an alias row (`nse_symbol`,
+//   `bse_code`) with `valid_from`/`valid_to`, so symbol changes and delistings close the old
+//   alias instead of overwriting it. The `entities` columns always carry the current values.
+//   Company names are spelt differently by each source ("Reliance Industries Limited" in the
+//   NSE list, "Reliance Industries Ltd" at BSE), so name aliases accumulate and are never
+//   closed, and `canonical_name` follows only the NSE equity list once the entity exists.
+
+use std::collections::HashSet;
+
+use chrono::NaiveDate;
+use rusqlite::{Connection, OptionalExtension};
+
+use crate::analysis::norm_name;
+
+/// Alias types used for exchange listings.
+pub const ALIAS_NSE_SYMBOL: &str = "nse_symbol";
+pub const ALIAS_BSE_CODE: &str = "bse_code";
+pub const ALIAS_NAME: &str = "name";
+
+/// One security as published in an exchange equity list or index constituent file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListedSecurity {
+    pub isin: String,
+    pub name: String,
+    /// "NSE" or "BSE".
+    pub exchange: String,
+    /// NSE trading symbol or BSE scrip code.
+    pub symbol: String,
+    pub sector: Option<String>,
+    pub listing_date: Option<NaiveDate>,
+    /// True for the source whose spelling is kept as `entities.canonical_name` (the NSE equity
+    /// list). Other sources only add a name alias, and name entities they create themselves.
+    pub canonical_name_source: bool,
+}
+
+impl ListedSecurity {
+    fn alias_type(&self) -> &'static str {
+        if self.exchange.eq_ignore_ascii_case("BSE") { ALIAS_BSE_CODE } else { ALIAS_NSE_SYMBOL }
+    }
+}
+
+/// Entity id of a listed security.
+pub fn security_entity_id(isin: &str) -> String {
+    format!("isin:{}", isin.trim().to_uppercase())
+}
+
+fn day(d: NaiveDate) -> String {
+    d.format("%Y-%m-%d").to_string()
+}
+
+/// Make `alias` the open alias of `alias_type` (a symbol or scrip code) for the entity: any
+/// other open alias of that type is closed on `from`. Returns true if a new alias row was opened.
+fn ensure_alias(conn: &Connection, entity_id: &str, alias_type: &str, alias: &str, from: NaiveDate) -> Result<bool, String> {
+    let open: Option<i64> = conn
+        .query_row(
+            "SELECT rowid FROM entity_aliases
+             WHERE entity_id=?1 AND alias_type=?2 AND alias=?3 AND valid_to IS NULL",
+            rusqlite::params![entity_id, alias_type, alias],
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("read alias({}, {}): {}", entity_id, alias, e))?;
+    if open.is_some() {
+        return Ok(false);
+    }
+    conn.execute(
+        "UPDATE entity_aliases SET valid_to=?3 WHERE entity_id=?1 AND alias_type=?2 AND valid_to IS NULL",
+        rusqlite::params![entity_id, alias_type, day(from)],
+    )
+    .map_err(|e| format!("close aliases({}, {}): {}", entity_id, alias_type, e))?;
+    conn.execute(
+        "INSERT INTO entity_aliases (entity_id, alias, alias_norm, alias_type, lang, valid_from)
+         VALUES (?1, ?2, ?3, ?4, 'en', ?5)",
+        rusqlite::params![entity_id, alias, norm_name(alias), alias_type, day(from)],
+    )
+    .map_err(|e| format!("insert alias({}, {}): {}", entity_id, alias, e))?;
+    Ok(true)
+}
+
+/// Add `name` as a name alias of the entity unless it already has it. Name aliases are not
+/// windowed: every spelling seen stays valid, and one closed by an earlier load is reopened.
+/// Returns true if a new alias row was inserted.
+fn ensure_name_alias(conn: &Connection, entity_id: &str, name: &str, from: NaiveDate) -> Result<bool, String> {
+    let reopened = conn
+        .execute(
+            "UPDATE entity_aliases SET valid_to=NULL WHERE entity_id=?1 AND alias_type=?2 AND alias=?3",
+            rusqlite::params![entity_id, ALIAS_NAME, name],
+        )
+        .map_err(|e| format!("reopen name alias({}, {}): {}", entity_id, name, e))?;
+    if reopened > 0 {
+        return Ok(false);
+    }
+    conn.execute(
+        "INSERT INTO entity_aliases (entity_id, alias, alias_norm, alias_type, lang, valid_from)
+         VALUES (?1, ?2, ?3, ?4, 'en', ?5)",
+        rusqlite::params![entity_id, name, norm_name(name), ALIAS_NAME, day(from)],
+    )
+    .map_err(|e| format!("insert name alias({}, {}): {}", entity_id, name, e))?;
+    Ok(true)
+}
+
+/// Insert or refresh a listed security as seen on `as_of`, opening its symbol alias and
+/// adding its name alias. A previously delisted entity is marked listed again.
+pub fn upsert_listing(conn: &Connection, sec: &ListedSecurity, as_of: NaiveDate) -> Result<String, String> {
+    let entity_id = security_entity_id(&sec.isin);
+    let from = sec.listing_date.unwrap_or(as_of);
+    conn.execute(
+        "INSERT INTO entities
+            (entity_id, type, canonical_name, name_norm, isin, sector, status, valid_from, last_update)
+         VALUES (?1, 'ORG', ?2, ?3, ?4, ?5, 'listed', ?6, ?7)
+         ON CONFLICT(entity_id) DO UPDATE SET
+            canonical_name=CASE WHEN ?8 THEN excluded.canonical_name ELSE entities.canonical_name END,
+            name_norm=CASE WHEN ?8 THEN excluded.name_norm ELSE entities.name_norm END,
+            sector=COALESCE(excluded.sector, entities.sector),
+            status='listed', valid_to=NULL, last_update=excluded.last_update",
+        rusqlite::params![
+            entity_id, sec.name, norm_name(&sec.name), sec.isin.trim().to_uppercase(), sec.sector,
+            day(from), day(as_of), sec.canonical_name_source
+        ],
+    )
+    .map_err(|e| format!("upsert entity({}): {}", entity_id, e))?;
+
+    let column = if sec.alias_type() == ALIAS_BSE_CODE { "bse_code" } else { "nse_symbol" };
+    conn.execute(
+        &format!("UPDATE entities SET {}=?2 WHERE entity_id=?1", column),
+        rusqlite::params![entity_id, sec.symbol],
+    )
+    .map_err(|e| format!("set {}({}): {}", column, entity_id, e))?;
+
+    ensure_alias(conn, &entity_id, sec.alias_type(), &sec.symbol, from)?;
+    ensure_name_alias(conn, &entity_id, &sec.name, from)?;
+    Ok(entity_id)
+}
+
+/// Apply an NSE symbol change effective on `effective`: the entity currently listed under
+/// `old_symbol` gets `new_symbol`. Returns false if no entity holds `old_symbol` (e.g. the
+/// change predates the first master load), or if the holder only took the symbol on or
+/// after `effective` — a reused symbol must not inherit an old change.
+pub fn apply_symbol_change(conn: &Connection, old_symbol: &str, new_symbol: &str, effective: NaiveDate) -> Result<bool, String> {
+    let holder: Option<(String, Option<String>)> = conn
+        .query_row(
+            "SELECT entity_id, valid_from FROM entity_aliases
+             WHERE alias_type=?1 AND alias=?2 AND valid_to IS NULL",
+            rusqlite::params![ALIAS_NSE_SYMBOL, old_symbol],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .optional()
+        .map_err(|e| format!("find symbol {}: {}", old_symbol, e))?;
+    let entity_id = match holder {
+        Some((_, Some(from))) if from >= day(effective) => return Ok(false),
+        Some((id, _)) => id,
+        None => return Ok(false),
+    };
+    ensure_alias(conn, &entity_id, ALIAS_NSE_SYMBOL, new_symbol, effective)?;
+    conn.execute(
+        "UPDATE entities SET nse_symbol=?2 WHERE entity_id=?1",
+        rusqlite::params![entity_id, new_symbol],
+    )
+    .map_err(|e| format!("set nse_symbol({}): {}", entity_id, e))?;
+    Ok(true)
+}
+
+/// After loading a complete equity list for `exchange`, close the listings of securities
+/// that are no longer in it, and mark entities without any open listing as delisted.
+/// Returns the number of listings closed.
+pub fn close_missing_listings(conn: &Connection, exchange: &str, seen_isins: &HashSet<String>, as_of: NaiveDate) -> Result<usize, String> {
+    let alias_type = if exchange.eq_ignore_ascii_case("BSE") { ALIAS_BSE_CODE } else { ALIAS_NSE_SYMBOL };
+    let open: Vec<(i64, String)> = {
+        let mut stmt = conn
+            .prepare("SELECT rowid, entity_id FROM entity_aliases WHERE alias_type=?1 AND valid_to IS NULL")
+            .map_err(|e| format!("prepare open listings: {}", e))?;
+        let rows = stmt
+            .query_map([alias_type], |r| Ok((r.get(0)?, r.get(1)?)))
+            .map_err(|e| format!("query open listings: {}", e))?;
+        rows.flatten().collect()
+    };
+
+    let mut closed = 0;
+    for (rowid, entity_id) in open {
+        let isin = entity_id.trim_start_matches("isin:");
+        if seen_isins.contains(isin) {
+            continue;
+        }
+        conn.execute(
+            "UPDATE entity_aliases SET valid_to=?2 WHERE rowid=?1",
+            rusqlite::params![rowid, day(as_of)],
+        )
+        .map_err(|e| format!("close listing({}): {}", entity_id, e))?;
+        closed += 1;
+    }
+
+    conn.execute(
+        "UPDATE entities SET status='delisted', valid_to=?1, last_update=?1
+         WHERE entity_id LIKE 'isin:%' AND status='listed'
+           AND NOT EXISTS (SELECT 1 FROM entity_aliases a
+                           WHERE a.entity_id=entities.entity_id
+                             AND a.alias_type IN (?2, ?3) AND a.valid_to IS NULL)",
+        rusqlite::params![day(as_of), ALIAS_NSE_SYMBOL, ALIAS_BSE_CODE],
+    )
+    .map_err(|e| format!("mark delisted: {}", e))?;
+    Ok(closed)
+}
+
+/// Record the current constituents of `index_name`: members not yet open are added from
+/// `as_of`, and open members missing from `entity_ids` are closed on `as_of`.
+/// Returns (added, removed).
+pub fn set_index_constituents(conn: &Connection, index_name: &str, entity_ids: &[String], as_of: NaiveDate) -> Result<(usize, usize), String> {
+    let current: HashSet<String> = {
+        let mut stmt = conn
+            .prepare("SELECT entity_id FROM index_constituents WHERE index_name=?1 AND valid_to IS NULL")
+            .map_err(|e| format!("prepare constituents: {}", e))?;
+        let rows = stmt
+            .query_map([index_name], |r| r.get(0))
+            .map_err(|e| format!("query constituents: {}", e))?;
+        rows.flatten().collect()
+    };
+    let wanted: HashSet<&String> = entity_ids.iter().collect();
+
+    let mut added = 0;
+    for id in &wanted {
+        if !current.contains(*id) {
+            conn.execute(
+                "INSERT OR IGNORE INTO index_constituents (index_name, entity_id, valid_from) VALUES (?1, ?2, ?3)",
+                rusqlite::params![index_name, id, day(as_of)],
+            )
+            .map_err(|e| format!("add constituent({}, {}): {}", index_name, id, e))?;
+            added += 1;
+        }
+    }
+    let mut removed = 0;
+    for id in current.iter().filter(|id| !wanted.contains(id)) {
+        conn.execute(
+            "UPDATE index_constituents SET valid_to=?3 WHERE index_name=?1 AND entity_id=?2 AND valid_to IS NULL",
+            rusqlite::params![index_name, id, day(as_of)],
+        )
+        .map_err(|e| format!("remove constituent({}, {}): {}", index_name, id, e))?;
+        removed += 1;
+    }
+    Ok((added, removed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store;
+
+    fn db() -> Connection {
+        let c = Connection::open_in_memory().unwrap();
+        store::migrate(&c).unwrap();
+        c
+    }
+
+    fn d(y: i32, m: u32, dd: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, dd).unwrap()
+    }
+
+    fn sec(isin: &str, name: &str, symbol: &str) -> ListedSecurity {
+        ListedSecurity {
+            isin: isin.to_string(),
+            name: name.to_string(),
+            exchange: "NSE".to_string(),
+            symbol: symbol.to_string(),
+            sector: None,
+            listing_date: Some(d(2010, 1, 4)),
+            canonical_name_source: true,
+        }
+    }
+
+    fn open_aliases(c: &Connection, entity_id: &str, alias_type: &str) -> Vec<String> {
+        let mut stmt = c
+            .prepare("SELECT alias FROM entity_aliases WHERE entity_id=?1 AND alias_type=?2 AND valid_to IS NULL")
+            .unwrap();
+        stmt.query_map([entity_id, alias_type], |r| r.get(0)).unwrap().flatten().collect()
+    }
+
+    #[test]
+    fn test_symbol_change_closes_old_alias() {
+        let c = db();
+        let id = upsert_listing(&c, &sec("INE000A01010", "Old Name Ltd", "OLDSYM"), d(2024, 1, 1)).unwrap();
+        // Re-loading the same listing does not duplicate aliases.
+        upsert_listing(&c, &sec("INE000A01010", "Old Name Ltd", "OLDSYM"), d(2024, 1, 2)).unwrap();
+        assert!(apply_symbol_change(&c, "OLDSYM", "NEWSYM", d(2024, 3, 1)).unwrap());
+        assert!(!apply_symbol_change(&c, "UNKNOWN", "X", d(2024, 3, 1)).unwrap());
+        // A change older than the current holder's listing is not re-applied.
+        assert!(!apply_symbol_change(&c, "NEWSYM", "OTHER", d(2020, 1, 1)).unwrap());
+
+        assert_eq!(open_aliases(&c, &id, ALIAS_NSE_SYMBOL), vec!["NEWSYM"]);
+        let closed: String = c.query_row(
+            "SELECT valid_to FROM entity_aliases WHERE entity_id=?1 AND alias='OLDSYM'",
+            [&id], |r| r.get(0)).unwrap();
+        assert_eq!(closed, "2024-03-01");
+        let symbol: String = c.query_row("SELECT nse_symbol FROM entities WHERE entity_id=?1", [&id], |r| r.get(0)).unwrap();
+        assert_eq!(symbol, "NEWSYM");
+    }
+
+    #[test]
+    fn test_missing_security_is_delisted_unless_listed_elsewhere() {
+        let c = db();
+        let gone = upsert_listing(&c, &sec("INE111A01011", "Gone Ltd", "GONE"), d(2024, 1, 1)).unwrap();
+        let dual = upsert_listing(&c, &sec("INE222A01012", "Dual Ltd", "DUAL"), d(2024, 1, 1)).unwrap();
+        let mut on_bse = sec("INE222A01012", "Dual Ltd", "500123");
+        on_bse.exchange = "BSE".to_string();
+        on_bse.canonical_name_source = false;
+        upsert_listing(&c, &on_bse, d(2024, 1, 1)).unwrap();
+
+        let seen: HashSet<String> = HashSet::new();
+        assert_eq!(close_missing_listings(&c, "NSE", &seen, d(2024, 6, 1)).unwrap(), 2);
+
+        let status = |id: &str| -> (String, Option<String>) {
+            c.query_row("SELECT status, valid_to FROM entities WHERE entity_id=?1", [id], |r| Ok((r.get(0)?, r.get(1)?))).unwrap()
+        };
+        assert_eq!(status(&gone), ("delisted".to_string(), Some("2024-06-01".to_string())));
+        assert_eq!(status(&dual).0, "listed");
+    }
+
+    #[test]
+    fn test_name_spellings_accumulate_without_changing_canonical_name() {
+        let c = db();
+        let id = upsert_listing(&c, &sec("INE002A01018", "Reliance Industries Limited", "RELIANCE"), d(2024, 1, 1)).unwrap();
+        let mut on_bse = sec("INE002A01018", "Reliance Industries Ltd", "500325");
+        on_bse.exchange = "BSE".to_string();
+        on_bse.canonical_name_source = false;
+        for as_of in [d(2024, 1, 2), d(2024, 1, 3)] {
+            upsert_listing(&c, &on_bse, as_of).unwrap();
+            upsert_listing(&c, &sec("INE002A01018", "Reliance Industries Limited", "RELIANCE"), as_of).unwrap();
+        }
+
+        let mut names = open_aliases(&c, &id, ALIAS_NAME);
+        names.sort();
+        assert_eq!(names, vec!["Reliance Industries Limited", "Reliance Industries Ltd"]);
+        let rows: i64 = c.query_row(
+            "SELECT COUNT(*) FROM entity_aliases WHERE entity_id=?1 AND alias_type=?2",
+            rusqlite::params![id, ALIAS_NAME], |r| r.get(0)).unwrap();
+        assert_eq!(rows, 2);
+        let canonical: String = c.query_row("SELECT canonical_name FROM entities WHERE entity_id=?1", [&id], |r| r.get(0)).unwrap();
+        assert_eq!(canonical, "Reliance Industries Limited");
+
+        // A name seen first at another source still names the new entity.
+        let mut bse_only = sec("INE999A01019", "Bse Only Ltd", "599999");
+        bse_only.exchange = "BSE".to_string();
+        bse_only.canonical_name_source = false;
+        let bse_id = upsert_listing(&c, &bse_only, d(2024, 1, 1)).unwrap();
+        let canonical: String = c.query_row("SELECT canonical_name FROM entities WHERE entity_id=?1", [&bse_id], |r| r.get(0)).unwrap();
+        assert_eq!(canonical, "Bse Only Ltd");
+    }
+
+    #[test]
+    fn test_index_constituents_track_additions_and_removals() {
+        let c = db();
+        let a = "isin:A".to_string();
+        let b = "isin:B".to_string();
+        let cc = "isin:C".to_string();
+        assert_eq!(set_index_constituents(&c, "NIFTY 50", &[a.clone(), b.clone()], d(2024, 1, 1)).unwrap(), (2, 0));
+        assert_eq!(set_index_constituents(&c, "NIFTY 50", &[a.clone(), cc.clone()], d(2024, 3, 28)).unwrap(), (1, 1));
+        let open: i64 = c.query_row(
+            "SELECT COUNT(*) FROM index_constituents WHERE index_name='NIFTY 50' AND valid_to IS NULL",
+            [], |r| r.get(0)).unwrap();
+        assert_eq!(open, 2);
+    }
+}
@@ -0,0 +1,144 @@
+// file: store/story_clusters.rs
+// Purpose:
+//   Persistent story clusters over the `doc_signatures` / `simhash_bands` tables (migration
+//   0010), used by `mod_dedupe` so a wire story republished tomorrow joins today's cluster.
+//   Each document's 64-bit SimHash is stored with its cluster id, and indexed in BANDS bands
+//   of 16 bits. Two SimHashes within Hamming distance BANDS - 1 (= 3) must agree exactly on
+//   at least one band (pigeonhole), so looking up the documents sharing any band finds every
+//   such near-duplicate without scanning; wider thresholds only find the ones that do share
+//   a band.
+
+use rusqlite::{Connection, OptionalExtension};
+
+/// Number of 16-bit bands each SimHash is split into.
+pub const BANDS: usize = 4;
+const BAND_BITS: usize = 64 / BANDS;
+
+/// A stored document close to a looked-up SimHash.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoryMatch {
+    pub doc_id: String,
+    /// The document's site-provided unique_id, if it had one.
+    pub unique_id: Option<String>,
+    pub cluster_id: String,
+    pub distance: u32,
+}
+
+/// The band values of a SimHash, lowest bits first.
+pub fn band_values(hash: u64) -> [i64; BANDS] {
+    let mut bands = [0i64; BANDS];
+    for (i, band) in bands.iter_mut().enumerate() {
+        *band = ((hash >> (i * BAND_BITS)) & ((1u64 << BAND_BITS) - 1)) as i64;
+    }
+    bands
+}
+
+/// The cluster `doc_id` was stored in, if its signature is already stored.
+pub fn cluster_of(conn: &Connection, doc_id: &str) -> Result<Option<String>, String> {
+    conn.query_row("SELECT cluster_id FROM doc_signatures WHERE doc_id=?1", [doc_id], |r| r.get(0))
+        .optional()
+        .map_err(|e| format!("read doc_signature({}): {}", doc_id, e))
+}
+
+/// The closest stored document within `threshold` bits of `hash` and dated within
+/// `window_ms` of `date_ms`; ties go to the earliest document.
+pub fn find_near_duplicate(conn: &Connection, hash: u64, date_ms: i64, window_ms: i64, threshold: u32) -> Result<Option<StoryMatch>, String> {
+    let mut stmt = conn
+        .prepare_cached(
+            "SELECT s.doc_id, s.unique_id, s.cluster_id, s.simhash, s.date_ms
+             FROM simhash_bands b JOIN doc_signatures s ON s.doc_id = b.doc_id
+             WHERE b.band=?1 AND b.band_value=?2 AND b.date_ms BETWEEN ?3 AND ?4",
+        )
+        .map_err(|e| format!("prepare near-duplicate lookup: {}", e))?;
+    let mut best: Option<(StoryMatch, i64)> = None;
+    for (band, value) in band_values(hash).iter().enumerate() {
+        let rows = stmt
+            .query_map(rusqlite::params![band as i64, value, date_ms - window_ms, date_ms + window_ms], |r| {
+                Ok((
+                    r.get::<_, String>(0)?,
+                    r.get::<_, Option<String>>(1)?,
+                    r.get::<_, String>(2)?,
+                    r.get::<_, i64>(3)?,
+                    r.get::<_, i64>(4)?,
+                ))
+            })
+            .map_err(|e| format!("near-duplicate lookup: {}", e))?;
+        for (doc_id, unique_id, cluster_id, stored, stored_date) in rows.flatten() {
+            let distance = (hash ^ stored as u64).count_ones();
+            let better = best.as_ref().is_none_or(|(m, d)| (distance, stored_date) < (m.distance, *d));
+            if distance <= threshold && better {
+                best = Some((StoryMatch { doc_id, unique_id, cluster_id, distance }, stored_date));
+            }
+        }
+    }
+    Ok(best.map(|(m, _)| m))
+}
+
+/// Store (or replace) a document's SimHash, cluster and unique_id (empty if the site gives
+/// none), with its band index rows.
+pub fn save_signature(conn: &Connection, doc_id: &str, unique_id: &str, cluster_id: &str, hash: u64, date_ms: i64) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR REPLACE INTO doc_signatures (doc_id, unique_id, cluster_id, simhash, date_ms)
+         VALUES (?1, NULLIF(?2, ''), ?3, ?4, ?5)",
+        rusqlite::params![doc_id, unique_id.trim(), cluster_id, hash as i64, date_ms],
+    )
+    .map_err(|e| format!("save doc_signature({}): {}", doc_id, e))?;
+    conn.execute("DELETE FROM simhash_bands WHERE doc_id=?1", [doc_id])
+        .map_err(|e| format!("clear simhash_bands({}): {}", doc_id, e))?;
+    for (band, value) in band_values(hash).iter().enumerate() {
+        conn.execute(
+            "INSERT OR IGNORE INTO simhash_bands (band, band_value, doc_id, date_ms) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![band as i64, value, doc_id, date_ms],
+        )
+        .map_err(|e| format!("save simhash_band({}): {}", doc_id, e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store;
+
+    const DAY_MS: i64 = 86_400_000;
+
+    fn db() -> Connection {
+        let c = Connection::open_in_memory().unwrap();
+        store::migrate(&c).unwrap();
+        c
+    }
+
+    #[test]
+    fn test_band_values_split_hash() {
+        assert_eq!(band_values(0x0004_0003_0002_0001), [1, 2, 3, 4]);
+        // Three flipped bits, one per band, still leave one band identical.
+        let a: u64 = 0xdead_beef_cafe_f00d;
+        let b = a ^ (1 << 3) ^ (1 << 20) ^ (1 << 40);
+        let same = band_values(a).iter().zip(band_values(b)).filter(|(x, y)| **x == *y).count();
+        assert_eq!(same, 1);
+    }
+
+    #[test]
+    fn test_find_near_duplicate_within_window() {
+        let c = db();
+        let day0 = 1_750_000_000_000;
+        let a: u64 = 0xdead_beef_cafe_f00d;
+        save_signature(&c, "D1", "RBI/2026/1", "D1", a, day0).unwrap();
+        save_signature(&c, "D2", "", "D2", !a, day0).unwrap();
+
+        let near = a ^ (1 << 3) ^ (1 << 20) ^ (1 << 40);
+        let m = find_near_duplicate(&c, near, day0 + DAY_MS, 7 * DAY_MS, 3).unwrap().unwrap();
+        assert_eq!((m.doc_id.as_str(), m.cluster_id.as_str(), m.distance), ("D1", "D1", 3));
+        assert_eq!(m.unique_id.as_deref(), Some("RBI/2026/1"));
+        let m = find_near_duplicate(&c, !a, day0, 7 * DAY_MS, 3).unwrap().unwrap();
+        assert_eq!((m.doc_id.as_str(), m.unique_id), ("D2", None));
+        assert!(find_near_duplicate(&c, near, day0 + 8 * DAY_MS, 7 * DAY_MS, 3).unwrap().is_none(), "outside window");
+        assert!(find_near_duplicate(&c, near ^ (1 << 60), day0, 7 * DAY_MS, 3).unwrap().is_none(), "too far");
+
+        // Re-saving a document replaces its signature and band rows.
+        save_signature(&c, "D1", "RBI/2026/1", "C9", a, day0).unwrap();
+        assert_eq!(cluster_of(&c, "D1").unwrap().as_deref(), Some("C9"));
+        let bands: i64 = c.query_row("SELECT COUNT(*) FROM simhash_bands WHERE doc_id='D1'", [], |r| r.get(0)).unwrap();
+        assert_eq!(bands, BANDS as i64);
+    }
+}
@@ -0,0 +1,475 @@
+// file: temporal.rs
+// Purpose:
+//   Temporal expression resolver for the relative and fiscal dates common in Indian financial
+//   news, complementing the explicit-date patterns of `mod_extract_quant::extract_dates`:
+//     - fiscal periods: "FY25", "FY2024-25", "fiscal 2025", "2024-25", "Q3FY24", "Q3 of FY24",
+//       "H1 FY25", "third quarter of FY24", and bare "Q3" / "H2" (the latest such period of the
+//       fiscal year that has begun by the anchor date);
+//     - relative periods: "last week", "this month", "next quarter", "current fiscal",
+//       "last financial year", "this year";
+//     - days: "today", "yesterday", "tomorrow", "last Friday", "next Monday", "this Tuesday",
+//       "on Friday"; "3 days ago", "two months ago", "in 10 days";
+//     - month ends: "March-end", "end-June", "end of March 2027", "by March-end".
+//   Relative expressions are anchored to the document's publish date; without one (or with the
+//   1970-01-01 placeholder of `Document::default()`) only the self-contained ones (fiscal years and quarters, month ends with a year) are resolved.
+//
+//   Fiscal years follow the Indian April–March convention, named by the calendar year they
+//   end in: FY25 is 1 April 2024 – 31 March 2025, and its Q3 is October–December 2024.
+//   Each expression becomes a `DateRef` holding the first day of the period it names, at the
+//   period's resolution ("day", "week", "month", "quarter", "half", "year", "fiscal_year"),
+//   with the matched text in `expression`. Where two patterns overlap the more specific one
+//   (listed first) wins: "Q3FY24" is one quarter, not a quarter and a fiscal year.
+
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use regex::{Captures, Regex};
+
+use crate::analysis::DateRef;
+
+/// First month of the fiscal year (April).
+pub const FY_START_MONTH: u32 = 4;
+
+/// A fiscal year: "FY25", "FY 2024-25", "FY'25", "fiscal 2025", "fiscal year 2024-25", or a
+/// bare "2024-25" (only when the second year follows the first).
+const FY_PATTERN: &str = r"(?:fy\s*'?(?P<fya>\d{4}|\d{2})(?:\s*[-–/]\s*(?P<fyb>\d{4}|\d{2}))?|fiscal(?:\s+year)?\s+(?P<fa>\d{4})(?:\s*[-–/]\s*(?P<fb>\d{4}|\d{2}))?|(?P<ya>\d{4})\s*[-–]\s*(?P<yb>\d{2}))\b";
+/// A month name or abbreviation. "May" is only read as a month when capitalised or followed
+/// by a year (checked by the caller), as "may" is usually the verb.
+const MONTH_PATTERN: &str = r"(jan(?:uary)?|feb(?:ruary)?|mar(?:ch)?|apr(?:il)?|may|june?|july?|aug(?:ust)?|sept?(?:ember)?|oct(?:ober)?|nov(?:ember)?|dec(?:ember)?)\b";
+const NUMBER_PATTERN: &str = r"(\d{1,3}|an?|one|two|three|four|five|six|seven|eight|nine|ten|eleven|twelve)";
+
+/// Publish dates before this year are placeholders (`Document::default()` has 1970-01-01),
+/// not real anchors.
+const MIN_ANCHOR_YEAR: i32 = 1990;
+
+/// The document's publish date ("YYYY-MM-DD..."), the anchor for relative expressions. None
+/// when the date is unparseable, earlier than MIN_ANCHOR_YEAR, or `publish_date_ms` is unset.
+pub fn anchor_date(publish_date: &str, publish_date_ms: i64) -> Option<NaiveDate> {
+    if publish_date_ms <= 0 {
+        return None;
+    }
+    NaiveDate::parse_from_str(publish_date.get(..10)?, "%Y-%m-%d")
+        .ok()
+        .filter(|d| d.year() >= MIN_ANCHOR_YEAR)
+}
+
+/// Resolve the relative and fiscal date expressions in `text` against `anchor`, in text order.
+pub fn resolve_expressions(text: &str, anchor: Option<NaiveDate>) -> Vec<DateRef> {
+    let mut found: Vec<(usize, usize, &'static str, NaiveDate)> = Vec::new();
+    let mut add = |start: usize, end: usize, resolution: &'static str, date: NaiveDate| {
+        if !found.iter().any(|(s, e, _, _)| start < *e && *s < end) {
+            found.push((start, end, resolution, date));
+        }
+    };
+
+    for c in fiscal_period_re().captures_iter(text) {
+        if in_reference_number(text, &c) {
+            continue;
+        }
+        let Some(fy_start) = fy_end_year(&c).and_then(|y| NaiveDate::from_ymd_opt(y - 1, FY_START_MONTH, 1)) else { continue };
+        let (resolution, index) = period_index(&c);
+        let months = if resolution == "quarter" { 3 } else { 6 };
+        let m = c.get(0).expect("whole match");
+        add(m.start(), m.end(), resolution, add_months(fy_start, months * (index - 1)));
+    }
+    for c in fiscal_year_re().captures_iter(text) {
+        if in_reference_number(text, &c) {
+            continue;
+        }
+        if let Some(date) = fy_end_year(&c).and_then(|y| NaiveDate::from_ymd_opt(y - 1, FY_START_MONTH, 1)) {
+            let m = c.get(0).expect("whole match");
+            add(m.start(), m.end(), "fiscal_year", date);
+        }
+    }
+    for c in month_end_re().captures_iter(text) {
+        let Some(month_match) = c.get(2).or(c.get(3)) else { continue };
+        let Some(month) = month_number(month_match.as_str()) else { continue };
+        let year = c.get(4).and_then(|y| y.as_str().parse::<i32>().ok());
+        if month == 5 && !month_match.as_str().starts_with('M') && year.is_none() {
+            continue;
+        }
+        let by = c.get(1).is_some();
+        if let Some(date) = month_end(month, year, by, anchor) {
+            let m = c.get(0).expect("whole match");
+            add(m.start(), m.end(), "day", date);
+        }
+    }
+    if let Some(anchor) = anchor {
+        resolve_relative(text, anchor, &mut add);
+    }
+    found.sort_by_key(|f| f.0);
+    found
+        .into_iter()
+        .map(|(start, end, resolution, date)| DateRef {
+            resolution: resolution.to_string(),
+            year: date.year(),
+            month: date.month(),
+            day: date.day(),
+            char_offset: start,
+            expression: text[start..end].to_string(),
+        })
+        .collect()
+}
+
+/// The expressions that need an anchor date.
+fn resolve_relative(text: &str, anchor: NaiveDate, add: &mut impl FnMut(usize, usize, &'static str, NaiveDate)) {
+    for c in bare_period_re().captures_iter(text) {
+        let (resolution, index) = period_index(&c);
+        let months = if resolution == "quarter" { 3 } else { 6 };
+        // The latest such period that has begun: this fiscal year's, else last year's.
+        let this_fy = period_start(anchor, "fiscal_year");
+        let mut start = add_months(this_fy, months * (index - 1));
+        if start > anchor {
+            start = add_months(start, -12);
+        }
+        let m = c.get(0).expect("whole match");
+        add(m.start(), m.end(), resolution, start);
+    }
+    for c in relative_period_re().captures_iter(text) {
+        let step = match c[1].to_lowercase().as_str() {
+            "last" | "previous" => -1,
+            "next" | "coming" => 1,
+            _ => 0,
+        };
+        let unit = c[2].to_lowercase();
+        let resolution = match unit.as_str() {
+            "week" => "week",
+            "month" => "month",
+            "quarter" => "quarter",
+            "year" => "year",
+            _ => "fiscal_year",
+        };
+        let m = c.get(0).expect("whole match");
+        add(m.start(), m.end(), resolution, shift(period_start(anchor, resolution), resolution, step));
+    }
+    for c in weekday_re().captures_iter(text) {
+        let Ok(weekday) = Weekday::from_str(&c[2]) else { continue };
+        let back = (anchor.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7;
+        let date = match c[1].to_lowercase().as_str() {
+            "last" => anchor - Duration::days(if back == 0 { 7 } else { back as i64 }),
+            "next" => anchor + Duration::days(if back == 0 { 7 } else { 7 - back as i64 }),
+            "this" => period_start(anchor, "week") + Duration::days(weekday.num_days_from_monday() as i64),
+            // "on Friday": news mostly reports the past, so the latest Friday up to the anchor.
+            _ => anchor - Duration::days(back as i64),
+        };
+        let m = c.get(0).expect("whole match");
+        add(m.start(), m.end(), "day", date);
+    }
+    for c in day_word_re().captures_iter(text) {
+        let offset = match c[1].to_lowercase().as_str() {
+            "yesterday" => -1,
+            "tomorrow" => 1,
+            _ => 0,
+        };
+        let m = c.get(0).expect("whole match");
+        add(m.start(), m.end(), "day", anchor + Duration::days(offset));
+    }
+    for c in ago_re().captures_iter(text).chain(within_re().captures_iter(text)) {
+        let Some(n) = small_number(&c[1]) else { continue };
+        let n = if c.get(0).expect("whole match").as_str().to_lowercase().ends_with("ago") { -n } else { n };
+        let (resolution, date) = match c[2].to_lowercase().as_str() {
+            "day" => ("day", anchor + Duration::days(n)),
+            "week" => ("day", anchor + Duration::weeks(n)),
+            "month" => ("month", add_months(period_start(anchor, "month"), n as i32)),
+            _ => ("year", NaiveDate::from_ymd_opt(anchor.year() + n as i32, 1, 1).unwrap_or(anchor)),
+        };
+        let m = c.get(0).expect("whole match");
+        add(m.start(), m.end(), resolution, date);
+    }
+}
+
+fn fiscal_period_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(&format!(
+            r"(?i)\b(?:q(?P<q>[1-4])|h(?P<h>[12])|(?P<qw>first|second|third|fourth|1st|2nd|3rd|4th)\s+quarter|(?P<hw>first|second|1st|2nd)\s+half)(?:\s*,\s*|\s+of\s+(?:the\s+)?|\s*){}",
+            FY_PATTERN
+        ))
+        .expect("fiscal period regex")
+    })
+}
+fn fiscal_year_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(&format!(r"(?i)\b{}", FY_PATTERN)).expect("fiscal year regex"))
+}
+fn bare_period_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\b(?:Q(?P<q>[1-4])|H(?P<h>[12]))\b").expect("bare period regex"))
+}
+fn relative_period_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)\b(last|previous|next|coming|this|current)\s+(week|month|quarter|year|fiscal(?:\s+year)?|financial\s+year)\b")
+            .expect("relative period regex")
+    })
+}
+fn weekday_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)\b(last|next|this|on)\s+(monday|tuesday|wednesday|thursday|friday|saturday|sunday)\b").expect("weekday regex")
+    })
+}
+fn day_word_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?i)\b(today|yesterday|tomorrow)\b").expect("day word regex"))
+}
+fn ago_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(&format!(r"(?i)\b{}\s+(day|week|month|year)s?\s+ago\b", NUMBER_PATTERN)).expect("ago regex"))
+}
+fn within_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(&format!(r"(?i)\b(?:in|within)\s+(?:the\s+next\s+)?{}\s+(day|week|month|year)s?\b", NUMBER_PATTERN)).expect("within regex")
+    })
+}
+fn month_end_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(&format!(
+            r"(?i)\b(?:(by|till|until|before)\s+(?:the\s+)?)?(?:end[\s-]+(?:of\s+)?{m}|{m}[\s-]+end)(?:,?\s+(\d{{4}}))?\b",
+            m = MONTH_PATTERN
+        ))
+        .expect("month end regex")
+    })
+}
+
+/// True for a bare "2024-25" fiscal year written next to a "/", as in the circular number
+/// "RBI/2024-25/123"; "FY" and "fiscal" forms are always read.
+fn in_reference_number(text: &str, c: &Captures) -> bool {
+    let (Some(first), Some(second)) = (c.name("ya"), c.name("yb")) else { return false };
+    text[..first.start()].ends_with('/') || text[second.end()..].starts_with('/')
+}
+
+/// Fiscal year named by a `FY_PATTERN` match, as the calendar year it ends in.
+fn fy_end_year(c: &Captures) -> Option<i32> {
+    let full = |s: &str, base: Option<i32>| -> Option<i32> {
+        let v: i32 = s.parse().ok()?;
+        Some(match (s.len(), base) {
+            (4, _) => v,
+            // The second year of "2024-25" / "1999-00" follows the first.
+            (_, Some(b)) => {
+                let y = b - b % 100 + v;
+                if y <= b { y + 100 } else { y }
+            }
+            _ => 2000 + v,
+        })
+    };
+    let (a, b) = if let Some(a) = c.name("fya") {
+        (a.as_str(), c.name("fyb"))
+    } else if let Some(a) = c.name("fa") {
+        (a.as_str(), c.name("fb"))
+    } else {
+        let (a, b) = (c.name("ya")?, c.name("yb")?);
+        let first: i32 = a.as_str().parse().ok()?;
+        // A bare "2024-25" is a fiscal year only if it spans consecutive years.
+        return (full(b.as_str(), Some(first))? == first + 1).then_some(first + 1);
+    };
+    match b {
+        Some(b) => {
+            let first = full(a, None)?;
+            let second = full(b.as_str(), Some(first))?;
+            (second == first + 1).then_some(second)
+        }
+        None => full(a, None),
+    }
+}
+
+/// ("quarter" | "half", 1-based index) of a fiscal or bare period match.
+fn period_index(c: &Captures) -> (&'static str, i32) {
+    let ordinal = |w: &str| match w.to_lowercase().as_str() {
+        "first" | "1st" => 1,
+        "second" | "2nd" => 2,
+        "third" | "3rd" => 3,
+        _ => 4,
+    };
+    if let Some(q) = c.name("q") {
+        ("quarter", q.as_str().parse().unwrap_or(1))
+    } else if let Some(w) = c.name("qw") {
+        ("quarter", ordinal(w.as_str()))
+    } else if let Some(h) = c.name("h") {
+        ("half", h.as_str().parse().unwrap_or(1))
+    } else {
+        ("half", c.name("hw").map_or(1, |w| ordinal(w.as_str())))
+    }
+}
+
+fn month_number(mon: &str) -> Option<u32> {
+    let m = match mon.get(..3)?.to_lowercase().as_str() {
+        "jan" => 1, "feb" => 2, "mar" => 3, "apr" => 4, "may" => 5, "jun" => 6,
+        "jul" => 7, "aug" => 8, "sep" => 9, "oct" => 10, "nov" => 11, "dec" => 12,
+        _ => return None,
+    };
+    Some(m)
+}
+
+fn small_number(s: &str) -> Option<i64> {
+    let n = match s.to_lowercase().as_str() {
+        "a" | "an" | "one" => 1, "two" => 2, "three" => 3, "four" => 4, "five" => 5, "six" => 6,
+        "seven" => 7, "eight" => 8, "nine" => 9, "ten" => 10, "eleven" => 11, "twelve" => 12,
+        digits => digits.parse().ok()?,
+    };
+    Some(n)
+}
+
+/// Last day of `month`: in `year` if given; else, for a deadline ("by March-end"), the first
+/// one on or after the anchor, otherwise the one nearest the anchor.
+fn month_end(month: u32, year: Option<i32>, deadline: bool, anchor: Option<NaiveDate>) -> Option<NaiveDate> {
+    let end = |y: i32| NaiveDate::from_ymd_opt(y, month, 1).map(|d| add_months(d, 1) - Duration::days(1));
+    if let Some(y) = year {
+        return end(y);
+    }
+    let anchor = anchor?;
+    let candidates = (anchor.year() - 1..=anchor.year() + 1).filter_map(end);
+    if deadline {
+        candidates.into_iter().find(|d| *d >= anchor)
+    } else {
+        candidates.min_by_key(|d| (*d - anchor).num_days().abs())
+    }
+}
+
+/// `date` moved by `n` months (day of month kept; callers pass the first of a month).
+fn add_months(date: NaiveDate, n: i32) -> NaiveDate {
+    let months = date.year() * 12 + date.month0() as i32 + n;
+    NaiveDate::from_ymd_opt(months.div_euclid(12), months.rem_euclid(12) as u32 + 1, date.day()).unwrap_or(date)
+}
+
+/// First day of the period of `resolution` containing `date`.
+fn period_start(date: NaiveDate, resolution: &str) -> NaiveDate {
+    let first = |m: u32| NaiveDate::from_ymd_opt(date.year(), m, 1).unwrap_or(date);
+    match resolution {
+        "week" => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+        "month" => first(date.month()),
+        "quarter" => first((date.month0() / 3) * 3 + 1),
+        "year" => first(1),
+        _ => {
+            let start = first(FY_START_MONTH);
+            if date.month() >= FY_START_MONTH { start } else { add_months(start, -12) }
+        }
+    }
+}
+
+/// The period of `resolution` `n` periods after the one starting at `start`.
+fn shift(start: NaiveDate, resolution: &str, n: i32) -> NaiveDate {
+    match resolution {
+        "week" => start + Duration::weeks(n as i64),
+        "month" => add_months(start, n),
+        "quarter" => add_months(start, 3 * n),
+        _ => add_months(start, 12 * n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolved(text: &str, anchor: &str) -> Vec<(String, String, String)> {
+        resolve_expressions(text, anchor_date(anchor, 1))
+            .into_iter()
+            .map(|d| (d.expression, d.resolution, format!("{:04}-{:02}-{:02}", d.year, d.month, d.day)))
+            .collect()
+    }
+
+    fn r(expression: &str, resolution: &str, date: &str) -> (String, String, String) {
+        (expression.to_string(), resolution.to_string(), date.to_string())
+    }
+
+    #[test]
+    fn test_fiscal_years_and_quarters() {
+        assert_eq!(
+            resolved("Profit in Q3FY24 beat estimates; FY25 guidance and the FY2025-26 capex plan were kept.", ""),
+            vec![r("Q3FY24", "quarter", "2023-10-01"), r("FY25", "fiscal_year", "2024-04-01"), r("FY2025-26", "fiscal_year", "2025-04-01")]
+        );
+        assert_eq!(
+            resolved("Margins in the third quarter of FY24 and H1 FY25 improved; exports grew in 2023-24, not in 2026-10.", ""),
+            vec![r("third quarter of FY24", "quarter", "2023-10-01"), r("H1 FY25", "half", "2024-04-01"), r("2023-24", "fiscal_year", "2023-04-01")]
+        );
+        assert_eq!(resolved("Sales in fiscal 2025 rose.", "")[0], r("fiscal 2025", "fiscal_year", "2024-04-01"));
+    }
+
+    #[test]
+    fn test_relative_periods_and_days() {
+        // Sunday 18 October 2026: Q3 of FY27.
+        let anchor = "2026-10-18T09:30:00";
+        assert_eq!(
+            resolved("Yesterday the bank said Q2 profit rose; it expects more next quarter and in the current fiscal.", anchor),
+            vec![
+                r("Yesterday", "day", "2026-10-17"),
+                r("Q2", "quarter", "2026-07-01"),
+                r("next quarter", "quarter", "2027-01-01"),
+                r("current fiscal", "fiscal_year", "2026-04-01"),
+            ]
+        );
+        assert_eq!(
+            resolved("Shares fell last Friday and on Wednesday; the board meets next Monday. Q4 results came last month.", anchor),
+            vec![
+                r("last Friday", "day", "2026-10-16"),
+                r("on Wednesday", "day", "2026-10-14"),
+                r("next Monday", "day", "2026-10-19"),
+                r("Q4", "quarter", "2026-01-01"),
+                r("last month", "month", "2026-09-01"),
+            ]
+        );
+        assert_eq!(
+            resolved("The plant shut three days ago and reopens in 2 weeks; the deal closed two years ago.", anchor),
+            vec![r("three days ago", "day", "2026-10-15"), r("in 2 weeks", "day", "2026-11-01"), r("two years ago", "year", "2024-01-01")]
+        );
+    }
+
+    #[test]
+    fn test_month_ends() {
+        let anchor = "2026-10-18";
+        assert_eq!(
+            resolved("Dues must be cleared by March-end; at end-September the ratio was 4%, and end of June 2025 too.", anchor),
+            vec![
+                r("by March-end", "day", "2027-03-31"),
+                r("end-September", "day", "2026-09-30"),
+                r("end of June 2025", "day", "2025-06-30"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_words_starting_like_months_are_not_month_ends() {
+        let anchor = "2026-10-18";
+        assert!(resolved("Sensex may end the week higher.", anchor).is_empty());
+        assert!(resolved("Trading halted before the end of market hours.", anchor).is_empty());
+        assert!(resolved("Rates are at their lowest since the decade end.", anchor).is_empty());
+        assert_eq!(
+            resolved("Sales rose by May-end and end-Sept, and end of may 2027 is the target.", anchor),
+            vec![
+                r("by May-end", "day", "2027-05-31"),
+                r("end-Sept", "day", "2026-09-30"),
+                r("end of may 2027", "day", "2027-05-31"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_circular_numbers_are_not_fiscal_years() {
+        assert!(resolved("Refer to circular RBI/2024-25/123 dated 1 April.", "unknown").is_empty());
+        assert_eq!(
+            resolved("Targets for 2024-25 and FY2025-26/27 guidance were set.", "unknown"),
+            vec![r("2024-25", "fiscal_year", "2024-04-01"), r("FY2025-26", "fiscal_year", "2025-04-01")]
+        );
+    }
+
+    #[test]
+    fn test_anchor_date_ignores_placeholder_dates() {
+        assert_eq!(anchor_date("2025-02-03T10:00:00", 1_738_576_800_000), NaiveDate::from_ymd_opt(2025, 2, 3));
+        let doc = crate::document::Document::default();
+        assert_eq!(anchor_date(&doc.publish_date, doc.publish_date_ms), None);
+        assert_eq!(anchor_date("1985-06-30", 1), None);
+        assert_eq!(anchor_date("2025-02-03", 0), None);
+        assert_eq!(anchor_date("unknown", 1), None);
+    }
+
+    #[test]
+    fn test_no_anchor_resolves_only_self_contained() {
+        assert_eq!(
+            resolved("Last week the FY26 target and the March-end 2026 deadline were set.", "unknown"),
+            vec![r("FY26", "fiscal_year", "2025-04-01"), r("March-end 2026", "day", "2026-03-31")]
+        );
+    }
+}
@@ -17,7 +17,8 @@ use std::path::{Path, PathBuf};
 use std::borrow::BorrowMut;
 use std::collections::HashMap;
 use std::panic::AssertUnwindSafe;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
+use std::process::Command;
 use nom::AsBytes;
 use log::{debug, error, info, LevelFilter, warn};
 use log4rs::append::file::FileAppender;
@@ -37,6 +38,7 @@ use regex::Regex;
 use rusqlite::{Row, Rows};
 use rusqlite::params;
 use scraper::{ElementRef};
+use serde::{Deserialize, Serialize};
 
 use crate::{document, network, utils};
 use crate::document::{Document};
@@ -214,36 +216,29 @@ pub fn to_local_datetime(date: NaiveDate) -> DateTime<Local> {
     }
 }
 
-/// Returns the most recent `count` weekday dates (Mon–Fri), ending at and including
-/// `from` if it is itself a weekday, walking backwards in time.
-///
-/// Stock exchanges (NSE/BSE) publish the daily bhavcopy only on trading days, and the
-/// current day's file does not exist until after market close. Callers should try each
-/// returned date in order (most recent first) and stop at the first that yields data.
-/// This does not account for exchange trading holidays — those simply produce a missing
-/// file for that date and the caller falls through to the next candidate.
-///
-/// # Arguments
-/// * `from`: the reference date (typically today)
-/// * `count`: how many weekday candidates to return
-pub fn recent_business_days(from: NaiveDate, count: usize) -> Vec<NaiveDate> {
+/// Returns every weekday (Mon–Fri) from `from` to `to` inclusive, in ascending order.
+/// Exchange holidays are not excluded here; a feed simply finds no file for those dates.
+pub fn business_days_between(from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
     use chrono::Datelike;
-    let mut days = Vec::with_capacity(count);
-    let mut cursor = from;
-    while days.len() < count {
-        let weekday = cursor.weekday();
-        if weekday != chrono::Weekday::Sat && weekday != chrono::Weekday::Sun {
-            days.push(cursor);
-        }
-        match cursor.pred_opt() {
-            Some(prev) => cursor = prev,
-            None => break,
-        }
-    }
-    days
+    from.iter_days()
+        .take_while(|d| *d <= to)
+        .filter(|d| d.weekday() != chrono::Weekday::Sat && d.weekday() != chrono::Weekday::Sun)
+        .collect()
 }
 
 
+/// Parses a date shown on a listing page, trying each of the given chrono formats in turn.
+/// Surrounding whitespace and a trailing full stop are ignored.
+///
+/// # Arguments
+/// * `date_text`: the date as displayed, e.g. "06/03/2026" or "Mar 06, 2026"
+/// * `formats`: the chrono format strings to try, most likely first
+pub fn parse_date_with_formats(date_text: &str, formats: &[&str]) -> Option<NaiveDate> {
+    let cleaned = date_text.split_whitespace().collect::<Vec<&str>>().join(" ");
+    let cleaned = cleaned.trim_end_matches('.');
+    formats.iter().find_map(|fmt| NaiveDate::parse_from_str(cleaned, fmt).ok())
+}
+
 /// Canonicalize a URL for de-duplication and storage: lowercase the scheme+host, drop the
 /// fragment, strip common tracking query parameters (utm_*, fbclid, gclid, …), and remove a
 /// trailing slash (except for the root path). Two URLs that differ only by tracking params,
@@ -466,6 +461,8 @@ pub fn insert_urls_info_to_database(config: Arc<config::Config>, processed_docin
 /// Extract content of the associated PDF file mentioned in the document's pdf_url attribute
 /// Converts to text and saves it to the document 'text' attribute.
 /// The PDF content is saved as a file in the data_folder.
+/// Pages without a text layer (scanned pages) are OCR'd, see `apply_ocr_fallback`, and
+/// tables are extracted into `generated_content`, see `pdf_tables::attach_tables`.
 ///
 /// # Arguments
 ///
@@ -490,13 +487,14 @@ pub fn load_pdf_content(
                 let txt_filename = make_unique_filename(&input_doc, "txt");
                 let txt_file_path = Path::new(pdf_folder).join(&txt_filename);
                 let result = panic::catch_unwind(AssertUnwindSafe(|| {
-                    input_doc.text = extract_text_from_pdf(pdf_file_path, txt_file_path);
+                    input_doc.text = extract_text_from_pdf(pdf_file_path.clone(), txt_file_path);
                 }));
                 if result.is_err() {
                     if let Err(errvar) = result {
                         error!("When reading PDF file the error was: {:?}", errvar);
                     }
                 }
+                post_process_pdf(input_doc, &pdf_file_path);
             }
         }else {
             // get pdf content, and its plaintext output
@@ -532,11 +530,31 @@ pub fn load_pdf_content(
                         error!("When creating pdf file: {}", file_err);
                     }
                 }
+                if pdf_file_path.exists() {
+                    post_process_pdf(input_doc, &pdf_file_path);
+                }
             }
         }
     }
 }
 
+/// Applies the OCR fallback with the global settings and then extracts any tables, trapping
+/// any panic raised while parsing a malformed PDF so that the worker thread survives.
+fn post_process_pdf(input_doc: &mut Document, pdf_file_path: &Path) {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        apply_ocr_fallback(input_doc, pdf_file_path, ocr_settings())
+    }));
+    if let Err(errvar) = result {
+        error!("When running OCR on PDF file '{:?}' the error was: {:?}", pdf_file_path, errvar);
+    }
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        crate::pdf_tables::attach_tables(input_doc, pdf_file_path)
+    }));
+    if let Err(errvar) = result {
+        error!("When extracting tables from PDF file '{:?}' the error was: {:?}", pdf_file_path, errvar);
+    }
+}
+
 pub fn extract_text_from_pdf(pdf_file_path: PathBuf, txt_file_path: PathBuf) -> String {
     // read PDF data from file:
     // create a text file to hold output from pdf extraction:
@@ -841,23 +859,285 @@ fn append_with_last_element(stringvec: &mut Vec<String>, text_to_append: String)
     //return stringvec;
 }
 
-pub fn get_text_using_ocr(){
-    // TODO: extract text from image:
-
-    // let mut tesseract_args = rusty_tesseract::Args {
-    //     lang: "eng".to_string(),
-    //
-    //     //map of config variables
-    //     //this example shows a whitelist for the normal alphabet. Multiple arguments are allowed.
-    //     //available arguments can be found by running 'rusty_tesseract::get_tesseract_config_parameters()'
-    //     config_variables: HashMap::from([(
-    //         "tessedit_char_whitelist".into(),
-    //         "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ".into(),
-    //     )]),
-    //     dpi: Some(150),       // specify DPI for input image
-    //     psm: Some(6),         // define page segmentation mode 6 (i.e. "Assume a single uniform block of text")
-    //     oem: Some(3),         // define optical character recognition mode 3 (i.e. "Default, based on what is available")
-    // };
+/// Settings for the OCR fallback applied to PDF pages that have no text layer (scanned
+/// circulars, signed orders, etc.). Read once at startup from the application config by
+/// `init_ocr_settings`; `load_pdf_content` uses the defaults if that was never called.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OcrSettings {
+    /// Whether scanned pages are rasterised and passed through Tesseract at all.
+    pub enabled: bool,
+    /// Tesseract language(s), e.g. "eng" or "eng+hin" for bilingual circulars.
+    pub lang: String,
+    /// Resolution at which pages are rasterised before OCR.
+    pub dpi: i32,
+    /// Pages with fewer non-whitespace characters than this in their text layer are OCR'd.
+    pub min_page_chars: usize,
+}
+
+impl Default for OcrSettings {
+    fn default() -> Self {
+        OcrSettings {
+            enabled: true,
+            lang: "eng".to_string(),
+            dpi: 300,
+            min_page_chars: 20,
+        }
+    }
+}
+
+static OCR_SETTINGS: OnceLock<OcrSettings> = OnceLock::new();
+
+/// Initialise the global OCR settings from the application config keys `ocr_enabled`,
+/// `ocr_lang`, `ocr_dpi` and `ocr_min_page_chars`. Must be called once at startup.
+pub fn init_ocr_settings(config: &Config) {
+    let defaults = OcrSettings::default();
+    let settings = OcrSettings {
+        enabled: config.get_bool("ocr_enabled").unwrap_or(defaults.enabled),
+        lang: config.get_string("ocr_lang").unwrap_or(defaults.lang),
+        dpi: config.get_int("ocr_dpi").map(|v| v as i32).unwrap_or(defaults.dpi),
+        min_page_chars: config.get_int("ocr_min_page_chars")
+            .map(|v| v.max(0) as usize)
+            .unwrap_or(defaults.min_page_chars),
+    };
+    info!("OCR fallback: enabled={}, lang='{}', dpi={}, min_page_chars={}",
+        settings.enabled, settings.lang, settings.dpi, settings.min_page_chars);
+    let _ = OCR_SETTINGS.set(settings);
+}
+
+fn ocr_settings() -> &'static OcrSettings {
+    OCR_SETTINGS.get_or_init(OcrSettings::default)
+}
+
+/// Per-page OCR outcome, recorded on the document as JSON under
+/// `generated_content["ocr_confidence"]`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OcrPageConfidence {
+    /// 1-based page number within the PDF.
+    pub page: u32,
+    /// Mean Tesseract word confidence for the page (0-100).
+    pub confidence: f32,
+}
+
+/// Extracts the text layer of every page of a PDF, in page order. Uses pdf-extract's
+/// per-page output, which is the same text `load_pdf_content` stores on the document, and
+/// falls back to lopdf if pdf-extract cannot parse the file. Pages whose text cannot be
+/// decoded are returned with empty text so that they are picked up by the OCR fallback.
+pub fn pdf_page_texts(pdf_data: &[u8]) -> Result<Vec<(u32, String)>, String> {
+    let extracted = panic::catch_unwind(AssertUnwindSafe(|| {
+        pdf_extract::extract_text_from_mem_by_pages(pdf_data)
+    }));
+    match extracted {
+        Ok(Ok(pages)) => {
+            return Ok(pages.into_iter()
+                .enumerate()
+                .map(|(idx, text)| (idx as u32 + 1, text))
+                .collect());
+        },
+        Ok(Err(e)) => debug!("pdf-extract could not split PDF into pages, using lopdf: {}", e),
+        Err(_) => debug!("pdf-extract panicked while splitting PDF into pages, using lopdf"),
+    }
+    let pdf_doc = lopdf::Document::load_mem(pdf_data)
+        .map_err(|e| format!("Could not parse PDF: {}", e))?;
+    let mut pages = Vec::new();
+    for page_no in pdf_doc.get_pages().keys() {
+        let text = match pdf_doc.extract_text(&[*page_no]) {
+            Ok(text) => text,
+            Err(e) => {
+                debug!("No decodable text layer on page {}: {}", page_no, e);
+                String::new()
+            }
+        };
+        pages.push((*page_no, text));
+    }
+    Ok(pages)
+}
+
+/// Returns true if a page's text layer is too sparse to be the real content of the page.
+pub fn page_needs_ocr(page_text: &str, min_page_chars: usize) -> bool {
+    page_text.chars().filter(|c| !c.is_whitespace()).count() < min_page_chars
+}
+
+/// Joins per-page texts in page order, separating pages with a blank line.
+pub fn merge_page_texts(pages: &[(u32, String)]) -> String {
+    let mut sorted: Vec<&(u32, String)> = pages.iter().collect();
+    sorted.sort_by_key(|(page_no, _)| *page_no);
+    sorted.iter()
+        .map(|(_, text)| text.trim())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<&str>>()
+        .join("\n\n")
+}
+
+static OCR_TOOLS_AVAILABLE: OnceLock<bool> = OnceLock::new();
+
+/// Checks once per process that the `pdftoppm` (poppler-utils) and `tesseract` executables
+/// can be run, logging a warning and disabling the OCR fallback if either is missing.
+fn ocr_tools_available() -> bool {
+    *OCR_TOOLS_AVAILABLE.get_or_init(|| {
+        let missing: Vec<&str> = ["pdftoppm", "tesseract"].into_iter()
+            .filter(|tool| Command::new(tool).arg("-v").output().is_err())
+            .collect();
+        if !missing.is_empty() {
+            warn!("OCR fallback disabled: could not run {} (install poppler-utils and tesseract-ocr, \
+                or set ocr_enabled=false)", missing.join(", "));
+        }
+        missing.is_empty()
+    })
+}
+
+/// Rasterises one page of a PDF into a PNG file using poppler's `pdftoppm`.
+/// The image is written to `<out_prefix>.png`, and its path is returned.
+pub fn rasterise_pdf_page(pdf_file_path: &Path, page_no: u32, dpi: i32, out_prefix: &Path) -> Result<PathBuf, String> {
+    let page_arg = page_no.to_string();
+    let output = Command::new("pdftoppm")
+        .args(["-f", &page_arg, "-l", &page_arg, "-r", &dpi.to_string(), "-png", "-singlefile"])
+        .arg(pdf_file_path)
+        .arg(out_prefix)
+        .output()
+        .map_err(|e| format!("Could not run pdftoppm: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("pdftoppm failed on page {} of {:?}: {}",
+            page_no, pdf_file_path, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    let mut image_path = out_prefix.as_os_str().to_owned();
+    image_path.push(".png");
+    let image_path = PathBuf::from(image_path);
+    if !image_path.exists() {
+        return Err(format!("pdftoppm did not produce image {:?}", image_path));
+    }
+    Ok(image_path)
+}
+
+/// Rebuilds plain text from Tesseract word boxes, keeping line and paragraph breaks, and
+/// returns it along with the mean word confidence (0-100). Boxes that are not words
+/// (negative confidence) or are blank are ignored.
+pub fn ocr_words_to_text(words: &[rusty_tesseract::Data]) -> (String, f32) {
+    let mut text = String::new();
+    let mut last_line: Option<(i32, i32, i32)> = None;
+    let mut conf_sum = 0.0f32;
+    let mut conf_count = 0usize;
+    for word in words {
+        if word.conf < 0.0 || word.text.trim().is_empty() {
+            continue;
+        }
+        let line_key = (word.block_num, word.par_num, word.line_num);
+        match last_line {
+            Some((block, par, _)) if (block, par) != (line_key.0, line_key.1) => text.push_str("\n\n"),
+            Some(prev) if prev != line_key => text.push('\n'),
+            Some(_) => text.push(' '),
+            None => {}
+        }
+        text.push_str(word.text.trim());
+        last_line = Some(line_key);
+        conf_sum += word.conf;
+        conf_count += 1;
+    }
+    let confidence = if conf_count > 0 { conf_sum / conf_count as f32 } else { 0.0 };
+    (text, confidence)
+}
+
+/// Runs Tesseract on an image file and returns the recognised text with its mean word
+/// confidence.
+///
+/// # Arguments
+///
+/// * `image_path`: The image (e.g. a rasterised PDF page) to read
+/// * `settings`: The OCR settings, of which the language and DPI are passed to Tesseract
+///
+/// returns: Result<(String, f32), String>
+pub fn get_text_using_ocr(image_path: &Path, settings: &OcrSettings) -> Result<(String, f32), String> {
+    let image = rusty_tesseract::Image::from_path(image_path)
+        .map_err(|e| format!("Could not load image {:?} for OCR: {}", image_path, e))?;
+    let tesseract_args = rusty_tesseract::Args {
+        lang: settings.lang.clone(),
+        config_variables: HashMap::new(),
+        dpi: Some(settings.dpi),
+        psm: Some(3),         // fully automatic page segmentation, no OSD
+        oem: Some(3),         // default engine, based on what is available
+    };
+    let data_output = rusty_tesseract::image_to_data(&image, &tesseract_args)
+        .map_err(|e| format!("Tesseract failed on {:?}: {}", image_path, e))?;
+    Ok(ocr_words_to_text(&data_output.data))
+}
+
+/// Detects pages of a saved PDF that have no usable text layer, rasterises and OCRs them,
+/// and splices the OCR text into the pdf-extract text of the other pages, in page order.
+/// Per-page confidences are stored in `generated_content["ocr_confidence"]` and the
+/// language used in `generated_content["ocr_lang"]`. Leaves the document untouched if
+/// every page already has text or OCR produced nothing.
+///
+/// returns: the number of pages that were OCR'd
+pub fn apply_ocr_fallback(input_doc: &mut Document, pdf_file_path: &Path, settings: &OcrSettings) -> usize {
+    if !settings.enabled || !ocr_tools_available() {
+        return 0;
+    }
+    let pdf_data = match fs::read(pdf_file_path) {
+        Ok(data) => data,
+        Err(e) => {
+            error!("[{}] Could not read PDF {:?} for OCR: {}", input_doc.module, pdf_file_path, e);
+            return 0;
+        }
+    };
+    let mut pages = match pdf_page_texts(&pdf_data) {
+        Ok(pages) => pages,
+        Err(e) => {
+            error!("[{}] {} ({:?})", input_doc.module, e, pdf_file_path);
+            return 0;
+        }
+    };
+    let scanned: Vec<u32> = pages.iter()
+        .filter(|(_, text)| page_needs_ocr(text, settings.min_page_chars))
+        .map(|(page_no, _)| *page_no)
+        .collect();
+    if scanned.is_empty() {
+        return 0;
+    }
+    info!("[{}] Running OCR on {} of {} page(s) without a text layer in {:?}",
+        input_doc.module, scanned.len(), pages.len(), pdf_file_path);
+
+    let mut confidences: Vec<OcrPageConfidence> = Vec::new();
+    for page_no in &scanned {
+        let out_prefix = pdf_file_path.with_file_name(format!(
+            "{}_ocr_p{}",
+            pdf_file_path.file_stem().and_then(|s| s.to_str()).unwrap_or("page"),
+            page_no
+        ));
+        let image_path = match rasterise_pdf_page(pdf_file_path, *page_no, settings.dpi, &out_prefix) {
+            Ok(path) => path,
+            Err(e) => {
+                error!("[{}] {}", input_doc.module, e);
+                continue;
+            }
+        };
+        match get_text_using_ocr(&image_path, settings) {
+            Ok((page_text, confidence)) => {
+                debug!("[{}] OCR page {}: {} chars, confidence {:.1}",
+                    input_doc.module, page_no, page_text.len(), confidence);
+                if let Some(entry) = pages.iter_mut().find(|(p, _)| p == page_no) {
+                    entry.1 = page_text;
+                }
+                confidences.push(OcrPageConfidence { page: *page_no, confidence });
+            },
+            Err(e) => error!("[{}] {}", input_doc.module, e),
+        }
+        if let Err(e) = fs::remove_file(&image_path) {
+            error!("When deleting OCR page image {:?}: {}", image_path, e);
+        }
+    }
+    if confidences.is_empty() {
+        return 0;
+    }
+
+    let merged = merge_page_texts(&pages);
+    if !merged.is_empty() {
+        input_doc.text = merged;
+    }
+    input_doc.generated_content.insert(
+        "ocr_confidence".to_string(),
+        serde_json::to_string(&confidences).unwrap_or_default(),
+    );
+    input_doc.generated_content.insert("ocr_lang".to_string(), settings.lang.clone());
+    confidences.len()
 }
 
 pub fn check_and_fix_url(url_to_check: &str, base_url: &str) -> Option<String> {
@@ -897,6 +1177,9 @@ mod tests {
     use crate::{document, utils};
     use crate::document::Document;
     use crate::utils::{append_with_last_element, check_and_fix_url, get_last_n_words, make_unique_filename, split_by_regex};
+    use crate::utils::{apply_ocr_fallback, merge_page_texts, ocr_words_to_text, page_needs_ocr, pdf_page_texts, rasterise_pdf_page, OcrPageConfidence, OcrSettings};
+    use std::fs;
+    use crate::pdf_tables::{make_test_pdf, TestPdfPage};
 
     #[test]
     fn test_to_local_datetime() {
@@ -939,40 +1222,34 @@ mod tests {
     }
 
     #[test]
-    fn test_recent_business_days_from_weekday() {
-        use chrono::NaiveDate;
-        // 2025-06-04 is a Wednesday
-        let wed = NaiveDate::from_ymd_opt(2025, 6, 4).unwrap();
-        let days = utils::recent_business_days(wed, 3);
-        assert_eq!(days, vec![
-            NaiveDate::from_ymd_opt(2025, 6, 4).unwrap(), // Wed
-            NaiveDate::from_ymd_opt(2025, 6, 3).unwrap(), // Tue
-            NaiveDate::from_ymd_opt(2025, 6, 2).unwrap(), // Mon
-        ]);
-    }
-
-    #[test]
-    fn test_recent_business_days_skips_weekend() {
+    fn test_business_days_between_skips_weekend() {
         use chrono::NaiveDate;
-        // 2025-06-08 is a Sunday -> should skip to Fri 6th, Thu 5th
-        let sun = NaiveDate::from_ymd_opt(2025, 6, 8).unwrap();
-        let days = utils::recent_business_days(sun, 2);
+        // Thu 2025-06-05 .. Tue 2025-06-10 → Thu, Fri, Mon, Tue
+        let days = utils::business_days_between(
+            NaiveDate::from_ymd_opt(2025, 6, 5).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 6, 10).unwrap(),
+        );
         assert_eq!(days, vec![
-            NaiveDate::from_ymd_opt(2025, 6, 6).unwrap(), // Fri
-            NaiveDate::from_ymd_opt(2025, 6, 5).unwrap(), // Thu
+            NaiveDate::from_ymd_opt(2025, 6, 5).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 6, 6).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 6, 9).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 6, 10).unwrap(),
         ]);
+        let none = utils::business_days_between(
+            NaiveDate::from_ymd_opt(2025, 6, 10).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 6, 5).unwrap(),
+        );
+        assert!(none.is_empty());
     }
 
     #[test]
-    fn test_recent_business_days_from_monday_back_to_friday() {
+    fn test_parse_date_with_formats() {
         use chrono::NaiveDate;
-        // 2025-06-09 is a Monday -> Mon 9th, then Fri 6th (skipping Sun 8 & Sat 7)
-        let mon = NaiveDate::from_ymd_opt(2025, 6, 9).unwrap();
-        let days = utils::recent_business_days(mon, 2);
-        assert_eq!(days, vec![
-            NaiveDate::from_ymd_opt(2025, 6, 9).unwrap(), // Mon
-            NaiveDate::from_ymd_opt(2025, 6, 6).unwrap(), // Fri
-        ]);
+        let formats = ["%d/%m/%Y", "%b %d, %Y", "%d.%m.%Y"];
+        assert_eq!(utils::parse_date_with_formats(" 06/03/2026 ", &formats), NaiveDate::from_ymd_opt(2026, 3, 6));
+        assert_eq!(utils::parse_date_with_formats("Mar  6, 2026", &formats), NaiveDate::from_ymd_opt(2026, 3, 6));
+        assert_eq!(utils::parse_date_with_formats("12.02.2025.", &formats), NaiveDate::from_ymd_opt(2025, 2, 12));
+        assert_eq!(utils::parse_date_with_formats("--", &formats), None);
     }
 
     #[test]
@@ -1122,4 +1399,99 @@ mod tests {
                    "Could not detect valid url.");
     }
 
-}
\ No newline at end of file
+    /// A page with a single line of large text, as used by the OCR tests.
+    fn text_page(text: &str) -> TestPdfPage<'_> {
+        TestPdfPage::Text(vec![(60, 600, 36, text)])
+    }
+
+    fn ocr_word(block: i32, par: i32, line: i32, text: &str, conf: f32) -> rusty_tesseract::Data {
+        rusty_tesseract::Data {
+            level: 5, page_num: 1, block_num: block, par_num: par, line_num: line, word_num: 1,
+            left: 0, top: 0, width: 10, height: 10, conf, text: text.to_string(),
+        }
+    }
+
+    fn tool_available(name: &str) -> bool {
+        std::process::Command::new(name).arg("-v").output().is_ok()
+    }
+
+    #[test]
+    fn test_pdf_page_texts_flags_image_only_page() {
+        let pdf_data = make_test_pdf(
+            &[text_page("RBI CIRCULAR ON KYC NORMS"), TestPdfPage::Image, text_page("ANNEX PAGE THREE TEXT")],
+            None,
+        );
+        let pages = pdf_page_texts(&pdf_data).expect("generated PDF should parse");
+        assert_eq!(pages.iter().map(|(p, _)| *p).collect::<Vec<u32>>(), vec![1, 2, 3]);
+        let needs_ocr: Vec<u32> = pages.iter()
+            .filter(|(_, text)| page_needs_ocr(text, 10))
+            .map(|(p, _)| *p)
+            .collect();
+        assert_eq!(needs_ocr, vec![2], "only the image page should need OCR: {:?}", pages);
+        assert!(pages[0].1.contains("RBI CIRCULAR ON KYC NORMS"), "{:?}", pages);
+    }
+
+    #[test]
+    fn test_merge_page_texts_in_page_order() {
+        let pages = vec![
+            (3, "third".to_string()),
+            (1, "first".to_string()),
+            (2, "  ".to_string()),
+            (4, "fourth\n".to_string()),
+        ];
+        assert_eq!(merge_page_texts(&pages), "first\n\nthird\n\nfourth");
+    }
+
+    #[test]
+    fn test_ocr_words_to_text_lines_and_confidence() {
+        let words = vec![
+            ocr_word(1, 1, 1, "", -1.0),
+            ocr_word(1, 1, 1, "Master", 90.0),
+            ocr_word(1, 1, 1, "Direction", 80.0),
+            ocr_word(1, 1, 2, "on", 70.0),
+            ocr_word(1, 2, 1, "KYC", 60.0),
+        ];
+        let (text, confidence) = ocr_words_to_text(&words);
+        assert_eq!(text, "Master Direction\non\n\nKYC");
+        assert!((confidence - 75.0).abs() < 1e-4, "got {}", confidence);
+        assert_eq!(ocr_words_to_text(&[]), (String::new(), 0.0));
+    }
+
+    #[test]
+    fn test_apply_ocr_fallback_on_generated_image_pdf() {
+        if !tool_available("pdftoppm") || !tool_available("tesseract") {
+            println!("Skipping OCR round-trip test: pdftoppm/tesseract not installed.");
+            return;
+        }
+        let dir = std::env::temp_dir().join(format!("nl_ocr_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        // Render a text page to an image, then embed that image in a text-less PDF page:
+        let text_pdf = dir.join("text_source.pdf");
+        fs::write(&text_pdf, make_test_pdf(&[text_page("PENALTY IMPOSED ON BANK")], None)).unwrap();
+        let png = rasterise_pdf_page(&text_pdf, 1, 100, &dir.join("text_source")).unwrap();
+        let gray = rusty_tesseract::image::open(&png).unwrap().to_luma8();
+        let scanned = make_test_pdf(
+            &[text_page("COVER PAGE OF THE CIRCULAR"), TestPdfPage::Image],
+            Some((gray.width(), gray.height(), gray.into_raw())),
+        );
+        let scanned_pdf = dir.join("scanned.pdf");
+        fs::write(&scanned_pdf, scanned).unwrap();
+
+        let mut doc = Document::default();
+        doc.module = "mod_test".to_string();
+        let settings = OcrSettings { dpi: 150, ..OcrSettings::default() };
+        let ocr_pages = apply_ocr_fallback(&mut doc, &scanned_pdf, &settings);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(ocr_pages, 1);
+        let cover_at = doc.text.find("COVER").expect("text-layer page kept");
+        let penalty_at = doc.text.to_uppercase().find("PENALTY").expect("OCR text merged");
+        assert!(cover_at < penalty_at, "pages must stay in order: {}", doc.text);
+        let confidences: Vec<OcrPageConfidence> =
+            serde_json::from_str(&doc.generated_content["ocr_confidence"]).unwrap();
+        assert_eq!(confidences.len(), 1);
+        assert_eq!(confidences[0].page, 2);
+        assert_eq!(doc.generated_content["ocr_lang"], "eng");
+    }
+}
@@ -0,0 +1,740 @@
+// file: vocab.rs
+// Purpose:
+//   External vocabularies for the lexicon-driven extractors, so analysts can extend them
+//   without a Rust change: the themebook (`mod_themes`), tone lexicons (`mod_tone`,
+//   `mod_mentions`; `mod_ner` scores targeted sentiment with `mod_tone`'s), the place
+//   gazetteer (`mod_geocode`) and the organisation gazetteer (`mod_ner`). Each plugin names
+//   its file in plugin config and keeps its embedded list as the default, used when no file
+//   is configured or the file fails validation.
+//
+//   A `VocabSource` loads the file once at plugin start and re-checks it while the plugin
+//   runs (at most every RELOAD_CHECK_SECS): a changed file is parsed and validated before
+//   it replaces the vocabulary in use, so a broken edit is logged and the previous version
+//   stays active. Every vocabulary carries a version, stamped on the rows emitted from it
+//   (`DocAnalysis.vocab_versions`); a file that does not declare one is versioned as
+//   `<file stem>@<content digest>`.
+//
+//   File formats (`.toml` is read as TOML, anything else as CSV with a header row; CSV lines
+//   starting with '#' are comments, and `# version: ...` / `# name: ...` comments set the
+//   version and name):
+//     themebook   TOML: version = "...", then [[themes]] tables with code = "ECON_INFLATION"
+//                       and phrases = ["inflation", "cpi"]
+//                 CSV:  theme,phrase
+//     lexicon     TOML: version, name (the GCAM dict_id), positive / negative / active arrays
+//                 CSV:  word,category   (category positive | negative | active)
+//                 CSV:  Loughran–McDonald master dictionary (Word, ..., Negative, Positive,
+//                       ... columns; a positive value, the year a word was added, marks
+//                       membership). It has no activity words, so the default ones are kept.
+//     gazetteer   CSV:  name,lat,lon,adm1,country
+//     org list    CSV:  surface,canonical
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use log::{error, info, warn};
+use serde::Deserialize;
+
+/// Minimum interval between checks of a vocabulary file for changes.
+pub const RELOAD_CHECK_SECS: u64 = 30;
+/// Validation warnings logged per load; the rest are only counted.
+const MAX_LOGGED_WARNINGS: usize = 20;
+
+/// Syntax of a vocabulary file, from its extension.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Toml,
+    Csv,
+}
+
+impl Format {
+    pub fn of(path: &Path) -> Format {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => Format::Toml,
+            _ => Format::Csv,
+        }
+    }
+}
+
+/// A parsed vocabulary with the problems found in rows that were skipped.
+#[derive(Debug)]
+pub struct Parsed<T> {
+    pub vocab: T,
+    pub warnings: Vec<String>,
+}
+
+/// A vocabulary that can be loaded from a file.
+pub trait Vocabulary: Sized {
+    /// Label used in log messages and as the `DocAnalysis.vocab_versions` key.
+    const KIND: &'static str;
+    /// Parse and validate file content. Bad rows become warnings; an unreadable file or one
+    /// with no usable entries is an error. `stem` is the file name without extension.
+    fn parse(content: &str, format: Format, stem: &str) -> Result<Parsed<Self>, String>;
+    fn version(&self) -> &str;
+    fn set_version(&mut self, version: String);
+    fn len(&self) -> usize;
+    /// Fill in from the embedded default whatever a loaded file leaves out.
+    fn inherit_defaults(&mut self, _default: &Self) {}
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Read, parse and validate a vocabulary file, versioning it by content digest if it does
+/// not declare a version.
+pub fn load_file<T: Vocabulary>(path: &Path) -> Result<Parsed<T>, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("read {} '{}': {}", T::KIND, path.display(), e))?;
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or(T::KIND);
+    let mut parsed = T::parse(&content, Format::of(path), stem).map_err(|e| format!("{} '{}': {}", T::KIND, path.display(), e))?;
+    if parsed.vocab.version().is_empty() {
+        parsed.vocab.set_version(format!("{}@{:08x}", stem, content_digest(&content)));
+    }
+    Ok(parsed)
+}
+
+/// A vocabulary in use by a plugin: the configured file's content once it validates, else
+/// the embedded default.
+pub struct VocabSource<T: Vocabulary> {
+    plugin: String,
+    path: Option<PathBuf>,
+    default: T,
+    loaded: Option<T>,
+    /// Modification time and length of the file when last loaded (or rejected).
+    stamp: Option<(SystemTime, u64)>,
+    checked: Instant,
+    check_interval: Duration,
+}
+
+impl<T: Vocabulary> VocabSource<T> {
+    /// Load the file at `path` (a blank or missing path means the default), falling back to
+    /// `default` if it cannot be read or fails validation.
+    pub fn new(plugin: &str, path: Option<String>, default: T) -> Self {
+        let path = path.filter(|p| !p.trim().is_empty()).map(|p| PathBuf::from(p.trim()));
+        let mut source = VocabSource {
+            plugin: plugin.to_string(),
+            path,
+            default,
+            loaded: None,
+            stamp: None,
+            checked: Instant::now(),
+            check_interval: Duration::from_secs(RELOAD_CHECK_SECS),
+        };
+        if source.path.is_some() {
+            source.reload();
+        }
+        source
+    }
+
+    /// Check for changes at most this often (tests use zero).
+    pub fn with_check_interval(mut self, interval: Duration) -> Self {
+        self.check_interval = interval;
+        self
+    }
+
+    pub fn get(&self) -> &T {
+        self.loaded.as_ref().unwrap_or(&self.default)
+    }
+
+    /// Reload the file if it changed since it was last read and the check interval has
+    /// passed. Returns true when a new version was loaded.
+    pub fn refresh(&mut self) -> bool {
+        if self.path.is_none() || self.checked.elapsed() < self.check_interval {
+            return false;
+        }
+        self.checked = Instant::now();
+        let stamp = self.path.as_deref().and_then(file_stamp);
+        if stamp.is_none() || stamp == self.stamp {
+            return false;
+        }
+        self.reload()
+    }
+
+    fn reload(&mut self) -> bool {
+        let Some(path) = self.path.clone() else {
+            return false;
+        };
+        self.stamp = file_stamp(&path);
+        match load_file::<T>(&path) {
+            Ok(mut parsed) => {
+                for w in parsed.warnings.iter().take(MAX_LOGGED_WARNINGS) {
+                    warn!("{}: {} '{}': {}", self.plugin, T::KIND, path.display(), w);
+                }
+                if parsed.warnings.len() > MAX_LOGGED_WARNINGS {
+                    warn!("{}: {} '{}': {} more row(s) skipped.", self.plugin, T::KIND, path.display(), parsed.warnings.len() - MAX_LOGGED_WARNINGS);
+                }
+                info!(
+                    "{}: loaded {} v{} ({} entries) from {}",
+                    self.plugin, T::KIND, parsed.vocab.version(), parsed.vocab.len(), path.display()
+                );
+                parsed.vocab.inherit_defaults(&self.default);
+                self.loaded = Some(parsed.vocab);
+                true
+            }
+            Err(e) => {
+                error!("{}: {}; keeping {} v{}.", self.plugin, e, T::KIND, self.get().version());
+                false
+            }
+        }
+    }
+}
+
+fn file_stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+/// FNV-1a digest of the content, folded to 32 bits.
+fn content_digest(content: &str) -> u32 {
+    let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+    for b in content.bytes() {
+        h ^= b as u64;
+        h = h.wrapping_mul(0x0100_0000_01b3);
+    }
+    (h ^ (h >> 32)) as u32
+}
+
+/// CSV content split into (line number, fields) rows after the header, plus the header
+/// fields (lowercased) and any `# version:` / `# name:` comment values.
+struct CsvRows<'a> {
+    header: Vec<String>,
+    rows: Vec<(usize, Vec<&'a str>)>,
+    version: String,
+    name: String,
+}
+
+fn csv_rows(content: &str) -> Result<CsvRows<'_>, String> {
+    let mut out = CsvRows { header: Vec::new(), rows: Vec::new(), version: String::new(), name: String::new() };
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim().trim_start_matches('\u{feff}');
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(comment) = line.strip_prefix('#') {
+            if let Some((key, value)) = comment.split_once(':') {
+                match key.trim().to_ascii_lowercase().as_str() {
+                    "version" => out.version = value.trim().to_string(),
+                    "name" => out.name = value.trim().to_string(),
+                    _ => {}
+                }
+            }
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(|f| f.trim().trim_matches('"').trim()).collect();
+        if out.header.is_empty() {
+            out.header = fields.iter().map(|f| f.to_ascii_lowercase()).collect();
+        } else {
+            out.rows.push((i + 1, fields));
+        }
+    }
+    if out.header.is_empty() {
+        return Err("no header row".to_string());
+    }
+    Ok(out)
+}
+
+fn column(header: &[String], name: &str) -> Result<usize, String> {
+    header.iter().position(|h| h == name).ok_or_else(|| format!("missing '{}' column", name))
+}
+
+fn from_toml<T: for<'de> Deserialize<'de>>(content: &str) -> Result<T, String> {
+    config::Config::builder()
+        .add_source(config::File::from_str(content, config::FileFormat::Toml))
+        .build()
+        .and_then(|c| c.try_deserialize::<T>())
+        .map_err(|e| format!("invalid TOML: {}", e))
+}
+
+// ---------------------------------------------------------------------------
+// Themebook
+// ---------------------------------------------------------------------------
+
+/// Theme codes with their trigger phrases (lowercased), in file order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Themebook {
+    pub version: String,
+    pub themes: Vec<(String, Vec<String>)>,
+}
+
+impl Themebook {
+    pub fn from_static(version: &str, themes: &[(&str, &[&str])]) -> Self {
+        Themebook {
+            version: version.to_string(),
+            themes: themes.iter().map(|(t, ps)| (t.to_string(), ps.iter().map(|p| p.to_lowercase()).collect())).collect(),
+        }
+    }
+
+    /// Add a phrase to a theme, validating both. Returns a warning for a rejected pair.
+    fn add(&mut self, theme: &str, phrase: &str) -> Result<(), String> {
+        let valid_code = theme.starts_with(|c: char| c.is_ascii_uppercase())
+            && theme.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+        if !valid_code {
+            return Err(format!("theme code '{}' is not UPPER_SNAKE_CASE", theme));
+        }
+        let phrase = phrase.trim().to_lowercase();
+        if phrase.is_empty() {
+            return Err(format!("empty phrase for theme {}", theme));
+        }
+        match self.themes.iter_mut().find(|(t, _)| t == theme) {
+            Some((_, phrases)) if phrases.contains(&phrase) => return Err(format!("duplicate phrase '{}' for theme {}", phrase, theme)),
+            Some((_, phrases)) => phrases.push(phrase),
+            None => self.themes.push((theme.to_string(), vec![phrase])),
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+struct ThemebookToml {
+    #[serde(default)]
+    version: String,
+    themes: Vec<ThemeToml>,
+}
+
+#[derive(Deserialize)]
+struct ThemeToml {
+    code: String,
+    phrases: Vec<String>,
+}
+
+impl Vocabulary for Themebook {
+    const KIND: &'static str = "themebook";
+
+    fn parse(content: &str, format: Format, _stem: &str) -> Result<Parsed<Self>, String> {
+        let mut book = Themebook::default();
+        let mut warnings = Vec::new();
+        match format {
+            Format::Toml => {
+                let file: ThemebookToml = from_toml(content)?;
+                book.version = file.version;
+                for theme in file.themes {
+                    for phrase in &theme.phrases {
+                        if let Err(w) = book.add(theme.code.trim(), phrase) {
+                            warnings.push(w);
+                        }
+                    }
+                }
+            }
+            Format::Csv => {
+                let csv = csv_rows(content)?;
+                let (theme_col, phrase_col) = (column(&csv.header, "theme")?, column(&csv.header, "phrase")?);
+                book.version = csv.version;
+                for (line, fields) in csv.rows {
+                    let added = match (fields.get(theme_col), fields.get(phrase_col)) {
+                        (Some(theme), Some(phrase)) => book.add(theme, phrase),
+                        _ => Err("too few columns".to_string()),
+                    };
+                    if let Err(w) = added {
+                        warnings.push(format!("line {}: {}", line, w));
+                    }
+                }
+            }
+        }
+        if book.themes.is_empty() {
+            return Err("no themes".to_string());
+        }
+        Ok(Parsed { vocab: book, warnings })
+    }
+
+    fn version(&self) -> &str {
+        &self.version
+    }
+
+    fn set_version(&mut self, version: String) {
+        self.version = version;
+    }
+
+    fn len(&self) -> usize {
+        self.themes.len()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tone lexicon
+// ---------------------------------------------------------------------------
+
+/// Positive, negative and activity word lists (lowercased, matched as whole words).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ToneLexicon {
+    /// Dictionary name, used as the GCAM `dict_id` ("finlex" for the embedded lexicon,
+    /// "lm" for Loughran–McDonald).
+    pub name: String,
+    pub version: String,
+    positive: HashSet<String>,
+    negative: HashSet<String>,
+    active: HashSet<String>,
+}
+
+impl ToneLexicon {
+    pub fn from_static(name: &str, version: &str, positive: &[&str], negative: &[&str], active: &[&str]) -> Self {
+        let set = |words: &[&str]| words.iter().map(|w| w.to_lowercase()).collect();
+        ToneLexicon {
+            name: name.to_string(),
+            version: version.to_string(),
+            positive: set(positive),
+            negative: set(negative),
+            active: set(active),
+        }
+    }
+
+    /// +1 for a positive word, -1 for a negative one, else 0. `word` must be lowercase.
+    pub fn polarity(&self, word: &str) -> i64 {
+        if self.positive.contains(word) {
+            1
+        } else if self.negative.contains(word) {
+            -1
+        } else {
+            0
+        }
+    }
+
+    pub fn is_active(&self, word: &str) -> bool {
+        self.active.contains(word)
+    }
+
+    /// Drop words listed as both positive and negative, returning one warning per word.
+    fn drop_conflicts(&mut self) -> Vec<String> {
+        let mut both: Vec<String> = self.positive.intersection(&self.negative).cloned().collect();
+        both.sort();
+        for w in &both {
+            self.positive.remove(w);
+            self.negative.remove(w);
+        }
+        both.into_iter().map(|w| format!("'{}' is both positive and negative; dropped", w)).collect()
+    }
+}
+
+#[derive(Deserialize)]
+struct LexiconToml {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    positive: Vec<String>,
+    #[serde(default)]
+    negative: Vec<String>,
+    #[serde(default)]
+    active: Vec<String>,
+}
+
+impl Vocabulary for ToneLexicon {
+    const KIND: &'static str = "tone_lexicon";
+
+    fn parse(content: &str, format: Format, stem: &str) -> Result<Parsed<Self>, String> {
+        let mut lex = ToneLexicon::default();
+        let mut warnings = Vec::new();
+        let clean = |w: &str| w.trim().to_lowercase();
+        match format {
+            Format::Toml => {
+                let file: LexiconToml = from_toml(content)?;
+                lex.name = file.name;
+                lex.version = file.version;
+                lex.positive = file.positive.iter().map(|w| clean(w)).filter(|w| !w.is_empty()).collect();
+                lex.negative = file.negative.iter().map(|w| clean(w)).filter(|w| !w.is_empty()).collect();
+                lex.active = file.active.iter().map(|w| clean(w)).filter(|w| !w.is_empty()).collect();
+            }
+            Format::Csv => {
+                let csv = csv_rows(content)?;
+                lex.name = csv.name;
+                lex.version = csv.version;
+                let word_col = column(&csv.header, "word")?;
+                if let Ok(category_col) = column(&csv.header, "category") {
+                    for (line, fields) in &csv.rows {
+                        let word = fields.get(word_col).map(|w| clean(w)).unwrap_or_default();
+                        let category = fields.get(category_col).map(|c| c.to_ascii_lowercase()).unwrap_or_default();
+                        let list = match category.as_str() {
+                            _ if word.is_empty() => None,
+                            "positive" => Some(&mut lex.positive),
+                            "negative" => Some(&mut lex.negative),
+                            "active" => Some(&mut lex.active),
+                            _ => None,
+                        };
+                        match list {
+                            Some(list) => {
+                                list.insert(word);
+                            }
+                            None => warnings.push(format!("line {}: expected a word and positive|negative|active", line)),
+                        }
+                    }
+                } else {
+                    // Loughran–McDonald master dictionary.
+                    let (pos_col, neg_col) = (column(&csv.header, "positive")?, column(&csv.header, "negative")?);
+                    if lex.name.is_empty() {
+                        lex.name = "lm".to_string();
+                    }
+                    let member = |v: Option<&&str>| v.and_then(|v| v.parse::<f64>().ok()).is_some_and(|v| v > 0.0);
+                    for (line, fields) in &csv.rows {
+                        let word = fields.get(word_col).map(|w| clean(w)).unwrap_or_default();
+                        if word.is_empty() || fields.len() <= pos_col.max(neg_col) {
+                            warnings.push(format!("line {}: too few columns", line));
+                            continue;
+                        }
+                        if member(fields.get(pos_col)) {
+                            lex.positive.insert(word.clone());
+                        }
+                        if member(fields.get(neg_col)) {
+                            lex.negative.insert(word);
+                        }
+                    }
+                }
+            }
+        }
+        warnings.extend(lex.drop_conflicts());
+        if lex.positive.is_empty() || lex.negative.is_empty() {
+            return Err("needs both positive and negative words".to_string());
+        }
+        if lex.name.is_empty() {
+            lex.name = stem.to_string();
+        }
+        Ok(Parsed { vocab: lex, warnings })
+    }
+
+    fn version(&self) -> &str {
+        &self.version
+    }
+
+    fn set_version(&mut self, version: String) {
+        self.version = version;
+    }
+
+    fn len(&self) -> usize {
+        self.positive.len() + self.negative.len() + self.active.len()
+    }
+
+    /// Keep the default activity words when the file has none (Loughran–McDonald).
+    fn inherit_defaults(&mut self, default: &Self) {
+        if self.active.is_empty() {
+            self.active = default.active.clone();
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Gazetteers
+// ---------------------------------------------------------------------------
+
+/// A gazetteer entry.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Place {
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+    /// State / region.
+    pub adm1: String,
+    /// ISO 3166-1 alpha-2 country code.
+    pub country: String,
+}
+
+/// Named places with coordinates.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Gazetteer {
+    pub version: String,
+    pub places: Vec<Place>,
+}
+
+impl Vocabulary for Gazetteer {
+    const KIND: &'static str = "gazetteer";
+
+    fn parse(content: &str, format: Format, _stem: &str) -> Result<Parsed<Self>, String> {
+        if format == Format::Toml {
+            return Err("gazetteers are read from CSV".to_string());
+        }
+        let csv = csv_rows(content)?;
+        let col = |c: &str| column(&csv.header, c);
+        let (name, lat, lon, adm1, country) = (col("name")?, col("lat")?, col("lon")?, col("adm1")?, col("country")?);
+        let mut gazetteer = Gazetteer { version: csv.version, places: Vec::new() };
+        let mut warnings = Vec::new();
+        for (line, fields) in csv.rows {
+            let field = |i: usize| fields.get(i).copied().unwrap_or_default();
+            let coord = |i: usize, limit: f64| field(i).parse::<f64>().ok().filter(|v| v.abs() <= limit);
+            let place = match (coord(lat, 90.0), coord(lon, 180.0)) {
+                (Some(lat), Some(lon)) => Place {
+                    name: field(name).to_string(),
+                    lat,
+                    lon,
+                    adm1: field(adm1).to_string(),
+                    country: field(country).to_ascii_uppercase(),
+                },
+                _ => {
+                    warnings.push(format!("line {}: bad lat/lon", line));
+                    continue;
+                }
+            };
+            if place.name.is_empty() || place.country.len() != 2 || !place.country.chars().all(|c| c.is_ascii_uppercase()) {
+                warnings.push(format!("line {}: needs a name and an ISO-2 country code", line));
+            } else if gazetteer.places.iter().any(|p| p.name == place.name && p.country == place.country) {
+                warnings.push(format!("line {}: duplicate place {}/{}", line, place.country, place.name));
+            } else {
+                gazetteer.places.push(place);
+            }
+        }
+        if gazetteer.places.is_empty() {
+            return Err("no places".to_string());
+        }
+        Ok(Parsed { vocab: gazetteer, warnings })
+    }
+
+    fn version(&self) -> &str {
+        &self.version
+    }
+
+    fn set_version(&mut self, version: String) {
+        self.version = version;
+    }
+
+    fn len(&self) -> usize {
+        self.places.len()
+    }
+}
+
+/// Organisation surface forms / acronyms with their canonical names.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OrgGazetteer {
+    pub version: String,
+    pub orgs: Vec<(String, String)>,
+}
+
+impl OrgGazetteer {
+    pub fn from_static(version: &str, orgs: &[(&str, &str)]) -> Self {
+        OrgGazetteer { version: version.to_string(), orgs: orgs.iter().map(|(s, c)| (s.to_string(), c.to_string())).collect() }
+    }
+}
+
+impl Vocabulary for OrgGazetteer {
+    const KIND: &'static str = "org_gazetteer";
+
+    fn parse(content: &str, format: Format, _stem: &str) -> Result<Parsed<Self>, String> {
+        if format == Format::Toml {
+            return Err("organisation gazetteers are read from CSV".to_string());
+        }
+        let csv = csv_rows(content)?;
+        let (surface_col, canonical_col) = (column(&csv.header, "surface")?, column(&csv.header, "canonical")?);
+        let mut orgs = OrgGazetteer { version: csv.version, orgs: Vec::new() };
+        let mut warnings = Vec::new();
+        for (line, fields) in csv.rows {
+            let surface = fields.get(surface_col).copied().unwrap_or_default();
+            // A blank canonical name means the surface form is the canonical name.
+            let canonical = fields.get(canonical_col).copied().filter(|c| !c.is_empty()).unwrap_or(surface);
+            if surface.is_empty() {
+                warnings.push(format!("line {}: empty surface form", line));
+            } else if orgs.orgs.iter().any(|(s, _)| s.eq_ignore_ascii_case(surface)) {
+                warnings.push(format!("line {}: duplicate surface form '{}'", line, surface));
+            } else {
+                orgs.orgs.push((surface.to_string(), canonical.to_string()));
+            }
+        }
+        if orgs.orgs.is_empty() {
+            return Err("no organisations".to_string());
+        }
+        Ok(Parsed { vocab: orgs, warnings })
+    }
+
+    fn version(&self) -> &str {
+        &self.version
+    }
+
+    fn set_version(&mut self, version: String) {
+        self.version = version;
+    }
+
+    fn len(&self) -> usize {
+        self.orgs.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_themebook_toml_and_csv() {
+        let toml = r#"
+version = "2026.10.1"
+[[themes]]
+code = "ECON_INFLATION"
+phrases = ["Inflation", "CPI"]
+[[themes]]
+code = "bad code"
+phrases = ["x"]
+"#;
+        let p = Themebook::parse(toml, Format::Toml, "themes").unwrap();
+        assert_eq!(p.vocab.version, "2026.10.1");
+        assert_eq!(p.vocab.themes, vec![("ECON_INFLATION".to_string(), vec!["inflation".to_string(), "cpi".to_string()])]);
+        assert_eq!(p.warnings.len(), 1, "{:?}", p.warnings);
+
+        let csv = "# version: 3\ntheme,phrase\nFIN_IPO,ipo\nFIN_IPO,public issue\nFIN_IPO,ipo\nFIN_IPO\n";
+        let p = Themebook::parse(csv, Format::Csv, "themes").unwrap();
+        assert_eq!((p.vocab.version.as_str(), p.vocab.themes[0].1.len()), ("3", 2));
+        assert_eq!(p.warnings.len(), 2, "{:?}", p.warnings);
+        assert!(Themebook::parse("theme,phrase\n", Format::Csv, "themes").is_err());
+    }
+
+    #[test]
+    fn test_lexicon_formats_and_loughran_mcdonald() {
+        let p = ToneLexicon::parse("word,category\nGain,positive\nloss,negative\nfile,active\nbad,meh\n", Format::Csv, "mylex").unwrap();
+        assert_eq!((p.vocab.polarity("gain"), p.vocab.polarity("loss"), p.vocab.polarity("file")), (1, -1, 0));
+        assert!(p.vocab.is_active("file"));
+        assert_eq!((p.vocab.name.as_str(), p.warnings.len()), ("mylex", 1));
+
+        let lm = "Word,Seq_num,Word Count,Negative,Positive,Uncertainty\n\
+                  ABANDON,1,100,2009,0,0\nACHIEVE,2,100,0,2009,0\nBOOST,3,100,0,-2020,0\nGOOD,4,100,2009,2009,0\n";
+        let p = ToneLexicon::parse(lm, Format::Csv, "LoughranMcDonald_MasterDictionary").unwrap();
+        assert_eq!(p.vocab.name, "lm");
+        assert_eq!((p.vocab.polarity("abandon"), p.vocab.polarity("achieve")), (-1, 1));
+        assert_eq!(p.vocab.polarity("boost"), 0, "negative year marks a removed word");
+        assert_eq!(p.vocab.polarity("good"), 0, "conflicting word dropped");
+        assert_eq!(p.warnings.len(), 1);
+        assert!(!p.vocab.is_active("file"));
+        let default = ToneLexicon::from_static("finlex", "1", &["up"], &["down"], &["file"]);
+        let mut lm = p.vocab;
+        lm.inherit_defaults(&default);
+        assert!(lm.is_active("file"));
+
+        let toml = "name = \"custom\"\npositive = [\"up\"]\nnegative = [\"down\"]\n";
+        let p = ToneLexicon::parse(toml, Format::Toml, "x").unwrap();
+        assert_eq!((p.vocab.name.as_str(), p.vocab.version.as_str()), ("custom", ""));
+        assert!(ToneLexicon::parse("positive = [\"up\"]\n", Format::Toml, "x").is_err());
+    }
+
+    #[test]
+    fn test_gazetteers_validate_rows() {
+        let csv = "name,lat,lon,adm1,country\nMumbai,19.07,72.87,Maharashtra,in\nNowhere,95,0,X,IN\nMumbai,19.07,72.87,Maharashtra,IN\nPune,18.5,73.8,Maharashtra,India\n";
+        let p = Gazetteer::parse(csv, Format::Csv, "g").unwrap();
+        assert_eq!(p.vocab.places.len(), 1);
+        assert_eq!(p.vocab.places[0].country, "IN");
+        assert_eq!(p.warnings.len(), 3, "{:?}", p.warnings);
+        assert!(Gazetteer::parse("name,lat,lon\n", Format::Csv, "g").is_err());
+
+        let p = OrgGazetteer::parse("surface,canonical\nRBI,Reserve Bank of India\nHDFC Bank,\nrbi,x\n", Format::Csv, "o").unwrap();
+        assert_eq!(p.vocab.orgs[1], ("HDFC Bank".to_string(), "HDFC Bank".to_string()));
+        assert_eq!((p.vocab.orgs.len(), p.warnings.len()), (2, 1));
+    }
+
+    #[test]
+    fn test_source_versions_and_hot_reload() {
+        let dir = std::env::temp_dir().join(format!("nl_vocab_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("orgs.csv");
+        std::fs::write(&path, "surface,canonical\nRBI,Reserve Bank of India\n").unwrap();
+        let default = OrgGazetteer::from_static("builtin", &[("SEBI", "SEBI")]);
+
+        let mut src = VocabSource::new("test", Some(path.to_string_lossy().to_string()), default.clone())
+            .with_check_interval(Duration::ZERO);
+        assert_eq!(src.get().orgs[0].0, "RBI");
+        assert!(src.get().version.starts_with("orgs@"), "digest version: {}", src.get().version);
+        assert!(!src.refresh(), "unchanged file is not reloaded");
+
+        // A broken edit keeps the version in use; a valid one replaces it.
+        std::fs::write(&path, "surface,canonical\n").unwrap();
+        assert!(!src.refresh());
+        assert_eq!(src.get().orgs[0].0, "RBI");
+        std::fs::write(&path, "# version: 7\nsurface,canonical\nNSE,National Stock Exchange\nBSE,Bombay Stock Exchange\n").unwrap();
+        assert!(src.refresh());
+        assert_eq!((src.get().version.as_str(), src.get().orgs.len()), ("7", 2));
+
+        let missing = VocabSource::new("test", Some(dir.join("missing.csv").to_string_lossy().to_string()), default.clone());
+        assert_eq!(missing.get(), &default);
+        assert_eq!(VocabSource::new("test", None, default.clone()).get(), &default);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
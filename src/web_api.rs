@@ -1,12 +1,14 @@
 // file: web_api.rs
 // Purpose: Lightweight HTTP status API server for the NewsLookout pipeline.
 // Endpoints: GET /  GET /health  GET /status  GET /status/summary  GET /dashboard.html
+//           GET /market/{latest,ohlcv,returns,movers,series} (market-data queries, JSON or CSV)
 
+use std::collections::HashMap;
 use std::io::{Read, Write};
 use std::net::TcpListener;
 use std::sync::{Arc, Mutex};
 use std::thread;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 use log::{error, info, warn};
 
 /// Shared pipeline stats updated by start_data_pipeline and read by the HTTP handler.
@@ -63,8 +65,9 @@ pub fn create_status_tracker() -> SharedStatus {
 }
 
 /// Start the HTTP status API in a background daemon thread.
-/// Returns immediately; the server runs until the process exits.
-pub fn start_web_api(host: &str, port: u16, status: SharedStatus) {
+/// Returns immediately; the server runs until the process exits. `market_db` is the
+/// market-data DB served by the `/market/...` endpoints (they answer 503 when None).
+pub fn start_web_api(host: &str, port: u16, status: SharedStatus, market_db: Option<String>) {
     let addr = format!("{}:{}", host, port);
     match TcpListener::bind(&addr) {
         Ok(listener) => {
@@ -76,7 +79,8 @@ pub fn start_web_api(host: &str, port: u16, status: SharedStatus) {
                         match stream {
                             Ok(s) => {
                                 let st = Arc::clone(&status);
-                                thread::spawn(move || handle_connection(s, st));
+                                let db = market_db.clone();
+                                thread::spawn(move || handle_connection(s, st, db.as_deref()));
                             }
                             Err(e) => warn!("Status API accept error: {}", e),
                         }
@@ -88,13 +92,14 @@ pub fn start_web_api(host: &str, port: u16, status: SharedStatus) {
     }
 }
 
-fn handle_connection(mut stream: std::net::TcpStream, status: SharedStatus) {
+fn handle_connection(mut stream: std::net::TcpStream, status: SharedStatus, market_db: Option<&str>) {
     let mut buf = [0u8; 2048];
     let n = stream.read(&mut buf).unwrap_or(0);
     if n == 0 { return; }
 
     let request = String::from_utf8_lossy(&buf[..n]);
-    let path = extract_request_path(&request);
+    let target = extract_request_path(&request);
+    let (path, params) = split_query(&target);
 
     let (status_line, content_type, body) = match path.trim_end_matches('/') {
         "" | "/index.html" => root_response(),
@@ -103,6 +108,7 @@ fn handle_connection(mut stream: std::net::TcpStream, status: SharedStatus) {
         "/status/summary" => summary_response(&status),
         "/metrics" => metrics_response(),
         "/dashboard.html" => dashboard_response(),
+        route if route.starts_with("/market/") => market_response(&route["/market/".len()..], &params, market_db),
         _ => (
             "HTTP/1.1 404 Not Found",
             "text/plain",
@@ -124,8 +130,178 @@ fn extract_request_path(request: &str) -> String {
     if parts.len() >= 2 { parts[1].to_string() } else { "/".to_string() }
 }
 
+/// Split "/path?a=1&b=x%20y" into the path and its decoded query parameters.
+fn split_query(target: &str) -> (&str, HashMap<String, String>) {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let params = query
+        .split('&')
+        .filter(|kv| !kv.is_empty())
+        .map(|kv| {
+            let (k, v) = kv.split_once('=').unwrap_or((kv, ""));
+            (percent_decode(k), percent_decode(v))
+        })
+        .collect();
+    (path, params)
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok().and_then(|h| u8::from_str_radix(h, 16).ok());
+                match hex {
+                    Some(b) => { out.push(b); i += 2; }
+                    None => out.push(b'%'),
+                }
+            }
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn json_error(status_line: &'static str, message: &str) -> (&'static str, &'static str, String) {
+    (status_line, "application/json", serde_json::json!({ "error": message }).to_string())
+}
+
+fn bar_json(b: &crate::market_data::OhlcvBar) -> serde_json::Value {
+    serde_json::json!({
+        "date": b.date.to_string(), "open": b.open, "high": b.high, "low": b.low,
+        "close": b.close, "volume": b.volume,
+    })
+}
+
+fn mover_json(m: &crate::market_data::query::Mover) -> serde_json::Value {
+    serde_json::json!({
+        "symbol": m.symbol, "isin": m.isin, "close": m.close, "prev_close": m.prev_close,
+        "change_pct": m.change_pct, "volume": m.volume,
+    })
+}
+
+/// Why a `/market/...` query failed: an unknown query (404), invalid parameters (400) or a
+/// storage error (500).
+enum MarketError {
+    NotFound(String),
+    Request(String),
+    Storage(String),
+}
+
+/// Answer a `/market/<route>` query against the market-data DB. Dates are YYYY-MM-DD;
+/// `format=csv` returns CSV instead of JSON for ohlcv, movers and series. Invalid
+/// parameters answer 400, database errors 500 and an unknown query 404.
+fn market_response(route: &str, params: &HashMap<String, String>, market_db: Option<&str>) -> (&'static str, &'static str, String) {
+    use crate::market_data::query;
+
+    let db_path = match market_db {
+        Some(p) => p,
+        None => return json_error("HTTP/1.1 503 Service Unavailable", "market data DB not configured"),
+    };
+    let date = |key: &str| -> Result<Option<NaiveDate>, MarketError> {
+        match params.get(key) {
+            Some(v) => NaiveDate::parse_from_str(v, "%Y-%m-%d").map(Some)
+                .map_err(|_| MarketError::Request(format!("invalid {} '{}'", key, v))),
+            None => Ok(None),
+        }
+    };
+    let number = |key: &str, default: usize| -> Result<usize, MarketError> {
+        params.get(key).map_or(Ok(default), |v| v.parse::<usize>()
+            .map_err(|_| MarketError::Request(format!("invalid {} '{}'", key, v))))
+    };
+    let required = |key: &str| params.get(key).filter(|v| !v.is_empty()).cloned()
+        .ok_or_else(|| MarketError::Request(format!("missing '{}'", key)));
+    let csv = params.get("format").map(|f| f == "csv").unwrap_or(false);
+    let today = Utc::now().date_naive();
+
+    let conn = match crate::store::open(db_path) {
+        Ok(c) => c,
+        Err(e) => {
+            error!("web_api: {}", e);
+            return json_error("HTTP/1.1 503 Service Unavailable", "market data DB unavailable");
+        }
+    };
+    let result: Result<(&'static str, String), MarketError> = (|| match route {
+        "latest" => {
+            let symbol = required("symbol")?;
+            Ok(match query::latest_close(&conn, &symbol).map_err(MarketError::Storage)? {
+                Some(bar) => ("application/json", serde_json::json!({ "symbol": symbol, "bar": bar_json(&bar) }).to_string()),
+                None => ("application/json", serde_json::Value::Null.to_string()),
+            })
+        }
+        "ohlcv" => {
+            let symbol = required("symbol")?;
+            let to = date("to")?.unwrap_or(today);
+            let from = date("from")?.unwrap_or(to - Duration::days(365));
+            let adjusted = params.get("adjusted").map(|v| v != "false").unwrap_or(true);
+            let bars = query::ohlcv(&conn, &symbol, from, to, adjusted).map_err(MarketError::Storage)?;
+            Ok(if csv {
+                ("text/csv", query::bars_to_csv(&bars))
+            } else {
+                let bars: Vec<serde_json::Value> = bars.iter().map(bar_json).collect();
+                ("application/json", serde_json::json!({ "symbol": symbol, "adjusted": adjusted, "bars": bars }).to_string())
+            })
+        }
+        "returns" => {
+            let symbol = required("symbol")?;
+            let window = number("window", 20)?;
+            if window == 0 {
+                return Err(MarketError::Request("window must be at least one trading day".to_string()));
+            }
+            let r = query::window_return(&conn, &symbol, date("date")?.unwrap_or(today), window)
+                .map_err(MarketError::Storage)?;
+            Ok(("application/json", match r {
+                Some(r) => serde_json::json!({
+                    "symbol": r.symbol, "window": window, "from": r.from.to_string(), "to": r.to.to_string(),
+                    "start_close": r.start_close, "end_close": r.end_close, "return_pct": r.return_pct,
+                }),
+                None => serde_json::Value::Null,
+            }.to_string()))
+        }
+        "movers" => {
+            let day = date("date")?.ok_or_else(|| MarketError::Request("missing 'date'".to_string()))?;
+            let movers = query::top_movers(&conn, day, number("limit", 10)?).map_err(MarketError::Storage)?;
+            Ok(if csv {
+                ("text/csv", query::movers_to_csv(&movers))
+            } else {
+                let gainers: Vec<serde_json::Value> = movers.gainers.iter().map(mover_json).collect();
+                let losers: Vec<serde_json::Value> = movers.losers.iter().map(mover_json).collect();
+                ("application/json", serde_json::json!({ "date": day.to_string(), "gainers": gainers, "losers": losers }).to_string())
+            })
+        }
+        "series" => {
+            let instrument = required("instrument")?;
+            let to = date("to")?.unwrap_or(today);
+            let from = date("from")?.unwrap_or(to - Duration::days(365));
+            let points = query::series(&conn, &instrument, from, to).map_err(MarketError::Storage)?;
+            Ok(if csv {
+                ("text/csv", query::series_to_csv(&points))
+            } else {
+                let points: Vec<serde_json::Value> = points.iter()
+                    .map(|p| serde_json::json!({ "date": p.date.to_string(), "source": p.source, "value": p.value, "unit": p.unit }))
+                    .collect();
+                ("application/json", serde_json::json!({ "instrument": instrument, "points": points }).to_string())
+            })
+        }
+        _ => Err(MarketError::NotFound(format!("unknown market query '{}'", route))),
+    })();
+
+    match result {
+        Ok((content_type, body)) => ("HTTP/1.1 200 OK", content_type, body),
+        Err(MarketError::NotFound(e)) => json_error("HTTP/1.1 404 Not Found", &e),
+        Err(MarketError::Request(e)) => json_error("HTTP/1.1 400 Bad Request", &e),
+        Err(MarketError::Storage(e)) => {
+            error!("web_api: /market/{}: {}", route, e);
+            json_error("HTTP/1.1 500 Internal Server Error", "market data query failed")
+        }
+    }
+}
+
 fn root_response() -> (&'static str, &'static str, String) {
-    let body = r#"{"service":"NewsLookout Status API","version":"0.5.0","endpoints":{"/status":"Full pipeline status","/status/summary":"Summary statistics","/health":"Health check","/dashboard.html":"Dashboard UI"}}"#;
+    let body = r#"{"service":"NewsLookout Status API","version":"0.5.0","endpoints":{"/status":"Full pipeline status","/status/summary":"Summary statistics","/health":"Health check","/dashboard.html":"Dashboard UI","/market/latest?symbol=":"Latest NSE close","/market/ohlcv?symbol=&from=&to=&adjusted=&format=":"Daily bars (JSON or CSV)","/market/returns?symbol=&window=&date=":"Return over a window of trading days","/market/movers?date=&limit=&format=":"Top gainers and losers","/market/series?instrument=&from=&to=&format=":"market_series observations"}}"#;
     ("HTTP/1.1 200 OK", "application/json", body.to_string())
 }
 
@@ -425,6 +601,52 @@ mod tests {
         assert_eq!(rbi_pair["url_discovery_complete"], true);
     }
 
+    #[test]
+    fn test_split_query_decodes_params() {
+        let (path, params) = split_query("/market/latest?symbol=M%26M&format=csv&x");
+        assert_eq!(path, "/market/latest");
+        assert_eq!(params.get("symbol").map(String::as_str), Some("M&M"));
+        assert_eq!(params.get("format").map(String::as_str), Some("csv"));
+        assert_eq!(params.get("x").map(String::as_str), Some(""));
+        assert_eq!(split_query("/status").0, "/status");
+        assert_eq!(percent_decode("a+b%2"), "a b%2");
+    }
+
+    #[test]
+    fn test_market_response_errors() {
+        let params = HashMap::new();
+        assert_eq!(market_response("latest", &params, None).0, "HTTP/1.1 503 Service Unavailable");
+
+        let tmp = std::env::temp_dir().join("web_api_market_test.db");
+        let db_path = tmp.to_str().unwrap();
+        let _ = std::fs::remove_file(db_path);
+        crate::store::init_at_startup(db_path);
+        let (status, _, body) = market_response("latest", &params, Some(db_path));
+        assert_eq!(status, "HTTP/1.1 400 Bad Request");
+        assert!(body.contains("missing 'symbol'"));
+        let mut params = HashMap::new();
+        params.insert("instrument".to_string(), "FBIL:MIBOR:OVERNIGHT".to_string());
+        params.insert("format".to_string(), "csv".to_string());
+        let (status, content_type, body) = market_response("series", &params, Some(db_path));
+        assert_eq!((status, content_type), ("HTTP/1.1 200 OK", "text/csv"));
+        assert_eq!(body, "date,source,instrument,value,unit\n");
+
+        params.insert("from".to_string(), "2025-13-01".to_string());
+        let (status, _, body) = market_response("series", &params, Some(db_path));
+        assert_eq!(status, "HTTP/1.1 400 Bad Request");
+        assert!(body.contains("invalid from"), "{}", body);
+        assert_eq!(market_response("quotes", &params, Some(db_path)).0, "HTTP/1.1 404 Not Found");
+
+        // A storage failure is the server's fault, not the caller's.
+        let conn = crate::store::open(db_path).unwrap();
+        conn.execute_batch("DROP TABLE market_series;").unwrap();
+        params.remove("from");
+        let (status, _, body) = market_response("series", &params, Some(db_path));
+        assert_eq!(status, "HTTP/1.1 500 Internal Server Error");
+        assert!(body.contains("market data query failed"), "{}", body);
+        let _ = std::fs::remove_file(db_path);
+    }
+
     #[test]
     fn test_dashboard_html_is_bundled() {
         let (_s, ct, body) = dashboard_response();